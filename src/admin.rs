@@ -0,0 +1,311 @@
+// src/admin.rs
+use axum::{
+    extract::{MatchedPath, Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::time::{Duration, UNIX_EPOCH};
+use crate::analytics::parse_window;
+use crate::blocklist::BlocklistRules;
+use crate::compression::CompressionChange;
+use crate::problem::ApiError;
+use crate::lists::{validate_slug, CuratedListInput, ListImport};
+use crate::mode::ModeChange;
+use crate::mtls::ClientIdentity;
+use crate::shadow::ShadowChange;
+use crate::themes::ThemeInput;
+use crate::state::AppState;
+
+/// Number of entries listed per ranking when `limit` is not given
+const DEFAULT_REPORT_LIMIT: usize = 10;
+
+/// Window used by `/analytics/titles/top` when `window` is not given
+const DEFAULT_TITLE_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Builds the `/admin` routes, guarded by the configured admin token and client certificate roles.
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/analytics/searches", get(search_analytics))
+        .route("/analytics/titles/top", get(top_titles))
+        .route("/analytics/experiments", get(experiment_exposures))
+        .route("/metrics/slow", get(slow_events))
+        .route("/metrics/negative-cache", get(negative_cache_stats))
+        .route("/metrics/upstream", get(upstream_stats))
+        .route("/metrics/mirrors", get(mirror_status))
+        .route("/metrics/home-rows", get(home_row_stats))
+        .route("/metrics/safe-search", get(safe_search_stats))
+        .route("/metrics/changes", get(change_feed_stats))
+        .route("/metrics/push", get(push_stats))
+        .route("/metrics/outage", get(outage_status))
+        .route("/metrics/compression", get(compression_status))
+        .route("/metrics/shadow", get(shadow_status))
+        .route("/slo", get(slo_status))
+        .route("/mode", get(get_mode).put(set_mode))
+        .route("/compression", get(compression_status).put(set_compression))
+        .route("/shadow", get(shadow_status).put(set_shadow))
+        .route("/blocklist", get(get_blocklist).put(set_blocklist))
+        .route("/lists", get(list_curated_lists))
+        .route("/lists/{slug}", get(get_curated_list).put(put_curated_list).delete(delete_curated_list))
+        .route("/lists/{slug}/import", post(import_curated_list))
+        .route("/themes", get(list_themes))
+        .route("/themes/{slug}", get(get_theme).put(put_theme).delete(delete_theme))
+        .route_layer(middleware::from_fn_with_state(state, require_admin))
+}
+
+/// Rejects requests without `Authorization: Bearer <ADMIN_TOKEN>` and, when
+/// client certificates are required, without one whose role allows the route.
+///
+/// When neither an admin token nor client certificates are configured the
+/// admin API is disabled and every request gets a 404, so nothing is
+/// exposed by default.
+pub(crate) async fn require_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    if state.admin_token.is_none() && !state.client_certs.is_enabled() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path(), MatchedPath::as_str);
+    let identity = request.extensions().get::<ClientIdentity>();
+    if let Err(rejection) = state.client_certs.authorize(identity, request.method(), route) {
+        return rejection.into_response();
+    }
+
+    if let Some(expected) = state.admin_token.as_deref() {
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        if provided != Some(expected) {
+            return (StatusCode::UNAUTHORIZED, "Invalid or missing admin token").into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Time range for analytics reports, as Unix timestamps in seconds
+#[derive(Deserialize)]
+pub struct AnalyticsQuery {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+async fn search_analytics(
+    State(state): State<AppState>,
+    Query(params): Query<AnalyticsQuery>
+) -> impl IntoResponse {
+    let from = params.from.map_or(UNIX_EPOCH, |secs| UNIX_EPOCH + Duration::from_secs(secs));
+    let to = params
+        .to
+        .map_or_else(|| state.clock.now(), |secs| UNIX_EPOCH + Duration::from_secs(secs));
+    let limit = params.limit.unwrap_or(DEFAULT_REPORT_LIMIT);
+
+    Json(state.search_analytics.report(from, to, limit))
+}
+
+/// Lookback window for title rankings, e.g. `7d` or `24h`
+#[derive(Deserialize)]
+pub struct TopTitlesQuery {
+    pub window: Option<String>,
+    pub limit: Option<usize>,
+}
+
+async fn top_titles(
+    State(state): State<AppState>,
+    Query(params): Query<TopTitlesQuery>
+) -> impl IntoResponse {
+    let window = match params.window.as_deref().map(parse_window) {
+        None => DEFAULT_TITLE_WINDOW,
+        Some(Some(window)) => window,
+        Some(None) => {
+            return (StatusCode::BAD_REQUEST, "window must look like 30m, 24h or 7d").into_response();
+        }
+    };
+    let from = state.clock.now().checked_sub(window).unwrap_or(UNIX_EPOCH);
+    let limit = params.limit.unwrap_or(DEFAULT_REPORT_LIMIT);
+
+    Json(state.title_analytics.top(from, limit)).into_response()
+}
+
+async fn experiment_exposures(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.experiments.exposure_counts())
+}
+
+async fn slow_events(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.slow_log.counts())
+}
+
+async fn negative_cache_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.negative_cache.stats())
+}
+
+async fn upstream_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.upstream_metrics.stats())
+}
+
+async fn mirror_status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.mirrors.status())
+}
+
+async fn home_row_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.home_row_metrics.stats())
+}
+
+async fn safe_search_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.safe_search.stats())
+}
+
+async fn change_feed_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.changes.stats())
+}
+
+async fn push_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.push.stats())
+}
+
+async fn outage_status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.outage.status())
+}
+
+async fn slo_status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.slos.status(state.clock.now()))
+}
+
+async fn get_mode(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.mode.status())
+}
+
+async fn set_mode(State(state): State<AppState>, Json(change): Json<ModeChange>) -> impl IntoResponse {
+    Json(state.mode.set(change.mode, state.clock.now()))
+}
+
+async fn compression_status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.compression.status())
+}
+
+async fn set_compression(State(state): State<AppState>, Json(change): Json<CompressionChange>) -> impl IntoResponse {
+    Json(state.compression.set(change.mode))
+}
+
+async fn shadow_status(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.shadow.status())
+}
+
+async fn set_shadow(State(state): State<AppState>, Json(change): Json<ShadowChange>) -> impl IntoResponse {
+    match state.shadow.set_sample_rate(change.sample_rate) {
+        Ok(status) => Json(status).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+async fn get_blocklist(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.blocklist.rules())
+}
+
+/// Replaces the blocklist; takes effect on the next request, cached results included
+async fn set_blocklist(State(state): State<AppState>, Json(rules): Json<BlocklistRules>) -> impl IntoResponse {
+    match state.blocklist.replace(rules) {
+        Ok(()) => Json(state.blocklist.rules()).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+async fn list_curated_lists(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.lists.all())
+}
+
+async fn get_curated_list(State(state): State<AppState>, Path(slug): Path<String>) -> impl IntoResponse {
+    match state.lists.get(&slug) {
+        Some(list) => Json(list).into_response(),
+        None => (StatusCode::NOT_FOUND, "List not found").into_response(),
+    }
+}
+
+/// Creates the list at `slug` (201) or replaces it (200)
+async fn put_curated_list(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(input): Json<CuratedListInput>
+) -> impl IntoResponse {
+    match state.lists.put(&slug, input, state.clock.now()) {
+        Ok((list, true)) => (StatusCode::CREATED, Json(list)).into_response(),
+        Ok((list, false)) => Json(list).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+/// Creates or replaces the list at `slug` from a public TMDB list and links
+/// it, so [`crate::lists::CuratedLists::sync`] keeps it up to date
+async fn import_curated_list(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(import): Json<ListImport>
+) -> impl IntoResponse {
+    if let Err(message) = validate_slug(&slug) {
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+    let tmdb_list = match state.tmdb_client.get_list(import.tmdb_list_id).await {
+        Ok(tmdb_list) => tmdb_list,
+        Err(e) => return ApiError::from(e).into_response(),
+    };
+
+    match state.lists.import(&slug, import.tmdb_list_id, tmdb_list, state.clock.now()) {
+        Ok((list, true)) => (StatusCode::CREATED, Json(list)).into_response(),
+        Ok((list, false)) => Json(list).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+async fn delete_curated_list(State(state): State<AppState>, Path(slug): Path<String>) -> impl IntoResponse {
+    if state.lists.delete(&slug, state.clock.now()) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (StatusCode::NOT_FOUND, "List not found").into_response()
+    }
+}
+
+/// Every themed row, including those outside their live dates
+async fn list_themes(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.themes.all())
+}
+
+async fn get_theme(State(state): State<AppState>, Path(slug): Path<String>) -> impl IntoResponse {
+    match state.themes.get(&slug) {
+        Some(theme) => Json(theme).into_response(),
+        None => (StatusCode::NOT_FOUND, "Themed row not found").into_response(),
+    }
+}
+
+/// Creates the themed row at `slug` (201) or replaces it (200)
+async fn put_theme(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(input): Json<ThemeInput>
+) -> impl IntoResponse {
+    match state.themes.put(&slug, input, state.clock.now()) {
+        Ok((theme, true)) => (StatusCode::CREATED, Json(theme)).into_response(),
+        Ok((theme, false)) => Json(theme).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+async fn delete_theme(State(state): State<AppState>, Path(slug): Path<String>) -> impl IntoResponse {
+    if state.themes.delete(&slug) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (StatusCode::NOT_FOUND, "Themed row not found").into_response()
+    }
+}
@@ -0,0 +1,214 @@
+// src/analytics.rs
+use serde::{Deserialize, Serialize};
+use crate::models::MediaType;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Maximum number of search events kept in memory; oldest events are dropped first.
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// A single search performed through the API.
+#[derive(Clone, Debug)]
+pub struct SearchEvent {
+    pub query: String,
+    pub normalized_query: String,
+    pub result_count: usize,
+    pub latency: Duration,
+    pub at: SystemTime,
+}
+
+/// How often a normalized query was searched in a report window.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryCount {
+    pub query: String,
+    pub count: usize,
+}
+
+/// Aggregated search statistics for a time range.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchReport {
+    pub total_searches: usize,
+    pub zero_result_searches: usize,
+    pub average_latency_ms: u64,
+    pub top_queries: Vec<QueryCount>,
+    pub top_zero_result_queries: Vec<QueryCount>,
+}
+
+/// In-memory analytics sink for search traffic.
+///
+/// Events are held in a bounded ring buffer, so memory use is capped and
+/// reports cover at most the last `capacity` searches.
+pub struct SearchAnalytics {
+    events: Mutex<VecDeque<SearchEvent>>,
+    capacity: usize,
+}
+
+impl SearchAnalytics {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(capacity.min(DEFAULT_CAPACITY))),
+            capacity,
+        }
+    }
+
+    /// Records a completed search
+    pub fn record(&self, query: &str, result_count: usize, latency: Duration, at: SystemTime) {
+        let event = SearchEvent {
+            query: query.to_string(),
+            normalized_query: normalize_query(query),
+            result_count,
+            latency,
+            at,
+        };
+
+        let mut events = self.events.lock().unwrap();
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Builds a report over events recorded in `[from, to)`, listing up to `limit` queries per ranking
+    pub fn report(&self, from: SystemTime, to: SystemTime, limit: usize) -> SearchReport {
+        let events = self.events.lock().unwrap();
+        let window: Vec<&SearchEvent> = events
+            .iter()
+            .filter(|event| event.at >= from && event.at < to)
+            .collect();
+
+        let total_latency: Duration = window.iter().map(|event| event.latency).sum();
+        let average_latency_ms = match window.len() {
+            0 => 0,
+            n => (total_latency.as_millis() / n as u128) as u64,
+        };
+
+        SearchReport {
+            total_searches: window.len(),
+            zero_result_searches: window.iter().filter(|event| event.result_count == 0).count(),
+            average_latency_ms,
+            top_queries: top_queries(window.iter().copied(), limit),
+            top_zero_result_queries: top_queries(
+                window.iter().copied().filter(|event| event.result_count == 0),
+                limit,
+            ),
+        }
+    }
+}
+
+impl Default for SearchAnalytics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lowercases and collapses whitespace so "The  Matrix " and "the matrix" count together
+pub fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn top_queries<'a>(events: impl Iterator<Item = &'a SearchEvent>, limit: usize) -> Vec<QueryCount> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for event in events {
+        *counts.entry(event.normalized_query.as_str()).or_default() += 1;
+    }
+
+    let mut ranked: Vec<QueryCount> = counts
+        .into_iter()
+        .map(|(query, count)| QueryCount { query: query.to_string(), count })
+        .collect();
+    // Ties are broken alphabetically so reports are stable
+    ranked.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.query.cmp(&b.query)));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// How often a title was requested in a report window.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TitleCount {
+    pub media_type: MediaType,
+    pub id: i64,
+    pub count: usize,
+}
+
+/// In-memory record of which titles are requested through the API, used to
+/// rank "popular on our service" independently of TMDB trending.
+///
+/// Like `SearchAnalytics`, only the most recent `capacity` requests are kept.
+pub struct TitleAnalytics {
+    views: Mutex<VecDeque<(MediaType, i64, SystemTime)>>,
+    capacity: usize,
+}
+
+impl TitleAnalytics {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            views: Mutex::new(VecDeque::with_capacity(capacity.min(DEFAULT_CAPACITY))),
+            capacity,
+        }
+    }
+
+    /// Records a request for a title
+    pub fn record(&self, media_type: MediaType, id: i64, at: SystemTime) {
+        let mut views = self.views.lock().unwrap();
+        if views.len() == self.capacity {
+            views.pop_front();
+        }
+        views.push_back((media_type, id, at));
+    }
+
+    /// Returns up to `limit` most requested titles since `from`
+    pub fn top(&self, from: SystemTime, limit: usize) -> Vec<TitleCount> {
+        let views = self.views.lock().unwrap();
+        let mut counts: HashMap<(MediaType, i64), usize> = HashMap::new();
+        for (media_type, id, at) in views.iter() {
+            if *at >= from {
+                *counts.entry((*media_type, *id)).or_default() += 1;
+            }
+        }
+
+        let mut ranked: Vec<TitleCount> = counts
+            .into_iter()
+            .map(|((media_type, id), count)| TitleCount { media_type, id, count })
+            .collect();
+        // Ties are broken by id so reports are stable
+        ranked.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.id.cmp(&b.id)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+impl Default for TitleAnalytics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses a duration such as `30s`, `30m`, `24h` or `7d`
+pub fn parse_window(window: &str) -> Option<Duration> {
+    let (split, _) = window.char_indices().last()?;
+    let (amount, unit) = window.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+
+    amount.checked_mul(unit_secs).map(Duration::from_secs)
+}
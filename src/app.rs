@@ -0,0 +1,210 @@
+// src/app.rs
+use axum::{http::{header, HeaderName}, middleware, routing::{delete, get, post}, Router};
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tower_http::{cors::CorsLayer, services::ServeDir};
+use crate::clock::Clock;
+use crate::mode::ModeAwareTmdbClient;
+use crate::negative_cache::{NegativeCache, NegativeCacheTtls, NegativeCachingTmdbClient};
+use crate::slow_log::TimedTmdbClient;
+use crate::state::AppState;
+use crate::tmdb_client::TmdbClient;
+use crate::{admin, budget, cache_policy, clock, deadline, etag, experiments, frontend, handlers, outage, pages, problem, profiling, safe_methods, session, shaping, signing, slo, slow_log, mode, stats};
+
+/// Optional parts of the app, switched on in config
+#[derive(Clone, Debug, Default)]
+pub struct AppOptions {
+    /// Mounts the profiling routes; they add overhead and reveal symbol names
+    pub profiling: bool,
+    /// Serves the web UI from this folder under `/app`; with the
+    /// `embed-frontend` feature, the built-in UI is served when unset
+    pub frontend_dir: Option<PathBuf>,
+}
+
+impl AppOptions {
+    /// Reads `PROFILING_ENABLED` and `FRONTEND_DIR`
+    pub fn from_env() -> Self {
+        Self {
+            profiling: env::var("PROFILING_ENABLED").is_ok_and(|value| value == "true"),
+            frontend_dir: env::var("FRONTEND_DIR").ok().map(PathBuf::from),
+        }
+    }
+}
+
+/// Wraps the state's client in the production decorators: timing and
+/// upstream metrics, the service mode, and the negative cache, each wired
+/// to the matching part of `state`
+pub fn decorate_upstream(state: AppState) -> AppState {
+    let client = NegativeCachingTmdbClient::new(
+        ModeAwareTmdbClient::new(
+            TimedTmdbClient::new(state.tmdb_client.clone(), state.slow_log.clone())
+                .with_metrics(state.upstream_metrics.clone())
+                .with_clock(state.clock.clone()),
+            state.mode.clone(),
+        ),
+        state.negative_cache.clone(),
+    );
+    AppState { tmdb_client: Arc::new(client), ..state }
+}
+
+/// Builds every route and middleware layer the service ships with
+pub fn router(state: AppState, options: &AppOptions) -> Router {
+    let cors = CorsLayer::new()
+        .allow_origin(tower_http::cors::Any)
+        .allow_headers([
+            HeaderName::from_static(experiments::USER_ID_HEADER),
+            HeaderName::from_static(experiments::ANONYMOUS_ID_HEADER),
+            HeaderName::from_static(session::SESSION_HEADER),
+            HeaderName::from_static(deadline::DEADLINE_HEADER),
+            HeaderName::from_static(shaping::CLIENT_PROFILE_HEADER),
+            HeaderName::from_static(problem::REQUEST_ID_HEADER),
+            header::CONTENT_TYPE,
+        ])
+        .expose_headers([
+            HeaderName::from_static(experiments::EXPERIMENTS_HEADER),
+            HeaderName::from_static(session::SESSION_HEADER),
+            HeaderName::from_static(problem::REQUEST_ID_HEADER),
+            HeaderName::from_static(signing::SIGNATURE_HEADER),
+            HeaderName::from_static(signing::SIGNATURE_KEY_HEADER),
+        ]);
+
+    let mut app = Router::new()
+        .route("/", get(handlers::root))
+        .route("/health/upstream", get(handlers::get_upstream_health))
+        .route(stats::PUBLIC_PATH, get(handlers::get_public_stats))
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/top_rated", get(handlers::get_top_rated))
+        .route("/api/popular", get(handlers::get_popular))
+        .route("/api/home", get(handlers::get_home))
+        .route("/api/search", get(handlers::search_content))
+        .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
+        .route("/api/movie/{id}/images", get(handlers::get_movie_images))
+        .route("/api/movie/{id}/alternative-titles", get(handlers::get_movie_alternative_titles))
+        .route("/api/movie/{id}/availability", get(handlers::get_movie_availability))
+        .route("/api/tv/{id}/season/{season_number}", get(handlers::get_tv_season))
+        .route("/api/tv/{id}/where_to_start", get(handlers::get_where_to_start))
+        .route("/api/collection/{id}/where_to_start", get(handlers::get_collection_where_to_start))
+        .route("/api/discover", get(handlers::discover))
+        .route("/api/discover/movie", get(handlers::discover))
+        .route("/api/providers", get(handlers::get_providers))
+        .route("/api/rows/new_on", get(handlers::get_new_on))
+        .route("/api/rows/themes", get(handlers::get_themes))
+        .route("/api/rows/themes/{slug}", get(handlers::get_theme_titles))
+        .route("/api/network/{id}", get(handlers::get_network))
+        .route("/api/network/{id}/titles", get(handlers::get_network_titles))
+        .route("/api/company/{id}/titles", get(handlers::get_company_titles))
+        .route("/api/lists/{slug}", get(handlers::get_curated_list))
+        .route("/api/session/recent", get(handlers::get_session_recent).delete(handlers::clear_session_recent))
+        .route("/api/session/recent/picks", post(handlers::add_session_pick))
+        .route("/api/recent", get(handlers::get_recently_viewed).delete(handlers::clear_recently_viewed))
+        .route("/api/me/taste", get(handlers::get_taste))
+        .route("/api/searches", get(handlers::get_saved_searches).post(handlers::save_search))
+        .route("/api/searches/{id}", delete(handlers::delete_saved_search))
+        .route("/api/notifications", get(handlers::get_notifications).delete(handlers::clear_notifications))
+        .route("/api/push/devices", get(handlers::get_push_devices).post(handlers::register_push_device))
+        .route("/api/push/devices/{token}", delete(handlers::unregister_push_device))
+        .route("/api/sync", get(handlers::get_sync))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
+        .route("/api/keys/public", get(handlers::get_public_keys))
+        .route("/title/{media_type}/{id}", get(pages::title_page))
+        .route("/oembed", get(handlers::get_oembed))
+        .route("/sitemap.xml", get(pages::sitemap))
+        .nest("/admin", admin::router(state.clone()))
+        .nest_service("/stream", ServeDir::new("assets"));
+
+    if options.profiling {
+        app = app.nest(profiling::MOUNT_PATH, profiling::router(state.clone()));
+    }
+    match &options.frontend_dir {
+        Some(frontend_dir) => app = app.merge(frontend::router(frontend_dir)),
+        #[cfg(feature = "embed-frontend")]
+        None => app = app.merge(frontend::embedded_router()),
+        #[cfg(not(feature = "embed-frontend"))]
+        None => {}
+    }
+
+    app.layer(middleware::from_fn_with_state(state.clone(), clock::today_middleware))
+        .layer(middleware::from_fn(etag::etag_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), cache_policy::cache_control_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), deadline::deadline_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), budget::budget_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), mode::mode_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), outage::upstream_status_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), slow_log::slow_request_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), slo::slo_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), experiments::experiments_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), session::session_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), safe_methods::safe_methods_middleware))
+        .layer(middleware::from_fn(problem::problem_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), signing::signing_middleware))
+        .layer(cors)
+        .with_state(state)
+}
+
+/// What [`test_app`] swaps into the production app
+pub struct AppStateOverrides {
+    tmdb_client: Arc<dyn TmdbClient>,
+    clock: Option<Arc<dyn Clock>>,
+    admin_token: Option<String>,
+    options: AppOptions,
+    configure: Box<dyn FnOnce(AppState) -> AppState>,
+}
+
+impl AppStateOverrides {
+    /// Serves `tmdb_client`, usually a fake, in place of TMDB
+    pub fn new(tmdb_client: Arc<dyn TmdbClient>) -> Self {
+        Self {
+            tmdb_client,
+            clock: None,
+            admin_token: None,
+            options: AppOptions::default(),
+            configure: Box::new(|state| state),
+        }
+    }
+
+    /// Uses `clock` everywhere, including the negative cache
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn with_admin_token(mut self, admin_token: impl Into<String>) -> Self {
+        self.admin_token = Some(admin_token.into());
+        self
+    }
+
+    pub fn with_options(mut self, options: AppOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Applies any other `AppState::with_*` settings, e.g. a budget or
+    /// cache policies, before the upstream decorators are wired in
+    pub fn with_state(mut self, configure: impl FnOnce(AppState) -> AppState + 'static) -> Self {
+        self.configure = Box::new(configure);
+        self
+    }
+}
+
+/// Assembles the production router and middleware around the fakes in
+/// `overrides`, so tests exercise what actually ships
+pub fn test_app(overrides: AppStateOverrides) -> Router {
+    test_app_with_state(overrides).0
+}
+
+/// [`test_app`] along with the state it serves, for tests that drive
+/// background jobs such as saved search refreshes themselves
+pub fn test_app_with_state(overrides: AppStateOverrides) -> (Router, AppState) {
+    let mut state = AppState::new(overrides.tmdb_client);
+    if let Some(clock) = overrides.clock {
+        state = state
+            .with_negative_cache(Arc::new(NegativeCache::new(NegativeCacheTtls::default(), clock.clone())))
+            .with_clock(clock);
+    }
+    if let Some(admin_token) = overrides.admin_token {
+        state = state.with_admin_token(admin_token);
+    }
+    let state = decorate_upstream((overrides.configure)(state));
+    (router(state.clone(), &overrides.options), state)
+}
@@ -0,0 +1,214 @@
+// src/bench.rs
+use reqwest::{header, StatusCode};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+const DEFAULT_URL: &str = "http://localhost:8080";
+const DEFAULT_REQUESTS: usize = 200;
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// A path to request and its relative share of the traffic
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MixEntry {
+    pub weight: usize,
+    pub path: String,
+}
+
+/// Settings for `netflix-service bench`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub url: String,
+    pub requests: usize,
+    pub concurrency: usize,
+    pub mix: Vec<MixEntry>,
+}
+
+impl BenchConfig {
+    /// Parses `--url <base>`, `--requests <n>`, `--concurrency <n>` and any
+    /// number of `--mix <weight>:<path>` arguments. Without `--mix` the
+    /// benchmark hits trending, search and videos in a 3:2:1 ratio.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = Self {
+            url: DEFAULT_URL.to_string(),
+            requests: DEFAULT_REQUESTS,
+            concurrency: DEFAULT_CONCURRENCY,
+            mix: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+            match flag.as_str() {
+                "--url" => config.url = value()?.trim_end_matches('/').to_string(),
+                "--requests" => config.requests = parse_count(flag, value()?)?,
+                "--concurrency" => config.concurrency = parse_count(flag, value()?)?,
+                "--mix" => {
+                    let entry = value()?;
+                    let (weight, path) = entry
+                        .split_once(':')
+                        .filter(|(_, path)| path.starts_with('/'))
+                        .ok_or_else(|| format!("--mix must look like 3:/api/trending, got '{}'", entry))?;
+                    config.mix.push(MixEntry {
+                        weight: parse_count(flag, weight)?,
+                        path: path.to_string(),
+                    });
+                }
+                other => return Err(format!("unknown argument '{}'", other)),
+            }
+        }
+
+        if config.mix.is_empty() {
+            config.mix = vec![
+                MixEntry { weight: 3, path: "/api/trending".to_string() },
+                MixEntry { weight: 2, path: "/api/search?query=matrix".to_string() },
+                MixEntry { weight: 1, path: "/api/movie/603/videos".to_string() },
+            ];
+        }
+        Ok(config)
+    }
+
+    /// Path of the `index`-th request; the mix repeats every `total weight` requests
+    pub fn path_for(&self, index: usize) -> &str {
+        let total: usize = self.mix.iter().map(|entry| entry.weight).sum();
+        let mut slot = index % total;
+        for entry in &self.mix {
+            if slot < entry.weight {
+                return &entry.path;
+            }
+            slot -= entry.weight;
+        }
+        unreachable!("slot is always below the total weight")
+    }
+}
+
+fn parse_count(flag: &str, value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!("{} must be a positive number, got '{}'", flag, value)),
+    }
+}
+
+/// Outcome of a benchmark run.
+#[derive(Clone, Debug)]
+pub struct BenchReport {
+    pub requests: usize,
+    pub errors: usize,
+    /// Requests answered `304 Not Modified` after sending a previously seen ETag
+    pub revalidated: usize,
+    pub elapsed: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl BenchReport {
+    pub fn from_samples(mut latencies: Vec<Duration>, errors: usize, revalidated: usize, elapsed: Duration) -> Self {
+        latencies.sort_unstable();
+        Self {
+            requests: latencies.len(),
+            errors,
+            revalidated,
+            elapsed,
+            p50: percentile(&latencies, 50.0),
+            p90: percentile(&latencies, 90.0),
+            p99: percentile(&latencies, 99.0),
+            max: latencies.last().copied().unwrap_or_default(),
+        }
+    }
+
+    /// Share of requests served from the client's ETag cache
+    pub fn cache_hit_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.revalidated as f64 / self.requests as f64
+        }
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let throughput = self.requests as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON);
+        writeln!(f, "requests:   {} ({} errors) in {:.2?}", self.requests, self.errors, self.elapsed)?;
+        writeln!(f, "throughput: {:.1} req/s", throughput)?;
+        writeln!(f, "latency:    p50 {:.2?}  p90 {:.2?}  p99 {:.2?}  max {:.2?}", self.p50, self.p90, self.p99, self.max)?;
+        write!(f, "cache hits: {:.1}% (304 revalidations)", self.cache_hit_rate() * 100.0)
+    }
+}
+
+/// Nearest-rank percentile of already sorted samples
+pub fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    errors: usize,
+    revalidated: usize,
+}
+
+/// Fires the configured request mix at a running instance.
+///
+/// Workers remember the ETag of each path and send it back as
+/// `If-None-Match`, like a browser would, so the 304 share approximates the
+/// cache hit rate clients see.
+pub async fn run(config: BenchConfig) -> BenchReport {
+    let config = Arc::new(config);
+    let client = reqwest::Client::new();
+    let next = Arc::new(AtomicUsize::new(0));
+    let etags = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+    let samples = Arc::new(Mutex::new(Samples::default()));
+
+    let started = Instant::now();
+    let mut workers = JoinSet::new();
+    for _ in 0..config.concurrency {
+        let (config, client, next, etags, samples) =
+            (config.clone(), client.clone(), next.clone(), etags.clone(), samples.clone());
+
+        workers.spawn(async move {
+            loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= config.requests {
+                    break;
+                }
+                let path = config.path_for(index);
+
+                let mut request = client.get(format!("{}{}", config.url, path));
+                if let Some(etag) = etags.lock().unwrap().get(path) {
+                    request = request.header(header::IF_NONE_MATCH, etag);
+                }
+
+                let request_started = Instant::now();
+                let result = request.send().await;
+                let latency = request_started.elapsed();
+
+                let mut samples = samples.lock().unwrap();
+                samples.latencies.push(latency);
+                match result {
+                    Ok(response) if response.status() == StatusCode::NOT_MODIFIED => samples.revalidated += 1,
+                    Ok(response) if response.status().is_success() => {
+                        if let Some(etag) = response.headers().get(header::ETAG).and_then(|v| v.to_str().ok()) {
+                            etags.lock().unwrap().insert(path.to_string(), etag.to_string());
+                        }
+                    }
+                    _ => samples.errors += 1,
+                }
+            }
+        });
+    }
+    while workers.join_next().await.is_some() {}
+
+    let elapsed = started.elapsed();
+    let samples = std::mem::take(&mut *samples.lock().unwrap());
+    BenchReport::from_samples(samples.latencies, samples.errors, samples.revalidated, elapsed)
+}
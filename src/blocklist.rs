@@ -0,0 +1,127 @@
+// src/blocklist.rs
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use crate::models::{MediaType, Movie, TitleDetails, TitleRef};
+use crate::problem::ApiError;
+
+/// Titles hidden for legal reasons, as configured
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlocklistRules {
+    /// Titles hidden by id
+    #[serde(default)]
+    pub titles: Vec<TitleRef>,
+    /// Words or phrases hiding every title whose name, overview or TMDB
+    /// keywords contain them, matched case-insensitively on whole words
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl BlocklistRules {
+    fn validate(&self) -> Result<(), String> {
+        if self.keywords.iter().any(|keyword| words(keyword).is_empty()) {
+            return Err("keywords must contain at least one letter or digit".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Titles that must never be shown, applied wherever results leave the
+/// catalog services. Rules can be replaced at runtime through the admin API.
+#[derive(Default)]
+pub struct Blocklist {
+    rules: RwLock<BlocklistRules>,
+}
+
+impl Blocklist {
+    pub fn new(rules: BlocklistRules) -> Result<Self, String> {
+        rules.validate()?;
+        Ok(Self { rules: RwLock::new(rules) })
+    }
+
+    /// Parses rules such as
+    /// `{"titles": [{"media_type": "movie", "id": 603}], "keywords": ["banned phrase"]}`
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        Self::new(serde_json::from_str(json).map_err(|e| e.to_string())?)
+    }
+
+    pub fn rules(&self) -> BlocklistRules {
+        self.rules.read().unwrap().clone()
+    }
+
+    pub fn replace(&self, rules: BlocklistRules) -> Result<(), String> {
+        rules.validate()?;
+        *self.rules.write().unwrap() = rules;
+        Ok(())
+    }
+
+    /// Whether the title is blocked by id; keyword rules need the title itself
+    pub fn blocks_id(&self, media_type: MediaType, id: i64) -> bool {
+        let rules = self.rules.read().unwrap();
+        blocked_id(&rules, media_type, id)
+    }
+
+    pub fn blocks_details(&self, media_type: MediaType, details: &TitleDetails) -> bool {
+        let rules = self.rules.read().unwrap();
+        let keywords = details.keywords.iter().map(|keyword| keyword.name.as_str());
+        blocked_id(&rules, media_type, details.id)
+            || matches_keyword(&rules, [&details.title, &details.name, &details.overview], keywords)
+    }
+
+    /// Drops blocked list items. Items without a `media_type` are taken to
+    /// be of `media_type`, as in discover results.
+    pub fn retain(&self, items: &mut Vec<Movie>, media_type: MediaType) {
+        let rules = self.rules.read().unwrap();
+        if rules.titles.is_empty() && rules.keywords.is_empty() {
+            return;
+        }
+        items.retain(|item| {
+            let item_type = match item.media_type.as_deref() {
+                Some("tv") => MediaType::Tv,
+                Some("movie") => MediaType::Movie,
+                _ => media_type,
+            };
+            !blocked_id(&rules, item_type, item.id)
+                && !matches_keyword(&rules, [&item.title, &item.name, &item.overview], std::iter::empty())
+        });
+    }
+
+    /// The error served for direct requests of a blocked title
+    pub fn error() -> ApiError {
+        ApiError::new(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS, "blocked", "This title is not available")
+    }
+}
+
+fn blocked_id(rules: &BlocklistRules, media_type: MediaType, id: i64) -> bool {
+    rules.titles.iter().any(|title| title.media_type == media_type && title.id.get() == id)
+}
+
+fn matches_keyword<'a>(
+    rules: &BlocklistRules,
+    texts: [&'a Option<String>; 3],
+    keywords: impl Iterator<Item = &'a str>
+) -> bool {
+    if rules.keywords.is_empty() {
+        return false;
+    }
+    let haystack: Vec<String> = texts
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .chain(keywords)
+        .map(|text| format!(" {} ", words(text)))
+        .collect();
+    rules.keywords.iter().any(|keyword| {
+        let needle = format!(" {} ", words(keyword));
+        haystack.iter().any(|text| text.contains(&needle))
+    })
+}
+
+/// Lowercase words of `text` joined by single spaces
+fn words(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,180 @@
+// src/budget.rs
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::session::SessionId;
+use crate::state::AppState;
+
+const DEFAULT_CAPACITY: u32 = 60;
+const DEFAULT_REFILL_PER_SEC: u32 = 1;
+
+/// Maximum number of tracked clients; full buckets are dropped first when exceeded.
+const CLIENT_CAPACITY: usize = 10_000;
+
+/// Cost of routes that fan out to several TMDB calls per request.
+/// Routes not listed here are free.
+pub const DEFAULT_ROUTE_COSTS: &[(&str, u32)] = &[
+    ("/api/titles/batch", 10),
+    ("/api/tv/{id}/where_to_start", 10),
+    ("/api/home", 3),
+    ("/sitemap.xml", 5),
+    ("/oembed", 2),
+];
+
+/// Size and refill rate of each client's budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetLimits {
+    pub capacity: u32,
+    pub refill_per_sec: u32,
+}
+
+impl BudgetLimits {
+    /// Reads `REQUEST_BUDGET_CAPACITY` and `REQUEST_BUDGET_REFILL_PER_SEC`, falling back to 60 and 1
+    pub fn from_env() -> Self {
+        let number = |key: &str, default: u32| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            capacity: number("REQUEST_BUDGET_CAPACITY", DEFAULT_CAPACITY),
+            refill_per_sec: number("REQUEST_BUDGET_REFILL_PER_SEC", DEFAULT_REFILL_PER_SEC),
+        }
+    }
+}
+
+impl Default for BudgetLimits {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_CAPACITY,
+            refill_per_sec: DEFAULT_REFILL_PER_SEC,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: SystemTime,
+}
+
+/// Per-client token buckets charged by route cost.
+///
+/// Each client starts with `capacity` tokens and regains `refill_per_sec`
+/// per second; a request to a costed route is refused when the client has
+/// fewer tokens left than the route costs.
+pub struct RequestBudget {
+    limits: BudgetLimits,
+    costs: HashMap<String, u32>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RequestBudget {
+    pub fn new(limits: BudgetLimits) -> Self {
+        Self {
+            limits,
+            costs: DEFAULT_ROUTE_COSTS
+                .iter()
+                .map(|(route, cost)| (route.to_string(), *cost))
+                .collect(),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Declares the cost of `route`, a route template such as `/api/titles/batch`; `0` makes it free
+    pub fn with_route_cost(mut self, route: impl Into<String>, cost: u32) -> Self {
+        self.costs.insert(route.into(), cost);
+        self
+    }
+
+    pub fn limits(&self) -> BudgetLimits {
+        self.limits
+    }
+
+    pub fn cost(&self, route: &str) -> u32 {
+        self.costs.get(route).copied().unwrap_or(0)
+    }
+
+    /// Charges `client` for a request to `route`.
+    ///
+    /// Returns how long the client has to wait when the budget is exhausted.
+    pub fn try_spend(&self, client: &str, route: &str, now: SystemTime) -> Result<(), Duration> {
+        let cost = self.cost(route);
+        if cost == 0 {
+            return Ok(());
+        }
+        let (capacity, refill) = (self.limits.capacity as f64, self.limits.refill_per_sec as f64);
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= CLIENT_CAPACITY && !buckets.contains_key(client) {
+            buckets.retain(|_, bucket| refilled(bucket, refill, capacity, now) < capacity);
+        }
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket { tokens: capacity, updated: now });
+        bucket.tokens = refilled(bucket, refill, capacity, now);
+        bucket.updated = now;
+
+        let cost = cost as f64;
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            return Ok(());
+        }
+        if refill == 0.0 || cost > capacity {
+            // Never affordable; ask the client to back off for a minute
+            return Err(Duration::from_secs(60));
+        }
+        Err(Duration::from_secs_f64((cost - bucket.tokens) / refill))
+    }
+}
+
+impl Default for RequestBudget {
+    fn default() -> Self {
+        Self::new(BudgetLimits::default())
+    }
+}
+
+fn refilled(bucket: &Bucket, refill: f64, capacity: f64, now: SystemTime) -> f64 {
+    let elapsed = now.duration_since(bucket.updated).unwrap_or_default();
+    (bucket.tokens + elapsed.as_secs_f64() * refill).min(capacity)
+}
+
+/// Enforces the state's [`RequestBudget`] on routed requests.
+///
+/// Clients are identified by peer IP when the server was started with
+/// connect info, otherwise by their session.
+pub async fn budget_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let Some(route) = request.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string()) else {
+        return next.run(request).await;
+    };
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .or_else(|| request.extensions().get::<SessionId>().map(|SessionId(id)| id.clone()));
+    let Some(client) = client else {
+        return next.run(request).await;
+    };
+
+    match state.budget.try_spend(&client, &route, state.clock.now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, "Request budget exceeded").into_response();
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            if let Ok(value) = HeaderValue::from_str(&seconds.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+            response
+        }
+    }
+}
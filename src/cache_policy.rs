@@ -0,0 +1,133 @@
+// src/cache_policy.rs
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Duration;
+use crate::analytics::parse_window;
+use crate::negative_cache::NegativeCacheTtls;
+use crate::state::AppState;
+
+/// How long responses of each kind may be reused, configured in one place.
+///
+/// `negative` applies to cached TMDB not-found results, `providers` to cached
+/// per-title watch providers, `provider_list` to cached provider lists and
+/// `new_on` to cached "New on <provider>" rows;
+/// all but `negative` set the `Cache-Control` max-age of the matching routes. A zero TTL disables caching for that kind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachePolicies {
+    pub trending: Duration,
+    pub search: Duration,
+    pub videos: Duration,
+    pub details: Duration,
+    pub providers: Duration,
+    pub provider_list: Duration,
+    pub new_on: Duration,
+    pub negative: Duration,
+}
+
+impl CachePolicies {
+    /// Parses a list such as `trending=300s,search=60s,negative=30s`.
+    /// Kinds not listed keep their defaults.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut policies = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (kind, ttl) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected kind=ttl, got '{}'", entry))?;
+            let ttl = parse_window(ttl.trim())
+                .ok_or_else(|| format!("invalid TTL '{}', use e.g. 60s or 5m", ttl))?;
+
+            match kind.trim() {
+                "trending" => policies.trending = ttl,
+                "search" => policies.search = ttl,
+                "videos" => policies.videos = ttl,
+                "details" => policies.details = ttl,
+                "providers" => policies.providers = ttl,
+                "provider_list" => policies.provider_list = ttl,
+                "new_on" => policies.new_on = ttl,
+                "negative" => policies.negative = ttl,
+                other => return Err(format!("unknown cache policy '{}'", other)),
+            }
+        }
+        Ok(policies)
+    }
+
+    /// TTL for successful responses of `route`, a route template such as `/api/search`
+    pub fn for_route(&self, route: &str) -> Option<Duration> {
+        self.configured(route).filter(|ttl| !ttl.is_zero())
+    }
+
+    /// Whether `route` has a policy at all, even one that disables caching
+    pub fn is_cacheable(&self, route: &str) -> bool {
+        self.configured(route).is_some()
+    }
+
+    fn configured(&self, route: &str) -> Option<Duration> {
+        let ttl = match route {
+            "/api/trending" => self.trending,
+            "/api/search" => self.search,
+            "/api/movie/{id}/videos" => self.videos,
+            "/title/{media_type}/{id}"
+            | "/api/tv/{id}/season/{season_number}"
+            | "/api/tv/{id}/where_to_start"
+            | "/api/collection/{id}/where_to_start" => self.details,
+            "/api/movie/{id}/availability" => self.providers,
+            "/api/providers" => self.provider_list,
+            "/api/rows/new_on" => self.new_on,
+            _ => return None,
+        };
+        Some(ttl)
+    }
+
+    /// Negative cache TTLs for both video and details lookups
+    pub fn negative_ttls(&self) -> NegativeCacheTtls {
+        let ttl = (!self.negative.is_zero()).then_some(self.negative);
+        NegativeCacheTtls { videos: ttl, details: ttl }
+    }
+}
+
+impl Default for CachePolicies {
+    fn default() -> Self {
+        Self {
+            trending: Duration::from_secs(300),
+            search: Duration::from_secs(60),
+            videos: Duration::from_secs(24 * 60 * 60),
+            details: Duration::from_secs(60 * 60),
+            providers: Duration::from_secs(6 * 60 * 60),
+            provider_list: Duration::from_secs(24 * 60 * 60),
+            new_on: Duration::from_secs(6 * 60 * 60),
+            negative: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Sets `Cache-Control` on successful GET responses of routes with a policy,
+/// including `304 Not Modified` answers from the ETag layer.
+///
+/// Responses are marked `private` because handlers record per-session
+/// activity, which a shared cache would skip.
+pub async fn cache_control_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let ttl = request
+        .extensions()
+        .get::<MatchedPath>()
+        .filter(|_| request.method() == Method::GET)
+        .and_then(|route| state.cache_policies.for_route(route.as_str()));
+    let mut response = next.run(request).await;
+
+    let reusable = response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED;
+    if let Some(ttl) = ttl.filter(|_| reusable) {
+        let policy = format!("private, max-age={}", ttl.as_secs());
+        if let Ok(value) = HeaderValue::from_str(&policy) {
+            response.headers_mut().entry(header::CACHE_CONTROL).or_insert(value);
+        }
+    }
+
+    response
+}
@@ -0,0 +1,133 @@
+// src/changes.rs
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use crate::error::TmdbError;
+use crate::models::{utc_date, MediaType};
+use crate::state::AppState;
+
+/// How often TMDB's change feeds are polled when `CHANGES_POLL_INTERVAL` isn't set
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Feed pages read per media type and poll, 100 titles each. Busy days list
+/// more; the rest of them expire with their TTL as before.
+const MAX_PAGES: i32 = 20;
+
+/// What the change feed has done since startup
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ChangeFeedStats {
+    /// Unix timestamp of the last successful poll in seconds
+    pub last_polled: Option<u64>,
+    /// Distinct changed titles TMDB reported
+    pub changed: u64,
+    /// Changed titles that had cached data, which was dropped
+    pub invalidated: u64,
+}
+
+/// The window being read and the titles of it already handled
+#[derive(Default)]
+struct Window {
+    start: Option<NaiveDate>,
+    seen: HashSet<(MediaType, i64)>,
+}
+
+/// Polls TMDB's `/movie/changes` and `/tv/changes` feeds and drops what the
+/// caches hold for changed titles, so long TTLs don't keep serving an old
+/// runtime, poster or trailer. Dropped entries are loaded again on the next
+/// request that needs them.
+///
+/// The feed is only dated by day: each poll reads from the day of the
+/// previous one and skips titles that poll already handled.
+#[derive(Default)]
+pub struct ChangeFeed {
+    window: Mutex<Window>,
+    last_polled: AtomicU64,
+    changed: AtomicU64,
+    invalidated: AtomicU64,
+}
+
+impl ChangeFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the feeds and invalidates changed titles, returning how many had
+    /// cached data. A failed poll changes nothing and is retried from the
+    /// same day next time.
+    pub async fn poll(&self, state: &AppState) -> Result<usize, TmdbError> {
+        let now = state.clock.now();
+        let today = utc_date(now);
+        let start = self.window.lock().unwrap().start.unwrap_or(today - Days::new(1));
+
+        let mut changed = Vec::new();
+        for media_type in [MediaType::Movie, MediaType::Tv] {
+            let mut page = 1;
+            loop {
+                let response = state.tmdb_client.get_changes(media_type, start, page).await?;
+                changed.extend(response.results.iter().map(|title| (media_type, title.id)));
+                if page >= response.total_pages {
+                    break;
+                }
+                if page >= MAX_PAGES {
+                    tracing::warn!(media_type = media_type.as_str(), pages = response.total_pages, "change feed truncated");
+                    break;
+                }
+                page += 1;
+            }
+        }
+
+        let fresh: Vec<(MediaType, i64)> = {
+            let mut window = self.window.lock().unwrap();
+            if window.start != Some(start) {
+                window.seen.clear();
+            }
+            let fresh = changed.into_iter().filter(|title| window.seen.insert(*title)).collect();
+            // Titles seen today may change again today, so the next window starts clean
+            if start != today {
+                window.seen.clear();
+            }
+            window.start = Some(today);
+            fresh
+        };
+        let invalidated = fresh.iter().filter(|(media_type, id)| invalidate_title(state, *media_type, *id)).count();
+
+        let polled_at = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.last_polled.store(polled_at, Ordering::Relaxed);
+        self.changed.fetch_add(fresh.len() as u64, Ordering::Relaxed);
+        self.invalidated.fetch_add(invalidated as u64, Ordering::Relaxed);
+        if invalidated > 0 {
+            tracing::info!(changed = fresh.len(), invalidated, "invalidated titles changed on TMDB");
+        }
+        Ok(invalidated)
+    }
+
+    pub fn stats(&self) -> ChangeFeedStats {
+        let last_polled = self.last_polled.load(Ordering::Relaxed);
+        ChangeFeedStats {
+            last_polled: (last_polled > 0).then_some(last_polled),
+            changed: self.changed.load(Ordering::Relaxed),
+            invalidated: self.invalidated.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Drops everything cached about a title: enrichments such as details,
+/// credits and providers, trailer flags, logos, availability, curated list
+/// resolutions containing it and remembered misses. Returns whether
+/// anything was cached.
+pub fn invalidate_title(state: &AppState, media_type: MediaType, id: i64) -> bool {
+    let trailer = media_type == MediaType::Movie && state.trailers.invalidate(id);
+    // Every cache is cleared, so no short-circuiting
+    [
+        state.enrichments.invalidate(media_type, id),
+        state.logos.invalidate(media_type, id),
+        state.availability.invalidate(media_type, id),
+        state.lists.invalidate_title(media_type, id),
+        state.negative_cache.forget_title(media_type, id),
+        trailer,
+    ]
+    .contains(&true)
+}
@@ -0,0 +1,54 @@
+// src/clock.rs
+use axum::{extract::{Request, State}, middleware::Next, response::Response};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::{models, state::AppState};
+
+/// Source of the current time for analytics windows, session expiry and
+/// anything else time-dependent, so tests can control it.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// The real wall clock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<SystemTime>,
+}
+
+impl MockClock {
+    pub fn new(now: SystemTime) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: SystemTime) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Serializes the response's derived `age` fields against `state.clock`, so
+/// they follow the same clock as everything else
+pub async fn today_middleware(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let today = models::utc_date(state.clock.now());
+    models::with_today(today, next.run(request)).await
+}
@@ -0,0 +1,138 @@
+// src/compression.rs
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Which content codings are asked of TMDB, switched at runtime through
+/// `PUT /admin/compression`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionMode {
+    /// Brotli or gzip, whichever TMDB or the mirror prefers
+    #[default]
+    Auto,
+    /// Gzip only, for mirrors whose brotli support is broken
+    Gzip,
+    /// Uncompressed bodies, e.g. to rule compression out while debugging
+    Off,
+}
+
+impl CompressionMode {
+    /// The `Accept-Encoding` sent with TMDB requests
+    pub fn accept_encoding(self) -> &'static str {
+        match self {
+            CompressionMode::Auto => "br, gzip",
+            CompressionMode::Gzip => "gzip",
+            CompressionMode::Off => "identity",
+        }
+    }
+}
+
+impl FromStr for CompressionMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode.trim() {
+            "auto" => Ok(CompressionMode::Auto),
+            "gzip" => Ok(CompressionMode::Gzip),
+            "off" => Ok(CompressionMode::Off),
+            other => Err(format!("unknown compression mode '{}', expected auto, gzip or off", other)),
+        }
+    }
+}
+
+/// Body of `PUT /admin/compression`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompressionChange {
+    pub mode: CompressionMode,
+}
+
+/// Current mode and byte counts since startup, shown in `/admin/compression`
+/// and `/admin/metrics/compression`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompressionStatus {
+    pub mode: CompressionMode,
+    pub responses: u64,
+    /// Responses TMDB sent with a gzip or brotli coding
+    pub compressed_responses: u64,
+    /// Body bytes as received, which is what egress is billed on
+    pub compressed_bytes: u64,
+    /// Body bytes after decoding
+    pub decompressed_bytes: u64,
+    /// `decompressed_bytes - compressed_bytes`
+    pub saved_bytes: u64,
+}
+
+/// Compression of TMDB responses, shared by the real client and the admin API.
+///
+/// The HTTP client's own decompression is left off so the size of bodies on
+/// the wire can be counted before they are decoded here.
+#[derive(Default)]
+pub struct UpstreamCompression {
+    mode: Mutex<CompressionMode>,
+    responses: AtomicU64,
+    compressed_responses: AtomicU64,
+    compressed_bytes: AtomicU64,
+    decompressed_bytes: AtomicU64,
+}
+
+impl UpstreamCompression {
+    pub fn new(mode: CompressionMode) -> Self {
+        Self { mode: Mutex::new(mode), ..Self::default() }
+    }
+
+    pub fn mode(&self) -> CompressionMode {
+        *self.mode.lock().unwrap()
+    }
+
+    /// Switches to `mode` from the next request on, writing an audit log entry
+    pub fn set(&self, mode: CompressionMode) -> CompressionStatus {
+        let previous = std::mem::replace(&mut *self.mode.lock().unwrap(), mode);
+        tracing::warn!(target: "audit", ?previous, ?mode, "TMDB compression changed");
+        self.status()
+    }
+
+    pub fn status(&self) -> CompressionStatus {
+        let compressed_bytes = self.compressed_bytes.load(Ordering::Relaxed);
+        let decompressed_bytes = self.decompressed_bytes.load(Ordering::Relaxed);
+        CompressionStatus {
+            mode: self.mode(),
+            responses: self.responses.load(Ordering::Relaxed),
+            compressed_responses: self.compressed_responses.load(Ordering::Relaxed),
+            compressed_bytes,
+            decompressed_bytes,
+            saved_bytes: decompressed_bytes.saturating_sub(compressed_bytes),
+        }
+    }
+
+    /// Decodes a body sent with `Content-Encoding: content_encoding` and
+    /// counts its size before and after. Codings that weren't asked for are
+    /// still decoded, since a mirror may ignore `Accept-Encoding`.
+    pub fn decode(&self, content_encoding: Option<&str>, body: &[u8]) -> Result<Vec<u8>, String> {
+        let coding = content_encoding.map(|coding| coding.trim().to_ascii_lowercase());
+        let (decoded, compressed) = match coding.as_deref() {
+            None | Some("") | Some("identity") => (body.to_vec(), false),
+            Some("gzip") | Some("x-gzip") => (read_all(flate2::read::MultiGzDecoder::new(body), "gzip")?, true),
+            Some("br") => (read_all(brotli_decompressor::Decompressor::new(body, 4096), "brotli")?, true),
+            Some(other) => return Err(format!("unsupported content encoding '{}'", other)),
+        };
+
+        self.responses.fetch_add(1, Ordering::Relaxed);
+        if compressed {
+            self.compressed_responses.fetch_add(1, Ordering::Relaxed);
+        }
+        self.compressed_bytes.fetch_add(body.len() as u64, Ordering::Relaxed);
+        self.decompressed_bytes.fetch_add(decoded.len() as u64, Ordering::Relaxed);
+        Ok(decoded)
+    }
+}
+
+fn read_all(mut decoder: impl Read, coding: &str) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    decoder
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("invalid {} body: {}", coding, e))?;
+    Ok(decoded)
+}
@@ -0,0 +1,50 @@
+// src/deadline.rs
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::{Duration, UNIX_EPOCH};
+use crate::state::AppState;
+
+/// Header carrying the client's deadline as a Unix timestamp in milliseconds
+pub const DEADLINE_HEADER: &str = "x-request-deadline";
+
+/// Abandons requests whose `X-Request-Deadline` has passed.
+///
+/// The handler future is dropped once the deadline is reached, which cancels
+/// any TMDB calls still in flight, and the client gets a 504. Requests whose
+/// deadline has already passed are not started at all. A client that
+/// disconnects has its handler dropped the same way by the server.
+pub async fn deadline_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let Some(value) = request.headers().get(DEADLINE_HEADER) else {
+        return next.run(request).await;
+    };
+    let Some(deadline) = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis))
+    else {
+        return (StatusCode::BAD_REQUEST, "Invalid X-Request-Deadline, expected Unix time in milliseconds").into_response();
+    };
+
+    let remaining = deadline.duration_since(state.clock.now()).unwrap_or_default();
+    if remaining.is_zero() {
+        return deadline_exceeded();
+    }
+
+    match tokio::time::timeout(remaining, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => deadline_exceeded(),
+    }
+}
+
+fn deadline_exceeded() -> Response {
+    (StatusCode::GATEWAY_TIMEOUT, "Request deadline exceeded").into_response()
+}
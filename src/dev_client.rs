@@ -0,0 +1,257 @@
+// src/dev_client.rs
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::error::TmdbError;
+use crate::models::{AlternativeTitles, ChangesResponse, Collection, DiscoverFilter, DiscoverSort, Image, ImagesResponse, MediaType, Movie, Network, ProviderList, RegionProviders, Season, TitleDetails, TmdbList, TmdbResponse, Video, VideoResponse, WatchProvider, WatchProvidersResponse};
+use crate::tmdb_client::TmdbClient;
+
+/// Fixture catalog bundled into the binary, so `dev-seed` needs no files or API key
+const CATALOG: &str = include_str!("../fixtures/dev_catalog.json");
+
+/// Results per page, as on TMDB
+const PAGE_SIZE: usize = 20;
+
+/// The only region the fixture providers stream in
+const CATALOG_REGION: &str = "US";
+
+#[derive(Deserialize)]
+struct Catalog {
+    /// Most popular first, which is also the trending order
+    titles: Vec<CatalogTitle>,
+    providers: Vec<WatchProvider>,
+    networks: Vec<Network>,
+    lists: Vec<CatalogList>,
+}
+
+#[derive(Deserialize)]
+struct CatalogTitle {
+    media_type: MediaType,
+    #[serde(flatten)]
+    details: TitleDetails,
+    #[serde(default)]
+    videos: Vec<Video>,
+    /// Providers streaming the title in [`CATALOG_REGION`]
+    #[serde(default)]
+    providers: Vec<i64>,
+    #[serde(default)]
+    networks: Vec<i64>,
+    #[serde(default)]
+    companies: Vec<i64>,
+    /// Only some shows have fixture seasons
+    #[serde(default)]
+    seasons: Vec<Season>,
+}
+
+impl CatalogTitle {
+    fn movie(&self) -> Movie {
+        Movie::from_details(self.media_type, self.details.clone())
+    }
+
+    fn matches(&self, filter: &DiscoverFilter) -> bool {
+        let any_of = |wanted: &[i64], ids: &[i64]| wanted.is_empty() || wanted.iter().any(|id| ids.contains(id));
+        let all_of = |wanted: &[i64], ids: &[i64]| wanted.iter().all(|id| ids.contains(id));
+        let genres: Vec<i64> = self.details.genres.iter().map(|genre| genre.id).collect();
+        let keywords: Vec<i64> = self.details.keywords.iter().map(|keyword| keyword.id).collect();
+        let providers = match filter.region.as_deref() {
+            Some(region) if !region.eq_ignore_ascii_case(CATALOG_REGION) => &[][..],
+            _ => &self.providers,
+        };
+
+        self.media_type == filter.media_type
+            && any_of(&filter.providers, providers)
+            && any_of(&filter.networks, &self.networks)
+            && any_of(&filter.companies, &self.companies)
+            && if filter.all_genres { all_of(&filter.genres, &genres) } else { any_of(&filter.genres, &genres) }
+            && any_of(&filter.keywords, &keywords)
+            && filter.released_from.is_none_or(|released_from| self.release_date().is_some_and(|date| date >= released_from))
+            && filter.released_by.is_none_or(|released_by| self.release_date().is_some_and(|date| date <= released_by))
+            && filter.release_year.is_none_or(|year| self.release_date().is_some_and(|date| date.year() == year))
+            && filter.min_vote_tenths.is_none_or(|tenths| {
+                self.details.vote_average.is_some_and(|vote| vote >= f64::from(tenths) / 10.0)
+            })
+    }
+
+    fn release_date(&self) -> Option<NaiveDate> {
+        self.details.release_date.or(self.details.first_air_date)
+    }
+
+    fn vote(&self) -> f64 {
+        self.details.vote_average.unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+struct CatalogList {
+    id: i64,
+    #[serde(flatten)]
+    list: TmdbList,
+}
+
+/// Serves a bundled catalog of popular movies and shows instead of calling
+/// TMDB, so the service runs offline for frontend development.
+///
+/// Titles outside the catalog are not found. Poster paths and video keys are
+/// placeholders and do not resolve on TMDB or YouTube.
+pub struct DevTmdbClient {
+    catalog: Catalog,
+}
+
+impl DevTmdbClient {
+    pub fn new() -> Self {
+        Self { catalog: serde_json::from_str(CATALOG).expect("bundled dev catalog must be valid") }
+    }
+
+    /// Number of titles in the catalog
+    pub fn len(&self) -> usize {
+        self.catalog.titles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.catalog.titles.is_empty()
+    }
+
+    fn title(&self, media_type: MediaType, id: i64) -> Result<&CatalogTitle, TmdbError> {
+        self.catalog
+            .titles
+            .iter()
+            .find(|title| title.media_type == media_type && title.details.id == id)
+            .ok_or(TmdbError::NotFound)
+    }
+
+    fn page<'a>(titles: impl Iterator<Item = &'a CatalogTitle>, page: i32) -> TmdbResponse {
+        let titles: Vec<&CatalogTitle> = titles.collect();
+        let start = usize::try_from(page.max(1) - 1).unwrap_or_default() * PAGE_SIZE;
+        TmdbResponse {
+            page,
+            results: titles.iter().skip(start).take(PAGE_SIZE).map(|title| title.movie()).collect(),
+            total_pages: titles.len().div_ceil(PAGE_SIZE).max(1) as i32,
+        }
+    }
+}
+
+impl Default for DevTmdbClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn image(file_path: &str, language: Option<&str>, width: u32, height: u32) -> Image {
+    Image {
+        file_path: file_path.to_string(),
+        iso_639_1: language.map(str::to_string),
+        width,
+        height,
+        vote_average: 5.0,
+        vote_count: 1,
+    }
+}
+
+#[async_trait]
+impl TmdbClient for DevTmdbClient {
+    async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
+        Ok(Self::page(self.catalog.titles.iter(), page))
+    }
+
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let mut titles: Vec<&CatalogTitle> = self.catalog.titles.iter().filter(|title| title.media_type == media_type).collect();
+        titles.sort_by(|a, b| b.vote().total_cmp(&a.vote()));
+        Ok(Self::page(titles.into_iter(), page))
+    }
+
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        Ok(Self::page(self.catalog.titles.iter().filter(|title| title.media_type == media_type), page))
+    }
+
+    async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let query = query.to_lowercase();
+        let matches = self.catalog.titles.iter().filter(|title| {
+            let details = &title.details;
+            [&details.title, &details.name, &details.original_title, &details.original_name]
+                .into_iter()
+                .flatten()
+                .any(|name| name.to_lowercase().contains(&query))
+        });
+        Ok(Self::page(matches, page))
+    }
+
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        let title = self.title(MediaType::Movie, movie_id)?;
+        Ok(VideoResponse { id: movie_id, results: title.videos.clone() })
+    }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        let title = self.title(media_type, id)?;
+        let mut details = title.details.clone();
+        // The fixture seasons stand in for the listing TMDB puts in show details
+        details.seasons = title.seasons.iter().map(Season::summary).collect();
+        Ok(details)
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        let details = &self.title(media_type, id)?.details;
+        Ok(ImagesResponse {
+            id,
+            posters: details.poster_path.iter().map(|path| image(path, Some("en"), 500, 750)).collect(),
+            backdrops: details.backdrop_path.iter().map(|path| image(path, None, 1280, 720)).collect(),
+            logos: Vec::new(),
+        })
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        let details = &self.title(media_type, id)?.details;
+        Ok(AlternativeTitles { id, titles: details.alternative_titles.clone() })
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        let title = self.title(media_type, id)?;
+        let flatrate = self
+            .catalog
+            .providers
+            .iter()
+            .filter(|provider| title.providers.contains(&provider.provider_id))
+            .cloned()
+            .collect();
+        let region = RegionProviders { flatrate, ..RegionProviders::default() };
+        Ok(WatchProvidersResponse { id, results: HashMap::from([(CATALOG_REGION.to_string(), region)]) })
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let mut titles: Vec<&CatalogTitle> = self.catalog.titles.iter().filter(|title| title.matches(filter)).collect();
+        match filter.sort {
+            DiscoverSort::Popularity => {}
+            DiscoverSort::Newest => titles.sort_by_key(|title| std::cmp::Reverse(title.release_date())),
+            DiscoverSort::TopRated => titles.sort_by(|a, b| b.vote().total_cmp(&a.vote())),
+        }
+        Ok(Self::page(titles.into_iter(), page))
+    }
+
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        let results = if region.eq_ignore_ascii_case(CATALOG_REGION) { self.catalog.providers.clone() } else { Vec::new() };
+        Ok(ProviderList { results })
+    }
+
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        self.catalog.networks.iter().find(|network| network.id == id).cloned().ok_or(TmdbError::NotFound)
+    }
+
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        self.catalog.lists.iter().find(|list| list.id == list_id).map(|list| list.list.clone()).ok_or(TmdbError::NotFound)
+    }
+
+    /// The fixtures have no collections
+    async fn get_collection(&self, _id: i64) -> Result<Collection, TmdbError> {
+        Err(TmdbError::NotFound)
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        let show = self.title(MediaType::Tv, tv_id)?;
+        show.seasons.iter().find(|season| season.season_number == season_number).cloned().ok_or(TmdbError::NotFound)
+    }
+
+    /// Fixtures never change
+    async fn get_changes(&self, _media_type: MediaType, _start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        Ok(ChangesResponse { results: Vec::new(), page, total_pages: 0 })
+    }
+}
@@ -0,0 +1,545 @@
+// src/enrich.rs
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::cache_policy::CachePolicies;
+use crate::error::TmdbError;
+use crate::models::{parse_region, BatchTitleResult, CastMember, MediaType, Movie, ProviderSummary, TitleDetails};
+use crate::providers::{top_streaming, DEFAULT_BADGE_REGION};
+use crate::state::AppState;
+use crate::trailers::look_up;
+
+/// Titles an enricher looks up at once, to stay well inside TMDB's rate limit
+const ENRICH_CONCURRENCY: usize = 4;
+
+/// Cast members returned per title for `include=credits`
+pub const CAST_MEMBERS: usize = 5;
+
+/// Maximum number of cached enrichments; expired entries are dropped first.
+const CAPACITY: usize = 50_000;
+
+/// Something a response can be enriched with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Include {
+    /// Full title details, loaded for the includes built on them and never returned by themselves
+    Details,
+    Videos,
+    Providers,
+    Credits,
+}
+
+impl Include {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Include::Details => "details",
+            Include::Videos => "videos",
+            Include::Providers => "providers",
+            Include::Credits => "credits",
+        }
+    }
+
+    /// An include a client may ask for by name
+    pub fn parse(name: &str) -> Result<Include, String> {
+        match name.trim() {
+            "videos" => Ok(Include::Videos),
+            "providers" => Ok(Include::Providers),
+            "credits" => Ok(Include::Credits),
+            other => Err(format!("unknown include '{}'", other)),
+        }
+    }
+}
+
+/// A title being enriched
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TitleRef {
+    pub media_type: MediaType,
+    pub id: i64,
+}
+
+/// What the includes found about one title; each include fills its own fields
+#[derive(Clone, Debug, Default)]
+pub struct Enriched {
+    pub details: Option<TitleDetails>,
+    /// From `videos`: whether TMDB lists a trailer
+    pub has_trailer: Option<bool>,
+    /// From `providers`: the most prominent services streaming the title
+    pub providers: Option<Vec<ProviderSummary>>,
+    /// From `credits`: the top-billed cast
+    pub cast: Option<Vec<CastMember>>,
+}
+
+impl Enriched {
+    fn is_empty(&self) -> bool {
+        self.details.is_none() && self.has_trailer.is_none() && self.providers.is_none() && self.cast.is_none()
+    }
+
+    /// Takes every field `other` found
+    fn merge(&mut self, other: Enriched) {
+        if other.details.is_some() {
+            self.details = other.details;
+        }
+        if other.has_trailer.is_some() {
+            self.has_trailer = other.has_trailer;
+        }
+        if other.providers.is_some() {
+            self.providers = other.providers;
+        }
+        if other.cast.is_some() {
+            self.cast = other.cast;
+        }
+    }
+}
+
+/// What enrichers may look at besides the titles
+pub struct EnrichContext {
+    pub state: AppState,
+    /// Region of the `providers` include
+    pub region: String,
+    pub now: SystemTime,
+}
+
+/// Adds one include to titles.
+///
+/// Results are cached per title for the enricher's TTL; empty results, e.g.
+/// after a failed lookup, are not, so the next request retries them.
+#[async_trait]
+pub trait Enricher: Send + Sync {
+    fn include(&self) -> Include;
+
+    /// Includes whose results this one reads from `known`
+    fn depends_on(&self) -> &'static [Include] {
+        &[]
+    }
+
+    /// How long results are reused; zero caches nothing
+    fn ttl(&self, policies: &CachePolicies) -> Duration;
+
+    /// What results depend on besides the title, e.g. the region
+    fn variant(&self, _context: &EnrichContext) -> String {
+        String::new()
+    }
+
+    /// This include's findings for each of `titles`, in order; `known` holds
+    /// what the includes it depends on found
+    async fn enrich(&self, context: &EnrichContext, titles: &[TitleRef], known: &[Enriched]) -> Vec<Enriched>;
+}
+
+/// Loads full details, skipping titles whose details the endpoint already has
+pub struct DetailsEnricher;
+
+#[async_trait]
+impl Enricher for DetailsEnricher {
+    fn include(&self) -> Include {
+        Include::Details
+    }
+
+    fn ttl(&self, policies: &CachePolicies) -> Duration {
+        policies.details
+    }
+
+    async fn enrich(&self, context: &EnrichContext, titles: &[TitleRef], known: &[Enriched]) -> Vec<Enriched> {
+        let missing: Vec<Option<TitleRef>> = titles
+            .iter()
+            .zip(known)
+            .map(|(title, known)| known.details.is_none().then_some(*title))
+            .collect();
+        let state = context.state.clone();
+        for_each_title(missing, move |title| {
+            let state = state.clone();
+            async move {
+                let details = state.tmdb_client.get_title_details(title.media_type, title.id).await.ok();
+                Enriched { details, ..Enriched::default() }
+            }
+        })
+        .await
+    }
+}
+
+/// Whether movies have a trailer, from the [`crate::trailers::TrailerIndex`].
+///
+/// Movies missing from the index are looked up in the background rather than
+/// while the caller waits, so they only get `has_trailer` on a later request.
+/// TV shows get none, as only movie videos are looked up.
+pub struct VideosEnricher;
+
+#[async_trait]
+impl Enricher for VideosEnricher {
+    fn include(&self) -> Include {
+        Include::Videos
+    }
+
+    fn ttl(&self, policies: &CachePolicies) -> Duration {
+        policies.videos
+    }
+
+    async fn enrich(&self, context: &EnrichContext, titles: &[TitleRef], _known: &[Enriched]) -> Vec<Enriched> {
+        let state = &context.state;
+        let ttl = state.cache_policies.videos;
+        let mut missing = Vec::new();
+        let results = titles
+            .iter()
+            .map(|title| {
+                if title.media_type != MediaType::Movie {
+                    return Enriched::default();
+                }
+                let has_trailer = state.trailers.get(title.id, context.now, ttl);
+                if has_trailer.is_none() {
+                    missing.push(title.id);
+                }
+                Enriched { has_trailer, ..Enriched::default() }
+            })
+            .collect();
+
+        let claimed = state.trailers.claim(missing);
+        if !claimed.is_empty() {
+            tokio::spawn(look_up(state.clone(), claimed));
+        }
+        results
+    }
+}
+
+/// The services streaming each title in the requested region, from the
+/// [`crate::providers::AvailabilityCache`]
+pub struct ProvidersEnricher;
+
+#[async_trait]
+impl Enricher for ProvidersEnricher {
+    fn include(&self) -> Include {
+        Include::Providers
+    }
+
+    fn ttl(&self, policies: &CachePolicies) -> Duration {
+        policies.providers
+    }
+
+    fn variant(&self, context: &EnrichContext) -> String {
+        context.region.clone()
+    }
+
+    async fn enrich(&self, context: &EnrichContext, titles: &[TitleRef], _known: &[Enriched]) -> Vec<Enriched> {
+        let (state, now) = (context.state.clone(), context.now);
+        let regions = Arc::new(vec![context.region.clone()]);
+        for_each_title(titles.iter().copied().map(Some).collect(), move |title| {
+            let (state, regions) = (state.clone(), regions.clone());
+            async move {
+                let client = state.tmdb_client.as_ref();
+                let ttl = state.cache_policies.providers;
+                let providers = match state.availability.lookup(client, title.media_type, title.id, &regions, now, ttl).await {
+                    Ok(mut offers) => offers.pop().map(|(_, offers)| top_streaming(&offers)),
+                    Err(TmdbError::NotFound) => Some(Vec::new()),
+                    Err(_) => None,
+                };
+                Enriched { providers, ..Enriched::default() }
+            }
+        })
+        .await
+    }
+}
+
+/// The top-billed cast, taken from the details
+pub struct CreditsEnricher;
+
+#[async_trait]
+impl Enricher for CreditsEnricher {
+    fn include(&self) -> Include {
+        Include::Credits
+    }
+
+    fn depends_on(&self) -> &'static [Include] {
+        &[Include::Details]
+    }
+
+    fn ttl(&self, policies: &CachePolicies) -> Duration {
+        policies.details
+    }
+
+    async fn enrich(&self, _context: &EnrichContext, _titles: &[TitleRef], known: &[Enriched]) -> Vec<Enriched> {
+        known
+            .iter()
+            .map(|known| {
+                let cast = known.details.as_ref().map(|details| {
+                    let mut cast = details.cast.clone();
+                    cast.sort_by_key(|member| member.order);
+                    cast.truncate(CAST_MEMBERS);
+                    cast
+                });
+                Enriched { cast, ..Enriched::default() }
+            })
+            .collect()
+    }
+}
+
+/// The built-in enricher for `include`
+pub fn enricher(include: Include) -> Arc<dyn Enricher> {
+    match include {
+        Include::Details => Arc::new(DetailsEnricher),
+        Include::Videos => Arc::new(VideosEnricher),
+        Include::Providers => Arc::new(ProvidersEnricher),
+        Include::Credits => Arc::new(CreditsEnricher),
+    }
+}
+
+/// Runs `lookup` for every title that is `Some`, a few at a time, keeping
+/// the order; `None` entries get an empty result
+async fn for_each_title<F, Fut>(titles: Vec<Option<TitleRef>>, lookup: F) -> Vec<Enriched>
+where
+    F: Fn(TitleRef) -> Fut,
+    Fut: Future<Output = Enriched> + Send + 'static,
+{
+    let permits = Arc::new(Semaphore::new(ENRICH_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (index, title) in titles.iter().enumerate() {
+        let Some(title) = *title else {
+            continue;
+        };
+        let (permits, lookup) = (permits.clone(), lookup(title));
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            (index, lookup.await)
+        });
+    }
+
+    let mut results = vec![Enriched::default(); titles.len()];
+    while let Some(Ok((index, result))) = tasks.join_next().await {
+        results[index] = result;
+    }
+    results
+}
+
+/// `requested` and everything it depends on, in stages that only depend on
+/// earlier ones, so each stage can run concurrently
+pub fn stages(requested: &[Include]) -> Vec<Vec<Include>> {
+    let mut pending = BTreeSet::new();
+    let mut queue = requested.to_vec();
+    while let Some(include) = queue.pop() {
+        if pending.insert(include) {
+            queue.extend_from_slice(enricher(include).depends_on());
+        }
+    }
+
+    let mut stages: Vec<Vec<Include>> = Vec::new();
+    while !pending.is_empty() {
+        let ready: Vec<Include> = pending
+            .iter()
+            .copied()
+            .filter(|include| enricher(*include).depends_on().iter().all(|dependency| !pending.contains(dependency)))
+            .collect();
+        // Built-in dependencies never form a cycle, but don't spin if one is added
+        if ready.is_empty() {
+            break;
+        }
+        for include in &ready {
+            pending.remove(include);
+        }
+        stages.push(ready);
+    }
+    stages
+}
+
+type EnrichmentKey = (Include, String, MediaType, i64);
+
+/// Enricher results per include, variant and title.
+#[derive(Default)]
+pub struct EnrichmentCache {
+    entries: Mutex<HashMap<EnrichmentKey, (Enriched, SystemTime)>>,
+}
+
+impl EnrichmentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Titles with a fresh entry at `now`
+    pub fn titles(&self, now: SystemTime) -> HashSet<(MediaType, i64)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|(_, (_, expires))| *expires > now)
+            .map(|((_, _, media_type, id), _)| (*media_type, *id))
+            .collect()
+    }
+
+    pub fn get(&self, include: Include, variant: &str, title: TitleRef, now: SystemTime) -> Option<Enriched> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&(include, variant.to_string(), title.media_type, title.id))
+            .filter(|(_, expires)| *expires > now)
+            .map(|(enriched, _)| enriched.clone())
+    }
+
+    /// Caches `enriched` for `ttl`; a zero TTL caches nothing
+    pub fn store(&self, include: Include, variant: &str, title: TitleRef, enriched: Enriched, now: SystemTime, ttl: Duration) {
+        if ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= CAPACITY {
+            entries.retain(|_, (_, expires)| *expires > now);
+            if entries.len() >= CAPACITY {
+                return;
+            }
+        }
+        entries.insert((include, variant.to_string(), title.media_type, title.id), (enriched, now + ttl));
+    }
+
+    /// Drops every entry of a title, returning whether there was one
+    pub fn invalidate(&self, media_type: MediaType, id: i64) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|(_, _, entry_type, entry_id), _| (*entry_type, *entry_id) != (media_type, id));
+        entries.len() < before
+    }
+}
+
+/// Runs one include over `titles`, answering what it can from the cache
+async fn run(context: Arc<EnrichContext>, include: Include, titles: Arc<Vec<TitleRef>>, known: Arc<Vec<Enriched>>) -> Vec<Enriched> {
+    let enricher = enricher(include);
+    let cache = &context.state.enrichments;
+    let (variant, ttl) = (enricher.variant(&context), enricher.ttl(&context.state.cache_policies));
+
+    let mut results: Vec<Option<Enriched>> = titles.iter().map(|title| cache.get(include, &variant, *title, context.now)).collect();
+    let misses: Vec<usize> = (0..titles.len()).filter(|index| results[*index].is_none()).collect();
+    if !misses.is_empty() {
+        let missing_titles: Vec<TitleRef> = misses.iter().map(|index| titles[*index]).collect();
+        let missing_known: Vec<Enriched> = misses.iter().map(|index| known[*index].clone()).collect();
+        let found = enricher.enrich(&context, &missing_titles, &missing_known).await;
+        for ((index, title), enriched) in misses.iter().zip(missing_titles).zip(found) {
+            if !enriched.is_empty() {
+                cache.store(include, &variant, title, enriched.clone(), context.now, ttl);
+            }
+            results[*index] = Some(enriched);
+        }
+    }
+    results.into_iter().map(Option::unwrap_or_default).collect()
+}
+
+/// Runs `includes` and their dependencies over `titles`, stage by stage with
+/// the includes of a stage running concurrently. `known` seeds what the
+/// endpoint already loaded, such as details.
+pub async fn enrich(context: Arc<EnrichContext>, includes: &[Include], titles: Vec<TitleRef>, known: Vec<Enriched>) -> Vec<Enriched> {
+    let titles = Arc::new(titles);
+    let mut known = known;
+    for stage in stages(includes) {
+        let snapshot = Arc::new(known.clone());
+        let mut tasks = JoinSet::new();
+        for include in stage {
+            tasks.spawn(run(context.clone(), include, titles.clone(), snapshot.clone()));
+        }
+        while let Some(Ok(found)) = tasks.join_next().await {
+            for (known, found) in known.iter_mut().zip(found) {
+                known.merge(found);
+            }
+        }
+    }
+    known
+}
+
+#[derive(Deserialize)]
+struct IncludeQuery {
+    include: Option<String>,
+    region: Option<String>,
+}
+
+/// Enrichments asked for with `?include=videos,providers,credits`, plus
+/// `?region=GB` for providers, default [`DEFAULT_BADGE_REGION`].
+///
+/// Enrichments never fail the response: titles an include couldn't look up
+/// come back without its field.
+pub struct Includes {
+    requested: Vec<Include>,
+    context: Arc<EnrichContext>,
+}
+
+impl Includes {
+    pub fn is_empty(&self) -> bool {
+        self.requested.is_empty()
+    }
+
+    /// Enriches list items; items without a media type are taken to be `media_type`
+    pub async fn apply(&self, items: &mut [Movie], media_type: MediaType) {
+        if self.is_empty() {
+            return;
+        }
+        let mut positions = Vec::new();
+        let mut titles = Vec::new();
+        for (position, item) in items.iter().enumerate() {
+            let media_type = match item.media_type.as_deref() {
+                None => media_type,
+                Some("movie") => MediaType::Movie,
+                Some("tv") => MediaType::Tv,
+                // People and anything else TMDB mixes into results have nothing to add
+                Some(_) => continue,
+            };
+            positions.push(position);
+            titles.push(TitleRef { media_type, id: item.id });
+        }
+
+        let known = vec![Enriched::default(); titles.len()];
+        let found = enrich(self.context.clone(), &self.requested, titles, known).await;
+        for (position, enriched) in positions.into_iter().zip(found) {
+            let item = &mut items[position];
+            item.has_trailer = enriched.has_trailer;
+            item.providers = enriched.providers;
+            item.cast = enriched.cast;
+        }
+    }
+
+    /// Enriches batch lookups, reusing the details they loaded; titles that
+    /// failed to load only carry their error
+    pub async fn apply_to_batch(&self, results: &mut [BatchTitleResult]) {
+        if self.is_empty() {
+            return;
+        }
+        let mut loaded: Vec<&mut BatchTitleResult> = results.iter_mut().filter(|result| result.error.is_none()).collect();
+        let titles = loaded.iter().map(|result| TitleRef { media_type: result.media_type, id: result.id }).collect();
+        let known = loaded
+            .iter()
+            .map(|result| Enriched { details: result.details.clone(), ..Enriched::default() })
+            .collect();
+        let found = enrich(self.context.clone(), &self.requested, titles, known).await;
+        for (result, enriched) in loaded.iter_mut().zip(found) {
+            result.has_trailer = enriched.has_trailer;
+            result.providers = enriched.providers;
+            result.cast = enriched.cast;
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for Includes {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<IncludeQuery>::try_from_uri(&parts.uri).map_err(IntoResponse::into_response)?;
+        let requested = query
+            .include
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(Include::parse)
+            .collect::<Result<BTreeSet<_>, _>>()
+            .map_err(|message| (StatusCode::BAD_REQUEST, message).into_response())?;
+        let region = match query.region.as_deref().map(parse_region) {
+            Some(Ok(region)) => region,
+            Some(Err(message)) if requested.contains(&Include::Providers) => {
+                return Err((StatusCode::BAD_REQUEST, message).into_response());
+            }
+            _ => DEFAULT_BADGE_REGION.to_string(),
+        };
+
+        Ok(Self {
+            requested: requested.into_iter().collect(),
+            context: Arc::new(EnrichContext { state: state.clone(), region, now: state.clock.now() }),
+        })
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt;
+use crate::redact::redact;
 
 /// Custom error type for TMDB API operations
 #[derive(Debug, Clone)]
@@ -26,19 +27,25 @@ pub enum TmdbError {
 
     /// Unknown error with status code
     Unknown(u16, String),
+
+    /// Upstream calls are switched off by the service mode
+    UpstreamDisabled,
 }
 
 impl fmt::Display for TmdbError {
+    /// Messages pass through [`redact`] so credentials echoed by upstream
+    /// responses or embedded in request URLs never reach logs or clients.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TmdbError::NetworkError(msg) => write!(f, "Network error: {}", msg),
-            TmdbError::ParseError(msg) => write!(f, "Failed to parse response: {}", msg),
+            TmdbError::NetworkError(msg) => write!(f, "Network error: {}", redact(msg)),
+            TmdbError::ParseError(msg) => write!(f, "Failed to parse response: {}", redact(msg)),
             TmdbError::RateLimitExceeded => write!(f, "API rate limit exceeded"),
             TmdbError::NotFound => write!(f, "Resource not found"),
             TmdbError::Unauthorized => write!(f, "Unauthorized: Invalid or missing API key"),
             TmdbError::ServerError(code) => write!(f, "Server error: {}", code),
-            TmdbError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
-            TmdbError::Unknown(code, msg) => write!(f, "Unknown error ({}): {}", code, msg),
+            TmdbError::BadRequest(msg) => write!(f, "Bad request: {}", redact(msg)),
+            TmdbError::Unknown(code, msg) => write!(f, "Unknown error ({}): {}", code, redact(msg)),
+            TmdbError::UpstreamDisabled => write!(f, "Upstream calls are disabled"),
         }
     }
 }
@@ -46,8 +53,10 @@ impl fmt::Display for TmdbError {
 impl std::error::Error for TmdbError {}
 
 impl From<reqwest::Error> for TmdbError {
+    /// reqwest includes the request URL, and with it the API key, in its
+    /// messages, so the text is scrubbed before it is stored.
     fn from(error: reqwest::Error) -> Self {
-        TmdbError::NetworkError(error.to_string())
+        TmdbError::NetworkError(redact(&error.to_string()))
     }
 }
 
@@ -60,6 +69,7 @@ impl From<serde_json::Error> for TmdbError {
 impl TmdbError {
     /// Creates a TmdbError from an HTTP status code
     pub fn from_status(status: reqwest::StatusCode, body: String) -> Self {
+        let body = redact(&body);
         match status.as_u16() {
             400 => TmdbError::BadRequest(body),
             401 => TmdbError::Unauthorized,
@@ -0,0 +1,102 @@
+// src/etag.rs
+use axum::{
+    body::{self, Body},
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use crate::hash::fnv1a;
+
+/// Middleware that attaches a content-derived `ETag` to successful JSON GET
+/// responses and answers matching `If-None-Match` requests with `304 Not Modified`.
+///
+/// The tag is a hash of the canonicalized JSON body plus the request path and
+/// sorted query parameters. It depends only on the content, never on process
+/// state, so every replica behind a load balancer produces the same value.
+pub async fn etag_middleware(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let resource = canonical_resource(&request);
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+    let response = next.run(request).await;
+
+    if response.status() != StatusCode::OK
+        || response.headers().contains_key(header::ETAG)
+        || !is_json(&response)
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+
+    let etag = compute_etag(&resource, &bytes);
+
+    if if_none_match.is_some_and(|value| etag_matches(&value, &etag)) {
+        let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+        not_modified.headers_mut().insert(header::ETAG, etag);
+        return not_modified;
+    }
+
+    parts.headers.insert(header::ETAG, etag);
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Builds the parameter part of the hash input: the path followed by the
+/// query pairs in sorted order, so `?a=1&b=2` and `?b=2&a=1` hash the same.
+pub(crate) fn canonical_resource(request: &Request) -> String {
+    let uri = request.uri();
+    let mut pairs: Vec<&str> = uri
+        .query()
+        .map(|q| q.split('&').filter(|p| !p.is_empty()).collect())
+        .unwrap_or_default();
+    pairs.sort_unstable();
+
+    format!("{}?{}", uri.path(), pairs.join("&"))
+}
+
+/// Computes a strong ETag for the given resource and body.
+///
+/// JSON bodies are re-serialized through `serde_json::Value`, whose object
+/// keys are ordered, so semantically identical payloads share a tag.
+fn compute_etag(resource: &str, body: &[u8]) -> HeaderValue {
+    let canonical = serde_json::from_slice::<serde_json::Value>(body)
+        .and_then(|value| serde_json::to_vec(&value))
+        .unwrap_or_else(|_| body.to_vec());
+
+    let hash = fnv1a(&[resource.as_bytes(), &[0], &canonical]);
+
+    HeaderValue::from_str(&format!("\"{:016x}\"", hash))
+        .expect("hex digest is always a valid header value")
+}
+
+/// Returns true if any entity tag in an `If-None-Match` header matches `etag`.
+///
+/// Uses the weak comparison required for `If-None-Match`, so `W/"x"` matches `"x"`.
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(candidates) = if_none_match.to_str() else {
+        return false;
+    };
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+
+    candidates.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.trim_start_matches("W/") == etag.trim_start_matches("W/")
+    })
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
@@ -0,0 +1,211 @@
+// src/experiments.rs
+use axum::{
+    extract::{Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use crate::hash::fnv1a;
+use crate::privacy::tracking_allowed;
+use crate::session::cookie_value;
+use crate::state::AppState;
+
+/// Maximum number of exposures kept in memory; oldest exposures are dropped first.
+const EXPOSURE_CAPACITY: usize = 10_000;
+
+/// Header carrying an authenticated user id, preferred over the anonymous id
+pub const USER_ID_HEADER: &str = "x-user-id";
+
+/// Header or cookie carrying a client-generated anonymous id
+pub const ANONYMOUS_ID_HEADER: &str = "x-anonymous-id";
+pub const ANONYMOUS_ID_COOKIE: &str = "anon_id";
+
+/// Response header listing the caller's assignments as `name=variant` pairs
+pub const EXPERIMENTS_HEADER: &str = "x-experiments";
+
+/// An experiment and the variants users are split between, in equal shares.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Experiment {
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+/// The variant a subject was bucketed into for one experiment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assignment {
+    pub experiment: String,
+    pub variant: String,
+}
+
+/// All assignments for the current request, available to handlers as an extension.
+#[derive(Clone, Debug, Default)]
+pub struct Assignments(pub Vec<Assignment>);
+
+impl Assignments {
+    /// Variant assigned for `experiment`, if the caller is enrolled
+    pub fn variant(&self, experiment: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|assignment| assignment.experiment == experiment)
+            .map(|assignment| assignment.variant.as_str())
+    }
+}
+
+/// A subject seeing a variant, kept for later analysis.
+#[derive(Clone, Debug)]
+pub struct Exposure {
+    pub subject: String,
+    pub assignment: Assignment,
+    pub at: SystemTime,
+}
+
+/// Exposure count for one experiment variant.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VariantExposures {
+    pub experiment: String,
+    pub variant: String,
+    pub exposures: usize,
+}
+
+/// Configured experiments plus an in-memory exposure log.
+pub struct Experiments {
+    definitions: Vec<Experiment>,
+    exposures: Mutex<VecDeque<Exposure>>,
+}
+
+impl Experiments {
+    pub fn new(definitions: Vec<Experiment>) -> Self {
+        Self {
+            // An experiment without variants cannot bucket anyone
+            definitions: definitions.into_iter().filter(|e| !e.variants.is_empty()).collect(),
+            exposures: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Parses experiment definitions from a JSON array such as
+    /// `[{"name": "hero_row", "variants": ["control", "large"]}]`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Deterministically buckets `subject` into one variant of every experiment.
+    ///
+    /// The bucket depends only on the experiment name and subject, so a user
+    /// keeps their variant across requests, replicas and restarts.
+    pub fn assign(&self, subject: &str) -> Vec<Assignment> {
+        self.definitions
+            .iter()
+            .map(|experiment| {
+                let hash = fnv1a(&[experiment.name.as_bytes(), b":", subject.as_bytes()]);
+                let index = (hash % experiment.variants.len() as u64) as usize;
+                Assignment {
+                    experiment: experiment.name.clone(),
+                    variant: experiment.variants[index].clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Logs that `subject` was served the given assignments
+    pub fn record_exposures(&self, subject: &str, assignments: &[Assignment], at: SystemTime) {
+        let mut exposures = self.exposures.lock().unwrap();
+        for assignment in assignments {
+            if exposures.len() == EXPOSURE_CAPACITY {
+                exposures.pop_front();
+            }
+            exposures.push_back(Exposure {
+                subject: subject.to_string(),
+                assignment: assignment.clone(),
+                at,
+            });
+        }
+    }
+
+    /// Exposure counts per experiment variant, ordered by experiment then variant
+    pub fn exposure_counts(&self) -> Vec<VariantExposures> {
+        let exposures = self.exposures.lock().unwrap();
+        let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for exposure in exposures.iter() {
+            let key = (exposure.assignment.experiment.as_str(), exposure.assignment.variant.as_str());
+            *counts.entry(key).or_default() += 1;
+        }
+
+        counts
+            .into_iter()
+            .map(|((experiment, variant), exposures)| VariantExposures {
+                experiment: experiment.to_string(),
+                variant: variant.to_string(),
+                exposures,
+            })
+            .collect()
+    }
+}
+
+impl Default for Experiments {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+/// Buckets identified callers into the configured experiments.
+///
+/// The caller is identified by `X-User-Id`, then `X-Anonymous-Id`, then the
+/// `anon_id` cookie. Assignments are exposed to handlers through the
+/// [`Assignments`] extension and to the client via the `X-Experiments` header,
+/// and every enrolled request is logged as an exposure unless the caller
+/// opted out of tracking.
+pub async fn experiments_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next
+) -> Response {
+    if state.experiments.is_empty() {
+        return next.run(request).await;
+    }
+    let Some(subject) = subject_id(&request) else {
+        return next.run(request).await;
+    };
+
+    let assignments = state.experiments.assign(&subject);
+    if tracking_allowed(request.headers()) {
+        state.experiments.record_exposures(&subject, &assignments, state.clock.now());
+    }
+
+    let header_value = assignments
+        .iter()
+        .map(|assignment| format!("{}={}", assignment.experiment, assignment.variant))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    request.extensions_mut().insert(Assignments(assignments));
+    let mut response = next.run(request).await;
+
+    if let Ok(value) = HeaderValue::from_str(&header_value) {
+        response.headers_mut().insert(EXPERIMENTS_HEADER, value);
+    }
+    response
+}
+
+fn subject_id(request: &Request) -> Option<String> {
+    let headers = request.headers();
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+
+    header_value(USER_ID_HEADER)
+        .or_else(|| header_value(ANONYMOUS_ID_HEADER))
+        .or_else(|| cookie_value(headers, ANONYMOUS_ID_COOKIE).map(str::to_string))
+}
@@ -0,0 +1,36 @@
+// src/extract.rs
+use axum::{
+    extract::{path::ErrorKind, rejection::PathRejection, FromRequestParts, Path},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use crate::models::ID_OUT_OF_RANGE;
+
+/// `Path` extractor with uniform rejections for id-taking routes.
+///
+/// Ids that are not integers are a client error (400), while integers that
+/// can never be a TMDB id, such as `-5`, are answered with 404 without asking
+/// TMDB, since no such resource can exist.
+pub struct ValidPath<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Path::<T>::from_request_parts(parts, state).await {
+            Ok(Path(value)) => Ok(Self(value)),
+            Err(PathRejection::FailedToDeserializePathParams(error)) => match error.kind() {
+                ErrorKind::Message(message) if message == ID_OUT_OF_RANGE => {
+                    Err((StatusCode::NOT_FOUND, "Resource not found").into_response())
+                }
+                _ => Err((StatusCode::BAD_REQUEST, error.body_text()).into_response()),
+            },
+            Err(rejection) => Err(rejection.into_response()),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// src/frontend.rs
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+use std::path::Path;
+use tower_http::services::{ServeDir, ServeFile};
+
+/// Cache policy for content-hashed build output (e.g. Vite's `assets/` folder).
+/// The file name changes whenever the content does, so it can be cached forever.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Cache policy for `index.html` and other unhashed files, which must be
+/// revalidated so clients pick up new deployments.
+const REVALIDATE: &str = "no-cache";
+
+/// Path prefix the web UI is served under.
+pub const MOUNT_PATH: &str = "/app";
+
+/// Builds a router serving a single-page application from `dir` under `/app`.
+///
+/// Existing files are served as-is; any other path falls back to `index.html`
+/// so client-side routes survive a page reload. Missing hashed assets answer
+/// `404` instead, so a stale asset URL is never cached as the index page.
+pub fn router<S>(dir: impl AsRef<Path>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let dir = dir.as_ref();
+    let index = ServeFile::new(dir.join("index.html"));
+
+    Router::new()
+        .nest_service(&format!("{}/assets", MOUNT_PATH), ServeDir::new(dir.join("assets")))
+        .nest_service(MOUNT_PATH, ServeDir::new(dir).fallback(index))
+        .layer(middleware::from_fn(cache_headers))
+}
+
+/// The web UI built into `frontend/dist` at compile time
+#[cfg(feature = "embed-frontend")]
+#[derive(rust_embed::Embed)]
+#[folder = "frontend/dist"]
+struct EmbeddedFrontend;
+
+/// Like [`router`], but serves the web UI compiled into the binary, so a
+/// release ships as a single file. Same index fallback and cache headers.
+#[cfg(feature = "embed-frontend")]
+pub fn embedded_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    use axum::routing::get;
+
+    Router::new()
+        .route(MOUNT_PATH, get(embedded_index))
+        .route(&format!("{}/", MOUNT_PATH), get(embedded_index))
+        .route(&format!("{}/{{*path}}", MOUNT_PATH), get(embedded_file))
+        .layer(middleware::from_fn(cache_headers))
+}
+
+#[cfg(feature = "embed-frontend")]
+async fn embedded_index() -> Response {
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    embedded("index.html").unwrap_or_else(|| StatusCode::NOT_FOUND.into_response())
+}
+
+#[cfg(feature = "embed-frontend")]
+async fn embedded_file(axum::extract::Path(path): axum::extract::Path<String>) -> Response {
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    match embedded(&path) {
+        Some(response) => response,
+        // Hashed assets never fall back, see `router`
+        None if path.starts_with("assets/") => StatusCode::NOT_FOUND.into_response(),
+        None => embedded_index().await,
+    }
+}
+
+#[cfg(feature = "embed-frontend")]
+fn embedded(path: &str) -> Option<Response> {
+    use axum::response::IntoResponse;
+
+    let file = EmbeddedFrontend::get(path)?;
+    let content_type = file.metadata.mimetype().to_string();
+    Some(([(header::CONTENT_TYPE, content_type)], file.data).into_response())
+}
+
+/// Sets `Cache-Control` based on whether the requested file is a hashed asset.
+async fn cache_headers(request: Request, next: Next) -> Response {
+    let hashed = request
+        .uri()
+        .path()
+        .starts_with(&format!("{}/assets/", MOUNT_PATH));
+    let mut response = next.run(request).await;
+
+    if response.status().is_success() {
+        let policy = if hashed { IMMUTABLE } else { REVALIDATE };
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static(policy));
+    }
+
+    response
+}
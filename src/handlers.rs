@@ -1,48 +1,737 @@
-use axum::{ extract::{ Path, Query, State }, Json, http::StatusCode, response::IntoResponse };
+use axum::{ extract::{ Path, Query, State }, Extension, Json, http::StatusCode, response::IntoResponse };
+use crate::enrich::Includes;
 use crate::error::TmdbError;
-use crate::models::{ PageQuery, SearchQuery };
+use crate::extract::ValidPath;
+use crate::health::{upstream_status, UpstreamHealth};
+use crate::home::{diversify, load_row, HomePage, HomeRow, RowOutcome, RowStatus};
+use crate::models::{ image_url, AvailabilityQuery, BatchItemError, BatchTitlesRequest, CountryQuery, DiscoverFilter, DiscoverQuery, ImagesQuery, LimitQuery, MediaPageQuery, MediaType, NewOnQuery, OembedQuery, OembedResponse, PageQuery, RegionQuery, SearchQuery, SyncQuery, TmdbId, WhereToStartQuery, parse_region, utc_date };
+use crate::privacy::Tracker;
+use crate::problem::ApiError;
+use crate::push::PushDevice;
+use crate::saved_searches::SavedSearchInput;
+use crate::ranking::Ranker;
+use crate::services::{CatalogService, SearchService, UserService};
+use crate::session::{RecentPick, SessionId};
+use crate::shaping::Device;
 use crate::state::AppState;
+use crate::stats::PublicStats;
+use crate::versioning::ListFormat;
+use tokio::task::JoinSet;
 
 pub async fn root() -> &'static str {
     "Netflix Backend is Online"
 }
 
+/// TMDB reachability for load balancers and dashboards: `200` while
+/// upstream works, even if degraded, and `503` when it is down or disabled
+pub async fn get_upstream_health(State(state): State<AppState>) -> impl IntoResponse {
+    let status = upstream_status(&state).await;
+    let code = match status.status {
+        UpstreamHealth::Ok | UpstreamHealth::Degraded => StatusCode::OK,
+        UpstreamHealth::Down | UpstreamHealth::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (code, Json(status))
+}
+
+/// Non-sensitive aggregates such as uptime and data freshness, for integrators
+pub async fn get_public_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(PublicStats::collect(&state))
+}
+
+/// Keys verifying `x-signature`, as a JWK set; empty when responses aren't signed
+pub async fn get_public_keys(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.signing.public_keys())
+}
+
 pub async fn get_trending_movies(
     State(state): State<AppState>,
-    Query(params): Query<PageQuery>
+    format: ListFormat,
+    Query(params): Query<PageQuery>,
+    ranker: Ranker,
+    includes: Includes
 ) -> impl IntoResponse {
     let page = params.page.unwrap_or(1);
 
-    match state.tmdb_client.get_trending(page).await {
-        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
-        Err(e) => map_error_to_response(e).into_response(),
+    match state.catalog().trending(page).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, MediaType::Movie).await;
+            format.respond(response, MediaType::Movie)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// All-time best movies, or TV shows with `?media_type=tv`, for "Top rated"
+/// rows; kept in TMDB's rating order rather than ranked
+pub async fn get_top_rated(
+    State(state): State<AppState>,
+    format: ListFormat,
+    Query(params): Query<MediaPageQuery>,
+    includes: Includes
+) -> impl IntoResponse {
+    let media_type = params.media_type.unwrap_or(MediaType::Movie);
+
+    match state.catalog().top_rated(media_type, params.page.unwrap_or(1)).await {
+        Ok(mut response) => {
+            includes.apply(&mut response.results, media_type).await;
+            format.respond(response, media_type)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Movies most popular right now, or TV shows with `?media_type=tv`
+pub async fn get_popular(
+    State(state): State<AppState>,
+    format: ListFormat,
+    Query(params): Query<MediaPageQuery>,
+    ranker: Ranker,
+    includes: Includes
+) -> impl IntoResponse {
+    let media_type = params.media_type.unwrap_or(MediaType::Movie);
+
+    match state.catalog().popular(media_type, params.page.unwrap_or(1)).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, media_type).await;
+            format.respond(response, media_type)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Rows of the home page, loaded in parallel and ranked like the list endpoints.
+///
+/// The rows and their order come from `HOME_LAYOUT`, by default trending
+/// titles, popular movies and popular TV shows. Lower rows skip titles already
+/// shown within the `HOME_DIVERSITY` limits. Rows that fail or time out are
+/// returned empty with an `error` status, so one broken source doesn't take
+/// the whole page down.
+pub async fn get_home(
+    State(state): State<AppState>,
+    ranker: Ranker,
+    includes: Includes,
+    device: Device
+) -> impl IntoResponse {
+    let layout = state.home_layout.clone();
+    let mut tasks = JoinSet::new();
+    for (index, row) in layout.rows.iter().cloned().enumerate() {
+        let state = state.clone();
+        tasks.spawn(async move {
+            // `None` when the row timed out
+            let result = tokio::time::timeout(row.timeout(), load_row(&state, &row)).await.ok();
+            (index, result)
+        });
+    }
+
+    let mut loaded = vec![None; layout.rows.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => loaded[index] = Some(result),
+            // The row stays unloaded and is reported as failed below
+            Err(e) => tracing::error!(error = %e, "home row task failed"),
+        }
     }
+
+    let mut rows = Vec::with_capacity(layout.rows.len());
+    for (row, result) in layout.rows.iter().zip(loaded) {
+        let (outcome, items, error) = match result {
+            Some(Some(Ok(mut items))) => {
+                device.retain(&mut items);
+                ranker.apply_to(&mut items);
+                let media_type = row.source.filter().map_or(MediaType::Movie, |filter| filter.media_type);
+                includes.apply(&mut items, media_type).await;
+                (RowOutcome::Ok, items, None)
+            }
+            Some(Some(Err(e))) => (RowOutcome::Error, Vec::new(), Some(map_error_to_response(e).1)),
+            Some(None) => (RowOutcome::TimedOut, Vec::new(), Some("Row timed out")),
+            None => (RowOutcome::Error, Vec::new(), Some("Row failed to load")),
+        };
+        state.home_row_metrics.record(&row.id, outcome);
+        if let Some(error) = error {
+            tracing::warn!(row = %row.id, error, "home row failed");
+        }
+        rows.push(HomeRow {
+            id: row.id.clone(),
+            title: row.title.clone(),
+            kind: row.kind,
+            status: if error.is_some() { RowStatus::Error } else { RowStatus::Ok },
+            error: error.map(str::to_string),
+            items,
+        });
+    }
+
+    diversify(&mut rows, &state.home_diversity);
+    Json(HomePage { rows }).into_response()
 }
 
 pub async fn search_content(
     State(state): State<AppState>,
-    Query(params): Query<SearchQuery>
+    tracker: Tracker,
+    session: Option<Extension<SessionId>>,
+    format: ListFormat,
+    Query(params): Query<SearchQuery>,
+    ranker: Ranker,
+    includes: Includes
 ) -> impl IntoResponse {
     let page = params.page.unwrap_or(1);
+    let session = session.map(|Extension(session)| session);
+    let include_adult = match state.safe_search.include_adult(params.include_adult, &format.device) {
+        Ok(include_adult) => include_adult,
+        Err(e) => return e.into_response(),
+    };
 
-    match state.tmdb_client.search_content(&params.query, page).await {
-        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
-        Err(e) => map_error_to_response(e).into_response(),
+    match state.search().search(&params.query, page, include_adult, session.as_ref(), &tracker).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, MediaType::Movie).await;
+            format.respond(response, MediaType::Movie)
+        }
+        Err(e) => e.into_response(),
     }
 }
 
 pub async fn get_movie_videos(
     State(state): State<AppState>,
-    Path(id): Path<i32>
+    tracker: Tracker,
+    ValidPath(id): ValidPath<TmdbId>
 ) -> impl IntoResponse {
-    match state.tmdb_client.get_movie_videos(id).await {
+    let id = id.get();
+    tracker.title_view(MediaType::Movie, id, state.clock.now());
+
+    match state.catalog().videos(id).await {
         Ok(response) => (StatusCode::OK, Json(response)).into_response(),
-        Err(e) => map_error_to_response(e).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Posters, backdrops and logos of a movie with full URLs, optionally
+/// filtered by `?language=en,null`
+pub async fn get_movie_images(
+    State(state): State<AppState>,
+    ValidPath(id): ValidPath<TmdbId>,
+    Query(params): Query<ImagesQuery>
+) -> impl IntoResponse {
+    match state.catalog().images(MediaType::Movie, id.get()).await {
+        Ok(images) => Json(images.to_links(params.languages().as_deref())).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Per-country alternative titles of a movie, optionally only for `?country=IT`
+pub async fn get_movie_alternative_titles(
+    State(state): State<AppState>,
+    ValidPath(id): ValidPath<TmdbId>,
+    Query(params): Query<CountryQuery>
+) -> impl IntoResponse {
+    match state.catalog().alternative_titles(MediaType::Movie, id.get()).await {
+        Ok(titles) => match params.country.as_deref().map(str::trim) {
+            Some(country) if !country.is_empty() => Json(titles.for_country(country)).into_response(),
+            _ => Json(titles).into_response(),
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Episodes of one season of a TV show; season `0` holds specials
+pub async fn get_tv_season(
+    State(state): State<AppState>,
+    ValidPath((id, season_number)): ValidPath<(TmdbId, u16)>
+) -> impl IntoResponse {
+    match state.catalog().season(id.get(), i32::from(season_number)).await {
+        Ok(season) => Json(season).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Suggested viewing order of a TV show with the episode to start from, in
+/// release order or, with `?order=chronological`, story order
+pub async fn get_where_to_start(
+    State(state): State<AppState>,
+    ValidPath(id): ValidPath<TmdbId>,
+    Query(params): Query<WhereToStartQuery>
+) -> impl IntoResponse {
+    match state.catalog().where_to_start(id.get(), params.order.unwrap_or_default()).await {
+        Ok(order) => Json(order).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Suggested viewing order of a movie collection with the movie to start
+/// from. TMDB has no story order for collections, so it is always release
+/// order, as the response's `order` says.
+pub async fn get_collection_where_to_start(
+    State(state): State<AppState>,
+    ValidPath(id): ValidPath<TmdbId>
+) -> impl IntoResponse {
+    match state.catalog().collection_where_to_start(id.get()).await {
+        Ok(order) => Json(order).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Popular movies, optionally only those streaming on `?providers=8,9` in
+/// `?region=US` and narrowed by TMDB's discover parameters such as
+/// `?with_genres=28&vote_average.gte=7`
+pub async fn discover(
+    State(state): State<AppState>,
+    format: ListFormat,
+    Query(params): Query<DiscoverQuery>,
+    ranker: Ranker,
+    includes: Includes
+) -> impl IntoResponse {
+    let filter = match params.filter() {
+        Ok(filter) => filter,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+    let filter = match state.safe_search.include_adult(params.include_adult, &format.device) {
+        Ok(include_adult) => DiscoverFilter { include_adult, ..filter },
+        Err(e) => return e.into_response(),
+    };
+
+    match state.catalog().discover(&filter, params.page.unwrap_or(1)).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, filter.media_type).await;
+            format.respond(response, filter.media_type)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Popular TV shows of a network, for "More from HBO" rows
+pub async fn get_network_titles(
+    State(state): State<AppState>,
+    format: ListFormat,
+    ValidPath(id): ValidPath<TmdbId>,
+    Query(params): Query<PageQuery>,
+    ranker: Ranker,
+    includes: Includes
+) -> impl IntoResponse {
+    let filter = DiscoverFilter { networks: vec![id.get()], ..DiscoverFilter::new(MediaType::Tv) };
+
+    match state.catalog().discover(&filter, params.page.unwrap_or(1)).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, MediaType::Tv).await;
+            format.respond(response, MediaType::Tv)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Popular movies of a production company
+pub async fn get_company_titles(
+    State(state): State<AppState>,
+    format: ListFormat,
+    ValidPath(id): ValidPath<TmdbId>,
+    Query(params): Query<PageQuery>,
+    ranker: Ranker,
+    includes: Includes
+) -> impl IntoResponse {
+    let filter = DiscoverFilter { companies: vec![id.get()], ..DiscoverFilter::new(MediaType::Movie) };
+
+    match state.catalog().discover(&filter, params.page.unwrap_or(1)).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, MediaType::Movie).await;
+            format.respond(response, MediaType::Movie)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Name, logo and origin of a TV network
+pub async fn get_network(
+    State(state): State<AppState>,
+    ValidPath(id): ValidPath<TmdbId>
+) -> impl IntoResponse {
+    match state.catalog().network(id.get()).await {
+        Ok(network) => Json(network).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// An editorial list with its titles' details, in curated order; titles TMDB no longer has are left out
+pub async fn get_curated_list(
+    State(state): State<AppState>,
+    device: Device,
+    Path(slug): Path<String>
+) -> impl IntoResponse {
+    match state.catalog().curated_list(&slug).await {
+        Ok(mut list) => {
+            list.items.retain_mut(|item| device.check_details(&mut item.details).is_ok());
+            Json(list).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Watch providers available in `?region=US`, most prominent first, with the ids `/api/discover` filters on
+pub async fn get_providers(
+    State(state): State<AppState>,
+    Query(params): Query<RegionQuery>
+) -> impl IntoResponse {
+    let region = match params.region.as_deref().map(parse_region) {
+        Some(Ok(region)) => region,
+        Some(Err(message)) => return (StatusCode::BAD_REQUEST, message).into_response(),
+        None => return (StatusCode::BAD_REQUEST, "region is required").into_response(),
+    };
+
+    match state.catalog().providers(&region).await {
+        Ok(list) => Json(list.summaries()).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// "New on <provider>" row: titles streaming on `?provider=8` in `?region=US`,
+/// latest release first; `?media_type=tv` for shows
+pub async fn get_new_on(
+    State(state): State<AppState>,
+    format: ListFormat,
+    Query(params): Query<NewOnQuery>,
+    includes: Includes
+) -> impl IntoResponse {
+    let provider = match params.provider {
+        Some(provider) if provider > 0 => provider,
+        Some(provider) => return (StatusCode::BAD_REQUEST, format!("invalid provider id '{}'", provider)).into_response(),
+        None => return (StatusCode::BAD_REQUEST, "provider is required").into_response(),
+    };
+    let region = match params.region.as_deref().map(parse_region) {
+        Some(Ok(region)) => region,
+        Some(Err(message)) => return (StatusCode::BAD_REQUEST, message).into_response(),
+        None => return (StatusCode::BAD_REQUEST, "region is required").into_response(),
+    };
+    let media_type = params.media_type.unwrap_or(MediaType::Movie);
+
+    match state.catalog().new_on(media_type, provider, &region).await {
+        Ok(mut response) => {
+            includes.apply(&mut response.results, media_type).await;
+            format.respond(response, media_type)
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Themed rows shown today, such as "Halloween picks", ordered by slug
+pub async fn get_themes(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.themes.live(utc_date(state.clock.now())))
+}
+
+/// Titles of a themed row, found by the row's rules; `404` outside its live dates
+pub async fn get_theme_titles(
+    State(state): State<AppState>,
+    format: ListFormat,
+    Path(slug): Path<String>,
+    Query(params): Query<PageQuery>,
+    ranker: Ranker,
+    includes: Includes
+) -> impl IntoResponse {
+    let Some(filter) = state.themes.live_filter(&slug, utc_date(state.clock.now())) else {
+        return ApiError::new(StatusCode::NOT_FOUND, "not_found", "Themed row not found").into_response();
+    };
+
+    match state.catalog().discover(&filter, params.page.unwrap_or(1)).await {
+        Ok(mut response) => {
+            ranker.apply(&mut response);
+            includes.apply(&mut response.results, filter.media_type).await;
+            format.respond(response, filter.media_type)
+        }
+        Err(e) => e.into_response(),
     }
 }
 
+/// Upper bound on regions per availability request
+pub const MAX_AVAILABILITY_REGIONS: usize = 20;
+
+/// Provider availability of a movie across `?regions=US,GB,DE`, one matrix row per region
+pub async fn get_movie_availability(
+    State(state): State<AppState>,
+    ValidPath(id): ValidPath<TmdbId>,
+    Query(params): Query<AvailabilityQuery>
+) -> impl IntoResponse {
+    let regions = match params.regions() {
+        Ok(regions) if regions.is_empty() => {
+            return (StatusCode::BAD_REQUEST, "regions must list at least one country code").into_response();
+        }
+        Ok(regions) if regions.len() > MAX_AVAILABILITY_REGIONS => {
+            let message = format!("At most {} regions may be requested", MAX_AVAILABILITY_REGIONS);
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        }
+        Ok(regions) => regions,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+
+    match state.catalog().availability(MediaType::Movie, id.get(), &regions).await {
+        Ok(matrix) => Json(matrix).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Recent searches and picks for the caller's anonymous session
+pub async fn get_session_recent(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    Json(state.users().recent(&session))
+}
+
+pub async fn add_session_pick(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Json(pick): Json<RecentPick>
+) -> impl IntoResponse {
+    state.users().record_pick(&session, pick);
+    StatusCode::NO_CONTENT
+}
+
+pub async fn clear_session_recent(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    state.users().clear_recent(&session);
+    StatusCode::NO_CONTENT
+}
+
+/// Number of titles returned by `/api/recent` when `limit` is not given
+const DEFAULT_RECENT_LIMIT: usize = 10;
+
+/// Titles whose detail pages the caller's session viewed, most recent first
+pub async fn get_recently_viewed(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Query(params): Query<LimitQuery>
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(DEFAULT_RECENT_LIMIT);
+    Json(state.users().recent_views(&session, limit))
+}
+
+/// Genre affinity built from the session's title views
+pub async fn get_taste(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    Json(state.users().taste(&session))
+}
+
+pub async fn clear_recently_viewed(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    state.users().clear_views(&session);
+    StatusCode::NO_CONTENT
+}
+
+/// Saves a search or discover query; a scheduled job re-runs it and adds a
+/// notification when new titles match
+pub async fn save_search(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Json(input): Json<SavedSearchInput>
+) -> impl IntoResponse {
+    match state.users().save_search(&session, input) {
+        Ok(search) => (StatusCode::CREATED, Json(search)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_saved_searches(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    Json(state.users().saved_searches(&session))
+}
+
+pub async fn delete_saved_search(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Path(id): Path<u64>
+) -> impl IntoResponse {
+    match state.users().delete_saved_search(&session, id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Curated lists and saved searches changed since `?since=`, a sync token
+/// or a Unix timestamp, for clients keeping an offline copy; without it
+/// everything is sent
+pub async fn get_sync(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Query(params): Query<SyncQuery>
+) -> impl IntoResponse {
+    match state.users().sync(&session, params.since.as_deref()) {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// New matches for the session's saved searches, newest first
+pub async fn get_notifications(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    Json(state.users().notifications(&session))
+}
+
+pub async fn clear_notifications(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    state.users().clear_notifications(&session);
+    StatusCode::NO_CONTENT
+}
+
+/// Registers a device to receive the session's notifications as pushes.
+/// Apps register on every launch, which also keeps the registration alive.
+pub async fn register_push_device(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Json(device): Json<PushDevice>
+) -> impl IntoResponse {
+    match state.users().register_device(&session, device) {
+        Ok(true) => StatusCode::CREATED.into_response(),
+        Ok(false) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn get_push_devices(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>
+) -> impl IntoResponse {
+    Json(state.users().devices(&session))
+}
+
+pub async fn unregister_push_device(
+    State(state): State<AppState>,
+    Extension(session): Extension<SessionId>,
+    Path(token): Path<String>
+) -> impl IntoResponse {
+    match state.users().unregister_device(&session, &token) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Maximum number of titles per batch lookup
+pub const MAX_BATCH_ITEMS: usize = 50;
+
+/// Looks up details for many titles at once.
+///
+/// Results come back in request order, and a title that fails to load gets
+/// its own error entry instead of failing the whole batch.
+pub async fn get_titles_batch(
+    State(state): State<AppState>,
+    includes: Includes,
+    device: Device,
+    Json(request): Json<BatchTitlesRequest>
+) -> impl IntoResponse {
+    if request.items.len() > MAX_BATCH_ITEMS {
+        let message = format!("A batch may contain at most {} items", MAX_BATCH_ITEMS);
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+
+    let mut results = state.catalog().batch(request).await;
+    for result in &mut results {
+        let Some(details) = result.details.as_mut() else { continue };
+        // Refused titles get an error entry like titles that failed to load
+        if let Err(error) = device.check_details(details) {
+            let message = error.detail.unwrap_or_default();
+            result.error = Some(BatchItemError { status: error.status.as_u16(), message });
+            (result.details, result.logo) = (None, None);
+            result.tags.clear();
+            result.content_warnings.clear();
+        }
+    }
+    includes.apply_to_batch(&mut results).await;
+    Json(results).into_response()
+}
+
+/// Default embed size, the standard 16:9 YouTube player
+const OEMBED_WIDTH: u32 = 560;
+const OEMBED_HEIGHT: u32 = 315;
+
+/// oEmbed provider for our `/title/movie/{id}` pages, embedding the title's best trailer.
+pub async fn get_oembed(
+    State(state): State<AppState>,
+    tracker: Tracker,
+    Query(params): Query<OembedQuery>
+) -> impl IntoResponse {
+    if params.format.as_deref().is_some_and(|format| format != "json") {
+        return (StatusCode::NOT_IMPLEMENTED, "Only the json format is supported").into_response();
+    }
+
+    let Some(movie_id) = movie_id_from_title_url(&params.url) else {
+        return (StatusCode::NOT_FOUND, "No embeddable content for this URL").into_response();
+    };
+
+    tracker.title_view(MediaType::Movie, movie_id, state.clock.now());
+
+    let catalog = state.catalog();
+    let (details, videos) = tokio::join!(
+        catalog.details(MediaType::Movie, movie_id),
+        catalog.videos(movie_id)
+    );
+    let (details, videos) = match (details, videos) {
+        (Ok(details), Ok(videos)) => (details, videos),
+        (Err(e), _) | (_, Err(e)) => return e.into_response(),
+    };
+
+    let Some(trailer) = videos.best_trailer() else {
+        return (StatusCode::NOT_FOUND, "No trailer available").into_response();
+    };
+
+    let (width, height) = fit_embed_size(params.maxwidth, params.maxheight);
+    let html = format!(
+        "<iframe width=\"{}\" height=\"{}\" src=\"https://www.youtube.com/embed/{}\" frameborder=\"0\" allow=\"autoplay; encrypted-media; picture-in-picture\" allowfullscreen></iframe>",
+        width, height, trailer.key
+    );
+
+    let response = OembedResponse {
+        version: "1.0".to_string(),
+        r#type: "video".to_string(),
+        title: details.display_title().to_string(),
+        provider_name: "Netflix Clone".to_string(),
+        html,
+        width,
+        height,
+        thumbnail_url: details.backdrop_path.as_deref().map(|path| image_url("w780", path)),
+    };
+
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Extracts the movie ID from a title page URL such as `https://host/title/movie/603`
+fn movie_id_from_title_url(url: &str) -> Option<i64> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+
+    match (segments.next(), segments.next(), segments.next(), segments.next()) {
+        (Some("title"), Some("movie"), Some(id), None) => id.parse().ok().and_then(TmdbId::new).map(TmdbId::get),
+        _ => None,
+    }
+}
+
+/// Scales the default 16:9 embed down to honour the consumer's `maxwidth`/`maxheight`
+fn fit_embed_size(max_width: Option<u32>, max_height: Option<u32>) -> (u32, u32) {
+    let mut width = OEMBED_WIDTH;
+    if let Some(max_width) = max_width {
+        width = width.min(max_width);
+    }
+    if let Some(max_height) = max_height {
+        // Widened so huge heights can't overflow
+        let fitted = u64::from(max_height) * u64::from(OEMBED_WIDTH) / u64::from(OEMBED_HEIGHT);
+        width = width.min(fitted.min(u64::from(OEMBED_WIDTH)) as u32);
+    }
+
+    (width, width * OEMBED_HEIGHT / OEMBED_WIDTH)
+}
+
 /// Maps TmdbError to appropriate HTTP response
-fn map_error_to_response(error: TmdbError) -> (StatusCode, &'static str) {
+pub(crate) fn map_error_to_response(error: TmdbError) -> (StatusCode, &'static str) {
     match error {
         TmdbError::NotFound => (StatusCode::NOT_FOUND, "Resource not found"),
         TmdbError::Unauthorized => (StatusCode::UNAUTHORIZED, "Invalid or missing API key"),
@@ -52,5 +741,6 @@ fn map_error_to_response(error: TmdbError) -> (StatusCode, &'static str) {
         TmdbError::NetworkError(_) => (StatusCode::SERVICE_UNAVAILABLE, "Network error occurred"),
         TmdbError::ParseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Failed to parse response"),
         TmdbError::Unknown(_, _) => (StatusCode::INTERNAL_SERVER_ERROR, "Unknown error occurred"),
+        TmdbError::UpstreamDisabled => (StatusCode::SERVICE_UNAVAILABLE, "Upstream calls are disabled"),
     }
 }
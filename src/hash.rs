@@ -0,0 +1,19 @@
+// src/hash.rs
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a over the concatenation of `parts`.
+///
+/// Used wherever a hash must agree across processes and releases (ETags,
+/// experiment buckets). `DefaultHasher` does not guarantee that.
+pub fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for part in parts {
+        for byte in *part {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
@@ -0,0 +1,140 @@
+// src/health.rs
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::mirrors::MirrorStatus;
+use crate::mode::ServiceMode;
+use crate::outage::OutageStatus;
+use crate::state::AppState;
+use crate::tmdb_client::TmdbClient;
+
+/// Path prefix of the health checks
+pub const MOUNT_PATH: &str = "/health";
+
+/// How long a probe result is reused, so frequent health checks add no TMDB load
+pub const DEFAULT_PROBE_TTL: Duration = Duration::from_secs(15);
+
+/// Network looked up by the probe: HBO, a small payload that always exists
+const PROBE_NETWORK_ID: i64 = 49;
+
+/// Overall state of the TMDB dependency
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamHealth {
+    Ok,
+    /// Answering, but slowly, through fewer mirrors, in degraded mode or
+    /// during an outage the monitor detected
+    Degraded,
+    Down,
+    /// The service mode keeps calls from reaching TMDB, so nothing was probed
+    Disabled,
+}
+
+/// Breaker state derived from the mirrors: a mirror that failed is skipped
+/// while it cools down, so it counts as an open circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    /// Some mirrors are cooling down; calls go to the others
+    PartiallyOpen,
+    Open,
+}
+
+impl CircuitState {
+    pub fn from_mirrors(mirrors: &[MirrorStatus]) -> Self {
+        match mirrors.iter().filter(|mirror| mirror.healthy).count() {
+            healthy if healthy == mirrors.len() => CircuitState::Closed,
+            0 => CircuitState::Open,
+            _ => CircuitState::PartiallyOpen,
+        }
+    }
+}
+
+/// Outcome of one TMDB probe
+#[derive(Clone, Debug, Serialize)]
+pub struct ProbeResult {
+    pub ok: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    /// Unix timestamp of the probe, in seconds
+    pub checked_at: u64,
+}
+
+/// Body of `/health/upstream`
+#[derive(Clone, Debug, Serialize)]
+pub struct UpstreamStatus {
+    pub status: UpstreamHealth,
+    /// `None` when the service mode disables upstream calls
+    pub probe: Option<ProbeResult>,
+    /// True if the probe result was reused from an earlier check
+    pub cached: bool,
+    pub circuit: CircuitState,
+    pub mode: ServiceMode,
+    pub mirrors: Vec<MirrorStatus>,
+    /// What the outage monitor last found
+    pub outage: OutageStatus,
+}
+
+/// Latest TMDB probe, reused for a TTL
+pub struct UpstreamProbe {
+    ttl: Duration,
+    last: Mutex<Option<(SystemTime, ProbeResult)>>,
+}
+
+impl UpstreamProbe {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, last: Mutex::new(None) }
+    }
+
+    /// The last result if younger than the TTL at `now`, otherwise a fresh
+    /// probe of `client`; the flag tells which
+    pub async fn check(&self, client: &dyn TmdbClient, now: SystemTime) -> (ProbeResult, bool) {
+        if let Some((at, result)) = self.last.lock().unwrap().as_ref()
+            && now.duration_since(*at).unwrap_or_default() < self.ttl
+        {
+            return (result.clone(), true);
+        }
+
+        let started = Instant::now();
+        let outcome = client.get_network(PROBE_NETWORK_ID).await;
+        let result = ProbeResult {
+            ok: outcome.is_ok(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: outcome.err().map(|error| error.to_string()),
+            checked_at: now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        };
+        *self.last.lock().unwrap() = Some((now, result.clone()));
+        (result, false)
+    }
+}
+
+impl Default for UpstreamProbe {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROBE_TTL)
+    }
+}
+
+/// Probes TMDB, or reuses a recent probe, and combines it with the mirror
+/// health and service mode
+pub async fn upstream_status(state: &AppState) -> UpstreamStatus {
+    let mode = state.mode.mode();
+    let mirrors = state.mirrors.status();
+    let circuit = CircuitState::from_mirrors(&mirrors);
+    let outage = state.outage.status();
+    if !mode.allows_upstream() {
+        return UpstreamStatus { status: UpstreamHealth::Disabled, probe: None, cached: false, circuit, mode, mirrors, outage };
+    }
+
+    let (probe, cached) = state.upstream_probe.check(state.tmdb_client.as_ref(), state.clock.now()).await;
+    let slow = Duration::from_millis(probe.latency_ms) > state.slow_log.thresholds().upstream;
+    let status = if !probe.ok {
+        UpstreamHealth::Down
+    } else if slow || circuit != CircuitState::Closed || mode != ServiceMode::Normal || outage.outage {
+        UpstreamHealth::Degraded
+    } else {
+        UpstreamHealth::Ok
+    };
+
+    UpstreamStatus { status, probe: Some(probe), cached, circuit, mode, mirrors, outage }
+}
@@ -0,0 +1,410 @@
+// src/home.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::analytics::parse_window;
+use crate::error::TmdbError;
+use crate::lists::validate_slug;
+use crate::models::{parse_region, utc_date, DiscoverFilter, MediaType, Movie};
+use crate::services::CatalogService;
+use crate::state::AppState;
+
+/// How long a row may take to load, unless the row sets its own `timeout`
+pub const DEFAULT_ROW_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How a row is presented by clients
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowKind {
+    #[default]
+    Carousel,
+    /// Large artwork for the first few items
+    Hero,
+    /// Numbered, as in "Top 10 today"
+    Ranked,
+}
+
+/// Where a row's titles come from
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum RowSource {
+    /// This week's trending titles
+    Trending,
+    /// Popular titles, optionally narrowed like `/api/discover`
+    Discover {
+        media_type: MediaType,
+        #[serde(default)]
+        providers: Vec<i64>,
+        #[serde(default)]
+        region: Option<String>,
+        #[serde(default)]
+        networks: Vec<i64>,
+        #[serde(default)]
+        companies: Vec<i64>,
+    },
+    /// Popular titles of one genre
+    Genre { media_type: MediaType, genre: i64 },
+    /// An admin-curated list, by slug
+    CuratedList { slug: String },
+    /// A rule-driven themed row, by slug; empty while the theme isn't live
+    Theme { slug: String },
+}
+
+impl RowSource {
+    /// The discover filter behind discover and genre rows; theme rows look
+    /// theirs up when loaded
+    pub fn filter(&self) -> Option<DiscoverFilter> {
+        match self {
+            RowSource::Discover { media_type, providers, region, networks, companies } => Some(DiscoverFilter {
+                providers: providers.clone(),
+                region: region.clone(),
+                networks: networks.clone(),
+                companies: companies.clone(),
+                ..DiscoverFilter::new(*media_type)
+            }),
+            RowSource::Genre { media_type, genre } => Some(DiscoverFilter { genres: vec![*genre], ..DiscoverFilter::new(*media_type) }),
+            RowSource::Trending | RowSource::CuratedList { .. } | RowSource::Theme { .. } => None,
+        }
+    }
+}
+
+/// One row of a home layout
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowDefinition {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub kind: RowKind,
+    #[serde(flatten)]
+    pub source: RowSource,
+    /// How long the row's titles are reused, e.g. `10m`; the trending cache
+    /// TTL when unset. Curated list rows follow the list's own cache.
+    #[serde(default)]
+    pub ttl: Option<String>,
+    /// How long loading may take before the row is reported as failed,
+    /// e.g. `2s`; [`DEFAULT_ROW_TIMEOUT`] when unset
+    #[serde(default)]
+    pub timeout: Option<String>,
+}
+
+impl RowDefinition {
+    fn new(id: &str, title: &str, source: RowSource) -> Self {
+        Self { id: id.to_string(), title: title.to_string(), kind: RowKind::default(), source, ttl: None, timeout: None }
+    }
+
+    /// The configured TTL; only `None` when unset, as layouts are validated on load
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl.as_deref().and_then(parse_window)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout.as_deref().and_then(parse_window).unwrap_or(DEFAULT_ROW_TIMEOUT)
+    }
+}
+
+/// Rows of `/api/home`, top to bottom.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HomeLayout {
+    pub rows: Vec<RowDefinition>,
+}
+
+impl HomeLayout {
+    /// Parses and checks a layout such as
+    /// `{"rows": [{"id": "trending", "title": "Trending now", "source": "trending"}]}`
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let layout: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let mut ids = HashSet::new();
+        for row in &layout.rows {
+            if row.id.trim().is_empty() {
+                return Err("row ids must not be empty".to_string());
+            }
+            if !ids.insert(row.id.as_str()) {
+                return Err(format!("duplicate row id '{}'", row.id));
+            }
+            if let Some(ttl) = &row.ttl
+                && parse_window(ttl).is_none()
+            {
+                return Err(format!("row '{}': ttl must look like 30s, 10m or 1h", row.id));
+            }
+            if let Some(timeout) = &row.timeout
+                && parse_window(timeout).is_none_or(|timeout| timeout.is_zero())
+            {
+                return Err(format!("row '{}': timeout must be a positive duration such as 2s", row.id));
+            }
+            match &row.source {
+                RowSource::Discover { providers, region, .. } => {
+                    if !providers.is_empty() && region.is_none() {
+                        return Err(format!("row '{}': providers need a region", row.id));
+                    }
+                    if let Some(region) = region {
+                        parse_region(region).map_err(|e| format!("row '{}': {}", row.id, e))?;
+                    }
+                }
+                RowSource::CuratedList { slug } | RowSource::Theme { slug } => {
+                    validate_slug(slug).map_err(|e| format!("row '{}': {}", row.id, e))?;
+                }
+                RowSource::Trending | RowSource::Genre { .. } => {}
+            }
+        }
+        Ok(layout)
+    }
+}
+
+impl Default for HomeLayout {
+    fn default() -> Self {
+        let popular = |media_type| RowSource::Discover {
+            media_type,
+            providers: Vec::new(),
+            region: None,
+            networks: Vec::new(),
+            companies: Vec::new(),
+        };
+
+        Self {
+            rows: vec![
+                RowDefinition::new("trending", "Trending now", RowSource::Trending),
+                RowDefinition::new("popular_movies", "Popular movies", popular(MediaType::Movie)),
+                RowDefinition::new("popular_tv", "Popular TV shows", popular(MediaType::Tv)),
+            ],
+        }
+    }
+}
+
+/// Whether a home row loaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowStatus {
+    #[default]
+    Ok,
+    /// The row's source failed or timed out; the row has no items
+    Error,
+}
+
+/// One row of the home page, such as "Trending now"
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HomeRow {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub kind: RowKind,
+    #[serde(default)]
+    pub status: RowStatus,
+    /// Why the row failed, only set with an `error` status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub items: Vec<Movie>,
+}
+
+/// Rows of `/api/home`, top to bottom
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HomePage {
+    pub rows: Vec<HomeRow>,
+}
+
+/// Limits applied across home rows so the same titles don't fill every row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiversityLimits {
+    /// Rows a title may appear in; later appearances are dropped
+    pub max_appearances: usize,
+    /// Titles per row sharing the same main genre, unlimited when `None`
+    pub max_per_genre: Option<usize>,
+}
+
+impl DiversityLimits {
+    /// Parses limits such as `max_appearances=1,max_per_genre=4`; keys not
+    /// listed keep their defaults
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut limits = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got '{}'", entry))?;
+            let value: usize = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&value| value > 0)
+                .ok_or_else(|| format!("invalid {} '{}', use a positive whole number", key.trim(), value.trim()))?;
+            match key.trim() {
+                "max_appearances" => limits.max_appearances = value,
+                "max_per_genre" => limits.max_per_genre = Some(value),
+                other => return Err(format!("unknown home diversity limit '{}'", other)),
+            }
+        }
+        Ok(limits)
+    }
+}
+
+impl Default for DiversityLimits {
+    fn default() -> Self {
+        Self { max_appearances: 1, max_per_genre: None }
+    }
+}
+
+/// Drops items that break `limits`, walking rows top to bottom so upper
+/// rows keep their titles. Rows keep their remaining items in order.
+pub fn diversify(rows: &mut [HomeRow], limits: &DiversityLimits) {
+    let mut appearances: HashMap<(Option<String>, i64), usize> = HashMap::new();
+    for row in rows {
+        let mut per_genre: HashMap<i64, usize> = HashMap::new();
+        row.items.retain(|item| {
+            let seen = appearances.entry((item.media_type.clone(), item.id)).or_default();
+            if *seen >= limits.max_appearances {
+                return false;
+            }
+            if let (Some(limit), Some(&genre)) = (limits.max_per_genre, item.genre_ids.first()) {
+                let count = per_genre.entry(genre).or_default();
+                if *count >= limit {
+                    return false;
+                }
+                *count += 1;
+            }
+            *seen += 1;
+            true
+        });
+    }
+}
+
+/// Titles per home row id, kept for each row's TTL
+#[derive(Default)]
+pub struct HomeRowCache {
+    rows: Mutex<HashMap<String, (Vec<Movie>, SystemTime)>>,
+}
+
+impl HomeRowCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, row_id: &str, now: SystemTime) -> Option<Vec<Movie>> {
+        let rows = self.rows.lock().unwrap();
+        rows.get(row_id)
+            .filter(|(_, expires)| *expires > now)
+            .map(|(items, _)| items.clone())
+    }
+
+    /// Keeps `items` for `ttl`; a zero TTL stores nothing
+    pub fn store(&self, row_id: &str, items: Vec<Movie>, now: SystemTime, ttl: Duration) {
+        if ttl.is_zero() {
+            return;
+        }
+        self.rows.lock().unwrap().insert(row_id.to_string(), (items, now + ttl));
+    }
+}
+
+/// Titles of `row` in source order, from the row cache when fresh.
+///
+/// A curated list or theme that doesn't exist gives an empty row rather than
+/// an error, so deleting one doesn't break the home page. So does a theme
+/// outside its live dates.
+pub async fn load_row(state: &AppState, row: &RowDefinition) -> Result<Vec<Movie>, TmdbError> {
+    let now = state.clock.now();
+    let client = state.tmdb_client.as_ref();
+
+    if let RowSource::CuratedList { slug } = &row.source {
+        let list = state.catalog().curated_list(slug).await.ok();
+        return Ok(list
+            .map(|list| {
+                list.items
+                    .into_iter()
+                    .map(|item| Movie::from_details(item.media_type, item.details))
+                    .collect()
+            })
+            .unwrap_or_default());
+    }
+
+    let filter = match &row.source {
+        RowSource::Theme { slug } => match state.themes.live_filter(slug, utc_date(now)) {
+            Some(filter) => Some(filter),
+            None => return Ok(Vec::new()),
+        },
+        source => source.filter(),
+    };
+
+    // Rows are cached unfiltered, so blocklist changes apply right away
+    let media_type = filter.as_ref().map_or(MediaType::Movie, |filter| filter.media_type);
+    if let Some(mut items) = state.home_rows.get(&row.id, now) {
+        state.blocklist.retain(&mut items, media_type);
+        state.safe_search.retain(&mut items, false, "home");
+        return Ok(items);
+    }
+    let mut items = match filter {
+        Some(filter) => client.discover(&filter, 1).await?.results,
+        None => client.get_trending(1).await?.results,
+    };
+    let ttl = row.ttl().unwrap_or(state.cache_policies.trending);
+    state.home_rows.store(&row.id, items.clone(), now, ttl);
+    state.blocklist.retain(&mut items, media_type);
+    state.safe_search.retain(&mut items, false, "home");
+    Ok(items)
+}
+
+/// How one row load ended, as counted by [`HomeRowMetrics`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowOutcome {
+    Ok,
+    Error,
+    TimedOut,
+}
+
+/// Loads and failures of one home row since startup
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HomeRowStats {
+    pub row: String,
+    pub loads: u64,
+    pub errors: u64,
+    pub timeouts: u64,
+    /// Share of loads that errored or timed out, rounded to three decimals
+    pub failure_rate: f64,
+}
+
+#[derive(Default)]
+struct RowCounts {
+    loads: u64,
+    errors: u64,
+    timeouts: u64,
+}
+
+/// Per-row load counters for `/api/home`, showing which sources fail.
+#[derive(Default)]
+pub struct HomeRowMetrics {
+    rows: Mutex<BTreeMap<String, RowCounts>>,
+}
+
+impl HomeRowMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, row_id: &str, outcome: RowOutcome) {
+        let mut rows = self.rows.lock().unwrap();
+        let counts = rows.entry(row_id.to_string()).or_default();
+        counts.loads += 1;
+        match outcome {
+            RowOutcome::Ok => {}
+            RowOutcome::Error => counts.errors += 1,
+            RowOutcome::TimedOut => counts.timeouts += 1,
+        }
+    }
+
+    /// Stats for every row loaded since startup, by row id
+    pub fn stats(&self) -> Vec<HomeRowStats> {
+        self.rows
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(row, counts)| {
+                let failures = (counts.errors + counts.timeouts) as f64;
+                HomeRowStats {
+                    row: row.clone(),
+                    loads: counts.loads,
+                    errors: counts.errors,
+                    timeouts: counts.timeouts,
+                    failure_rate: (failures / counts.loads as f64 * 1000.0).round() / 1000.0,
+                }
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,67 @@
 // src/lib.rs
+pub mod admin;
+pub mod analytics;
+pub mod app;
+pub mod bench;
+pub mod blocklist;
+pub mod cache_policy;
+pub mod changes;
+pub mod compression;
+pub mod budget;
+pub mod clock;
+pub mod deadline;
+pub mod dev_client;
+pub mod enrich;
 pub mod error;
+pub mod etag;
+pub mod experiments;
+pub mod extract;
+pub mod frontend;
 pub mod handlers;
+pub mod hash;
+pub mod health;
+pub mod home;
+pub mod lists;
+pub mod logging;
+pub mod logos;
+pub mod metadata;
+pub mod mirrors;
+pub mod mode;
+pub mod mtls;
 pub mod models;
+pub mod negative_cache;
+pub mod new_on;
+pub mod outage;
+pub mod pages;
+pub mod privacy;
+pub mod problem;
+pub mod profiling;
+pub mod providers;
+pub mod push;
+pub mod quality;
+pub mod ranking;
+pub mod redact;
+pub mod safe_methods;
+pub mod safe_search;
+pub mod saved_searches;
+pub mod services;
+pub mod shadow;
+pub mod session;
+pub mod shaping;
+pub mod signing;
+pub mod shutdown;
+pub mod slo;
+pub mod slow_log;
+pub mod startup;
 pub mod state;
+pub mod stats;
+pub mod sync;
+pub mod tags;
+pub mod taste;
+pub mod themes;
+pub mod tls;
 pub mod tmdb_client;
+pub mod trailers;
+pub mod upstream_metrics;
+pub mod versioning;
+pub mod viewing_order;
@@ -0,0 +1,333 @@
+// src/lists.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::error::TmdbError;
+use crate::models::{MediaType, TitleDetails, TitleRef, TmdbList};
+use crate::sync::{Delta, Since, Stamp};
+use crate::tmdb_client::TmdbClient;
+
+/// Upper bound on titles per list, matching a batch lookup
+pub const MAX_LIST_ITEMS: usize = 50;
+
+/// Longest accepted slug
+const MAX_SLUG_CHARS: usize = 64;
+
+/// Parallel TMDB lookups while resolving one list
+const RESOLVE_CONCURRENCY: usize = 8;
+
+/// Deleted slugs remembered for delta sync; clients older than the oldest
+/// forgotten one get a full copy
+const MAX_TOMBSTONES: usize = 1_000;
+
+/// Body of `PUT /admin/lists/{slug}`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CuratedListInput {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Titles in display order
+    pub items: Vec<TitleRef>,
+}
+
+/// An editorial list such as "Staff picks", as stored
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CuratedList {
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub items: Vec<TitleRef>,
+    /// Unix timestamp of the last change, in seconds
+    pub updated_at: u64,
+    /// TMDB list this list was imported from and is kept in sync with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmdb_list_id: Option<i64>,
+}
+
+/// Body of `POST /admin/lists/{slug}/import`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ListImport {
+    pub tmdb_list_id: i64,
+}
+
+/// One title of a resolved list
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResolvedItem {
+    pub media_type: MediaType,
+    pub id: i64,
+    pub details: TitleDetails,
+}
+
+/// A list with its titles loaded from TMDB, as served by `/api/lists/{slug}`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResolvedList {
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub items: Vec<ResolvedItem>,
+}
+
+/// Checks a slug: 1 to 64 lowercase letters, digits and dashes
+pub fn validate_slug(slug: &str) -> Result<(), &'static str> {
+    let allowed = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-';
+    if slug.is_empty() || slug.chars().count() > MAX_SLUG_CHARS || !slug.chars().all(allowed) {
+        return Err("slug may only contain lowercase letters, digits and dashes, up to 64 characters");
+    }
+    Ok(())
+}
+
+/// When each list last changed, for delta sync. Every change takes the
+/// next revision.
+#[derive(Default)]
+struct Revisions {
+    current: u64,
+    changed: HashMap<String, Stamp>,
+    deleted: HashMap<String, Stamp>,
+    /// The newest forgotten deletion; before the first change, the time of
+    /// that change, since lists of an earlier process are unknown
+    floor: Stamp,
+}
+
+impl Revisions {
+    fn record(&mut self, slug: &str, deleted: bool, now: SystemTime) {
+        let at = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        if self.current == 0 {
+            self.floor = Stamp { revision: 0, at };
+        }
+        self.current += 1;
+        let stamp = Stamp { revision: self.current, at };
+        if deleted {
+            self.changed.remove(slug);
+            self.deleted.insert(slug.to_string(), stamp);
+        } else {
+            self.deleted.remove(slug);
+            self.changed.insert(slug.to_string(), stamp);
+        }
+        if self.deleted.len() > MAX_TOMBSTONES
+            && let Some((oldest, stamp)) = self.deleted.iter().min_by_key(|(_, stamp)| stamp.revision)
+        {
+            let (oldest, stamp) = (oldest.clone(), *stamp);
+            self.deleted.remove(&oldest);
+            self.floor = stamp;
+        }
+    }
+}
+
+/// Admin-curated lists kept in memory, with their resolved form cached.
+///
+/// Changing or deleting a list drops its cached resolution right away.
+#[derive(Default)]
+pub struct CuratedLists {
+    lists: Mutex<BTreeMap<String, CuratedList>>,
+    resolved: Mutex<HashMap<String, (ResolvedList, SystemTime)>>,
+    revisions: Mutex<Revisions>,
+}
+
+impl CuratedLists {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All lists, ordered by slug
+    pub fn all(&self) -> Vec<CuratedList> {
+        self.lists.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn get(&self, slug: &str) -> Option<CuratedList> {
+        self.lists.lock().unwrap().get(slug).cloned()
+    }
+
+    /// Creates or replaces the list at `slug`, dropping any link to a TMDB list.
+    ///
+    /// Returns the stored list and whether it is new.
+    pub fn put(&self, slug: &str, input: CuratedListInput, now: SystemTime) -> Result<(CuratedList, bool), &'static str> {
+        self.store(slug, input, None, now)
+    }
+
+    /// Creates or replaces the list at `slug` with the contents of TMDB list
+    /// `tmdb_list_id`, keeping the link for [`CuratedLists::sync`]. Titles past
+    /// the first 50 are dropped.
+    pub fn import(
+        &self,
+        slug: &str,
+        tmdb_list_id: i64,
+        tmdb_list: TmdbList,
+        now: SystemTime
+    ) -> Result<(CuratedList, bool), &'static str> {
+        let mut items = tmdb_list.title_refs();
+        items.truncate(MAX_LIST_ITEMS);
+        let input = CuratedListInput { title: tmdb_list.name, description: tmdb_list.description, items };
+        self.store(slug, input, Some(tmdb_list_id), now)
+    }
+
+    /// Re-imports every list linked to a TMDB list and returns how many were
+    /// updated. Lists whose TMDB list can't be loaded keep their titles.
+    pub async fn sync(&self, client: &dyn TmdbClient, now: SystemTime) -> usize {
+        let linked: Vec<(String, i64)> = {
+            let lists = self.lists.lock().unwrap();
+            lists
+                .values()
+                .filter_map(|list| Some((list.slug.clone(), list.tmdb_list_id?)))
+                .collect()
+        };
+
+        let mut synced = 0;
+        for (slug, tmdb_list_id) in linked {
+            let result = client.get_list(tmdb_list_id).await;
+            // Skip lists an admin replaced or deleted while TMDB was being asked
+            if self.get(&slug).and_then(|list| list.tmdb_list_id) != Some(tmdb_list_id) {
+                continue;
+            }
+            let result = match result {
+                Ok(tmdb_list) => self.import(&slug, tmdb_list_id, tmdb_list, now).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            match result {
+                Ok(_) => synced += 1,
+                Err(error) => tracing::warn!(%slug, tmdb_list_id, %error, "curated list sync failed"),
+            }
+        }
+        synced
+    }
+
+    fn store(
+        &self,
+        slug: &str,
+        input: CuratedListInput,
+        tmdb_list_id: Option<i64>,
+        now: SystemTime
+    ) -> Result<(CuratedList, bool), &'static str> {
+        validate_slug(slug)?;
+        let title = input.title.trim();
+        if title.is_empty() {
+            return Err("title must not be empty");
+        }
+        if input.items.len() > MAX_LIST_ITEMS {
+            return Err("A list may contain at most 50 items");
+        }
+
+        let list = CuratedList {
+            slug: slug.to_string(),
+            title: title.to_string(),
+            description: input.description.map(|text| text.trim().to_string()).filter(|text| !text.is_empty()),
+            items: input.items,
+            updated_at: now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            tmdb_list_id,
+        };
+        let created = self.lists.lock().unwrap().insert(slug.to_string(), list.clone()).is_none();
+        self.resolved.lock().unwrap().remove(slug);
+        self.revisions.lock().unwrap().record(slug, false, now);
+        Ok((list, created))
+    }
+
+    /// Drops cached resolutions of lists containing the title, returning whether there were any
+    pub fn invalidate_title(&self, media_type: MediaType, id: i64) -> bool {
+        let mut resolved = self.resolved.lock().unwrap();
+        let before = resolved.len();
+        resolved.retain(|_, (list, _)| !list.items.iter().any(|item| item.media_type == media_type && item.id == id));
+        resolved.len() < before
+    }
+
+    /// Removes the list at `slug`, returning whether it existed
+    pub fn delete(&self, slug: &str, now: SystemTime) -> bool {
+        self.resolved.lock().unwrap().remove(slug);
+        let existed = self.lists.lock().unwrap().remove(slug).is_some();
+        if existed {
+            self.revisions.lock().unwrap().record(slug, true, now);
+        }
+        existed
+    }
+
+    /// Lists changed and slugs deleted after `since`, with the current
+    /// revision. Everything is sent when `since` is unset or older than the
+    /// deletions still remembered.
+    pub fn changes_since(&self, since: Option<Since>) -> (Delta<CuratedList, String>, u64) {
+        let (changed, deleted, current) = {
+            let revisions = self.revisions.lock().unwrap();
+            let since = since.filter(|since| !revisions.floor.after(*since) && !since.is_ahead_of(revisions.current));
+            let Some(since) = since else {
+                return (Delta::full(self.all()), revisions.current);
+            };
+            let after = |entries: &HashMap<String, Stamp>| -> Vec<String> {
+                entries.iter().filter(|(_, stamp)| stamp.after(since)).map(|(slug, _)| slug.clone()).collect()
+            };
+            (after(&revisions.changed), after(&revisions.deleted), revisions.current)
+        };
+
+        let lists = self.lists.lock().unwrap();
+        let mut upserted: Vec<CuratedList> = changed.iter().filter_map(|slug| lists.get(slug).cloned()).collect();
+        upserted.sort_by(|a, b| a.slug.cmp(&b.slug));
+        let mut deleted = deleted;
+        deleted.sort();
+        (Delta { full: false, upserted, deleted }, current)
+    }
+
+    /// The list at `slug` with its titles' details, or `None` if there is no such list.
+    ///
+    /// Titles TMDB doesn't know are left out. A resolution is only cached
+    /// when every other title loaded, so transient failures heal on the
+    /// next request; a zero TTL caches nothing.
+    pub async fn resolve(
+        &self,
+        client: Arc<dyn TmdbClient>,
+        slug: &str,
+        now: SystemTime,
+        ttl: Duration
+    ) -> Option<ResolvedList> {
+        let cached = {
+            let resolved = self.resolved.lock().unwrap();
+            resolved
+                .get(slug)
+                .filter(|(_, expires)| *expires > now)
+                .map(|(list, _)| list.clone())
+        };
+        if cached.is_some() {
+            return cached;
+        }
+        let list = self.get(slug)?;
+
+        let permits = Arc::new(Semaphore::new(RESOLVE_CONCURRENCY));
+        let mut tasks = JoinSet::new();
+        for (index, item) in list.items.iter().copied().enumerate() {
+            let (client, permits) = (client.clone(), permits.clone());
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                (index, client.get_title_details(item.media_type, item.id.get()).await)
+            });
+        }
+
+        let mut details = vec![None; list.items.len()];
+        let mut complete = true;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((index, Ok(found))) => details[index] = Some(found),
+                Ok((_, Err(TmdbError::NotFound))) => {}
+                _ => complete = false,
+            }
+        }
+
+        let resolved = ResolvedList {
+            slug: list.slug.clone(),
+            title: list.title,
+            description: list.description,
+            items: list
+                .items
+                .iter()
+                .zip(details)
+                .filter_map(|(item, details)| {
+                    details.map(|details| ResolvedItem { media_type: item.media_type, id: item.id.get(), details })
+                })
+                .collect(),
+        };
+
+        // Skip caching if the list changed while it was being resolved
+        let unchanged = self.get(slug).is_some_and(|current| current.items == list.items);
+        if complete && unchanged && !ttl.is_zero() {
+            self.resolved.lock().unwrap().insert(list.slug, (resolved.clone(), now + ttl));
+        }
+        Some(resolved)
+    }
+}
@@ -0,0 +1,218 @@
+// src/logging.rs
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+
+/// Base name of log files written to `LOG_DIR`
+const LOG_FILE_NAME: &str = "netflix-service.log";
+
+const DEFAULT_LOG_DIR: &str = "logs";
+const DEFAULT_MAX_FILES: usize = 7;
+const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// When a log file is closed and a new one started
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogRotation {
+    Daily,
+    Hourly,
+    Never,
+    /// Rotate once the current file reaches this many bytes
+    Size(u64),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogOutput {
+    Stdout,
+    File {
+        dir: PathBuf,
+        rotation: LogRotation,
+        /// Number of rotated files kept; older files are deleted
+        max_files: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogConfig {
+    pub output: LogOutput,
+    pub json: bool,
+}
+
+impl LogConfig {
+    /// Reads the logging configuration from environment variables:
+    ///
+    /// - `LOG_OUTPUT`: `stdout` (default) or `file`
+    /// - `LOG_FORMAT`: `text` (default) or `json`
+    /// - `LOG_DIR`: directory for log files (default `logs`)
+    /// - `LOG_ROTATION`: `daily` (default), `hourly`, `never` or `size`
+    /// - `LOG_MAX_FILE_BYTES`: size limit for `size` rotation (default 10 MiB)
+    /// - `LOG_MAX_FILES`: rotated files to keep (default 7)
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Same as [`LogConfig::from_env`] but reading values through `lookup`
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let json = match lookup("LOG_FORMAT").as_deref() {
+            None | Some("text") => false,
+            Some("json") => true,
+            Some(other) => return Err(format!("unknown LOG_FORMAT '{}'", other)),
+        };
+
+        let output = match lookup("LOG_OUTPUT").as_deref() {
+            None | Some("stdout") => LogOutput::Stdout,
+            Some("file") => {
+                let max_files = parse_number(&lookup, "LOG_MAX_FILES", DEFAULT_MAX_FILES)?;
+                let rotation = match lookup("LOG_ROTATION").as_deref() {
+                    None | Some("daily") => LogRotation::Daily,
+                    Some("hourly") => LogRotation::Hourly,
+                    Some("never") => LogRotation::Never,
+                    Some("size") => LogRotation::Size(parse_number(
+                        &lookup,
+                        "LOG_MAX_FILE_BYTES",
+                        DEFAULT_MAX_FILE_BYTES,
+                    )?),
+                    Some(other) => return Err(format!("unknown LOG_ROTATION '{}'", other)),
+                };
+                LogOutput::File {
+                    dir: lookup("LOG_DIR").unwrap_or_else(|| DEFAULT_LOG_DIR.to_string()).into(),
+                    rotation,
+                    max_files,
+                }
+            }
+            Some(other) => return Err(format!("unknown LOG_OUTPUT '{}'", other)),
+        };
+
+        Ok(Self { output, json })
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    key: &str,
+    default: T
+) -> Result<T, String> {
+    match lookup(key) {
+        None => Ok(default),
+        Some(value) => value.parse().map_err(|_| format!("{} must be a number, got '{}'", key, value)),
+    }
+}
+
+/// Installs the global tracing subscriber. Filtering follows `RUST_LOG`,
+/// defaulting to `info`.
+///
+/// File output is written on a background thread; keep the returned guard
+/// alive for the lifetime of the process so buffered lines are flushed on exit.
+pub fn init(config: &LogConfig) -> io::Result<Option<WorkerGuard>> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    let LogOutput::File { dir, rotation, max_files } = &config.output else {
+        if config.json {
+            builder.json().init();
+        } else {
+            builder.init();
+        }
+        return Ok(None);
+    };
+
+    let (writer, guard) = match rotation {
+        LogRotation::Size(max_bytes) => {
+            tracing_appender::non_blocking(SizeRotatingFile::new(dir, *max_bytes, *max_files)?)
+        }
+        time_based => {
+            let rotation = match time_based {
+                LogRotation::Hourly => Rotation::HOURLY,
+                LogRotation::Never => Rotation::NEVER,
+                _ => Rotation::DAILY,
+            };
+            let appender = RollingFileAppender::builder()
+                .rotation(rotation)
+                .filename_prefix(LOG_FILE_NAME)
+                .max_log_files(*max_files)
+                .build(dir)
+                .map_err(io::Error::other)?;
+            tracing_appender::non_blocking(appender)
+        }
+    };
+
+    let builder = builder.with_writer(writer).with_ansi(false);
+    if config.json {
+        builder.json().init();
+    } else {
+        builder.init();
+    }
+    Ok(Some(guard))
+}
+
+/// Log file that rolls over once it reaches `max_bytes`.
+///
+/// The active file is `netflix-service.log`; on rotation it becomes
+/// `netflix-service.log.1`, existing backups shift up by one, and anything
+/// beyond `max_files` backups is deleted.
+pub struct SizeRotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl SizeRotatingFile {
+    pub fn new(dir: impl AsRef<Path>, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let file = open_append(&dir.join(LOG_FILE_NAME))?;
+        let written = file.metadata()?.len();
+
+        Ok(Self { dir, max_bytes, max_files, file, written })
+    }
+
+    fn backup_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            fs::remove_file(self.dir.join(LOG_FILE_NAME))?;
+        } else {
+            let _ = fs::remove_file(self.backup_path(self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = self.backup_path(index);
+                if from.exists() {
+                    fs::rename(from, self.backup_path(index + 1))?;
+                }
+            }
+            fs::rename(self.dir.join(LOG_FILE_NAME), self.backup_path(1))?;
+        }
+
+        self.file = open_append(&self.dir.join(LOG_FILE_NAME))?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Rotate before a write that would cross the limit, so lines are never split
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
@@ -0,0 +1,120 @@
+// src/logos.rs
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::models::{Image, ImageLink, ImagesResponse, MediaType};
+use crate::tmdb_client::TmdbClient;
+
+/// Maximum number of cached logo choices; expired entries are dropped first.
+const CAPACITY: usize = 10_000;
+
+/// Language used when a logo in the requested language is missing
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+type LogoKey = (MediaType, i64, String);
+
+/// Picks the logo to show over a title's backdrop.
+///
+/// Logos in `language` beat English ones, which beat logos without a
+/// language; logos in any other language are never picked. Within a tier,
+/// PNGs beat SVGs since every client can render them, then higher rated
+/// and more voted logos win. Ties keep TMDB's order.
+pub fn best_logo<'a>(images: &'a ImagesResponse, language: &str) -> Option<&'a Image> {
+    let tier = |image: &Image| match image.iso_639_1.as_deref() {
+        Some(lang) if lang == language => Some(0),
+        Some(FALLBACK_LANGUAGE) => Some(1),
+        None => Some(2),
+        Some(_) => None,
+    };
+
+    images
+        .logos
+        .iter()
+        .filter_map(|image| tier(image).map(|tier| (tier, image)))
+        .min_by(|(tier_a, a), (tier_b, b)| {
+            tier_a
+                .cmp(tier_b)
+                .then_with(|| b.file_path.ends_with(".png").cmp(&a.file_path.ends_with(".png")))
+                .then_with(|| b.vote_average.total_cmp(&a.vote_average))
+                .then_with(|| b.vote_count.cmp(&a.vote_count))
+        })
+        .map(|(_, image)| image)
+}
+
+/// Remembers the chosen logo per title and language, including titles
+/// without a suitable logo, so repeat lookups skip the images call.
+#[derive(Default)]
+pub struct LogoCache {
+    entries: Mutex<HashMap<LogoKey, (Option<ImageLink>, SystemTime)>>,
+}
+
+impl LogoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Titles with a fresh entry at `now`
+    pub fn titles(&self, now: SystemTime) -> HashSet<(MediaType, i64)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|(_, (_, expires))| *expires > now)
+            .map(|((media_type, id, _), _)| (*media_type, *id))
+            .collect()
+    }
+
+    /// Returns the cached choice, `Some(None)` meaning "no logo", unless expired
+    pub fn get(&self, media_type: MediaType, id: i64, language: &str, now: SystemTime) -> Option<Option<ImageLink>> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&(media_type, id, language.to_string()))
+            .filter(|(_, expires)| *expires > now)
+            .map(|(logo, _)| logo.clone())
+    }
+
+    /// Caches `logo` for `ttl`; a zero TTL caches nothing
+    pub fn store(&self, media_type: MediaType, id: i64, language: &str, logo: Option<ImageLink>, now: SystemTime, ttl: Duration) {
+        if ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= CAPACITY {
+            entries.retain(|_, (_, expires)| *expires > now);
+            if entries.len() >= CAPACITY {
+                return;
+            }
+        }
+        entries.insert((media_type, id, language.to_string()), (logo, now + ttl));
+    }
+
+    /// Drops the title's logo in every language, returning whether there was one
+    pub fn invalidate(&self, media_type: MediaType, id: i64) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|(entry_type, entry_id, _), _| (*entry_type, *entry_id) != (media_type, id));
+        entries.len() < before
+    }
+
+    /// Cached or freshly selected logo for a title.
+    ///
+    /// Failures to load images are not cached and yield no logo, since a
+    /// logo is decoration and must not fail the surrounding response.
+    pub async fn lookup(
+        &self,
+        client: &dyn TmdbClient,
+        media_type: MediaType,
+        id: i64,
+        language: &str,
+        now: SystemTime,
+        ttl: Duration
+    ) -> Option<ImageLink> {
+        if let Some(logo) = self.get(media_type, id, language, now) {
+            return logo;
+        }
+
+        let images = client.get_images(media_type, id).await.ok()?;
+        let logo = best_logo(&images, language).map(ImageLink::from);
+        self.store(media_type, id, language, logo.clone(), now, ttl);
+        logo
+    }
+}
@@ -1,35 +1,347 @@
 // src/main.rs
-use axum::{routing::get, Router};
+use axum::middleware;
 use dotenv::dotenv;
-use std::{env, sync::Arc};
-use tower_http::{cors::CorsLayer, services::ServeDir};
-use netflix_service::{handlers, state::AppState, tmdb_client::RealTmdbClient};
+use std::{env, future::IntoFuture, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::sync::watch;
+#[cfg(feature = "alloc-stats")]
+use netflix_service::profiling::CountingAllocator;
+use netflix_service::{analytics::parse_window, app::{self, AppOptions}, bench::{self, BenchConfig}, blocklist::Blocklist, budget::{BudgetLimits, RequestBudget}, cache_policy::CachePolicies, changes, clock::{Clock, SystemClock}, compression::{CompressionMode, UpstreamCompression}, dev_client::DevTmdbClient, experiments::Experiments, home::{DiversityLimits, HomeLayout}, logging::{self, LogConfig}, metadata::{MetadataChains, MetadataProvider, MetadataSources, OmdbMetadata}, mirrors::Mirrors, mode::ModeSwitch, mtls::{ClientCertPolicy, RoleMap}, negative_cache::{NegativeCache, NegativeCacheTtls}, new_on, outage::{self, OutageMonitor, StatuspageFeed}, push::PushNotifier, quality::QualityPriors, ranking::RankingStrategies, saved_searches::DEFAULT_REFRESH_INTERVAL, session::{SessionStore, DEFAULT_SESSION_TTL}, shaping::DevicePresets, shutdown::{self, DEFAULT_SHUTDOWN_GRACE}, signing::ResponseSigner, slo::SloTracker, slow_log::{SlowLog, SlowThresholds}, startup::StartupSummary, state::AppState, tags::TagRules, themes::ThemedRows, tls::{self, TlsListener, TlsPeer, TlsSettings}, tmdb_client::{RealTmdbClient, TmdbClient}, upstream_metrics::UpstreamMetrics};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    let api_key = env::var("TMDB_API_KEY").expect("TMDB_API_KEY must be set in .env");
+    let log_config = LogConfig::from_env().expect("invalid logging configuration");
+    let _log_guard = logging::init(&log_config).expect("failed to initialise logging");
+
+    // `netflix-service bench [options]` load-tests a running instance instead of serving
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "bench") {
+        let config = BenchConfig::from_args(&args[1..]).unwrap_or_else(|error| {
+            eprintln!("bench: {}", error);
+            std::process::exit(2);
+        });
+        println!("{}", bench::run(config).await);
+        return;
+    }
 
-    let tmdb_client = Arc::new(RealTmdbClient::new(api_key));
+    // `netflix-service dev-seed` serves a bundled fixture catalog, offline and without an API key
+    let dev_seed = args.first().is_some_and(|command| command == "dev-seed");
 
-    let state = AppState {
-        tmdb_client,
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    let slow_log = Arc::new(SlowLog::new(SlowThresholds::from_env()));
+    let cache_policies = env::var("CACHE_ROUTES")
+        .map(|spec| CachePolicies::parse(&spec).expect("CACHE_ROUTES must look like trending=300s,search=60s,negative=30s"))
+        .unwrap_or_default();
+    // NEGATIVE_CACHE_TTLS sets video and details misses separately and wins over CACHE_ROUTES
+    let negative_ttls = env::var("NEGATIVE_CACHE_TTLS")
+        .map(|spec| NegativeCacheTtls::parse(&spec).expect("NEGATIVE_CACHE_TTLS must look like videos=30s,details=30s"))
+        .unwrap_or_else(|_| cache_policies.negative_ttls());
+    let negative_cache = Arc::new(NegativeCache::new(negative_ttls, clock.clone()));
+    let upstream_metrics = Arc::new(UpstreamMetrics::new());
+    let mut mode_switch = ModeSwitch::new();
+    if let Ok(secs) = env::var("MAINTENANCE_RETRY_AFTER_SECS") {
+        let secs = secs.parse().expect("MAINTENANCE_RETRY_AFTER_SECS must be a number");
+        mode_switch = mode_switch.with_maintenance_retry_after(Duration::from_secs(secs));
+    }
+    let mode_switch = Arc::new(mode_switch);
+    // Lets a deploy start in maintenance, e.g. while a migration runs
+    if let Ok(mode) = env::var("SERVICE_MODE") {
+        mode_switch.set(mode.parse().expect("SERVICE_MODE must be normal, degraded, cache_only or maintenance"), clock.now());
+    }
+    let compression = env::var("TMDB_COMPRESSION")
+        .map(|mode| mode.parse().expect("TMDB_COMPRESSION must be auto, gzip or off"))
+        .unwrap_or(CompressionMode::Auto);
+    let compression = Arc::new(UpstreamCompression::new(compression));
+    let mirrors = Arc::new(Mirrors::parse(&env::var("TMDB_BASE_URLS").unwrap_or_default(), clock.clone()));
+    let tmdb_client: Arc<dyn TmdbClient> = if dev_seed {
+        let client = DevTmdbClient::new();
+        tracing::info!("Serving {} fixture titles instead of TMDB", client.len());
+        Arc::new(client)
+    } else {
+        let api_key = env::var("TMDB_API_KEY").expect("TMDB_API_KEY must be set in .env");
+        let mut real_client = RealTmdbClient::new(api_key)
+            .with_mirrors(mirrors.clone())
+            .with_compression(compression.clone());
+        if let Ok(millis) = env::var("TMDB_TIMEOUT_MS") {
+            let millis = millis.parse().expect("TMDB_TIMEOUT_MS must be a number");
+            real_client = real_client.with_timeout(Duration::from_millis(millis));
+        }
+        Arc::new(real_client)
     };
+    let mut state = app::decorate_upstream(
+        AppState::new(tmdb_client)
+            .with_clock(clock)
+            .with_slow_log(slow_log)
+            .with_negative_cache(negative_cache)
+            .with_upstream_metrics(upstream_metrics)
+            .with_mirrors(mirrors)
+            .with_compression(compression)
+            .with_mode_switch(mode_switch)
+            .with_budget(RequestBudget::new(BudgetLimits::from_env()))
+            .with_push(PushNotifier::from_env())
+            .with_cache_policies(cache_policies),
+    );
+    if let Ok(admin_token) = env::var("ADMIN_TOKEN") {
+        state = state.with_admin_token(admin_token);
+    }
+    if let Ok(url) = env::var("PUBLIC_BASE_URL") {
+        state = state.with_public_base_url(url);
+    }
+    if let Ok(secret) = env::var("SESSION_SECRET") {
+        state = state.with_sessions(SessionStore::new(secret, DEFAULT_SESSION_TTL));
+    }
+    if let Ok(chars) = env::var("SEARCH_MIN_QUERY_CHARS") {
+        let chars = chars.parse().expect("SEARCH_MIN_QUERY_CHARS must be a number");
+        state = state.with_min_search_query_chars(chars);
+    }
+    if let Ok(presets) = env::var("DEVICE_PRESETS") {
+        let presets = DevicePresets::from_json(&presets).expect("DEVICE_PRESETS must be a JSON object of device presets");
+        state = state.with_device_presets(presets);
+    }
+    if let Ok(warnings) = env::var("CONTENT_WARNINGS") {
+        let rules = TagRules::parse_content_warnings(&warnings).expect("CONTENT_WARNINGS must look like suicide=suicide,rape=sexual violence");
+        state = state.with_tag_rules(rules);
+    }
+    if let Ok(priors) = env::var("QUALITY_PRIORS") {
+        let priors = QualityPriors::parse(&priors).expect("QUALITY_PRIORS must look like mean=6.5,votes=250");
+        state = state.with_quality_priors(priors);
+    }
+    if let Ok(strategies) = env::var("RANKING_STRATEGIES") {
+        let strategies = RankingStrategies::from_json(&strategies)
+            .expect("RANKING_STRATEGIES must map strategy names to chains such as popularity=1,recency=0.5");
+        state = state.with_ranking_strategies(strategies);
+    }
+    if let Ok(layout) = env::var("HOME_LAYOUT") {
+        let layout = HomeLayout::from_json(&layout).expect("HOME_LAYOUT must be a JSON object with a rows array");
+        state = state.with_home_layout(layout);
+    }
+    if let Ok(limits) = env::var("HOME_DIVERSITY") {
+        let limits = DiversityLimits::parse(&limits).expect("HOME_DIVERSITY must look like max_appearances=1,max_per_genre=4");
+        state = state.with_home_diversity(limits);
+    }
+    if let Ok(themes) = env::var("THEMED_ROWS") {
+        let themes = ThemedRows::from_json(&themes, state.clock.now()).expect("THEMED_ROWS must be a JSON array of themed rows");
+        state = state.with_themes(themes);
+    }
+    if let Ok(rules) = env::var("BLOCKLIST") {
+        let blocklist = Blocklist::from_json(&rules).expect("BLOCKLIST must be a JSON object with titles and keywords");
+        state = state.with_blocklist(blocklist);
+    }
+    if let Ok(policy) = env::var("ADULT_CONTENT_POLICY") {
+        let policy = policy.parse().expect("ADULT_CONTENT_POLICY must be always_off, user_choice or profile_based");
+        state = state.with_adult_content_policy(policy);
+    }
+    // Other metadata sources per field group, e.g. METADATA_SOURCES=ratings=omdb+tmdb with OMDB_API_KEY
+    if let Ok(spec) = env::var("METADATA_SOURCES") {
+        let chains = MetadataChains::parse(&spec).expect("METADATA_SOURCES must look like ratings=omdb+tmdb,text=tmdb+omdb");
+        let mut providers: Vec<Arc<dyn MetadataProvider>> = Vec::new();
+        if let Ok(api_key) = env::var("OMDB_API_KEY") {
+            providers.push(Arc::new(OmdbMetadata::new(api_key, state.cache_policies.details).with_clock(state.clock.clone())));
+        }
+        let sources = MetadataSources::new(chains, providers).expect("METADATA_SOURCES names a source that isn't configured");
+        state = state.with_metadata_sources(sources);
+    }
+    // A Statuspage status.json read by the outage monitor next to its own probes
+    if let Ok(url) = env::var("TMDB_STATUS_URL") {
+        state = state.with_outage_monitor(OutageMonitor::new().with_status_page(Arc::new(StatuspageFeed::new(url))));
+    }
+    // Ed25519 response signing from a base64 32-byte seed; earlier keys stay published while clients catch up
+    if let Ok(seed) = env::var("RESPONSE_SIGNING_KEY") {
+        let mut signer = ResponseSigner::from_base64_seed(&seed).expect("RESPONSE_SIGNING_KEY must be a base64 32-byte Ed25519 seed");
+        if let Ok(retired) = env::var("RESPONSE_SIGNING_RETIRED_KEYS") {
+            for key in retired.split(',').filter(|key| !key.trim().is_empty()) {
+                signer = signer
+                    .with_retired_key(key)
+                    .expect("RESPONSE_SIGNING_RETIRED_KEYS must be comma separated base64 32-byte public keys");
+            }
+        }
+        state = state.with_response_signer(signer);
+    }
+    // Client certificate SANs allowed on internal routes, e.g. CLIENT_CERT_ROLES=spiffe://mesh/ns/ops/*=admin
+    if let Ok(roles) = env::var("CLIENT_CERT_ROLES") {
+        assert!(env::var("TLS_CLIENT_CA").is_ok(), "CLIENT_CERT_ROLES needs TLS_CERT, TLS_KEY and TLS_CLIENT_CA");
+        let roles = RoleMap::parse(&roles).expect("CLIENT_CERT_ROLES must look like spiffe://mesh/ns/ops/*=admin,monitor.internal=metrics");
+        state = state.with_client_cert_policy(ClientCertPolicy::new(roles));
+    }
+    if let Ok(slos) = env::var("SLOS") {
+        let slos = SloTracker::from_json(&slos).expect("SLOS must be a JSON array of route objectives");
+        state = state.with_slos(slos);
+    }
+    if let Ok(definitions) = env::var("EXPERIMENTS") {
+        let experiments = Experiments::from_json(&definitions).expect("EXPERIMENTS must be a JSON array of experiments");
+        state = state.with_experiments(experiments);
+    }
+    // Re-imports curated lists linked to a TMDB list, e.g. LIST_SYNC_INTERVAL=6h
+    if let Ok(interval) = env::var("LIST_SYNC_INTERVAL") {
+        let interval = parse_window(&interval)
+            .filter(|interval| !interval.is_zero())
+            .expect("LIST_SYNC_INTERVAL must look like 30m, 6h or 1d");
+        let (lists, client, clock) = (state.lists.clone(), state.tmdb_client.clone(), state.clock.clone());
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            // The first tick completes immediately; lists were just created empty
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                lists.sync(client.as_ref(), clock.now()).await;
+            }
+        });
+    }
+
+    // Re-runs saved searches to notify about new matches, e.g. SAVED_SEARCH_INTERVAL=1d
+    let refresh_interval = env::var("SAVED_SEARCH_INTERVAL")
+        .map(|interval| {
+            parse_window(&interval)
+                .filter(|interval| !interval.is_zero())
+                .expect("SAVED_SEARCH_INTERVAL must look like 30m, 6h or 1d")
+        })
+        .unwrap_or(DEFAULT_REFRESH_INTERVAL);
+    let (saved_searches, client, clock) = (state.saved_searches.clone(), state.tmdb_client.clone(), state.clock.clone());
+    let (blocklist, push) = (state.blocklist.clone(), state.push.clone());
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(refresh_interval);
+        loop {
+            ticks.tick().await;
+            let created = saved_searches.refresh(client.as_ref(), &blocklist, clock.now()).await;
+            if created > 0 {
+                tracing::info!(created, "saved search notifications created");
+                let delivered = push.deliver(&saved_searches, clock.now()).await;
+                tracing::info!(delivered, "saved search notifications pushed");
+            }
+        }
+    });
 
-    let cors = CorsLayer::new().allow_origin(tower_http::cors::Any);
+    // Drops cached data of titles changed on TMDB, e.g. CHANGES_POLL_INTERVAL=30m
+    let poll_interval = env::var("CHANGES_POLL_INTERVAL")
+        .map(|interval| {
+            parse_window(&interval)
+                .filter(|interval| !interval.is_zero())
+                .expect("CHANGES_POLL_INTERVAL must look like 30m, 6h or 1d")
+        })
+        .unwrap_or(changes::DEFAULT_POLL_INTERVAL);
+    let poll_state = state.clone();
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(poll_interval);
+        // The first tick completes immediately; caches were just created empty
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            if let Err(error) = poll_state.changes.poll(&poll_state).await {
+                tracing::warn!(%error, "change feed poll failed");
+            }
+        }
+    });
 
-    let app = Router::new()
-        .route("/", get(handlers::root))
-        .route("/api/trending", get(handlers::get_trending_movies))
-        .route("/api/search", get(handlers::search_content))
-        .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
-        .nest_service("/stream", ServeDir::new("assets"))
-        .layer(cors)
-        .with_state(state);
+    // Keeps cached "New on <provider>" rows current, e.g. NEW_ON_REFRESH_INTERVAL=1h
+    let refresh_interval = env::var("NEW_ON_REFRESH_INTERVAL")
+        .map(|interval| {
+            parse_window(&interval)
+                .filter(|interval| !interval.is_zero())
+                .expect("NEW_ON_REFRESH_INTERVAL must look like 30m, 6h or 1d")
+        })
+        .unwrap_or(new_on::DEFAULT_REFRESH_INTERVAL);
+    let new_on_state = state.clone();
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(refresh_interval);
+        // The first tick completes immediately; rows were just created empty
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            let state = &new_on_state;
+            let refreshed = state.new_on.refresh(state.tmdb_client.as_ref(), state.clock.now(), state.cache_policies.new_on).await;
+            tracing::debug!(refreshed, "new-on rows refreshed");
+        }
+    });
 
-    //let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
-    println!("Server listening on http://{}", listener.local_addr().unwrap());
+    // Switches to degraded mode during TMDB outages, e.g. OUTAGE_CHECK_INTERVAL=30s
+    let check_interval = env::var("OUTAGE_CHECK_INTERVAL")
+        .map(|interval| {
+            parse_window(&interval)
+                .filter(|interval| !interval.is_zero())
+                .expect("OUTAGE_CHECK_INTERVAL must look like 30s, 1m or 5m")
+        })
+        .unwrap_or(outage::DEFAULT_CHECK_INTERVAL);
+    let outage_state = state.clone();
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(check_interval);
+        loop {
+            ticks.tick().await;
+            outage_state.outage.check(&outage_state).await;
+        }
+    });
 
-    axum::serve(listener, app).await.unwrap();
-}
\ No newline at end of file
+    let options = AppOptions::from_env();
+    let mut summary = StartupSummary::new(&state, &options, |key| env::var(key).ok());
+    if dev_seed {
+        summary = summary.with_dev_seed();
+    }
+    let app = app::router(state, &options);
+
+    // PORT is set by Heroku and Cloud Run; HOST=127.0.0.1 keeps the service local
+    let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
+    let port: u16 = env::var("PORT").map(|port| port.parse().expect("PORT must be a port number")).unwrap_or(8080);
+    let grace = env::var("SHUTDOWN_GRACE_SECONDS")
+        .map(|secs| Duration::from_secs(secs.parse().expect("SHUTDOWN_GRACE_SECONDS must be a number")))
+        .unwrap_or(DEFAULT_SHUTDOWN_GRACE);
+    // TLS_CERT and TLS_KEY serve HTTPS; TLS_CLIENT_CA also asks clients for certificates, checked against TLS_CRL
+    let tls_settings = env::var("TLS_CERT").ok().map(|cert_path| TlsSettings {
+        cert_path: cert_path.into(),
+        key_path: env::var("TLS_KEY").expect("TLS_CERT needs TLS_KEY").into(),
+        client_ca_path: env::var("TLS_CLIENT_CA").ok().map(Into::into),
+        crl_paths: env::var("TLS_CRL")
+            .map(|paths| paths.split(',').filter(|path| !path.trim().is_empty()).map(|path| path.trim().into()).collect())
+            .unwrap_or_default(),
+    });
+    let listener = tokio::net::TcpListener::bind((host.as_str(), port)).await.unwrap();
+    summary.with_bind(listener.local_addr().unwrap()).with_shutdown_grace(grace).log();
+    let scheme = if tls_settings.is_some() { "https" } else { "http" };
+    tracing::info!("Server listening on {}://{}", scheme, listener.local_addr().unwrap());
+
+    let (stop, stopping) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown::signal().await;
+        tracing::info!("Shutting down, waiting up to {}s for in-flight requests", grace.as_secs());
+        let _ = stop.send(true);
+    });
+    let served = match tls_settings {
+        Some(settings) => {
+            let (listener, reloader) = TlsListener::new(listener, settings).expect("TLS_CERT, TLS_KEY, TLS_CLIENT_CA and TLS_CRL must be readable PEM files");
+            // Picks up renewed certificates and revocation lists, e.g. TLS_RELOAD_INTERVAL=1m
+            let reload_interval = env::var("TLS_RELOAD_INTERVAL")
+                .map(|interval| {
+                    parse_window(&interval)
+                        .filter(|interval| !interval.is_zero())
+                        .expect("TLS_RELOAD_INTERVAL must look like 30s, 5m or 1h")
+                })
+                .unwrap_or(tls::DEFAULT_RELOAD_INTERVAL);
+            tokio::spawn(async move {
+                let mut ticks = tokio::time::interval(reload_interval);
+                // The first tick completes immediately; the files were just read
+                ticks.tick().await;
+                loop {
+                    ticks.tick().await;
+                    if let Err(error) = reloader.reload() {
+                        tracing::error!(%error, "TLS reload failed, keeping the previous certificates");
+                    }
+                }
+            });
+            let app = app.layer(middleware::from_fn(tls::peer_middleware));
+            let server = axum::serve(listener, app.into_make_service_with_connect_info::<TlsPeer>())
+                .with_graceful_shutdown(shutdown::stopped(stopping.clone()));
+            shutdown::with_grace(server.into_future(), shutdown::stopped(stopping), grace).await
+        }
+        None => {
+            let server = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(shutdown::stopped(stopping.clone()));
+            shutdown::with_grace(server.into_future(), shutdown::stopped(stopping), grace).await
+        }
+    };
+    match served {
+        Ok(result) => result.unwrap(),
+        Err(_) => tracing::warn!("Shutdown grace period elapsed, dropping open connections"),
+    }
+}
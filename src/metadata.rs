@@ -0,0 +1,377 @@
+// src/metadata.rs
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use crate::clock::{Clock, SystemClock};
+use crate::error::TmdbError;
+use crate::models::{MediaType, TitleDetails};
+
+/// Name of the TMDB source in `METADATA_SOURCES`
+pub const TMDB: &str = "tmdb";
+
+const OMDB_BASE_URL: &str = "https://www.omdbapi.com";
+
+/// Fields of a title that are always taken from the same sources
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldGroup {
+    /// Title, tagline and overview
+    Text,
+    /// Vote average and vote count
+    Ratings,
+    /// Release or first air date and runtime
+    Release,
+}
+
+impl FieldGroup {
+    pub const ALL: [FieldGroup; 3] = [FieldGroup::Text, FieldGroup::Ratings, FieldGroup::Release];
+}
+
+impl FromStr for FieldGroup {
+    type Err = String;
+
+    fn from_str(group: &str) -> Result<Self, Self::Err> {
+        match group.trim() {
+            "text" => Ok(FieldGroup::Text),
+            "ratings" => Ok(FieldGroup::Ratings),
+            "release" => Ok(FieldGroup::Release),
+            other => Err(format!("unknown field group '{}'", other)),
+        }
+    }
+}
+
+/// A title's metadata in a form every source can provide; `None` where a
+/// source has nothing
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TitleMetadata {
+    pub title: Option<String>,
+    pub tagline: Option<String>,
+    pub overview: Option<String>,
+    pub vote_average: Option<f64>,
+    pub vote_count: Option<u32>,
+    /// Release date of movies, first air date of TV shows
+    pub release_date: Option<NaiveDate>,
+    /// Minutes
+    pub runtime: Option<i32>,
+}
+
+impl TitleMetadata {
+    /// Sets the fields of `group` that are still empty from `other`
+    fn fill(&mut self, group: FieldGroup, other: &TitleMetadata) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                field.clone_from(other);
+            }
+        }
+        match group {
+            FieldGroup::Text => {
+                fill(&mut self.title, &other.title);
+                fill(&mut self.tagline, &other.tagline);
+                fill(&mut self.overview, &other.overview);
+            }
+            FieldGroup::Ratings => {
+                fill(&mut self.vote_average, &other.vote_average);
+                fill(&mut self.vote_count, &other.vote_count);
+            }
+            FieldGroup::Release => {
+                fill(&mut self.release_date, &other.release_date);
+                fill(&mut self.runtime, &other.runtime);
+            }
+        }
+    }
+
+    /// Replaces the fields of `group` in `details`
+    fn apply(self, group: FieldGroup, media_type: MediaType, details: &mut TitleDetails) {
+        match group {
+            FieldGroup::Text => {
+                match media_type {
+                    MediaType::Movie => details.title = self.title,
+                    MediaType::Tv => details.name = self.title,
+                }
+                details.tagline = self.tagline;
+                details.overview = self.overview;
+            }
+            FieldGroup::Ratings => {
+                details.vote_average = self.vote_average;
+                details.vote_count = self.vote_count;
+            }
+            FieldGroup::Release => {
+                match media_type {
+                    MediaType::Movie => details.release_date = self.release_date,
+                    MediaType::Tv => details.first_air_date = self.release_date,
+                }
+                details.runtime = self.runtime;
+            }
+        }
+    }
+}
+
+impl From<&TitleDetails> for TitleMetadata {
+    /// TMDB sends blank text for missing taglines and overviews, which counts as missing
+    fn from(details: &TitleDetails) -> Self {
+        let text = |text: &Option<String>| text.clone().filter(|text| !text.trim().is_empty());
+        Self {
+            title: text(&details.title).or_else(|| text(&details.name)),
+            tagline: text(&details.tagline),
+            overview: text(&details.overview),
+            vote_average: details.vote_average,
+            vote_count: details.vote_count,
+            release_date: details.release_date.or(details.first_air_date),
+            runtime: details.runtime,
+        }
+    }
+}
+
+/// A source of title metadata. Titles are keyed by TMDB throughout the
+/// service, so sources get the TMDB record and find their own through its
+/// ids, e.g. [`TitleDetails::external_ids`]. Errors use [`TmdbError`], which
+/// describes any HTTP upstream.
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    /// Name used in `METADATA_SOURCES`
+    fn name(&self) -> &'static str;
+
+    /// The source's metadata of `title`, `None` when it doesn't know it
+    async fn lookup(&self, media_type: MediaType, title: &TitleDetails) -> Result<Option<TitleMetadata>, TmdbError>;
+}
+
+/// TMDB as a metadata source, answered from the record details were loaded with
+pub struct TmdbMetadata;
+
+#[async_trait]
+impl MetadataProvider for TmdbMetadata {
+    fn name(&self) -> &'static str {
+        TMDB
+    }
+
+    async fn lookup(&self, _media_type: MediaType, title: &TitleDetails) -> Result<Option<TitleMetadata>, TmdbError> {
+        Ok(Some(TitleMetadata::from(title)))
+    }
+}
+
+/// The OMDb API, looked up by IMDb id. Its free tier allows 1,000 requests
+/// a day, so answers, including unknown titles, are kept for a TTL.
+pub struct OmdbMetadata {
+    api_key: String,
+    base_url: String,
+    client: reqwest::Client,
+    ttl: Duration,
+    clock: Arc<dyn Clock>,
+    cache: Mutex<HashMap<String, (SystemTime, Option<TitleMetadata>)>>,
+}
+
+impl OmdbMetadata {
+    pub fn new(api_key: String, ttl: Duration) -> Self {
+        Self {
+            api_key,
+            base_url: OMDB_BASE_URL.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("OMDb HTTP client should build"),
+            ttl,
+            clock: Arc::new(SystemClock),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    async fn fetch(&self, imdb_id: &str) -> Result<Option<TitleMetadata>, TmdbError> {
+        let response = self
+            .client
+            .get(&self.base_url)
+            .query(&[("apikey", self.api_key.as_str()), ("i", imdb_id), ("plot", "short")])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(TmdbError::from_status(status, response.text().await.unwrap_or_default()));
+        }
+        let title: OmdbTitle = serde_json::from_slice(&response.bytes().await?)?;
+        if title.response != "True" {
+            let error = title.error.unwrap_or_default();
+            // OMDb answers 200 for every failure and tells them apart by message
+            return match error.as_str() {
+                "Incorrect IMDb ID." | "Movie not found!" | "Series not found!" => Ok(None),
+                "Request limit reached!" => Err(TmdbError::RateLimitExceeded),
+                "Invalid API key!" | "No API key provided." => Err(TmdbError::Unauthorized),
+                _ => Err(TmdbError::Unknown(200, error)),
+            };
+        }
+        Ok(Some(title.into()))
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for OmdbMetadata {
+    fn name(&self) -> &'static str {
+        "omdb"
+    }
+
+    async fn lookup(&self, _media_type: MediaType, title: &TitleDetails) -> Result<Option<TitleMetadata>, TmdbError> {
+        let Some(imdb_id) = title.external_ids.imdb_id.as_deref().filter(|id| !id.is_empty()) else {
+            return Ok(None);
+        };
+        let now = self.clock.now();
+        if let Some((fetched_at, metadata)) = self.cache.lock().unwrap().get(imdb_id)
+            && now.duration_since(*fetched_at).is_ok_and(|age| age < self.ttl)
+        {
+            return Ok(metadata.clone());
+        }
+
+        let metadata = self.fetch(imdb_id).await?;
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|_, (fetched_at, _)| now.duration_since(*fetched_at).is_ok_and(|age| age < self.ttl));
+        cache.insert(imdb_id.to_string(), (now, metadata.clone()));
+        Ok(metadata)
+    }
+}
+
+/// OMDb payload; every value is a string and missing ones are `N/A`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OmdbTitle {
+    response: String,
+    error: Option<String>,
+    title: Option<String>,
+    plot: Option<String>,
+    released: Option<String>,
+    runtime: Option<String>,
+    #[serde(rename = "imdbRating")]
+    imdb_rating: Option<String>,
+    #[serde(rename = "imdbVotes")]
+    imdb_votes: Option<String>,
+}
+
+impl From<OmdbTitle> for TitleMetadata {
+    fn from(title: OmdbTitle) -> Self {
+        let value = |value: Option<String>| value.filter(|value| !value.is_empty() && value != "N/A");
+        Self {
+            title: value(title.title),
+            tagline: None,
+            overview: value(title.plot),
+            vote_average: value(title.imdb_rating).and_then(|rating| rating.parse().ok()),
+            vote_count: value(title.imdb_votes).and_then(|votes| votes.replace(',', "").parse().ok()),
+            release_date: value(title.released).and_then(|date| NaiveDate::parse_from_str(&date, "%d %b %Y").ok()),
+            runtime: value(title.runtime).and_then(|runtime| runtime.trim_end_matches(" min").parse().ok()),
+        }
+    }
+}
+
+/// The sources of each field group in order of preference, e.g.
+/// `ratings=omdb+tmdb`. The first source with a value for a field wins;
+/// later ones fill in what it lacks. Groups not configured use TMDB only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataChains {
+    chains: HashMap<FieldGroup, Vec<String>>,
+}
+
+impl MetadataChains {
+    /// Parses a list such as `ratings=omdb+tmdb,text=tmdb+omdb`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut chains = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (group, sources) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected group=source+source, got '{}'", entry))?;
+            let sources: Vec<String> = sources.split('+').map(|source| source.trim().to_string()).collect();
+            if sources.iter().any(String::is_empty) {
+                return Err(format!("empty source in '{}'", entry));
+            }
+            chains.chains.insert(group.parse()?, sources);
+        }
+        Ok(chains)
+    }
+
+    /// Sources of `group`, most preferred first
+    pub fn sources(&self, group: FieldGroup) -> &[String] {
+        &self.chains[&group]
+    }
+
+    fn is_tmdb_only(&self) -> bool {
+        self.chains.values().all(|sources| sources.len() == 1 && sources[0] == TMDB)
+    }
+}
+
+impl Default for MetadataChains {
+    fn default() -> Self {
+        Self { chains: FieldGroup::ALL.into_iter().map(|group| (group, vec![TMDB.to_string()])).collect() }
+    }
+}
+
+/// Completes TMDB details with other metadata sources as [`MetadataChains`]
+/// configures. Without other sources details are left as TMDB sent them.
+pub struct MetadataSources {
+    providers: Vec<Arc<dyn MetadataProvider>>,
+    chains: MetadataChains,
+}
+
+impl MetadataSources {
+    /// Fails when a chain names a source that isn't among `providers`;
+    /// TMDB is always available
+    pub fn new(chains: MetadataChains, providers: Vec<Arc<dyn MetadataProvider>>) -> Result<Self, String> {
+        let mut all: Vec<Arc<dyn MetadataProvider>> = vec![Arc::new(TmdbMetadata)];
+        all.extend(providers.into_iter().filter(|provider| provider.name() != TMDB));
+        for group in FieldGroup::ALL {
+            if let Some(unknown) = chains.sources(group).iter().find(|name| !all.iter().any(|provider| provider.name() == *name)) {
+                return Err(format!("unknown metadata source '{}'", unknown));
+            }
+        }
+        Ok(Self { providers: all, chains })
+    }
+
+    pub fn chains(&self) -> &MetadataChains {
+        &self.chains
+    }
+
+    /// Rewrites each field group of `details` from its sources. A source
+    /// that fails is skipped like one that doesn't know the title; fields no
+    /// source has are left empty, so list `tmdb` last to fall back to it.
+    pub async fn complete(&self, media_type: MediaType, details: &mut TitleDetails) {
+        if self.chains.is_tmdb_only() {
+            return;
+        }
+        let mut found: HashMap<&str, TitleMetadata> = HashMap::new();
+        for provider in &self.providers {
+            if !FieldGroup::ALL.iter().any(|group| self.chains.sources(*group).iter().any(|name| name == provider.name())) {
+                continue;
+            }
+            match provider.lookup(media_type, details).await {
+                Ok(Some(metadata)) => {
+                    found.insert(provider.name(), metadata);
+                }
+                Ok(None) => {}
+                Err(error) => tracing::warn!(source = provider.name(), id = details.id, %error, "metadata lookup failed"),
+            }
+        }
+
+        for group in FieldGroup::ALL {
+            let mut merged = TitleMetadata::default();
+            for name in self.chains.sources(group) {
+                if let Some(metadata) = found.get(name.as_str()) {
+                    merged.fill(group, metadata);
+                }
+            }
+            merged.apply(group, media_type, details);
+        }
+    }
+}
+
+impl Default for MetadataSources {
+    fn default() -> Self {
+        Self { providers: vec![Arc::new(TmdbMetadata)], chains: MetadataChains::default() }
+    }
+}
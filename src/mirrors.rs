@@ -0,0 +1,125 @@
+// src/mirrors.rs
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::clock::Clock;
+
+/// Official TMDB API base URL, used when no mirrors are configured
+pub const DEFAULT_BASE_URL: &str = "https://api.themoviedb.org/3";
+
+/// How long a mirror that failed is skipped before it is tried again
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Health of one upstream base URL
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MirrorStatus {
+    pub base_url: String,
+    pub healthy: bool,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    /// Unix timestamp of the last network failure, in seconds
+    pub last_failure: Option<u64>,
+}
+
+#[derive(Default)]
+struct Health {
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    last_failure: Option<SystemTime>,
+}
+
+/// Ordered list of TMDB base URLs with per-mirror health.
+///
+/// Calls go to the first healthy mirror and fail over to the next one on
+/// network errors. A mirror becomes unhealthy when a call to it fails and is
+/// skipped for 30 seconds, after which it is tried again in its usual place.
+pub struct Mirrors {
+    base_urls: Vec<String>,
+    clock: Arc<dyn Clock>,
+    health: Mutex<Vec<Health>>,
+}
+
+impl Mirrors {
+    /// `base_urls` are tried in order; trailing slashes are ignored
+    pub fn new(base_urls: Vec<String>, clock: Arc<dyn Clock>) -> Self {
+        let base_urls: Vec<String> = base_urls
+            .into_iter()
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .collect();
+        let base_urls = if base_urls.is_empty() { vec![DEFAULT_BASE_URL.to_string()] } else { base_urls };
+        let health = base_urls.iter().map(|_| Health::default()).collect();
+
+        Self {
+            base_urls,
+            clock,
+            health: Mutex::new(health),
+        }
+    }
+
+    /// Parses a comma-separated list such as `https://tmdb-mirror.internal/3,https://api.themoviedb.org/3`
+    pub fn parse(spec: &str, clock: Arc<dyn Clock>) -> Self {
+        Self::new(spec.split(',').map(str::to_string).collect(), clock)
+    }
+
+    pub fn base_url(&self, index: usize) -> &str {
+        &self.base_urls[index]
+    }
+
+    /// Mirror indexes in the order they should be tried: healthy mirrors
+    /// first, then mirrors still cooling down, each group in configured order
+    pub fn attempt_order(&self) -> Vec<usize> {
+        let now = self.clock.now();
+        let health = self.health.lock().unwrap();
+        let (healthy, cooling): (Vec<usize>, Vec<usize>) =
+            (0..self.base_urls.len()).partition(|&index| !cooling_down(&health[index], now));
+
+        healthy.into_iter().chain(cooling).collect()
+    }
+
+    pub fn record_success(&self, index: usize) {
+        let mut health = self.health.lock().unwrap();
+        let mirror = &mut health[index];
+        mirror.successes += 1;
+        mirror.consecutive_failures = 0;
+        mirror.last_failure = None;
+    }
+
+    pub fn record_failure(&self, index: usize) {
+        let now = self.clock.now();
+        let mut health = self.health.lock().unwrap();
+        let mirror = &mut health[index];
+        mirror.failures += 1;
+        mirror.consecutive_failures += 1;
+        mirror.last_failure = Some(now);
+    }
+
+    pub fn status(&self) -> Vec<MirrorStatus> {
+        let now = self.clock.now();
+        let health = self.health.lock().unwrap();
+
+        self.base_urls
+            .iter()
+            .zip(health.iter())
+            .map(|(base_url, mirror)| MirrorStatus {
+                base_url: base_url.clone(),
+                healthy: !cooling_down(mirror, now),
+                successes: mirror.successes,
+                failures: mirror.failures,
+                consecutive_failures: mirror.consecutive_failures,
+                last_failure: mirror
+                    .last_failure
+                    .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+                    .map(|since_epoch| since_epoch.as_secs()),
+            })
+            .collect()
+    }
+}
+
+fn cooling_down(mirror: &Health, now: SystemTime) -> bool {
+    mirror
+        .last_failure
+        .is_some_and(|at| now.duration_since(at).unwrap_or_default() < RETRY_AFTER)
+}
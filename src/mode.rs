@@ -0,0 +1,290 @@
+// src/mode.rs
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::error::TmdbError;
+use crate::{frontend, health, profiling, stats};
+use crate::models::{AlternativeTitles, ChangesResponse, Collection, DiscoverFilter, ImagesResponse, Network, ProviderList, Season, TmdbList, WatchProvidersResponse, MediaType, TitleDetails, TmdbResponse, VideoResponse};
+use crate::state::AppState;
+use crate::tmdb_client::TmdbClient;
+
+/// How much of the service is available, switched at runtime during incidents
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceMode {
+    #[default]
+    Normal,
+    /// Expensive fan-out routes answer 503; everything else works
+    Degraded,
+    /// As `Degraded`, and no calls reach TMDB; only cached not-found
+    /// results are still answered
+    CacheOnly,
+    /// Every route outside the allowlist answers 503 with `Retry-After`
+    Maintenance,
+}
+
+/// Default `Retry-After` sent during maintenance
+const DEFAULT_MAINTENANCE_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Path prefixes still served during maintenance: the status root, health
+/// checks, the admin and profiling APIs, and the web UI shell
+const MAINTENANCE_ALLOWLIST: &[&str] = &["/admin", health::MOUNT_PATH, stats::PUBLIC_PATH, profiling::MOUNT_PATH, frontend::MOUNT_PATH];
+
+impl ServiceMode {
+    /// True if routes with a request budget cost are switched off
+    pub fn blocks_expensive_routes(self) -> bool {
+        self != ServiceMode::Normal
+    }
+
+    pub fn allows_upstream(self) -> bool {
+        !matches!(self, ServiceMode::CacheOnly | ServiceMode::Maintenance)
+    }
+}
+
+impl FromStr for ServiceMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode.trim() {
+            "normal" => Ok(ServiceMode::Normal),
+            "degraded" => Ok(ServiceMode::Degraded),
+            "cache_only" => Ok(ServiceMode::CacheOnly),
+            "maintenance" => Ok(ServiceMode::Maintenance),
+            other => Err(format!("unknown service mode '{}'", other)),
+        }
+    }
+}
+
+/// Current mode and when it was last changed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModeStatus {
+    pub mode: ServiceMode,
+    /// Unix timestamp of the last change in seconds, unset since startup
+    pub changed_at: Option<u64>,
+}
+
+/// Body of `PUT /admin/mode`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModeChange {
+    pub mode: ServiceMode,
+}
+
+/// Runtime service mode shared by the router and the client stack.
+pub struct ModeSwitch {
+    status: Mutex<(ServiceMode, Option<SystemTime>)>,
+    maintenance_retry_after: Duration,
+}
+
+impl ModeSwitch {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new((ServiceMode::Normal, None)),
+            maintenance_retry_after: DEFAULT_MAINTENANCE_RETRY_AFTER,
+        }
+    }
+
+    /// Sets the `Retry-After` sent during maintenance; five minutes by default
+    pub fn with_maintenance_retry_after(mut self, retry_after: Duration) -> Self {
+        self.maintenance_retry_after = retry_after;
+        self
+    }
+
+    pub fn mode(&self) -> ServiceMode {
+        self.status.lock().unwrap().0
+    }
+
+    pub fn status(&self) -> ModeStatus {
+        let (mode, changed_at) = *self.status.lock().unwrap();
+        ModeStatus {
+            mode,
+            changed_at: changed_at
+                .and_then(|at| at.duration_since(UNIX_EPOCH).ok())
+                .map(|since_epoch| since_epoch.as_secs()),
+        }
+    }
+
+    /// Switches to `mode`, writing an audit log entry naming the old and new mode
+    pub fn set(&self, mode: ServiceMode, now: SystemTime) -> ModeStatus {
+        let mut status = self.status.lock().unwrap();
+        let previous = status.0;
+        *status = (mode, Some(now));
+        drop(status);
+
+        tracing::warn!(target: "audit", ?previous, ?mode, "service mode changed");
+        self.status()
+    }
+}
+
+impl Default for ModeSwitch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Answers 503 for routes the current service mode switches off.
+///
+/// In maintenance mode that is every path outside the allowlist; otherwise
+/// it is the expensive routes, those the request budget gives a cost.
+pub async fn mode_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    if state.mode.mode() == ServiceMode::Maintenance && !allowed_in_maintenance(request.uri().path()) {
+        return maintenance_response(state.mode.maintenance_retry_after);
+    }
+
+    let blocked = state.mode.mode().blocks_expensive_routes()
+        && request
+            .extensions()
+            .get::<MatchedPath>()
+            .is_some_and(|route| state.budget.cost(route.as_str()) > 0);
+
+    if blocked {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Temporarily disabled").into_response();
+    }
+    next.run(request).await
+}
+
+fn allowed_in_maintenance(path: &str) -> bool {
+    path == "/"
+        || MAINTENANCE_ALLOWLIST.iter().any(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+}
+
+fn maintenance_response(retry_after: Duration) -> Response {
+    let seconds = retry_after.as_secs().max(1);
+    let body = json!({
+        "error": "maintenance",
+        "message": "Netflix Backend is down for maintenance, please try again later",
+        "retry_after": seconds,
+    });
+
+    let mut response = (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response();
+    if let Ok(value) = HeaderValue::from_str(&seconds.to_string()) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
+/// `TmdbClient` decorator that refuses upstream calls in cache-only and
+/// maintenance mode.
+pub struct ModeAwareTmdbClient<C> {
+    inner: C,
+    mode: Arc<ModeSwitch>,
+}
+
+impl<C: TmdbClient> ModeAwareTmdbClient<C> {
+    pub fn new(inner: C, mode: Arc<ModeSwitch>) -> Self {
+        Self { inner, mode }
+    }
+
+    fn check(&self) -> Result<(), TmdbError> {
+        if self.mode.mode().allows_upstream() {
+            Ok(())
+        } else {
+            Err(TmdbError::UpstreamDisabled)
+        }
+    }
+}
+
+#[async_trait]
+impl<C: TmdbClient> TmdbClient for ModeAwareTmdbClient<C> {
+    async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_trending(page).await
+    }
+
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_top_rated(media_type, page).await
+    }
+
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_popular(media_type, page).await
+    }
+
+    async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.check()?;
+        self.inner.search_content(query, page).await
+    }
+
+    async fn search_content_including_adult(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.check()?;
+        self.inner.search_content_including_adult(query, page).await
+    }
+
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_movie_videos(movie_id).await
+    }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        self.check()?;
+        self.inner.get_title_details(media_type, id).await
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_images(media_type, id).await
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        self.check()?;
+        self.inner.get_alternative_titles(media_type, id).await
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_watch_providers(media_type, id).await
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.check()?;
+        self.inner.discover(filter, page).await
+    }
+
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        self.check()?;
+        self.inner.get_provider_list(region).await
+    }
+
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        self.check()?;
+        self.inner.get_network(id).await
+    }
+
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        self.check()?;
+        self.inner.get_list(list_id).await
+    }
+
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError> {
+        self.check()?;
+        self.inner.get_collection(id).await
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        self.check()?;
+        self.inner.get_tv_season(tv_id, season_number).await
+    }
+
+    async fn get_changes(&self, media_type: MediaType, start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        self.check()?;
+        self.inner.get_changes(media_type, start_date, page).await
+    }
+}
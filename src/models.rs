@@ -1,26 +1,499 @@
 // src/models.rs
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, NaiveDate};
+use serde::{de::DeserializeOwned, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::clock::{Clock, SystemClock};
+use crate::viewing_order::ViewingOrder;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// List item as returned by trending and search.
+///
+/// Serializes with the derived `year`, `vote_average_rounded` and `age`
+/// fields, so clients don't compute them themselves.
+#[derive(Clone, Debug, Deserialize)]
 pub struct Movie {
-    pub id: i32,
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient")]
     pub title: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub overview: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub poster_path: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub backdrop_path: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
     pub vote_average: Option<f64>,
-    pub release_date: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub vote_count: Option<u32>,
+    /// Bayesian average of the votes, set by [`crate::quality::rank`] before responding
+    #[serde(default, deserialize_with = "lenient")]
+    pub quality_score: Option<f64>,
+    /// Whether TMDB lists a trailer, set by [`crate::enrich::Includes`] for
+    /// `include=videos` once the answer is known
+    #[serde(default, deserialize_with = "lenient")]
+    pub has_trailer: Option<bool>,
+    /// Up to three providers streaming the title in the requested region, set
+    /// by [`crate::enrich::Includes`] for `include=providers`
+    #[serde(default, deserialize_with = "lenient")]
+    pub providers: Option<Vec<ProviderSummary>>,
+    /// Top-billed cast, set by [`crate::enrich::Includes`] for `include=credits`
+    #[serde(default, deserialize_with = "lenient")]
+    pub cast: Option<Vec<CastMember>>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub genre_ids: Vec<i64>,
+    #[serde(default, deserialize_with = "tmdb_date")]
+    pub release_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "lenient")]
     pub media_type: Option<String>,
+    /// TMDB's adult flag; not serialized, only used to keep such titles from kids profiles
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub adult: bool,
+}
+
+impl Movie {
+    pub fn year(&self) -> Option<u16> {
+        release_year(self.release_date)
+    }
+
+    /// Vote average rounded to one decimal, as shown on rating badges
+    pub fn vote_average_rounded(&self) -> Option<f64> {
+        self.vote_average.map(round_vote)
+    }
+
+    /// Full years since release on `today`; `None` when unreleased or undated
+    pub fn age_at(&self, today: NaiveDate) -> Option<u32> {
+        age_in_years(self.release_date, today)
+    }
+}
+
+impl Movie {
+    /// A list item for a title whose details were loaded, e.g. from a curated list
+    pub fn from_details(media_type: MediaType, details: TitleDetails) -> Self {
+        Self {
+            id: details.id,
+            title: details.title,
+            name: details.name,
+            overview: details.overview,
+            poster_path: details.poster_path,
+            backdrop_path: details.backdrop_path,
+            vote_average: details.vote_average,
+            vote_count: details.vote_count,
+            quality_score: None,
+            has_trailer: None,
+            providers: None,
+            cast: None,
+            genre_ids: details.genres.iter().map(|genre| genre.id).collect(),
+            release_date: details.release_date.or(details.first_air_date),
+            media_type: Some(media_type.as_str().to_string()),
+            adult: details.adult,
+        }
+    }
+}
+
+impl Serialize for Movie {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut movie = serializer.serialize_struct("Movie", 18)?;
+        movie.serialize_field("id", &self.id)?;
+        movie.serialize_field("title", &self.title)?;
+        movie.serialize_field("name", &self.name)?;
+        movie.serialize_field("overview", &self.overview)?;
+        movie.serialize_field("poster_path", &self.poster_path)?;
+        movie.serialize_field("backdrop_path", &self.backdrop_path)?;
+        movie.serialize_field("vote_average", &self.vote_average)?;
+        movie.serialize_field("release_date", &self.release_date)?;
+        movie.serialize_field("year", &self.year())?;
+        movie.serialize_field("vote_average_rounded", &self.vote_average_rounded())?;
+        movie.serialize_field("vote_count", &self.vote_count)?;
+        movie.serialize_field("quality_score", &self.quality_score)?;
+        movie.serialize_field("genre_ids", &self.genre_ids)?;
+        movie.serialize_field("age", &self.age_at(today()))?;
+        movie.serialize_field("media_type", &self.media_type)?;
+        // Only present when includes were asked for, so other clients see no change
+        if let Some(has_trailer) = self.has_trailer {
+            movie.serialize_field("has_trailer", &has_trailer)?;
+        }
+        if let Some(providers) = &self.providers {
+            movie.serialize_field("providers", providers)?;
+        }
+        if let Some(cast) = &self.cast {
+            movie.serialize_field("cast", cast)?;
+        }
+        movie.end()
+    }
+}
+
+/// Formats a runtime in minutes as `2h 19m`, `2h` or `45m`; `None` when unknown
+pub fn format_runtime(minutes: Option<i32>) -> Option<String> {
+    let minutes = minutes.filter(|&minutes| minutes > 0)?;
+    Some(match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    })
+}
+
+fn round_vote(vote_average: f64) -> f64 {
+    (vote_average * 10.0).round() / 10.0
+}
+
+fn age_in_years(date: Option<NaiveDate>, today: NaiveDate) -> Option<u32> {
+    date.and_then(|date| today.years_since(date))
+}
+
+tokio::task_local! {
+    /// Date derived `age` fields are relative to while a response is built
+    static TODAY: NaiveDate;
+}
+
+/// Runs `f` with derived `age` fields relative to `today` rather than the
+/// system clock; see [`crate::clock::today_middleware`]
+pub async fn with_today<F: std::future::Future>(today: NaiveDate, f: F) -> F::Output {
+    TODAY.scope(today, f).await
+}
+
+/// The date set by [`with_today`], or today's UTC date outside of it
+fn today() -> NaiveDate {
+    TODAY.try_with(|today| *today).unwrap_or_else(|_| utc_date(SystemClock.now()))
+}
+
+/// The UTC calendar date of `at`
+pub fn utc_date(at: SystemTime) -> NaiveDate {
+    let since_epoch = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    DateTime::from_timestamp(since_epoch.as_secs() as i64, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/// Deserializes a TMDB `YYYY-MM-DD` date, treating `""` and malformed dates
+/// as missing rather than failing the whole payload.
+fn tmdb_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+    let raw = lenient::<D, String>(deserializer)?;
+    Ok(raw.and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()))
+}
+
+// TMDB payloads are parsed leniently: a field holding `null` or a value of
+// the wrong type counts as missing, and list entries that don't parse are
+// dropped, so one odd value never fails a whole page. Only the fields a
+// payload can't be used without, such as ids, are strict.
+
+/// Deserializes an optional field, treating values of the wrong type as missing
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Deserializes a field that has a natural empty value, using it for values of the wrong type
+fn lenient_or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    Ok(lenient(deserializer)?.unwrap_or_default())
+}
+
+/// Deserializes a list, skipping entries that don't parse; anything but a
+/// list is empty
+fn lenient_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Ok(lenient_items(Value::deserialize(deserializer)?))
+}
+
+fn lenient_items<T: DeserializeOwned>(value: Value) -> Vec<T> {
+    match value {
+        Value::Array(items) => items.into_iter().filter_map(|item| serde_json::from_value(item).ok()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn release_year(date: Option<NaiveDate>) -> Option<u16> {
+    date.and_then(|date| u16::try_from(date.year()).ok())
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TmdbResponse {
     pub page: i32,
+    #[serde(default, deserialize_with = "lenient_list")]
     pub results: Vec<Movie>,
     pub total_pages: i32,
 }
 
+/// Base URL for TMDB-hosted images; a size segment and the file path are appended.
+pub const IMAGE_BASE_URL: &str = "https://image.tmdb.org/t/p";
+
+/// Builds a full image URL from a TMDB file path such as `/abc.jpg`.
+pub fn image_url(size: &str, path: &str) -> String {
+    format!("{}/{}{}", IMAGE_BASE_URL, size, path)
+}
+
+/// Error message for ids that are well-formed integers but can never name a TMDB resource.
+pub const ID_OUT_OF_RANGE: &str = "id must be a positive integer";
+
+/// A TMDB id taken from a request, guaranteed to be positive and in range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "i64", into = "i64")]
+pub struct TmdbId(i64);
+
+impl TmdbId {
+    pub fn new(id: i64) -> Option<Self> {
+        (id > 0).then_some(Self(id))
+    }
+
+    pub fn get(self) -> i64 {
+        self.0
+    }
+}
+
+impl TryFrom<i64> for TmdbId {
+    type Error = &'static str;
+
+    fn try_from(id: i64) -> Result<Self, Self::Error> {
+        Self::new(id).ok_or(ID_OUT_OF_RANGE)
+    }
+}
+
+impl From<TmdbId> for i64 {
+    fn from(id: TmdbId) -> Self {
+        id.0
+    }
+}
+
+/// The two kinds of titles TMDB serves under separate endpoints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaType {
+    Movie,
+    Tv,
+}
+
+impl MediaType {
+    /// Path segment used by TMDB for this media type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaType::Movie => "movie",
+            MediaType::Tv => "tv",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Genre {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+}
+
+/// TMDB keyword such as `based on novel or book`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keyword {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+}
+
+/// An actor in a title's cast
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CastMember {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub character: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub profile_path: Option<String>,
+    /// Billing position, lowest first
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub order: i32,
+}
+
+/// Full details for a single movie or TV show.
+///
+/// Movies populate `title`/`release_date`/`runtime`, TV shows populate
+/// `name`/`first_air_date`, mirroring the TMDB payloads. Serializes with the
+/// derived `runtime_display`, `vote_average_rounded` and `age` fields.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TitleDetails {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient")]
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub tagline: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub overview: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub poster_path: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub backdrop_path: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub vote_average: Option<f64>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub vote_count: Option<u32>,
+    #[serde(default, deserialize_with = "tmdb_date")]
+    pub release_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "tmdb_date")]
+    pub first_air_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub runtime: Option<i32>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub genres: Vec<Genre>,
+    /// Title in the original language, for movies
+    #[serde(default, deserialize_with = "lenient")]
+    pub original_title: Option<String>,
+    /// Name in the original language, for TV shows
+    #[serde(default, deserialize_with = "lenient")]
+    pub original_name: Option<String>,
+    /// ISO 639-1 code of the original language
+    #[serde(default, deserialize_with = "lenient")]
+    pub original_language: Option<String>,
+    #[serde(default, deserialize_with = "appended_list")]
+    pub alternative_titles: Vec<AlternativeTitle>,
+    #[serde(default, deserialize_with = "appended_list")]
+    pub keywords: Vec<Keyword>,
+    /// Appended `credits`; only returned through `include=credits`
+    #[serde(rename = "credits", default, deserialize_with = "appended_list")]
+    pub cast: Vec<CastMember>,
+    /// Appended `external_ids`; used to find the title in other metadata sources
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub external_ids: ExternalIds,
+    /// Seasons of a TV show, without their episodes; empty for movies.
+    /// Only used to build viewing orders, so not serialized.
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub seasons: Vec<SeasonSummary>,
+    /// TMDB's adult flag; not serialized, like [`Movie::adult`]
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub adult: bool,
+}
+
+/// Ids of a title on other sites
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExternalIds {
+    #[serde(default, deserialize_with = "lenient")]
+    pub imdb_id: Option<String>,
+}
+
+impl TitleDetails {
+    /// Display title regardless of media type
+    pub fn display_title(&self) -> &str {
+        self.title
+            .as_deref()
+            .or(self.name.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Release year for movies, first-air year for TV shows
+    pub fn year(&self) -> Option<u16> {
+        release_year(self.release_date.or(self.first_air_date))
+    }
+
+    /// Title in the original language regardless of media type
+    pub fn original_display_title(&self) -> Option<&str> {
+        self.original_title.as_deref().or(self.original_name.as_deref())
+    }
+
+    /// Runtime such as `2h 19m`; TV shows have none
+    pub fn runtime_display(&self) -> Option<String> {
+        format_runtime(self.runtime)
+    }
+
+    /// Vote average rounded to one decimal, as shown on rating badges
+    pub fn vote_average_rounded(&self) -> Option<f64> {
+        self.vote_average.map(round_vote)
+    }
+
+    /// Full years since release or first air date on `today`; `None` when
+    /// unreleased or undated
+    pub fn age_at(&self, today: NaiveDate) -> Option<u32> {
+        age_in_years(self.release_date.or(self.first_air_date), today)
+    }
+}
+
+impl Serialize for TitleDetails {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut details = serializer.serialize_struct("TitleDetails", 21)?;
+        details.serialize_field("id", &self.id)?;
+        details.serialize_field("title", &self.title)?;
+        details.serialize_field("name", &self.name)?;
+        details.serialize_field("tagline", &self.tagline)?;
+        details.serialize_field("overview", &self.overview)?;
+        details.serialize_field("poster_path", &self.poster_path)?;
+        details.serialize_field("backdrop_path", &self.backdrop_path)?;
+        details.serialize_field("vote_average", &self.vote_average)?;
+        details.serialize_field("vote_average_rounded", &self.vote_average_rounded())?;
+        details.serialize_field("vote_count", &self.vote_count)?;
+        details.serialize_field("release_date", &self.release_date)?;
+        details.serialize_field("first_air_date", &self.first_air_date)?;
+        details.serialize_field("age", &self.age_at(today()))?;
+        details.serialize_field("runtime", &self.runtime)?;
+        details.serialize_field("runtime_display", &self.runtime_display())?;
+        details.serialize_field("genres", &self.genres)?;
+        details.serialize_field("original_title", &self.original_title)?;
+        details.serialize_field("original_name", &self.original_name)?;
+        details.serialize_field("original_language", &self.original_language)?;
+        details.serialize_field("alternative_titles", &self.alternative_titles)?;
+        details.serialize_field("keywords", &self.keywords)?;
+        details.end()
+    }
+}
+
+/// A title used in one country instead of, or next to, the main title.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlternativeTitle {
+    /// ISO 3166-1 country code
+    pub iso_3166_1: String,
+    pub title: String,
+    /// TMDB's free-form note such as `working title`; often empty
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub r#type: String,
+}
+
+/// TMDB payload for a title's alternative titles.
+///
+/// Movies list them under `titles` and TV shows under `results`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AlternativeTitles {
+    #[serde(default)]
+    pub id: i64,
+    #[serde(alias = "results", default, deserialize_with = "lenient_list")]
+    pub titles: Vec<AlternativeTitle>,
+}
+
+impl AlternativeTitles {
+    /// Keeps only titles for `country`, matched case-insensitively
+    pub fn for_country(mut self, country: &str) -> Self {
+        self.titles.retain(|title| title.iso_3166_1.eq_ignore_ascii_case(country));
+        self
+    }
+}
+
+/// Accepts a list both as appended by TMDB to a details payload, wrapped in
+/// an object under `titles`, `keywords` or `results`, and as the plain list
+/// this service serializes.
+fn appended_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Object(mut wrapper) => ["results", "titles", "keywords", "cast"]
+            .iter()
+            .find_map(|key| wrapper.remove(*key))
+            .map(lenient_items)
+            .unwrap_or_default(),
+        list => lenient_items(list),
+    })
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Video {
     pub id: String,
@@ -32,18 +505,792 @@ pub struct Video {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VideoResponse {
-    pub id: i32,
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_list")]
     pub results: Vec<Video>,
 }
 
+impl VideoResponse {
+    /// Picks the video best suited as "the" trailer for a title.
+    ///
+    /// Only YouTube videos are embeddable; among those a `Trailer` beats a
+    /// `Teaser`, and earlier entries win ties since TMDB lists official media first.
+    pub fn best_trailer(&self) -> Option<&Video> {
+        let youtube = || self.results.iter().filter(|v| v.site == "YouTube");
+
+        youtube()
+            .find(|v| v.r#type == "Trailer")
+            .or_else(|| youtube().find(|v| v.r#type == "Teaser"))
+    }
+}
+
+/// One poster, backdrop or logo as listed by TMDB.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Image {
+    pub file_path: String,
+    /// Language of any text in the image; `None` for images without text
+    #[serde(default, deserialize_with = "lenient")]
+    pub iso_639_1: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub width: u32,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub height: u32,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub vote_average: f64,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub vote_count: u32,
+}
+
+/// TMDB payload for a title's images.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImagesResponse {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub posters: Vec<Image>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub backdrops: Vec<Image>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub logos: Vec<Image>,
+}
+
+impl ImagesResponse {
+    /// Converts to full image URLs, keeping only images whose language is in
+    /// `languages` when given; `None` in the list matches images without text.
+    pub fn to_links(&self, languages: Option<&[Option<String>]>) -> TitleImages {
+        let links = |images: &[Image]| {
+            images
+                .iter()
+                .filter(|image| languages.is_none_or(|languages| languages.contains(&image.iso_639_1)))
+                .map(ImageLink::from)
+                .collect()
+        };
+
+        TitleImages {
+            id: self.id,
+            posters: links(&self.posters),
+            backdrops: links(&self.backdrops),
+            logos: links(&self.logos),
+        }
+    }
+}
+
+/// An image with its full URL, as served by `/api/movie/{id}/images`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImageLink {
+    pub url: String,
+    pub language: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub vote_average: f64,
+}
+
+impl From<&Image> for ImageLink {
+    fn from(image: &Image) -> Self {
+        Self {
+            url: image_url("original", &image.file_path),
+            language: image.iso_639_1.clone(),
+            width: image.width,
+            height: image.height,
+            vote_average: image.vote_average,
+        }
+    }
+}
+
+/// Images of a title grouped by kind
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TitleImages {
+    pub id: i64,
+    pub posters: Vec<ImageLink>,
+    pub backdrops: Vec<ImageLink>,
+    pub logos: Vec<ImageLink>,
+}
+
+/// Embed payload for `/oembed`, following the oEmbed 1.0 `video` type.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OembedResponse {
+    pub version: String,
+    pub r#type: String,
+    pub title: String,
+    pub provider_name: String,
+    pub html: String,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail_url: Option<String>,
+}
+
+/// A movie or TV show identified by media type and id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TitleRef {
+    pub media_type: MediaType,
+    pub id: TmdbId,
+}
+
+/// Body of `POST /api/titles/batch`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchTitlesRequest {
+    pub items: Vec<TitleRef>,
+    /// Adds each title's best logo for hero banners
+    #[serde(default)]
+    pub include_logo: bool,
+    /// Preferred logo language, English when unset
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Why one item of a batch lookup failed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchItemError {
+    pub status: u16,
+    pub message: String,
+}
+
+/// Result for one requested title; exactly one of `details` and `error` is set.
+///
+/// `logo` is only set when requested and a suitable logo exists.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchTitleResult {
+    pub media_type: MediaType,
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<TitleDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<ImageLink>,
+    /// Badges derived from the title's keywords, e.g. `based on a book`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_warnings: Vec<String>,
+    /// Set for `include=videos` once known, like [`Movie::has_trailer`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_trailer: Option<bool>,
+    /// Set for `include=providers`, like [`Movie::providers`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<Vec<ProviderSummary>>,
+    /// Set for `include=credits`, like [`Movie::cast`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast: Option<Vec<CastMember>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchItemError>,
+}
+
 // Parametri di Query
 #[derive(Deserialize)]
 pub struct PageQuery {
     pub page: Option<i32>,
 }
 
+/// Page of a list offered for both movies and TV shows
+#[derive(Deserialize)]
+pub struct MediaPageQuery {
+    /// Movies unless set
+    pub media_type: Option<MediaType>,
+    pub page: Option<i32>,
+}
+
 #[derive(Deserialize)]
 pub struct SearchQuery {
     pub query: String,
     pub page: Option<i32>,
-}
\ No newline at end of file
+    /// Opts into adult titles where the adult content policy allows it
+    pub include_adult: Option<bool>,
+}
+
+/// Query of `GET /api/sync`
+#[derive(Deserialize)]
+pub struct SyncQuery {
+    /// `sync_token` of the previous sync, or a Unix timestamp in seconds
+    pub since: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LimitQuery {
+    pub limit: Option<usize>,
+}
+
+/// Comma-separated image languages such as `en,null`, where `null` selects images without text
+#[derive(Deserialize)]
+pub struct ImagesQuery {
+    pub language: Option<String>,
+}
+
+impl ImagesQuery {
+    pub fn languages(&self) -> Option<Vec<Option<String>>> {
+        self.language.as_deref().map(|languages| {
+            languages
+                .split(',')
+                .map(str::trim)
+                .filter(|language| !language.is_empty())
+                .map(|language| (language != "null").then(|| language.to_lowercase()))
+                .collect()
+        })
+    }
+}
+
+/// A streaming service or store as listed by TMDB
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WatchProvider {
+    pub provider_id: i64,
+    pub provider_name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub logo_path: Option<String>,
+    /// Lower is more prominent
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub display_priority: i32,
+}
+
+/// Where a title can be watched in one region, by kind of offer
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegionProviders {
+    /// TMDB page listing the offers, required for JustWatch attribution
+    #[serde(default, deserialize_with = "lenient")]
+    pub link: Option<String>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub flatrate: Vec<WatchProvider>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub free: Vec<WatchProvider>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub ads: Vec<WatchProvider>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub rent: Vec<WatchProvider>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub buy: Vec<WatchProvider>,
+}
+
+/// TMDB payload for a title's watch providers, keyed by ISO 3166-1 region
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WatchProvidersResponse {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub results: HashMap<String, RegionProviders>,
+}
+
+/// Provider ids available in one region, by kind of offer
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegionAvailability {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flatrate: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub free: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ads: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rent: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buy: Vec<i64>,
+}
+
+/// A provider named in an availability matrix
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProviderSummary {
+    pub id: i64,
+    pub name: String,
+    pub logo_url: Option<String>,
+}
+
+/// TMDB image size used for provider logos
+pub const PROVIDER_LOGO_SIZE: &str = "w92";
+
+impl From<&WatchProvider> for ProviderSummary {
+    fn from(provider: &WatchProvider) -> Self {
+        Self {
+            id: provider.provider_id,
+            name: provider.provider_name.clone(),
+            logo_url: provider.logo_path.as_deref().map(|path| image_url(PROVIDER_LOGO_SIZE, path)),
+        }
+    }
+}
+
+/// Provider availability of a title across regions, as served by
+/// `/api/movie/{id}/availability`.
+///
+/// Each provider is described once in `providers`; `regions` only refers to
+/// provider ids, with an empty object for regions without any offer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AvailabilityMatrix {
+    pub id: i64,
+    pub providers: Vec<ProviderSummary>,
+    pub regions: BTreeMap<String, RegionAvailability>,
+}
+
+#[derive(Deserialize)]
+pub struct AvailabilityQuery {
+    pub regions: Option<String>,
+}
+
+impl AvailabilityQuery {
+    /// Listed regions, upper-cased and without duplicates, in request order.
+    /// Fails on anything that isn't a two-letter country code.
+    pub fn regions(&self) -> Result<Vec<String>, String> {
+        let mut regions: Vec<String> = Vec::new();
+        for region in self.regions.as_deref().unwrap_or_default().split(',').map(str::trim) {
+            if region.is_empty() {
+                continue;
+            }
+            let region = parse_region(region)?;
+            if !regions.contains(&region) {
+                regions.push(region);
+            }
+        }
+        Ok(regions)
+    }
+}
+
+/// Upper-cases a two-letter ISO 3166-1 country code, rejecting anything else
+pub fn parse_region(region: &str) -> Result<String, String> {
+    let region = region.trim();
+    if region.len() != 2 || !region.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("invalid region '{}'", region));
+    }
+    Ok(region.to_ascii_uppercase())
+}
+
+/// TMDB payload listing the watch providers available in a region
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProviderList {
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub results: Vec<WatchProvider>,
+}
+
+impl ProviderList {
+    /// Providers with logo URLs, most prominent first
+    pub fn summaries(&self) -> Vec<ProviderSummary> {
+        let mut providers: Vec<&WatchProvider> = self.results.iter().collect();
+        providers.sort_by(|a, b| {
+            a.display_priority
+                .cmp(&b.display_priority)
+                .then_with(|| a.provider_name.cmp(&b.provider_name))
+        });
+        providers.into_iter().map(ProviderSummary::from).collect()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RegionQuery {
+    pub region: Option<String>,
+}
+
+/// Filters for browsing titles through TMDB discover.
+///
+/// Each list matches titles linked to any of its ids, except genres with
+/// `all_genres` set; empty lists don't filter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DiscoverFilter {
+    pub media_type: MediaType,
+    /// Watch provider ids; titles streaming on any of them match
+    pub providers: Vec<i64>,
+    /// ISO 3166-1 region the providers are looked up in
+    pub region: Option<String>,
+    /// TV network ids, only meaningful for TV shows
+    pub networks: Vec<i64>,
+    /// Production company ids
+    pub companies: Vec<i64>,
+    /// TMDB genre ids
+    pub genres: Vec<i64>,
+    /// Only titles in all of `genres` match, TMDB's `,` rather than `|`
+    pub all_genres: bool,
+    /// TMDB keyword ids, such as `3335` for "halloween"
+    pub keywords: Vec<i64>,
+    /// Lets TMDB return titles flagged as adult; only set where the adult
+    /// content policy allows it
+    pub include_adult: bool,
+    pub sort: DiscoverSort,
+    /// Leaves out titles released or first aired before this day
+    pub released_from: Option<NaiveDate>,
+    /// Leaves out titles released or first aired after this day
+    pub released_by: Option<NaiveDate>,
+    /// Only titles released or first aired in this year
+    pub release_year: Option<i32>,
+    /// Lowest vote average, in tenths of a point so filters stay hashable
+    pub min_vote_tenths: Option<u8>,
+}
+
+/// Order of discover results
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverSort {
+    #[default]
+    Popularity,
+    /// Latest release or first air date first
+    Newest,
+    /// Highest vote average first
+    TopRated,
+}
+
+impl DiscoverSort {
+    /// Parses TMDB's `sort_by` names for the supported orders, e.g. `vote_average.desc`
+    pub fn from_tmdb(sort_by: &str) -> Result<Self, String> {
+        match sort_by.trim() {
+            "popularity.desc" => Ok(DiscoverSort::Popularity),
+            "primary_release_date.desc" | "first_air_date.desc" => Ok(DiscoverSort::Newest),
+            "vote_average.desc" => Ok(DiscoverSort::TopRated),
+            other => Err(format!(
+                "unsupported sort_by '{}', expected popularity.desc, primary_release_date.desc or vote_average.desc",
+                other
+            )),
+        }
+    }
+}
+
+impl DiscoverFilter {
+    /// A filter matching every title of `media_type`
+    pub fn new(media_type: MediaType) -> Self {
+        Self {
+            media_type,
+            providers: Vec::new(),
+            region: None,
+            networks: Vec::new(),
+            companies: Vec::new(),
+            genres: Vec::new(),
+            all_genres: false,
+            keywords: Vec::new(),
+            include_adult: false,
+            sort: DiscoverSort::Popularity,
+            released_from: None,
+            released_by: None,
+            release_year: None,
+            min_vote_tenths: None,
+        }
+    }
+
+    /// Query parameters for TMDB's `/discover/{media_type}`
+    pub fn tmdb_params(&self) -> Vec<(&'static str, String)> {
+        // `|` means "any of" to TMDB, `,` would require all of them
+        let any_of = |ids: &[i64]| ids.iter().map(i64::to_string).collect::<Vec<_>>().join("|");
+
+        let sort_by = match (self.sort, self.media_type) {
+            (DiscoverSort::Popularity, _) => "popularity.desc",
+            (DiscoverSort::Newest, MediaType::Movie) => "primary_release_date.desc",
+            (DiscoverSort::Newest, MediaType::Tv) => "first_air_date.desc",
+            (DiscoverSort::TopRated, _) => "vote_average.desc",
+        };
+        let mut params = vec![("sort_by", sort_by.to_string())];
+        let (from, by) = match self.media_type {
+            MediaType::Movie => ("primary_release_date.gte", "primary_release_date.lte"),
+            MediaType::Tv => ("first_air_date.gte", "first_air_date.lte"),
+        };
+        if let Some(released_from) = self.released_from {
+            params.push((from, released_from.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(released_by) = self.released_by {
+            params.push((by, released_by.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(year) = self.release_year {
+            let key = match self.media_type {
+                MediaType::Movie => "primary_release_year",
+                MediaType::Tv => "first_air_date_year",
+            };
+            params.push((key, year.to_string()));
+        }
+        if let Some(tenths) = self.min_vote_tenths {
+            params.push(("vote_average.gte", format!("{:.1}", f64::from(tenths) / 10.0)));
+        }
+        if !self.providers.is_empty() {
+            params.push(("with_watch_providers", any_of(&self.providers)));
+            params.push(("with_watch_monetization_types", "flatrate|free|ads".to_string()));
+        }
+        if let Some(region) = &self.region {
+            params.push(("watch_region", region.clone()));
+        }
+        if !self.networks.is_empty() {
+            params.push(("with_networks", any_of(&self.networks)));
+        }
+        if !self.companies.is_empty() {
+            params.push(("with_companies", any_of(&self.companies)));
+        }
+        if !self.genres.is_empty() {
+            let genres = if self.all_genres {
+                self.genres.iter().map(i64::to_string).collect::<Vec<_>>().join(",")
+            } else {
+                any_of(&self.genres)
+            };
+            params.push(("with_genres", genres));
+        }
+        if !self.keywords.is_empty() {
+            params.push(("with_keywords", any_of(&self.keywords)));
+        }
+        params
+    }
+}
+
+/// Query of `/api/discover` and `/api/discover/movie`; the filter
+/// parameters keep TMDB's names
+#[derive(Default, Deserialize)]
+pub struct DiscoverQuery {
+    pub providers: Option<String>,
+    pub region: Option<String>,
+    pub page: Option<i32>,
+    /// Opts into adult titles where the adult content policy allows it
+    pub include_adult: Option<bool>,
+    /// Genre ids as TMDB takes them: separated by `|`, titles in any of them
+    /// match; separated by `,`, only titles in all of them
+    pub with_genres: Option<String>,
+    pub primary_release_year: Option<i32>,
+    /// Lowest vote average, 0 to 10
+    #[serde(rename = "vote_average.gte")]
+    pub vote_average_gte: Option<f64>,
+    /// `popularity.desc` (the default), `primary_release_date.desc` or `vote_average.desc`
+    pub sort_by: Option<String>,
+}
+
+impl DiscoverQuery {
+    /// Movie filter for the query; provider filters need a region
+    pub fn filter(&self) -> Result<DiscoverFilter, String> {
+        let providers = parse_ids(self.providers.as_deref(), &[','], "provider")?;
+        let region = self.region.as_deref().map(parse_region).transpose()?;
+        if !providers.is_empty() && region.is_none() {
+            return Err("region is required when filtering by providers".to_string());
+        }
+        // TMDB reads `,` as "all of" and `|` as "any of"
+        let with_genres = self.with_genres.as_deref().unwrap_or_default();
+        let all_genres = with_genres.contains(',');
+        if all_genres && with_genres.contains('|') {
+            return Err("with_genres may use ',' (all of) or '|' (any of), not both".to_string());
+        }
+        let genres = parse_ids(self.with_genres.as_deref(), &[',', '|'], "genre")?;
+        if let Some(year) = self.primary_release_year
+            && !(1000..=9999).contains(&year)
+        {
+            return Err(format!("invalid primary_release_year '{}'", year));
+        }
+        let min_vote_tenths = match self.vote_average_gte {
+            Some(vote) if (0.0..=10.0).contains(&vote) => Some((vote * 10.0).round() as u8),
+            Some(_) => return Err("vote_average.gte must be between 0 and 10".to_string()),
+            None => None,
+        };
+        let sort = self.sort_by.as_deref().map(DiscoverSort::from_tmdb).transpose()?.unwrap_or_default();
+
+        Ok(DiscoverFilter {
+            providers,
+            region,
+            genres,
+            all_genres,
+            sort,
+            release_year: self.primary_release_year,
+            min_vote_tenths,
+            ..DiscoverFilter::new(MediaType::Movie)
+        })
+    }
+}
+
+/// Positive ids separated by any of `separators`, without duplicates
+fn parse_ids(ids: Option<&str>, separators: &[char], kind: &str) -> Result<Vec<i64>, String> {
+    let mut parsed: Vec<i64> = Vec::new();
+    for id in ids.unwrap_or_default().split(separators).map(str::trim) {
+        if id.is_empty() {
+            continue;
+        }
+        let value = id
+            .parse::<i64>()
+            .ok()
+            .filter(|id| *id > 0)
+            .ok_or_else(|| format!("invalid {} id '{}'", kind, id))?;
+        if !parsed.contains(&value) {
+            parsed.push(value);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Query of `/api/rows/new_on`; `provider` and `region` are required
+#[derive(Deserialize)]
+pub struct NewOnQuery {
+    pub provider: Option<i64>,
+    pub region: Option<String>,
+    /// Movies unless set
+    pub media_type: Option<MediaType>,
+}
+
+/// Query of `/api/tv/{id}/where_to_start`
+#[derive(Deserialize)]
+pub struct WhereToStartQuery {
+    /// Release order unless set
+    pub order: Option<ViewingOrder>,
+}
+
+/// TMDB payload for a TV network
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Network {
+    pub id: i64,
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub headquarters: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub homepage: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub logo_path: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub origin_country: Option<String>,
+}
+
+/// A season as listed in a TV show's details
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SeasonSummary {
+    /// `0` holds specials
+    #[serde(default)]
+    pub season_number: i32,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+    /// Unset for seasons not yet scheduled
+    #[serde(default, deserialize_with = "tmdb_date")]
+    pub air_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub episode_count: u32,
+}
+
+/// TMDB payload for one season of a TV show with its episodes, as served
+/// by `/api/tv/{id}/season/{season_number}`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Season {
+    pub id: i64,
+    /// `0` holds specials
+    #[serde(default)]
+    pub season_number: i32,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub overview: Option<String>,
+    #[serde(default, deserialize_with = "tmdb_date")]
+    pub air_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub poster_path: Option<String>,
+    /// In episode order
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub episodes: Vec<Episode>,
+}
+
+impl Season {
+    /// The season as listed in its show's details
+    pub fn summary(&self) -> SeasonSummary {
+        SeasonSummary {
+            season_number: self.season_number,
+            name: self.name.clone(),
+            air_date: self.air_date,
+            episode_count: self.episodes.len() as u32,
+        }
+    }
+}
+
+/// An episode of a [`Season`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Episode {
+    pub id: i64,
+    pub episode_number: i32,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub overview: Option<String>,
+    /// Unset for episodes not yet scheduled
+    #[serde(default, deserialize_with = "tmdb_date")]
+    pub air_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub still_path: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub runtime: Option<i32>,
+}
+
+/// One page of TMDB's `/movie/changes` or `/tv/changes` feed
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ChangesResponse {
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub results: Vec<ChangedTitle>,
+    #[serde(default)]
+    pub page: i32,
+    #[serde(default)]
+    pub total_pages: i32,
+}
+
+/// A title whose data changed on TMDB
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedTitle {
+    pub id: i64,
+}
+
+/// TMDB payload for a movie collection such as a franchise, with its movies
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub overview: Option<String>,
+    /// The collection's movies, in no particular order
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub parts: Vec<Movie>,
+}
+
+/// TMDB payload for a public list made by a TMDB user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TmdbList {
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub items: Vec<TmdbListItem>,
+}
+
+/// One entry of a TMDB list; only movies and TV shows carry a `media_type` we use
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TmdbListItem {
+    pub id: i64,
+    #[serde(default, deserialize_with = "lenient")]
+    pub media_type: Option<String>,
+}
+
+impl TmdbList {
+    /// The list's movies and TV shows in list order. Entries without a media
+    /// type are movies, as in older movie-only lists; anything else is skipped.
+    pub fn title_refs(&self) -> Vec<TitleRef> {
+        self.items
+            .iter()
+            .filter_map(|item| {
+                let media_type = match item.media_type.as_deref() {
+                    None | Some("movie") => MediaType::Movie,
+                    Some("tv") => MediaType::Tv,
+                    Some(_) => return None,
+                };
+                Some(TitleRef { media_type, id: TmdbId::new(item.id)? })
+            })
+            .collect()
+    }
+}
+
+/// TMDB image size used for network logos
+pub const NETWORK_LOGO_SIZE: &str = "w300";
+
+/// A TV network with its logo URL, as served by `/api/network/{id}`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NetworkDetails {
+    pub id: i64,
+    pub name: String,
+    pub headquarters: Option<String>,
+    pub homepage: Option<String>,
+    pub origin_country: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+impl From<Network> for NetworkDetails {
+    fn from(network: Network) -> Self {
+        Self {
+            logo_url: network.logo_path.as_deref().map(|path| image_url(NETWORK_LOGO_SIZE, path)),
+            id: network.id,
+            name: network.name,
+            // TMDB sends "" rather than null for unknown values
+            headquarters: network.headquarters.filter(|value| !value.is_empty()),
+            homepage: network.homepage.filter(|value| !value.is_empty()),
+            origin_country: network.origin_country.filter(|value| !value.is_empty()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CountryQuery {
+    pub country: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OembedQuery {
+    pub url: String,
+    pub maxwidth: Option<u32>,
+    pub maxheight: Option<u32>,
+    pub format: Option<String>,
+}
@@ -0,0 +1,170 @@
+// src/mtls.rs
+use axum::http::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::str::FromStr;
+use x509_parser::extensions::GeneralName;
+
+/// What a client certificate lets its holder reach
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Every `/admin` and `/debug/pprof` route
+    Admin,
+    /// Read-only `/admin/metrics/*` and `/admin/slo`, for dashboards and scrapers
+    Metrics,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Metrics => "metrics",
+        }
+    }
+
+    /// Whether the role may send `method` to `route`, a full route template
+    /// such as `/admin/metrics/slow`
+    pub fn allows(self, method: &Method, route: &str) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Metrics => {
+                (method == Method::GET || method == Method::HEAD)
+                    && (route.starts_with("/admin/metrics/") || route == "/admin/slo")
+            }
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role.trim() {
+            "admin" => Ok(Role::Admin),
+            "metrics" => Ok(Role::Metrics),
+            other => Err(format!("unknown role '{}', expected admin or metrics", other)),
+        }
+    }
+}
+
+/// Who a verified client certificate belongs to: its subject alternative
+/// names, e.g. `spiffe://mesh/ns/ops/sa/console`, `console.ops.svc` or
+/// `10.0.0.7`. Added to requests on connections that presented one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientIdentity {
+    pub sans: Vec<String>,
+}
+
+impl ClientIdentity {
+    /// Reads the DNS, URI, email and IP address SANs of a DER certificate
+    pub fn from_der(certificate: &[u8]) -> Result<Self, String> {
+        let (_, certificate) = x509_parser::parse_x509_certificate(certificate).map_err(|e| e.to_string())?;
+        let Some(names) = certificate.subject_alternative_name().map_err(|e| e.to_string())? else {
+            return Ok(Self::default());
+        };
+        let sans = names
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(name) | GeneralName::URI(name) | GeneralName::RFC822Name(name) => Some(name.to_string()),
+                GeneralName::IPAddress(bytes) => ip_address(bytes).map(|ip| ip.to_string()),
+                _ => None,
+            })
+            .collect();
+        Ok(Self { sans })
+    }
+}
+
+fn ip_address(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => <[u8; 4]>::try_from(bytes).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(bytes).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+/// Which SANs get which roles
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoleMap {
+    rules: Vec<(String, BTreeSet<Role>)>,
+}
+
+impl RoleMap {
+    /// Parses a list such as
+    /// `spiffe://mesh/ns/ops/sa/console=admin,spiffe://mesh/ns/monitoring/*=metrics`.
+    /// A pattern ending in `*` matches SANs starting with the rest; several
+    /// roles are joined with `+`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            // SANs may hold `=`, roles never do
+            let (pattern, roles) = entry
+                .rsplit_once('=')
+                .ok_or_else(|| format!("expected san=role, got '{}'", entry))?;
+            let pattern = pattern.trim();
+            if pattern.is_empty() || pattern == "*" {
+                return Err(format!("'{}' must name a SAN or a SAN prefix", entry));
+            }
+            let roles = roles.split('+').map(Role::from_str).collect::<Result<BTreeSet<_>, _>>()?;
+            rules.push((pattern.to_string(), roles));
+        }
+        if rules.is_empty() {
+            return Err("no SAN mapped to a role".to_string());
+        }
+        Ok(Self { rules })
+    }
+
+    /// Roles of every rule matching one of the identity's SANs
+    pub fn roles(&self, identity: &ClientIdentity) -> BTreeSet<Role> {
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| identity.sans.iter().any(|san| san_matches(pattern, san)))
+            .flat_map(|(_, roles)| roles.iter().copied())
+            .collect()
+    }
+}
+
+fn san_matches(pattern: &str, san: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => san.starts_with(prefix),
+        None => pattern.eq_ignore_ascii_case(san),
+    }
+}
+
+/// Client certificate requirements for internal routes. Without a role map
+/// they are off and internal routes rely on the admin token alone.
+#[derive(Clone, Debug, Default)]
+pub struct ClientCertPolicy {
+    roles: Option<RoleMap>,
+}
+
+impl ClientCertPolicy {
+    pub fn new(roles: RoleMap) -> Self {
+        Self { roles: Some(roles) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.roles.is_some()
+    }
+
+    /// Checks that `identity` holds a role allowing `method` on `route`.
+    /// Requests without a certificate get `401`, those whose certificate
+    /// maps to no fitting role `403`.
+    pub fn authorize(&self, identity: Option<&ClientIdentity>, method: &Method, route: &str) -> Result<(), (StatusCode, &'static str)> {
+        let Some(role_map) = &self.roles else {
+            return Ok(());
+        };
+        let Some(identity) = identity else {
+            return Err((StatusCode::UNAUTHORIZED, "Client certificate required"));
+        };
+        if role_map.roles(identity).iter().any(|role| role.allows(method, route)) {
+            Ok(())
+        } else {
+            tracing::warn!(sans = ?identity.sans, %method, route, "client certificate lacks a role for route");
+            Err((StatusCode::FORBIDDEN, "Client certificate not allowed on this route"))
+        }
+    }
+}
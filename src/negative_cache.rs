@@ -0,0 +1,308 @@
+// src/negative_cache.rs
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use crate::analytics::parse_window;
+use crate::clock::Clock;
+use crate::error::TmdbError;
+use crate::models::{AlternativeTitles, ChangesResponse, Collection, DiscoverFilter, ImagesResponse, Network, ProviderList, Season, TmdbList, WatchProvidersResponse, MediaType, TitleDetails, TmdbResponse, VideoResponse};
+use crate::tmdb_client::TmdbClient;
+
+/// TTL for cached misses when none is configured
+const DEFAULT_TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of cached misses; new misses are not cached beyond this.
+const CAPACITY: usize = 10_000;
+
+/// TMDB lookups whose `NotFound` results can be cached
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lookup {
+    Videos(i64),
+    Details(MediaType, i64),
+    Images(MediaType, i64),
+    AlternativeTitles(MediaType, i64),
+    WatchProviders(MediaType, i64),
+    Network(i64),
+    List(i64),
+    Collection(i64),
+    Season(i64, i32),
+}
+
+/// How long misses are remembered, per lookup kind. A missing TTL disables
+/// negative caching for that kind. Image, alternative title, watch provider,
+/// network, list, collection and season lookups use the details TTL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeCacheTtls {
+    pub videos: Option<Duration>,
+    pub details: Option<Duration>,
+}
+
+impl NegativeCacheTtls {
+    /// Parses a list such as `videos=30s,details=5m`; `0s` disables a kind.
+    /// Kinds not listed keep the 30 second default.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut ttls = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (kind, ttl) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected kind=ttl, got '{}'", entry))?;
+            let ttl = parse_window(ttl.trim())
+                .ok_or_else(|| format!("invalid TTL '{}', use e.g. 30s or 5m", ttl))?;
+            let ttl = (!ttl.is_zero()).then_some(ttl);
+
+            match kind.trim() {
+                "videos" => ttls.videos = ttl,
+                "details" => ttls.details = ttl,
+                other => return Err(format!("unknown negative cache kind '{}'", other)),
+            }
+        }
+        Ok(ttls)
+    }
+
+    fn ttl(&self, lookup: &Lookup) -> Option<Duration> {
+        match lookup {
+            Lookup::Videos(_) => self.videos,
+            Lookup::Details(..)
+            | Lookup::Images(..)
+            | Lookup::AlternativeTitles(..)
+            | Lookup::WatchProviders(..)
+            | Lookup::Network(_)
+            | Lookup::List(_)
+            | Lookup::Collection(_)
+            | Lookup::Season(..) => self.details,
+        }
+    }
+}
+
+impl Default for NegativeCacheTtls {
+    fn default() -> Self {
+        Self {
+            videos: Some(DEFAULT_TTL),
+            details: Some(DEFAULT_TTL),
+        }
+    }
+}
+
+/// Negative cache counters since startup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NegativeCacheStats {
+    /// Lookups answered from the cache without calling TMDB
+    pub hits: u64,
+    /// `NotFound` results stored in the cache
+    pub stored: u64,
+    /// Misses currently cached
+    pub entries: usize,
+}
+
+/// Remembers which titles TMDB recently reported as not found.
+pub struct NegativeCache {
+    ttls: NegativeCacheTtls,
+    clock: Arc<dyn Clock>,
+    expiries: Mutex<HashMap<Lookup, SystemTime>>,
+    hits: AtomicU64,
+    stored: AtomicU64,
+}
+
+impl NegativeCache {
+    pub fn new(ttls: NegativeCacheTtls, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            ttls,
+            clock,
+            expiries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            stored: AtomicU64::new(0),
+        }
+    }
+
+    /// True if `lookup` is a cached miss that has not expired yet
+    pub fn is_known_missing(&self, lookup: &Lookup) -> bool {
+        let now = self.clock.now();
+        let mut expiries = self.expiries.lock().unwrap();
+
+        match expiries.get(lookup) {
+            Some(expires) if *expires > now => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Some(_) => {
+                expiries.remove(lookup);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Caches `lookup` as missing if its kind has a TTL
+    pub fn store(&self, lookup: Lookup) {
+        let Some(ttl) = self.ttls.ttl(&lookup) else {
+            return;
+        };
+        let now = self.clock.now();
+        let mut expiries = self.expiries.lock().unwrap();
+
+        if expiries.len() >= CAPACITY {
+            expiries.retain(|_, expires| *expires > now);
+            if expiries.len() >= CAPACITY {
+                return;
+            }
+        }
+        expiries.insert(lookup, now + ttl);
+        self.stored.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Forgets cached misses of a title's details, videos, images,
+    /// alternative titles and watch providers, returning whether there were any
+    pub fn forget_title(&self, media_type: MediaType, id: i64) -> bool {
+        let mut expiries = self.expiries.lock().unwrap();
+        let before = expiries.len();
+        expiries.retain(|lookup, _| match *lookup {
+            Lookup::Videos(movie_id) => (media_type, id) != (MediaType::Movie, movie_id),
+            Lookup::Details(entry_type, entry_id)
+            | Lookup::Images(entry_type, entry_id)
+            | Lookup::AlternativeTitles(entry_type, entry_id)
+            | Lookup::WatchProviders(entry_type, entry_id) => (entry_type, entry_id) != (media_type, id),
+            Lookup::Season(tv_id, _) => (media_type, id) != (MediaType::Tv, tv_id),
+            Lookup::Network(_) | Lookup::List(_) | Lookup::Collection(_) => true,
+        });
+        expiries.len() < before
+    }
+
+    pub fn stats(&self) -> NegativeCacheStats {
+        NegativeCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            stored: self.stored.load(Ordering::Relaxed),
+            entries: self.expiries.lock().unwrap().len(),
+        }
+    }
+}
+
+/// `TmdbClient` decorator that answers recently missing titles from a
+/// [`NegativeCache`], so clients retrying bad ids do not reach TMDB.
+pub struct NegativeCachingTmdbClient<C> {
+    inner: C,
+    cache: Arc<NegativeCache>,
+}
+
+impl<C: TmdbClient> NegativeCachingTmdbClient<C> {
+    pub fn new(inner: C, cache: Arc<NegativeCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    fn remember<T>(&self, lookup: Lookup, result: Result<T, TmdbError>) -> Result<T, TmdbError> {
+        if matches!(result, Err(TmdbError::NotFound)) {
+            self.cache.store(lookup);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<C: TmdbClient> TmdbClient for NegativeCachingTmdbClient<C> {
+    async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.inner.get_trending(page).await
+    }
+
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.inner.get_top_rated(media_type, page).await
+    }
+
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.inner.get_popular(media_type, page).await
+    }
+
+    async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.inner.search_content(query, page).await
+    }
+
+    async fn search_content_including_adult(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.inner.search_content_including_adult(query, page).await
+    }
+
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        let lookup = Lookup::Videos(movie_id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_movie_videos(movie_id).await)
+    }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        let lookup = Lookup::Details(media_type, id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_title_details(media_type, id).await)
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        let lookup = Lookup::Images(media_type, id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_images(media_type, id).await)
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        let lookup = Lookup::AlternativeTitles(media_type, id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_alternative_titles(media_type, id).await)
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        let lookup = Lookup::WatchProviders(media_type, id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_watch_providers(media_type, id).await)
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.inner.discover(filter, page).await
+    }
+
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        self.inner.get_provider_list(region).await
+    }
+
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        let lookup = Lookup::Network(id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_network(id).await)
+    }
+
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        let lookup = Lookup::List(list_id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_list(list_id).await)
+    }
+
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError> {
+        let lookup = Lookup::Collection(id);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_collection(id).await)
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        let lookup = Lookup::Season(tv_id, season_number);
+        if self.cache.is_known_missing(&lookup) {
+            return Err(TmdbError::NotFound);
+        }
+        self.remember(lookup, self.inner.get_tv_season(tv_id, season_number).await)
+    }
+
+    async fn get_changes(&self, media_type: MediaType, start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        self.inner.get_changes(media_type, start_date, page).await
+    }
+}
@@ -0,0 +1,127 @@
+// src/new_on.rs
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::error::TmdbError;
+use crate::models::{utc_date, DiscoverFilter, DiscoverSort, MediaType, TmdbResponse};
+use crate::tmdb_client::TmdbClient;
+
+/// How often cached rows are fetched again when `NEW_ON_REFRESH_INTERVAL` isn't set
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum number of cached (media type, provider, region) rows; expired
+/// rows are dropped first
+const CAPACITY: usize = 5_000;
+
+type RowKey = (MediaType, i64, String);
+
+struct Row {
+    response: TmdbResponse,
+    expires: SystemTime,
+    /// Last time a request asked for the row; refreshes don't move it
+    requested: SystemTime,
+}
+
+/// "New on <provider>" rows: a provider's titles in a region, latest
+/// release first, up to today.
+///
+/// TMDB doesn't tell when a title was added to a provider, so the release or
+/// first air date stands in for it. Rows are cached per media type, provider
+/// and region, and [`NewOnCache::refresh`] fetches the cached ones again in
+/// the background so requests rarely wait on TMDB.
+#[derive(Default)]
+pub struct NewOnCache {
+    rows: Mutex<HashMap<RowKey, Row>>,
+}
+
+impl NewOnCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The discover query behind a row as of `today`
+    pub fn filter(media_type: MediaType, provider: i64, region: &str, today: NaiveDate) -> DiscoverFilter {
+        DiscoverFilter {
+            providers: vec![provider],
+            region: Some(region.to_string()),
+            sort: DiscoverSort::Newest,
+            released_by: Some(today),
+            ..DiscoverFilter::new(media_type)
+        }
+    }
+
+    /// Cached or freshly fetched first page of the row.
+    ///
+    /// Failed lookups are not cached; a zero TTL caches nothing.
+    pub async fn lookup(
+        &self,
+        client: &dyn TmdbClient,
+        media_type: MediaType,
+        provider: i64,
+        region: &str,
+        now: SystemTime,
+        ttl: Duration
+    ) -> Result<TmdbResponse, TmdbError> {
+        let key = (media_type, provider, region.to_string());
+        let cached = {
+            let mut rows = self.rows.lock().unwrap();
+            rows.get_mut(&key).filter(|row| row.expires > now).map(|row| {
+                row.requested = now;
+                row.response.clone()
+            })
+        };
+        if let Some(response) = cached {
+            return Ok(response);
+        }
+
+        let response = fetch(client, &key, now).await?;
+        self.store(key, response.clone(), now, ttl, now);
+        Ok(response)
+    }
+
+    /// Fetches every row requested within the last `ttl` again and pushes
+    /// its expiry out by `ttl`, returning how many were refreshed. Other rows
+    /// are dropped rather than refreshed, so rows nobody asks for stop
+    /// costing TMDB calls.
+    pub async fn refresh(&self, client: &dyn TmdbClient, now: SystemTime, ttl: Duration) -> usize {
+        let keys: Vec<(RowKey, SystemTime)> = {
+            let mut rows = self.rows.lock().unwrap();
+            rows.retain(|_, row| row.expires > now && row.requested + ttl > now);
+            rows.iter().map(|(key, row)| (key.clone(), row.requested)).collect()
+        };
+
+        let mut refreshed = 0;
+        for (key, requested) in keys {
+            match fetch(client, &key, now).await {
+                Ok(response) => {
+                    self.store(key, response, now, ttl, requested);
+                    refreshed += 1;
+                }
+                // The cached row stays until it expires
+                Err(error) => tracing::warn!(%error, provider = key.1, region = %key.2, "new-on row refresh failed"),
+            }
+        }
+        refreshed
+    }
+
+    fn store(&self, key: RowKey, response: TmdbResponse, now: SystemTime, ttl: Duration, requested: SystemTime) {
+        if ttl.is_zero() {
+            return;
+        }
+        let mut rows = self.rows.lock().unwrap();
+        if rows.len() >= CAPACITY && !rows.contains_key(&key) {
+            rows.retain(|_, row| row.expires > now);
+            if rows.len() >= CAPACITY {
+                return;
+            }
+        }
+        // A lookup may have asked for the row while it was being refreshed
+        let requested = rows.get(&key).map_or(requested, |row| row.requested.max(requested));
+        rows.insert(key, Row { response, expires: now + ttl, requested });
+    }
+}
+
+async fn fetch(client: &dyn TmdbClient, (media_type, provider, region): &RowKey, now: SystemTime) -> Result<TmdbResponse, TmdbError> {
+    client.discover(&NewOnCache::filter(*media_type, *provider, region, utc_date(now)), 1).await
+}
@@ -0,0 +1,258 @@
+// src/outage.rs
+use async_trait::async_trait;
+use axum::{
+    extract::{Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::error::TmdbError;
+use crate::mode::ServiceMode;
+use crate::state::AppState;
+
+/// How often TMDB is checked when `OUTAGE_CHECK_INTERVAL` isn't set
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Set to `degraded` on every response while an outage is detected
+pub const UPSTREAM_STATUS_HEADER: &str = "x-upstream-status";
+
+/// Failed probes in a row taken as an outage
+const FAILURES_TO_DETECT: u32 = 3;
+
+/// Successful probes in a row before an outage counts as over
+const SUCCESSES_TO_RECOVER: u32 = 2;
+
+/// Overall indicator of a Statuspage-style status page
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusIndicator {
+    None,
+    Minor,
+    Major,
+    Critical,
+    Maintenance,
+    #[serde(other)]
+    Unknown,
+}
+
+impl StatusIndicator {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusIndicator::None => "none",
+            StatusIndicator::Minor => "minor",
+            StatusIndicator::Major => "major",
+            StatusIndicator::Critical => "critical",
+            StatusIndicator::Maintenance => "maintenance",
+            StatusIndicator::Unknown => "unknown",
+        }
+    }
+
+    /// Major and critical incidents and maintenance windows; minor ones
+    /// usually leave the API answering
+    pub fn is_outage(self) -> bool {
+        matches!(self, StatusIndicator::Major | StatusIndicator::Critical | StatusIndicator::Maintenance)
+    }
+}
+
+/// Where TMDB reports incidents and maintenance windows
+#[async_trait]
+pub trait StatusPage: Send + Sync {
+    async fn indicator(&self) -> Result<StatusIndicator, TmdbError>;
+}
+
+/// Reads `status.json` of a page hosted on Atlassian Statuspage, e.g.
+/// `https://status.example.com/api/v2/status.json`
+pub struct StatuspageFeed {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl StatuspageFeed {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("status page HTTP client should build"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct StatusFeed {
+    status: StatusFeedStatus,
+}
+
+#[derive(Deserialize)]
+struct StatusFeedStatus {
+    indicator: StatusIndicator,
+}
+
+#[async_trait]
+impl StatusPage for StatuspageFeed {
+    async fn indicator(&self) -> Result<StatusIndicator, TmdbError> {
+        let response = self.client.get(&self.url).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(TmdbError::from_status(status, response.text().await.unwrap_or_default()));
+        }
+        let feed: StatusFeed = serde_json::from_slice(&response.bytes().await?)?;
+        Ok(feed.status.indicator)
+    }
+}
+
+/// What the monitor knows, shown in `/health/upstream` and `/admin/metrics/outage`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OutageStatus {
+    pub outage: bool,
+    /// Why the current outage was declared
+    pub reason: Option<String>,
+    /// Unix timestamp the current outage was declared at, in seconds
+    pub since: Option<u64>,
+    /// Last status page reading; unset without a status page or when it couldn't be read
+    pub indicator: Option<StatusIndicator>,
+    pub consecutive_failures: u32,
+    /// Outages declared since startup
+    pub detected: u64,
+    /// Whether the monitor switched the service to degraded mode and will switch it back
+    pub switched_mode: bool,
+}
+
+#[derive(Default)]
+struct Monitor {
+    reason: Option<String>,
+    since: Option<SystemTime>,
+    indicator: Option<StatusIndicator>,
+    failures: u32,
+    successes: u32,
+    /// When the monitor switched to degraded mode, to tell its change from an operator's
+    switched_at: Option<u64>,
+}
+
+/// Watches TMDB for outages and maintenance windows and switches the service
+/// to degraded mode while one lasts.
+///
+/// An outage is declared when the status page reports a major incident or
+/// maintenance, or when the health probe fails several times in a row. It
+/// ends once the page is clear and probes succeed again. The mode is only
+/// switched from `normal`, and only switched back if nobody changed it since,
+/// so operator decisions win.
+#[derive(Default)]
+pub struct OutageMonitor {
+    status_page: Option<Arc<dyn StatusPage>>,
+    monitor: Mutex<Monitor>,
+    detected: AtomicU64,
+}
+
+impl OutageMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_status_page(mut self, status_page: Arc<dyn StatusPage>) -> Self {
+        self.status_page = Some(status_page);
+        self
+    }
+
+    pub fn in_outage(&self) -> bool {
+        self.monitor.lock().unwrap().since.is_some()
+    }
+
+    pub fn status(&self) -> OutageStatus {
+        let monitor = self.monitor.lock().unwrap();
+        OutageStatus {
+            outage: monitor.since.is_some(),
+            reason: monitor.reason.clone(),
+            since: monitor.since.map(unix_seconds),
+            indicator: monitor.indicator,
+            consecutive_failures: monitor.failures,
+            detected: self.detected.load(Ordering::Relaxed),
+            switched_mode: monitor.switched_at.is_some(),
+        }
+    }
+
+    /// Reads the status page, probes TMDB and declares or ends an outage.
+    /// Nothing is probed while the service mode keeps calls from TMDB.
+    pub async fn check(&self, state: &AppState) -> OutageStatus {
+        let now = state.clock.now();
+        let indicator = match &self.status_page {
+            Some(status_page) => match status_page.indicator().await {
+                Ok(indicator) => Some(indicator),
+                Err(error) => {
+                    tracing::debug!(%error, "status page unreadable");
+                    None
+                }
+            },
+            None => None,
+        };
+        let probe_ok = if state.mode.mode().allows_upstream() {
+            Some(state.upstream_probe.check(state.tmdb_client.as_ref(), now).await.0.ok)
+        } else {
+            None
+        };
+
+        let mut monitor = self.monitor.lock().unwrap();
+        monitor.indicator = indicator;
+        match probe_ok {
+            Some(true) => (monitor.failures, monitor.successes) = (0, monitor.successes + 1),
+            Some(false) => (monitor.failures, monitor.successes) = (monitor.failures + 1, 0),
+            None => {}
+        }
+
+        let page_outage = indicator.filter(|indicator| indicator.is_outage());
+        if monitor.since.is_none() {
+            let reason = if let Some(indicator) = page_outage {
+                Some(format!("status page reports {}", indicator.as_str()))
+            } else if monitor.failures >= FAILURES_TO_DETECT {
+                Some(format!("{} failed probes in a row", monitor.failures))
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                tracing::warn!(%reason, "TMDB outage detected");
+                self.detected.fetch_add(1, Ordering::Relaxed);
+                monitor.since = Some(now);
+                monitor.reason = Some(reason);
+                if state.mode.mode() == ServiceMode::Normal {
+                    monitor.switched_at = state.mode.set(ServiceMode::Degraded, now).changed_at;
+                }
+            }
+        } else if page_outage.is_none() && monitor.successes >= SUCCESSES_TO_RECOVER {
+            tracing::info!(reason = monitor.reason.as_deref(), "TMDB outage over");
+            monitor.since = None;
+            monitor.reason = None;
+            let mode = state.mode.status();
+            if let Some(switched_at) = monitor.switched_at.take()
+                && mode.mode == ServiceMode::Degraded
+                && mode.changed_at == Some(switched_at)
+            {
+                state.mode.set(ServiceMode::Normal, now);
+            }
+        }
+        drop(monitor);
+        self.status()
+    }
+}
+
+/// Marks responses with `x-upstream-status: degraded` while an outage lasts,
+/// so clients can explain stale or missing data
+pub async fn upstream_status_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let mut response = next.run(request).await;
+    if state.outage.in_outage() {
+        response.headers_mut().insert(UPSTREAM_STATUS_HEADER, HeaderValue::from_static("degraded"));
+    }
+    response
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
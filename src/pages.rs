@@ -0,0 +1,138 @@
+// src/pages.rs
+use askama::Template;
+use axum::{
+    extract::State,
+    Extension,
+    http::{header, StatusCode},
+    response::{Html, IntoResponse},
+};
+use std::collections::HashSet;
+use tokio::task::JoinSet;
+use crate::extract::ValidPath;
+use crate::models::{image_url, MediaType, Movie, TitleDetails, TmdbId};
+use crate::privacy::Tracker;
+use crate::services::{CatalogService, UserService};
+use crate::session::{RecentTitle, SessionId};
+use crate::shaping::Device;
+use crate::state::AppState;
+
+/// Number of trending pages (20 titles each) listed in the sitemap
+const SITEMAP_TRENDING_PAGES: i32 = 5;
+
+/// Server-rendered title page carrying Open Graph and Twitter card metadata,
+/// so shared links unfurl in chat apps and are readable by crawlers.
+#[derive(Template)]
+#[template(path = "title.html")]
+struct TitlePage {
+    title: String,
+    description: String,
+    image: Option<String>,
+    og_type: &'static str,
+}
+
+impl TitlePage {
+    fn new(media_type: MediaType, details: &TitleDetails) -> Self {
+        Self {
+            title: details.display_title().to_string(),
+            description: details.overview.clone().unwrap_or_default(),
+            image: details.poster_path.as_deref().map(|path| image_url("w500", path)),
+            og_type: match media_type {
+                MediaType::Movie => "video.movie",
+                MediaType::Tv => "video.tv_show",
+            },
+        }
+    }
+}
+
+pub async fn title_page(
+    State(state): State<AppState>,
+    tracker: Tracker,
+    session: Option<Extension<SessionId>>,
+    device: Device,
+    ValidPath((media_type, id)): ValidPath<(MediaType, TmdbId)>
+) -> impl IntoResponse {
+    let id = id.get();
+    let now = state.clock.now();
+    tracker.title_view(media_type, id, now);
+
+    let mut details = match state.catalog().details(media_type, id).await {
+        Ok(details) => details,
+        Err(e) => return e.into_response(),
+    };
+    if let Err(e) = device.check_details(&mut details) {
+        return e.into_response();
+    }
+
+    if let Some(Extension(session)) = session {
+        let title = RecentTitle {
+            media_type,
+            id,
+            title: details.display_title().to_string(),
+            poster_path: details.poster_path.clone(),
+        };
+        state.users().record_view(&session, title, &details.genres);
+    }
+
+    match TitlePage::new(media_type, &details).render() {
+        Ok(html) => Html(html).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render page").into_response(),
+    }
+}
+
+/// Sitemap of title pages for crawlers, built from the current trending feed.
+///
+/// URLs are absolute, built from the configured public base URL rather than
+/// request headers, which a client could spoof.
+pub async fn sitemap(State(state): State<AppState>) -> impl IntoResponse {
+    let mut tasks = JoinSet::new();
+    for page in 1..=SITEMAP_TRENDING_PAGES {
+        let catalog = state.catalog();
+        tasks.spawn(async move { (page, catalog.trending(page).await) });
+    }
+
+    let mut responses = Vec::with_capacity(SITEMAP_TRENDING_PAGES as usize);
+    while let Some(Ok(response)) = tasks.join_next().await {
+        responses.push(response);
+    }
+    responses.sort_by_key(|(page, _)| *page);
+
+    // The first page is required; later pages are best effort
+    let mut titles: Vec<Movie> = Vec::new();
+    for (page, response) in responses {
+        match response {
+            Ok(response) => titles.extend(response.results),
+            Err(e) if page == 1 => return e.into_response(),
+            Err(_) => {}
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for title in titles {
+        let media_type = match title.media_type.as_deref() {
+            Some("movie") => MediaType::Movie,
+            Some("tv") => MediaType::Tv,
+            _ => continue,
+        };
+        if seen.insert((media_type, title.id)) {
+            xml.push_str(&format!(
+                "  <url><loc>{}/title/{}/{}</loc></url>\n",
+                escape_xml(&state.public_base_url), media_type.as_str(), title.id
+            ));
+        }
+    }
+    xml.push_str("</urlset>\n");
+
+    ([(header::CONTENT_TYPE, "application/xml")], xml).into_response()
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,62 @@
+// src/privacy.rs
+use axum::{extract::FromRequestParts, http::{request::Parts, HeaderMap}};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use crate::analytics::{SearchAnalytics, TitleAnalytics};
+use crate::models::MediaType;
+use crate::state::AppState;
+
+/// Returns false if the caller opted out of tracking via `DNT: 1` or the
+/// Global Privacy Control header `Sec-GPC: 1`.
+pub fn tracking_allowed(headers: &HeaderMap) -> bool {
+    let opted_out = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.trim() == "1")
+    };
+
+    !(opted_out("dnt") || opted_out("sec-gpc"))
+}
+
+/// Single entry point for analytics events emitted by handlers.
+///
+/// Extracted per request, it carries the caller's tracking preference, so
+/// opted-out requests are dropped here rather than in every handler.
+pub struct Tracker {
+    enabled: bool,
+    search_analytics: Arc<SearchAnalytics>,
+    title_analytics: Arc<TitleAnalytics>,
+}
+
+impl Tracker {
+    /// A tracker recording into `state`'s analytics when `enabled`
+    pub fn new(state: &AppState, enabled: bool) -> Self {
+        Tracker {
+            enabled,
+            search_analytics: state.search_analytics.clone(),
+            title_analytics: state.title_analytics.clone(),
+        }
+    }
+
+    pub fn search(&self, query: &str, result_count: usize, latency: Duration, at: SystemTime) {
+        if self.enabled {
+            self.search_analytics.record(query, result_count, latency, at);
+        }
+    }
+
+    pub fn title_view(&self, media_type: MediaType, id: i64, at: SystemTime) {
+        if self.enabled {
+            self.title_analytics.record(media_type, id, at);
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for Tracker {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        Ok(Tracker::new(state, tracking_allowed(&parts.headers)))
+    }
+}
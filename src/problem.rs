@@ -0,0 +1,216 @@
+// src/problem.rs
+use axum::{
+    body::{self, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rand::RngCore;
+use serde::Serialize;
+use serde_json::Value;
+use crate::error::TmdbError;
+
+/// Media type of RFC 9457 problem details
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Header carrying the request id, taken from the client when valid and
+/// echoed on every response
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request id that is reused
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Largest error body read back to build a problem; error bodies are short
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Identifies the current request in logs and problem details.
+///
+/// Inserted into request extensions by [`problem_middleware`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// An error as every layer reports it: a status, a stable machine-readable
+/// `code` and a human-readable `detail`.
+///
+/// As a response it is the plain-text `detail`, as before;
+/// [`problem_middleware`] turns it into problem details for clients that
+/// accept them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: String,
+    pub detail: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { status, code: code.into(), detail: Some(detail.into()) }
+    }
+
+    /// The error behind a response built without an `ApiError`, e.g. by a
+    /// middleware or an extractor rejection. JSON bodies may name the code
+    /// in `error` and the detail in `message`; other bodies are the detail.
+    pub fn from_response(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let code = default_code(status).to_string();
+        let is_json = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+        if is_json && let Ok(value) = serde_json::from_slice::<Value>(body) {
+            return Self {
+                status,
+                code: value["error"].as_str().map_or(code, str::to_string),
+                detail: value["message"].as_str().map(str::to_string),
+            };
+        }
+        let detail = String::from_utf8_lossy(body).trim().to_string();
+        Self { status, code, detail: (!detail.is_empty()).then_some(detail) }
+    }
+
+    /// RFC 9457 problem details for the request at `instance`
+    pub fn to_problem(&self, instance: &str, request_id: Option<&RequestId>) -> Problem {
+        Problem {
+            r#type: "about:blank",
+            title: self.status.canonical_reason().unwrap_or("Error").to_string(),
+            status: self.status.as_u16(),
+            detail: self.detail.clone(),
+            instance: instance.to_string(),
+            code: self.code.clone(),
+            request_id: request_id.map(|id| id.0.clone()),
+        }
+    }
+}
+
+impl From<TmdbError> for ApiError {
+    fn from(error: TmdbError) -> Self {
+        let code = match &error {
+            TmdbError::NotFound => "not_found",
+            TmdbError::Unauthorized => "upstream_unauthorized",
+            TmdbError::RateLimitExceeded => "upstream_rate_limited",
+            TmdbError::BadRequest(_) => "upstream_bad_request",
+            TmdbError::ServerError(_) | TmdbError::Unknown(_, _) => "upstream_error",
+            TmdbError::NetworkError(_) => "upstream_unreachable",
+            TmdbError::ParseError(_) => "upstream_invalid_response",
+            TmdbError::UpstreamDisabled => "upstream_disabled",
+        };
+        let (status, message) = crate::handlers::map_error_to_response(error);
+        ApiError::new(status, code, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let detail = self.detail.clone().unwrap_or_default();
+        let mut response = (self.status, detail).into_response();
+        // Keeps the code for problem_middleware
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Body of an `application/problem+json` response, with our `code` and
+/// `request_id` extension members
+#[derive(Clone, Debug, Serialize)]
+pub struct Problem {
+    pub r#type: &'static str,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Path of the request that failed
+    pub instance: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Code used when a layer didn't give one
+fn default_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "bad_request",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::UNPROCESSABLE_ENTITY => "unprocessable_entity",
+        StatusCode::TOO_MANY_REQUESTS => "too_many_requests",
+        StatusCode::BAD_GATEWAY => "upstream_error",
+        StatusCode::SERVICE_UNAVAILABLE => "service_unavailable",
+        StatusCode::GATEWAY_TIMEOUT => "timeout",
+        status if status.is_server_error() => "internal_error",
+        _ => "error",
+    }
+}
+
+/// True if the client lists `application/problem+json` in `Accept` without `q=0`
+pub fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            params.next().is_some_and(|media| media.eq_ignore_ascii_case(PROBLEM_JSON))
+                && !params.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q == 0.0)
+                })
+        })
+}
+
+/// Gives every request an id and, for clients accepting
+/// `application/problem+json`, rewrites error responses from any layer as
+/// problem details. Other clients get error bodies unchanged.
+pub async fn problem_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map_or_else(new_request_id, str::to_string);
+    let request_id = RequestId(request_id);
+    let wants_problem = accepts_problem_json(request.headers());
+    let instance = request.uri().path().to_string();
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    let status = response.status();
+    if !wants_problem || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let error = match parts.extensions.remove::<ApiError>() {
+        Some(error) => error,
+        None => match body::to_bytes(body, MAX_ERROR_BODY).await {
+            Ok(bytes) => ApiError::from_response(status, &parts.headers, &bytes),
+            Err(_) => ApiError { status, code: default_code(status).to_string(), detail: None },
+        },
+    };
+
+    let problem = serde_json::to_vec(&error.to_problem(&instance, Some(&request_id))).unwrap_or_default();
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(problem))
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn new_request_id() -> String {
+    let mut bytes = [0u8; 8];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,150 @@
+// src/profiling.rs
+use axum::{
+    extract::Query,
+    http::{header, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use pprof::protos::Message;
+use serde::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use crate::admin::require_admin;
+use crate::state::AppState;
+
+/// Mount point of the profiling routes
+pub const MOUNT_PATH: &str = "/debug/pprof";
+
+const DEFAULT_PROFILE_SECONDS: u64 = 10;
+const MAX_PROFILE_SECONDS: u64 = 60;
+const DEFAULT_FREQUENCY: i32 = 99;
+
+/// Builds the profiling routes, guarded by the admin token and client certificates like `/admin`.
+///
+/// Only mounted when profiling is switched on in config, since sampling adds
+/// overhead and the output reveals symbol names.
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/profile", get(cpu_profile))
+        .route("/allocs", get(allocs))
+        .route_layer(middleware::from_fn_with_state(state, require_admin))
+}
+
+#[derive(Deserialize)]
+pub struct ProfileQuery {
+    pub seconds: Option<u64>,
+    pub frequency: Option<i32>,
+}
+
+/// Samples the whole process for `seconds` and returns an uncompressed pprof
+/// protobuf, readable with `go tool pprof`.
+async fn cpu_profile(Query(params): Query<ProfileQuery>) -> Response {
+    let seconds = params.seconds.unwrap_or(DEFAULT_PROFILE_SECONDS);
+    if seconds == 0 || seconds > MAX_PROFILE_SECONDS {
+        return (StatusCode::BAD_REQUEST, "seconds must be between 1 and 60").into_response();
+    }
+    let frequency = params.frequency.unwrap_or(DEFAULT_FREQUENCY);
+    if !(1..=1000).contains(&frequency) {
+        return (StatusCode::BAD_REQUEST, "frequency must be between 1 and 1000").into_response();
+    }
+
+    // The profiler guard is not Send, so sample on a blocking thread
+    let profile = tokio::task::spawn_blocking(move || {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(frequency)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .map_err(|_| (StatusCode::CONFLICT, "A profile is already being taken"))?;
+        std::thread::sleep(Duration::from_secs(seconds));
+
+        guard
+            .report()
+            .build()
+            .and_then(|report| report.pprof())
+            .map(|profile| profile.encode_to_vec())
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to build profile"))
+    })
+    .await;
+
+    match profile {
+        Ok(Ok(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "application/octet-stream"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"profile.pb\""),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(Err(error)) => error.into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Failed to build profile").into_response(),
+    }
+}
+
+async fn allocs() -> impl IntoResponse {
+    Json(allocation_stats())
+}
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static FREED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Heap activity since startup, as counted by [`CountingAllocator`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AllocationStats {
+    pub allocations: u64,
+    pub deallocations: u64,
+    pub allocated_bytes: u64,
+    pub freed_bytes: u64,
+    pub live_bytes: u64,
+}
+
+pub fn allocation_stats() -> AllocationStats {
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let freed_bytes = FREED_BYTES.load(Ordering::Relaxed);
+
+    AllocationStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+        allocated_bytes,
+        freed_bytes,
+        live_bytes: allocated_bytes.saturating_sub(freed_bytes),
+    }
+}
+
+/// System allocator that counts allocations for `/debug/pprof/allocs`.
+///
+/// Stats stay at zero unless the binary registers it as `#[global_allocator]`,
+/// which it only does when built with the `alloc-stats` feature.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        FREED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+            FREED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
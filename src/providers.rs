@@ -0,0 +1,205 @@
+// src/providers.rs
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::error::TmdbError;
+use crate::models::{AvailabilityMatrix, MediaType, ProviderList, ProviderSummary, RegionAvailability, RegionProviders, WatchProvider};
+use crate::tmdb_client::TmdbClient;
+
+/// Maximum number of cached (title, region) entries; expired entries are dropped first.
+const CAPACITY: usize = 50_000;
+
+type AvailabilityKey = (MediaType, i64, String);
+
+/// Watch providers per title and region.
+///
+/// TMDB returns every region in one payload, so a miss on any requested
+/// region costs a single call that refreshes all requested regions.
+/// Regions without offers are cached too.
+#[derive(Default)]
+pub struct AvailabilityCache {
+    entries: Mutex<HashMap<AvailabilityKey, (RegionProviders, SystemTime)>>,
+}
+
+impl AvailabilityCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Titles with a fresh entry at `now`
+    pub fn titles(&self, now: SystemTime) -> HashSet<(MediaType, i64)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|(_, (_, expires))| *expires > now)
+            .map(|((media_type, id, _), _)| (*media_type, *id))
+            .collect()
+    }
+
+    /// Returns the cached offers for `region`, unless expired
+    pub fn get(&self, media_type: MediaType, id: i64, region: &str, now: SystemTime) -> Option<RegionProviders> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&(media_type, id, region.to_string()))
+            .filter(|(_, expires)| *expires > now)
+            .map(|(providers, _)| providers.clone())
+    }
+
+    /// Caches `providers` for `ttl`; a zero TTL caches nothing
+    pub fn store(&self, media_type: MediaType, id: i64, region: &str, providers: RegionProviders, now: SystemTime, ttl: Duration) {
+        if ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= CAPACITY {
+            entries.retain(|_, (_, expires)| *expires > now);
+            if entries.len() >= CAPACITY {
+                return;
+            }
+        }
+        entries.insert((media_type, id, region.to_string()), (providers, now + ttl));
+    }
+
+    /// Drops the title's offers in every region, returning whether there were any
+    pub fn invalidate(&self, media_type: MediaType, id: i64) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|(entry_type, entry_id, _), _| (*entry_type, *entry_id) != (media_type, id));
+        entries.len() < before
+    }
+
+    /// Offers for each of `regions`, in the same order, from the cache or TMDB.
+    ///
+    /// Failed lookups are not cached.
+    pub async fn lookup(
+        &self,
+        client: &dyn TmdbClient,
+        media_type: MediaType,
+        id: i64,
+        regions: &[String],
+        now: SystemTime,
+        ttl: Duration
+    ) -> Result<Vec<(String, RegionProviders)>, TmdbError> {
+        let cached: Vec<Option<RegionProviders>> = regions
+            .iter()
+            .map(|region| self.get(media_type, id, region, now))
+            .collect();
+        if cached.iter().all(Option::is_some) {
+            return Ok(regions.iter().cloned().zip(cached.into_iter().flatten()).collect());
+        }
+
+        let mut response = client.get_watch_providers(media_type, id).await?;
+        Ok(regions
+            .iter()
+            .map(|region| {
+                let providers = response.results.remove(region).unwrap_or_default();
+                self.store(media_type, id, region, providers.clone(), now, ttl);
+                (region.clone(), providers)
+            })
+            .collect())
+    }
+}
+
+/// Watch provider lists per region, which change rarely and back every
+/// filter chip row.
+#[derive(Default)]
+pub struct ProviderListCache {
+    entries: Mutex<HashMap<String, (ProviderList, SystemTime)>>,
+}
+
+impl ProviderListCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached or freshly fetched provider list for `region`.
+    ///
+    /// Failed lookups are not cached; a zero TTL caches nothing.
+    pub async fn lookup(
+        &self,
+        client: &dyn TmdbClient,
+        region: &str,
+        now: SystemTime,
+        ttl: Duration
+    ) -> Result<ProviderList, TmdbError> {
+        let cached = {
+            let entries = self.entries.lock().unwrap();
+            entries
+                .get(region)
+                .filter(|(_, expires)| *expires > now)
+                .map(|(list, _)| list.clone())
+        };
+        if let Some(list) = cached {
+            return Ok(list);
+        }
+
+        let list = client.get_provider_list(region).await?;
+        if !ttl.is_zero() {
+            // At most one entry per country, so no capacity bound is needed
+            self.entries.lock().unwrap().insert(region.to_string(), (list.clone(), now + ttl));
+        }
+        Ok(list)
+    }
+}
+
+/// Builds the compact matrix for `id` from per-region offers.
+///
+/// Providers are listed once, most prominent first, and regions refer to
+/// them by id.
+pub fn availability_matrix(id: i64, regions: Vec<(String, RegionProviders)>) -> AvailabilityMatrix {
+    let mut providers: Vec<&WatchProvider> = Vec::new();
+    for (_, offers) in &regions {
+        for provider in offer_lists(offers).into_iter().flatten() {
+            if !providers.iter().any(|known| known.provider_id == provider.provider_id) {
+                providers.push(provider);
+            }
+        }
+    }
+    providers.sort_by(|a, b| {
+        a.display_priority
+            .cmp(&b.display_priority)
+            .then_with(|| a.provider_name.cmp(&b.provider_name))
+    });
+
+    let ids = |list: &[WatchProvider]| list.iter().map(|provider| provider.provider_id).collect();
+    AvailabilityMatrix {
+        id,
+        providers: providers.into_iter().map(ProviderSummary::from).collect(),
+        regions: regions
+            .iter()
+            .map(|(region, offers)| {
+                let availability = RegionAvailability {
+                    flatrate: ids(&offers.flatrate),
+                    free: ids(&offers.free),
+                    ads: ids(&offers.ads),
+                    rent: ids(&offers.rent),
+                    buy: ids(&offers.buy),
+                };
+                (region.clone(), availability)
+            })
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+fn offer_lists(offers: &RegionProviders) -> [&[WatchProvider]; 5] {
+    [&offers.flatrate, &offers.free, &offers.ads, &offers.rent, &offers.buy]
+}
+
+/// Providers shown on a list item's badge
+pub const BADGE_PROVIDERS: usize = 3;
+
+/// Region of the badges when the request names none
+pub const DEFAULT_BADGE_REGION: &str = "US";
+
+/// The most prominent providers streaming a title, with or without a
+/// subscription; rentals and purchases don't make it "available on" one
+pub fn top_streaming(offers: &RegionProviders) -> Vec<ProviderSummary> {
+    let mut streaming: Vec<&WatchProvider> = Vec::new();
+    for provider in [&offers.flatrate, &offers.free, &offers.ads].into_iter().flatten() {
+        if !streaming.iter().any(|known| known.provider_id == provider.provider_id) {
+            streaming.push(provider);
+        }
+    }
+    streaming.sort_by_key(|provider| provider.display_priority);
+    streaming.into_iter().take(BADGE_PROVIDERS).map(ProviderSummary::from).collect()
+}
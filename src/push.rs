@@ -0,0 +1,495 @@
+// src/push.rs
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use crate::saved_searches::{Notification, SavedSearches};
+use crate::session::{SessionId, DEFAULT_SESSION_TTL};
+
+/// Devices per session; registering another replaces the oldest
+pub const MAX_DEVICES: usize = 5;
+
+/// Longest device token accepted; FCM tokens are a few hundred characters
+const MAX_TOKEN_CHARS: usize = 4096;
+
+/// Attempts per push before a transient failure is given up on
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry, doubled for each further one
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Titles named in a push's body
+const BODY_TITLES: usize = 3;
+
+/// Push service a device token belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    /// Firebase Cloud Messaging, for Android and web
+    Fcm,
+    /// Apple Push Notification service
+    Apns,
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Fcm => "fcm",
+            Platform::Apns => "apns",
+        }
+    }
+}
+
+/// Body of `POST /api/push/devices`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushDevice {
+    pub platform: Platform,
+    pub token: String,
+}
+
+/// What a device shows, the same on every platform
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    /// Passed to the app, e.g. to open the notification's search
+    pub data: BTreeMap<String, String>,
+}
+
+impl PushMessage {
+    /// Names the first few new titles, e.g. "Dune, Arrival and 2 more"
+    pub fn from_notification(notification: &Notification) -> Self {
+        let names: Vec<&str> = notification
+            .titles
+            .iter()
+            .filter_map(|title| title.title.as_deref().or(title.name.as_deref()))
+            .take(BODY_TITLES)
+            .collect();
+        let body = match notification.titles.len() - names.len() {
+            0 => names.join(", "),
+            more => format!("{} and {} more", names.join(", "), more),
+        };
+        let data = BTreeMap::from([
+            ("notification_id".to_string(), notification.id.to_string()),
+            ("search_id".to_string(), notification.search_id.to_string()),
+        ]);
+        Self { title: notification.message.clone(), body, data }
+    }
+}
+
+/// Why a push failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PushError {
+    /// Worth retrying, e.g. a timeout, throttling or an expired credential
+    Transient(String),
+    /// The token will never work again, e.g. the app was uninstalled
+    Unregistered(String),
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushError::Transient(reason) => write!(f, "transient push failure: {}", reason),
+            PushError::Unregistered(reason) => write!(f, "device token unregistered: {}", reason),
+        }
+    }
+}
+
+/// Delivers a message to one device of a platform
+#[async_trait]
+pub trait PushSender: Send + Sync {
+    async fn send(&self, token: &str, message: &PushMessage) -> Result<(), PushError>;
+}
+
+/// Registered devices and what happened to pushes since startup
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PushStats {
+    pub devices: usize,
+    pub delivered: u64,
+    /// Pushes given up on after retrying
+    pub failed: u64,
+    /// Tokens dropped because their push service rejected them for good
+    pub removed: u64,
+}
+
+struct Registration {
+    device: PushDevice,
+    registered_at: SystemTime,
+}
+
+/// Pushes saved search notifications to the devices of their session.
+///
+/// [`PushNotifier::deliver`] runs after every saved search refresh and sends
+/// notifications created since the previous run. Transient failures are
+/// retried with backoff; tokens the push service reports as unregistered are
+/// removed. Apps re-register on launch, so registrations expire like
+/// sessions when they aren't renewed.
+pub struct PushNotifier {
+    senders: HashMap<Platform, Arc<dyn PushSender>>,
+    ttl: Duration,
+    devices: Mutex<HashMap<SessionId, Vec<Registration>>>,
+    /// Id of the newest notification already handed to delivery
+    cursor: AtomicU64,
+    delivered: AtomicU64,
+    failed: AtomicU64,
+    removed: AtomicU64,
+}
+
+impl PushNotifier {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            senders: HashMap::new(),
+            ttl,
+            devices: Mutex::new(HashMap::new()),
+            cursor: AtomicU64::new(0),
+            delivered: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            removed: AtomicU64::new(0),
+        }
+    }
+
+    /// Devices of `platform` can only be registered once it has a sender
+    pub fn with_sender(mut self, platform: Platform, sender: Arc<dyn PushSender>) -> Self {
+        self.senders.insert(platform, sender);
+        self
+    }
+
+    /// Configures the senders of the enabled `fcm` and `apns` features from
+    /// `FCM_PROJECT_ID` and `FCM_ACCESS_TOKEN_FILE`, and `APNS_KEY_FILE`,
+    /// `APNS_KEY_ID`, `APNS_TEAM_ID`, `APNS_TOPIC` and `APNS_SANDBOX`
+    pub fn from_env() -> Self {
+        let notifier = Self::default();
+        #[cfg(feature = "fcm")]
+        let notifier = match (std::env::var("FCM_PROJECT_ID"), std::env::var("FCM_ACCESS_TOKEN_FILE")) {
+            (Ok(project_id), Ok(token_file)) => notifier.with_sender(Platform::Fcm, Arc::new(FcmSender::new(&project_id, token_file))),
+            _ => notifier,
+        };
+        #[cfg(feature = "apns")]
+        let notifier = match std::env::var("APNS_KEY_FILE") {
+            Ok(key_file) => {
+                let var = |key: &str| std::env::var(key).unwrap_or_else(|_| panic!("{} must be set with APNS_KEY_FILE", key));
+                let key = std::fs::read_to_string(&key_file).expect("APNS_KEY_FILE must be a readable .p8 key");
+                let sandbox = std::env::var("APNS_SANDBOX").is_ok_and(|sandbox| sandbox == "true");
+                let sender = ApnsSender::new(&key, var("APNS_KEY_ID"), var("APNS_TEAM_ID"), var("APNS_TOPIC"), sandbox)
+                    .expect("APNS_KEY_FILE must hold a PKCS#8 P-256 key");
+                notifier.with_sender(Platform::Apns, Arc::new(sender))
+            }
+            Err(_) => notifier,
+        };
+        notifier
+    }
+
+    /// Registers a device for the session's notifications, returning whether
+    /// it is new to the session. A token registered by another session moves
+    /// to this one.
+    pub fn register(&self, session: &SessionId, device: PushDevice, now: SystemTime) -> Result<bool, String> {
+        if !self.senders.contains_key(&device.platform) {
+            return Err(format!("Push notifications via {} are not available", device.platform.as_str()));
+        }
+        let token = device.token.trim();
+        if token.is_empty() || token.chars().count() > MAX_TOKEN_CHARS {
+            return Err(format!("token must be between 1 and {} characters", MAX_TOKEN_CHARS));
+        }
+        let device = PushDevice { platform: device.platform, token: token.to_string() };
+
+        let mut devices = self.devices.lock().unwrap();
+        self.evict_expired(&mut devices, now);
+        let known = devices
+            .get(session)
+            .is_some_and(|registrations| registrations.iter().any(|registration| registration.device == device));
+        for registrations in devices.values_mut() {
+            registrations.retain(|registration| registration.device.token != device.token);
+        }
+        devices.retain(|_, registrations| !registrations.is_empty());
+
+        let registrations = devices.entry(session.clone()).or_default();
+        registrations.push(Registration { device, registered_at: now });
+        if registrations.len() > MAX_DEVICES {
+            registrations.remove(0);
+        }
+        Ok(!known)
+    }
+
+    /// Removes a device of the session, returning whether it was registered
+    pub fn unregister(&self, session: &SessionId, token: &str) -> bool {
+        let mut devices = self.devices.lock().unwrap();
+        let Some(registrations) = devices.get_mut(session) else {
+            return false;
+        };
+        let before = registrations.len();
+        registrations.retain(|registration| registration.device.token != token);
+        let removed = registrations.len() < before;
+        if registrations.is_empty() {
+            devices.remove(session);
+        }
+        removed
+    }
+
+    /// The session's devices, oldest registration first
+    pub fn devices(&self, session: &SessionId, now: SystemTime) -> Vec<PushDevice> {
+        let mut devices = self.devices.lock().unwrap();
+        self.evict_expired(&mut devices, now);
+        devices
+            .get(session)
+            .map(|registrations| registrations.iter().map(|registration| registration.device.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Pushes notifications created since the last run to their session's
+    /// devices and returns how many pushes were delivered
+    pub async fn deliver(&self, saved_searches: &SavedSearches, now: SystemTime) -> usize {
+        let pending = saved_searches.notifications_after(self.cursor.load(Ordering::Relaxed));
+        let Some(newest) = pending.iter().map(|(_, notification)| notification.id).max() else {
+            return 0;
+        };
+        self.cursor.store(newest, Ordering::Relaxed);
+
+        let mut delivered = 0;
+        for (session, notification) in &pending {
+            let devices = self.devices(session, now);
+            if devices.is_empty() {
+                continue;
+            }
+            let message = PushMessage::from_notification(notification);
+            for device in devices {
+                let Some(sender) = self.senders.get(&device.platform) else {
+                    continue;
+                };
+                match self.send(sender.as_ref(), &device.token, &message).await {
+                    Ok(()) => {
+                        self.delivered.fetch_add(1, Ordering::Relaxed);
+                        delivered += 1;
+                    }
+                    Err(PushError::Unregistered(reason)) => {
+                        tracing::info!(platform = device.platform.as_str(), %reason, "removed unregistered push token");
+                        self.remove_token(&device.token);
+                    }
+                    Err(PushError::Transient(reason)) => {
+                        tracing::warn!(platform = device.platform.as_str(), %reason, "push failed");
+                        self.failed.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+        delivered
+    }
+
+    pub fn stats(&self) -> PushStats {
+        PushStats {
+            devices: self.devices.lock().unwrap().values().map(Vec::len).sum(),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            removed: self.removed.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn send(&self, sender: &dyn PushSender, token: &str, message: &PushMessage) -> Result<(), PushError> {
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match sender.send(token, message).await {
+                Err(PushError::Transient(reason)) if attempt < MAX_ATTEMPTS => {
+                    tracing::debug!(attempt, %reason, "retrying push");
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn remove_token(&self, token: &str) {
+        let mut devices = self.devices.lock().unwrap();
+        for registrations in devices.values_mut() {
+            let before = registrations.len();
+            registrations.retain(|registration| registration.device.token != token);
+            self.removed.fetch_add((before - registrations.len()) as u64, Ordering::Relaxed);
+        }
+        devices.retain(|_, registrations| !registrations.is_empty());
+    }
+
+    fn evict_expired(&self, devices: &mut HashMap<SessionId, Vec<Registration>>, now: SystemTime) {
+        for registrations in devices.values_mut() {
+            registrations.retain(|registration| {
+                now.duration_since(registration.registered_at).ok().is_none_or(|age| age <= self.ttl)
+            });
+        }
+        devices.retain(|_, registrations| !registrations.is_empty());
+    }
+}
+
+impl Default for PushNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_TTL)
+    }
+}
+
+#[cfg(any(feature = "fcm", feature = "apns"))]
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("push HTTP client should build")
+}
+
+/// Sends through the FCM HTTP v1 API. The OAuth access token is read from a
+/// file on every push, so whatever refreshes it (e.g. a metadata server
+/// sidecar) needs no restart.
+#[cfg(feature = "fcm")]
+pub struct FcmSender {
+    client: reqwest::Client,
+    url: String,
+    access_token_file: std::path::PathBuf,
+}
+
+#[cfg(feature = "fcm")]
+impl FcmSender {
+    pub fn new(project_id: &str, access_token_file: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            client: http_client(),
+            url: format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", project_id),
+            access_token_file: access_token_file.into(),
+        }
+    }
+}
+
+#[cfg(feature = "fcm")]
+#[async_trait]
+impl PushSender for FcmSender {
+    async fn send(&self, token: &str, message: &PushMessage) -> Result<(), PushError> {
+        let access_token = tokio::fs::read_to_string(&self.access_token_file)
+            .await
+            .map_err(|e| PushError::Transient(format!("FCM access token unreadable: {}", e)))?;
+        let body = serde_json::json!({
+            "message": {
+                "token": token,
+                "notification": { "title": message.title, "body": message.body },
+                "data": message.data,
+            }
+        });
+        let response = self
+            .client
+            .post(&self.url)
+            .bearer_auth(access_token.trim())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| PushError::Transient(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let detail = response.text().await.unwrap_or_default();
+        // Uninstalled apps answer 404 UNREGISTERED, malformed tokens 400 INVALID_ARGUMENT
+        if status == reqwest::StatusCode::NOT_FOUND || (status == reqwest::StatusCode::BAD_REQUEST && detail.contains("INVALID_ARGUMENT")) {
+            return Err(PushError::Unregistered(format!("FCM answered {}", status)));
+        }
+        Err(PushError::Transient(format!("FCM answered {}: {}", status, detail)))
+    }
+}
+
+/// APNs provider tokens are valid for an hour and may be renewed every 20 minutes
+#[cfg(feature = "apns")]
+const PROVIDER_TOKEN_LIFETIME: Duration = Duration::from_secs(40 * 60);
+
+/// Sends through APNs over HTTP/2, authenticated with a provider token
+/// signed by the team's `.p8` key
+#[cfg(feature = "apns")]
+pub struct ApnsSender {
+    client: reqwest::Client,
+    base_url: &'static str,
+    key_id: String,
+    team_id: String,
+    topic: String,
+    key: ring::signature::EcdsaKeyPair,
+    provider_token: Mutex<Option<(String, SystemTime)>>,
+}
+
+#[cfg(feature = "apns")]
+impl ApnsSender {
+    /// `key_pem` is the contents of the `.p8` key; `topic` is the app's bundle id
+    pub fn new(key_pem: &str, key_id: String, team_id: String, topic: String, sandbox: bool) -> Result<Self, String> {
+        use base64::Engine;
+        use ring::signature::{EcdsaKeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+
+        let body: String = key_pem.lines().filter(|line| !line.starts_with("-----")).collect();
+        let der = base64::engine::general_purpose::STANDARD.decode(body.trim()).map_err(|e| e.to_string())?;
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &der, &ring::rand::SystemRandom::new())
+            .map_err(|e| e.to_string())?;
+        let base_url = if sandbox { "https://api.sandbox.push.apple.com" } else { "https://api.push.apple.com" };
+        Ok(Self { client: http_client(), base_url, key_id, team_id, topic, key, provider_token: Mutex::new(None) })
+    }
+
+    /// The current provider token, signing a new one once it gets old
+    fn provider_token(&self) -> Result<String, PushError> {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let now = SystemTime::now();
+        let mut cached = self.provider_token.lock().unwrap();
+        if let Some((token, issued)) = cached.as_ref()
+            && now.duration_since(*issued).is_ok_and(|age| age < PROVIDER_TOKEN_LIFETIME)
+        {
+            return Ok(token.clone());
+        }
+
+        let issued_at = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+        let header = serde_json::json!({ "alg": "ES256", "kid": self.key_id });
+        let claims = serde_json::json!({ "iss": self.team_id, "iat": issued_at });
+        let signing_input = format!("{}.{}", URL_SAFE_NO_PAD.encode(header.to_string()), URL_SAFE_NO_PAD.encode(claims.to_string()));
+        let signature = self
+            .key
+            .sign(&ring::rand::SystemRandom::new(), signing_input.as_bytes())
+            .map_err(|_| PushError::Transient("signing the APNs provider token failed".to_string()))?;
+        let token = format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature.as_ref()));
+        *cached = Some((token.clone(), now));
+        Ok(token)
+    }
+}
+
+#[cfg(feature = "apns")]
+#[async_trait]
+impl PushSender for ApnsSender {
+    async fn send(&self, token: &str, message: &PushMessage) -> Result<(), PushError> {
+        let mut payload = serde_json::json!({
+            "aps": { "alert": { "title": message.title, "body": message.body } }
+        });
+        for (key, value) in &message.data {
+            payload[key] = serde_json::Value::String(value.clone());
+        }
+        let response = self
+            .client
+            .post(format!("{}/3/device/{}", self.base_url, token))
+            .bearer_auth(self.provider_token()?)
+            .header("apns-topic", &self.topic)
+            .header("apns-push-type", "alert")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| PushError::Transient(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let reason = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body["reason"].as_str().map(str::to_string))
+            .unwrap_or_default();
+        match (status.as_u16(), reason.as_str()) {
+            (410, _) | (400, "BadDeviceToken" | "DeviceTokenNotForTopic") => Err(PushError::Unregistered(reason)),
+            (403, "ExpiredProviderToken") => {
+                *self.provider_token.lock().unwrap() = None;
+                Err(PushError::Transient(reason))
+            }
+            _ => Err(PushError::Transient(format!("APNs answered {}: {}", status, reason))),
+        }
+    }
+}
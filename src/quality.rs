@@ -0,0 +1,91 @@
+// src/quality.rs
+use serde::Deserialize;
+use crate::models::{Movie, TmdbResponse};
+
+const DEFAULT_MEAN: f64 = 6.5;
+const DEFAULT_VOTES: u32 = 250;
+
+/// Priors of the Bayesian average behind `quality_score`: every title is
+/// scored as if it also had `votes` extra votes averaging `mean`, so a 9.0
+/// from a handful of votes doesn't outrank a 8.4 from thousands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QualityPriors {
+    pub mean: f64,
+    pub votes: u32,
+}
+
+impl QualityPriors {
+    /// Parses a list such as `mean=6.5,votes=250`; keys not listed keep their defaults
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut priors = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got '{}'", entry))?;
+            let value = value.trim();
+            match key.trim() {
+                "mean" => {
+                    priors.mean = value
+                        .parse()
+                        .ok()
+                        .filter(|mean: &f64| (0.0..=10.0).contains(mean))
+                        .ok_or_else(|| format!("invalid mean '{}', use a vote between 0 and 10", value))?;
+                }
+                "votes" => {
+                    priors.votes = value
+                        .parse()
+                        .map_err(|_| format!("invalid votes '{}', use a whole number", value))?;
+                }
+                other => return Err(format!("unknown quality prior '{}'", other)),
+            }
+        }
+        Ok(priors)
+    }
+
+    /// Quality score out of 10, rounded to two decimals; `None` without a vote average
+    pub fn score(&self, vote_average: Option<f64>, vote_count: Option<u32>) -> Option<f64> {
+        let average = vote_average?;
+        let (votes, prior_votes) = (vote_count.unwrap_or(0) as f64, self.votes as f64);
+        if votes + prior_votes == 0.0 {
+            return None;
+        }
+        let score = (average * votes + self.mean * prior_votes) / (votes + prior_votes);
+        Some((score * 100.0).round() / 100.0)
+    }
+}
+
+impl Default for QualityPriors {
+    fn default() -> Self {
+        Self { mean: DEFAULT_MEAN, votes: DEFAULT_VOTES }
+    }
+}
+
+/// Order of list items
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListSort {
+    /// TMDB's own order
+    #[default]
+    Popularity,
+    /// Highest `quality_score` first; unscored items last
+    Quality,
+}
+
+/// Scores every item of a list response and applies `sort` within the page
+pub fn rank(response: &mut TmdbResponse, priors: &QualityPriors, sort: ListSort) {
+    rank_items(&mut response.results, priors, sort);
+}
+
+/// Scores `items` and applies `sort` to them
+pub fn rank_items(items: &mut [Movie], priors: &QualityPriors, sort: ListSort) {
+    for item in items.iter_mut() {
+        item.quality_score = priors.score(item.vote_average, item.vote_count);
+    }
+    if sort == ListSort::Quality {
+        // Stable, so equal scores keep TMDB's order
+        items.sort_by(|a, b| {
+            let (a, b) = (a.quality_score.unwrap_or(f64::NEG_INFINITY), b.quality_score.unwrap_or(f64::NEG_INFINITY));
+            b.total_cmp(&a)
+        });
+    }
+}
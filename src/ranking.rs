@@ -0,0 +1,329 @@
+// src/ranking.rs
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use crate::experiments::Assignments;
+use crate::models::{utc_date, Movie, TmdbResponse};
+use crate::quality::{rank_items, ListSort, QualityPriors};
+use crate::session::SessionId;
+use crate::state::AppState;
+use crate::taste::TasteProfile;
+
+/// Experiment whose variant names the ranking strategy a caller gets
+pub const RANKING_EXPERIMENT: &str = "ranking";
+
+/// Weight of the personalization scorer added by `personalized=true`, high
+/// enough that the caller's favourite genres come first
+const PERSONALIZED_WEIGHT: f64 = 10.0;
+
+/// Recently placed items the diversity penalty compares against
+const DIVERSITY_WINDOW: usize = 3;
+
+/// What scorers may look at besides the item itself
+#[derive(Clone, Debug)]
+pub struct RankingContext {
+    pub today: NaiveDate,
+    /// The caller's taste, when the chain personalizes and the caller has a session
+    pub taste: Option<TasteProfile>,
+}
+
+/// An item being placed, with its position in the incoming order
+pub struct Candidate<'a> {
+    pub item: &'a Movie,
+    pub position: usize,
+    pub total: usize,
+}
+
+/// One signal of a ranking chain.
+///
+/// Scores are roughly between 0 and 1, or -1 and 0 for penalties, so chain
+/// weights are comparable. `placed` holds the items already ranked above the
+/// candidate, best first.
+pub trait Scorer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn score(&self, candidate: &Candidate, placed: &[Movie], context: &RankingContext) -> f64;
+}
+
+/// Incoming order, which is TMDB's popularity order unless `sort` changed it
+pub struct Popularity;
+
+impl Scorer for Popularity {
+    fn name(&self) -> &'static str {
+        "popularity"
+    }
+
+    fn score(&self, candidate: &Candidate, _placed: &[Movie], _context: &RankingContext) -> f64 {
+        1.0 - candidate.position as f64 / candidate.total as f64
+    }
+}
+
+/// `quality_score` out of 10; unscored items get 0
+pub struct Quality;
+
+impl Scorer for Quality {
+    fn name(&self) -> &'static str {
+        "quality"
+    }
+
+    fn score(&self, candidate: &Candidate, _placed: &[Movie], _context: &RankingContext) -> f64 {
+        candidate.item.quality_score.unwrap_or(0.0) / 10.0
+    }
+}
+
+/// 1 for unreleased titles, halving after a year and fading from there;
+/// titles without a release date get 0
+pub struct Recency;
+
+impl Scorer for Recency {
+    fn name(&self) -> &'static str {
+        "recency"
+    }
+
+    fn score(&self, candidate: &Candidate, _placed: &[Movie], context: &RankingContext) -> f64 {
+        let Some(released) = candidate.item.release_date else {
+            return 0.0;
+        };
+        let days = (context.today - released).num_days().max(0) as f64;
+        1.0 / (1.0 + days / 365.0)
+    }
+}
+
+/// The caller's affinity for the item's genres; 0 without a taste profile
+pub struct Personalization;
+
+impl Scorer for Personalization {
+    fn name(&self) -> &'static str {
+        "personalization"
+    }
+
+    fn score(&self, candidate: &Candidate, _placed: &[Movie], context: &RankingContext) -> f64 {
+        context
+            .taste
+            .as_ref()
+            .map_or(0.0, |taste| taste.affinity(&candidate.item.genre_ids))
+    }
+}
+
+/// Penalty for sharing a genre with the items placed just above, so rows
+/// don't show the same kind of title several times in a row
+pub struct Diversity;
+
+impl Scorer for Diversity {
+    fn name(&self) -> &'static str {
+        "diversity"
+    }
+
+    fn score(&self, candidate: &Candidate, placed: &[Movie], _context: &RankingContext) -> f64 {
+        let recent = &placed[placed.len().saturating_sub(DIVERSITY_WINDOW)..];
+        if recent.is_empty() {
+            return 0.0;
+        }
+        let overlapping = recent
+            .iter()
+            .filter(|item| item.genre_ids.iter().any(|genre| candidate.item.genre_ids.contains(genre)))
+            .count();
+        -(overlapping as f64 / recent.len() as f64)
+    }
+}
+
+/// The built-in scorer called `name`
+pub fn scorer(name: &str) -> Option<Arc<dyn Scorer>> {
+    let scorer: Arc<dyn Scorer> = match name {
+        "popularity" => Arc::new(Popularity),
+        "quality" => Arc::new(Quality),
+        "recency" => Arc::new(Recency),
+        "personalization" => Arc::new(Personalization),
+        "diversity" => Arc::new(Diversity),
+        _ => return None,
+    };
+    Some(scorer)
+}
+
+/// Weighted scorers applied to list results.
+///
+/// Items are placed one at a time, each time taking the remaining item with
+/// the highest weighted score, so penalties can react to what is already
+/// placed. Ties keep the incoming order, and an empty chain changes nothing.
+#[derive(Clone, Default)]
+pub struct RankingChain {
+    stages: Vec<(Arc<dyn Scorer>, f64)>,
+}
+
+impl RankingChain {
+    /// Parses a chain such as `popularity=1,recency=0.5,diversity=0.3`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut chain = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (name, weight) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected scorer=weight, got '{}'", entry))?;
+            let (name, weight) = (name.trim(), weight.trim());
+            let scorer = scorer(name).ok_or_else(|| format!("unknown scorer '{}'", name))?;
+            let weight = weight
+                .parse()
+                .ok()
+                .filter(|weight: &f64| weight.is_finite())
+                .ok_or_else(|| format!("invalid weight '{}' for {}", weight, name))?;
+            chain = chain.with(scorer, weight);
+        }
+        Ok(chain)
+    }
+
+    /// Adds a scorer at the end of the chain
+    pub fn with(mut self, scorer: Arc<dyn Scorer>, weight: f64) -> Self {
+        self.stages.push((scorer, weight));
+        self
+    }
+
+    /// Scorer names in chain order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|(scorer, _)| scorer.name()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    fn uses(&self, name: &str) -> bool {
+        self.stages.iter().any(|(scorer, _)| scorer.name() == name)
+    }
+
+    /// Reorders `items` by the chain
+    pub fn apply(&self, items: &mut Vec<Movie>, context: &RankingContext) {
+        if self.stages.is_empty() {
+            return;
+        }
+        let total = items.len();
+        let mut remaining: Vec<(usize, Movie)> = items.drain(..).enumerate().collect();
+        while !remaining.is_empty() {
+            let score = |(position, item): &(usize, Movie)| {
+                let candidate = Candidate { item, position: *position, total };
+                self.stages
+                    .iter()
+                    .map(|(scorer, weight)| weight * scorer.score(&candidate, items, context))
+                    .sum::<f64>()
+            };
+            let mut best = (0, score(&remaining[0]));
+            for (index, entry) in remaining.iter().enumerate().skip(1) {
+                let entry_score = score(entry);
+                if entry_score > best.1 {
+                    best = (index, entry_score);
+                }
+            }
+            items.push(remaining.remove(best.0).1);
+        }
+    }
+}
+
+impl fmt::Debug for RankingChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.stages.iter().map(|(scorer, weight)| (scorer.name(), weight)))
+            .finish()
+    }
+}
+
+/// The default ranking chain and named alternatives for experiments.
+#[derive(Clone, Debug, Default)]
+pub struct RankingStrategies {
+    default: RankingChain,
+    named: HashMap<String, RankingChain>,
+}
+
+impl RankingStrategies {
+    /// Parses strategies from JSON such as
+    /// `{"default": "popularity=1", "fresh": "popularity=1,recency=0.5"}`.
+    /// Without a `default` entry, results keep TMDB's order.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let specs: HashMap<String, String> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let mut strategies = Self::default();
+        for (name, spec) in specs {
+            let chain = RankingChain::parse(&spec).map_err(|e| format!("strategy '{}': {}", name, e))?;
+            if name == "default" {
+                strategies.default = chain;
+            } else {
+                strategies.named.insert(name, chain);
+            }
+        }
+        Ok(strategies)
+    }
+
+    /// The strategy named by the caller's `ranking` experiment variant, or the default
+    pub fn for_assignments(&self, assignments: Option<&Assignments>) -> &RankingChain {
+        assignments
+            .and_then(|assignments| assignments.variant(RANKING_EXPERIMENT))
+            .and_then(|variant| self.named.get(variant))
+            .unwrap_or(&self.default)
+    }
+}
+
+#[derive(Deserialize)]
+struct RankingQuery {
+    #[serde(default)]
+    sort: ListSort,
+    #[serde(default)]
+    personalized: bool,
+}
+
+/// How list results are ordered for the current request.
+///
+/// `?sort=quality` orders by `quality_score` instead of the configured
+/// strategy, and `?personalized=true` adds the caller's taste on top.
+pub struct Ranker {
+    sort: ListSort,
+    priors: QualityPriors,
+    chain: RankingChain,
+    context: RankingContext,
+}
+
+impl Ranker {
+    /// Scores every item, then orders the page
+    pub fn apply(&self, response: &mut TmdbResponse) {
+        self.apply_to(&mut response.results);
+    }
+
+    /// Scores and orders `items`, e.g. a home row
+    pub fn apply_to(&self, items: &mut Vec<Movie>) {
+        rank_items(items, &self.priors, self.sort);
+        self.chain.apply(items, &self.context);
+    }
+}
+
+impl FromRequestParts<AppState> for Ranker {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<RankingQuery>::try_from_uri(&parts.uri).map_err(IntoResponse::into_response)?;
+
+        // An explicit sort is what the caller asked for, so experiments don't reorder it
+        let mut chain = match query.sort {
+            ListSort::Popularity => state.ranking.for_assignments(parts.extensions.get::<Assignments>()).clone(),
+            ListSort::Quality => RankingChain::default(),
+        };
+        if query.personalized && !chain.uses("personalization") {
+            chain = chain.with(Arc::new(Personalization), PERSONALIZED_WEIGHT);
+        }
+
+        let now = state.clock.now();
+        let taste = parts
+            .extensions
+            .get::<SessionId>()
+            .filter(|_| chain.uses("personalization"))
+            .map(|session| state.sessions.taste(session, now));
+
+        Ok(Self {
+            sort: query.sort,
+            priors: *state.quality_priors,
+            chain,
+            context: RankingContext { today: utc_date(now), taste },
+        })
+    }
+}
@@ -0,0 +1,35 @@
+// src/redact.rs
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Placeholder substituted for scrubbed values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Credentials passed as query or form parameters, e.g. TMDB's `api_key=...`
+static SECRET_PARAM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(api_key|apikey|access_token|token|password|secret)=([^&\s"']+)"#).unwrap()
+});
+
+/// An `Authorization` header rendered as text, up to the end of the line
+static AUTHORIZATION_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(authorization:\s*)([^\r\n]+)").unwrap());
+
+/// Bare bearer tokens, e.g. inside a debug-printed header map
+static BEARER_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9\-._~+/]+=*").unwrap());
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}").unwrap());
+
+/// Scrubs secrets and personal data from text destined for logs or error messages.
+///
+/// Removes credential query parameters (`api_key=...`), `Authorization`
+/// headers, bearer tokens and email addresses, keeping the surrounding text
+/// so messages stay useful for debugging.
+pub fn redact(text: &str) -> String {
+    let text = SECRET_PARAM.replace_all(text, format!("${{1}}={}", REDACTED));
+    let text = AUTHORIZATION_HEADER.replace_all(&text, format!("${{1}}{}", REDACTED));
+    let text = BEARER_TOKEN.replace_all(&text, format!("Bearer {}", REDACTED));
+    let text = EMAIL.replace_all(&text, REDACTED);
+    text.into_owned()
+}
@@ -0,0 +1,86 @@
+// src/safe_methods.rs
+use axum::{
+    body::HttpBody,
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use crate::problem::ApiError;
+use crate::shaping::CLIENT_PROFILE_HEADER;
+use crate::state::AppState;
+
+/// Request headers that change GET responses: `Accept` picks plain-text or
+/// problem+json errors and the list format version, and `X-Client-Profile`
+/// picks the device shaping.
+///
+/// Languages are chosen with query parameters, which caches already key on,
+/// and responses aren't compressed, so neither `Accept-Language` nor
+/// `Accept-Encoding` is listed.
+pub const NEGOTIATED_HEADERS: [&str; 2] = ["accept", CLIENT_PROFILE_HEADER];
+
+/// Keeps GET and HEAD safe to retry and to cache:
+///
+/// - GET and HEAD requests with a body are rejected, since caches and
+///   retries drop bodies and the response would depend on what was lost
+/// - routes with a cache policy only answer GET and HEAD, so a handler added
+///   later can't make a cacheable route change state
+/// - GET and HEAD responses list [`NEGOTIATED_HEADERS`] in `Vary`, so a cache
+///   never serves one client's variant to another
+pub async fn safe_methods_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let safe = matches!(*request.method(), Method::GET | Method::HEAD);
+    if safe && has_body(&request) {
+        return ApiError::new(StatusCode::BAD_REQUEST, "body_not_allowed", "GET and HEAD requests must not have a body")
+            .into_response();
+    }
+    let cacheable = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|route| state.cache_policies.is_cacheable(route.as_str()));
+    if !safe && cacheable && request.method() != Method::OPTIONS {
+        let mut response =
+            ApiError::new(StatusCode::METHOD_NOT_ALLOWED, "method_not_allowed", "This route is read-only")
+                .into_response();
+        response.headers_mut().insert(header::ALLOW, HeaderValue::from_static("GET, HEAD"));
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    if safe {
+        add_vary(response.headers_mut());
+    }
+    response
+}
+
+/// Looks at the framing headers and, for bodies sent without them, at the stream itself
+fn has_body(request: &Request) -> bool {
+    let headers = request.headers();
+    let content_length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    content_length.is_some_and(|length| length > 0)
+        || headers.contains_key(header::TRANSFER_ENCODING)
+        || !request.body().is_end_stream()
+}
+
+/// Adds the negotiated headers `Vary` doesn't list yet
+fn add_vary(headers: &mut HeaderMap) {
+    let listed: Vec<String> = headers
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|name| name.trim().to_ascii_lowercase())
+        .collect();
+    if listed.iter().any(|name| name == "*") {
+        return;
+    }
+    for name in NEGOTIATED_HEADERS.iter().filter(|name| !listed.iter().any(|listed| listed == *name)) {
+        headers.append(header::VARY, HeaderValue::from_static(name));
+    }
+}
@@ -0,0 +1,103 @@
+// src/safe_search.rs
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::models::Movie;
+use crate::problem::ApiError;
+use crate::shaping::Device;
+
+/// Who decides whether search and discover may return titles TMDB flags as adult
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdultContentPolicy {
+    /// Adult titles are never returned and `include_adult` is rejected
+    #[default]
+    AlwaysOff,
+    /// Clients opt in per request with `?include_adult=true`
+    UserChoice,
+    /// Like `user_choice`, except for device profiles that drop adult titles,
+    /// such as `kids`, which can't opt in
+    ProfileBased,
+}
+
+impl FromStr for AdultContentPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.trim() {
+            "always_off" => Ok(AdultContentPolicy::AlwaysOff),
+            "user_choice" => Ok(AdultContentPolicy::UserChoice),
+            "profile_based" => Ok(AdultContentPolicy::ProfileBased),
+            other => Err(format!("unknown adult content policy '{}'", other)),
+        }
+    }
+}
+
+/// Configured policy and how many adult titles it kept out of results
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SafeSearchStats {
+    pub policy: AdultContentPolicy,
+    pub filtered: u64,
+}
+
+/// Enforces the [`AdultContentPolicy`] on list results. TMDB already leaves
+/// adult titles out when asked to, but trending has no such switch and
+/// cached or mirrored responses may predate it, so flagged items are dropped
+/// here too and counted.
+#[derive(Default)]
+pub struct SafeSearch {
+    policy: AdultContentPolicy,
+    filtered: AtomicU64,
+}
+
+impl SafeSearch {
+    pub fn new(policy: AdultContentPolicy) -> Self {
+        Self { policy, filtered: AtomicU64::new(0) }
+    }
+
+    pub fn policy(&self) -> AdultContentPolicy {
+        self.policy
+    }
+
+    /// Drops items flagged as adult unless the request opted in
+    pub fn retain(&self, items: &mut Vec<Movie>, include_adult: bool, source: &'static str) {
+        if include_adult {
+            return;
+        }
+        let before = items.len();
+        items.retain(|item| !item.adult);
+        let dropped = before - items.len();
+        if dropped > 0 {
+            self.filtered.fetch_add(dropped as u64, Ordering::Relaxed);
+            tracing::info!(source, dropped, "filtered adult titles");
+        }
+    }
+
+    /// Whether a request asking for `?include_adult` may see adult titles.
+    /// Asking where the policy doesn't allow it is a 403, so clients can tell
+    /// a refused opt-in from an empty result.
+    pub fn include_adult(&self, requested: Option<bool>, device: &Device) -> Result<bool, ApiError> {
+        if requested != Some(true) {
+            return Ok(false);
+        }
+        let allowed = match self.policy {
+            AdultContentPolicy::AlwaysOff => false,
+            AdultContentPolicy::UserChoice => true,
+            AdultContentPolicy::ProfileBased => !device.0.as_ref().is_some_and(|preset| preset.drop_adult),
+        };
+        if !allowed {
+            return Err(Self::error());
+        }
+        Ok(true)
+    }
+
+    /// Answer to adult content the caller may not see
+    pub fn error() -> ApiError {
+        ApiError::new(StatusCode::FORBIDDEN, "adult_content_not_allowed", "Adult content is not available")
+    }
+
+    pub fn stats(&self) -> SafeSearchStats {
+        SafeSearchStats { policy: self.policy, filtered: self.filtered.load(Ordering::Relaxed) }
+    }
+}
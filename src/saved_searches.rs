@@ -0,0 +1,379 @@
+// src/saved_searches.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::blocklist::Blocklist;
+use crate::error::TmdbError;
+use crate::models::{parse_region, DiscoverFilter, MediaType, Movie};
+use crate::session::{SessionId, DEFAULT_SESSION_TTL};
+use crate::sync::{Delta, Since, Stamp};
+use crate::tmdb_client::TmdbClient;
+
+/// How often saved searches are re-run when `SAVED_SEARCH_INTERVAL` is unset
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Saved searches allowed per session
+pub const MAX_SAVED_SEARCHES: usize = 20;
+
+/// Notifications kept per session, newest first
+const NOTIFICATION_LIMIT: usize = 50;
+
+/// Titles remembered per search, so titles seen long ago aren't announced again
+const SEEN_LIMIT: usize = 1_000;
+
+/// Deleted searches remembered per session for delta sync
+const TOMBSTONE_LIMIT: usize = 100;
+
+/// What a saved search runs
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum SavedQuery {
+    /// Multi-search, like `/api/search`
+    Search { query: String },
+    /// Popular titles narrowed like `/api/discover`
+    Discover {
+        media_type: MediaType,
+        #[serde(default)]
+        genres: Vec<i64>,
+        #[serde(default)]
+        providers: Vec<i64>,
+        #[serde(default)]
+        region: Option<String>,
+        #[serde(default)]
+        networks: Vec<i64>,
+        #[serde(default)]
+        companies: Vec<i64>,
+    },
+}
+
+impl SavedQuery {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            SavedQuery::Search { query } if query.trim().is_empty() => Err("query must not be empty".to_string()),
+            SavedQuery::Search { .. } => Ok(()),
+            SavedQuery::Discover { providers, region, .. } => {
+                if !providers.is_empty() && region.is_none() {
+                    return Err("providers need a region".to_string());
+                }
+                if let Some(region) = region {
+                    parse_region(region)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn default_name(&self) -> String {
+        match self {
+            SavedQuery::Search { query } => query.trim().to_string(),
+            SavedQuery::Discover { media_type: MediaType::Movie, .. } => "Popular movies".to_string(),
+            SavedQuery::Discover { media_type: MediaType::Tv, .. } => "Popular TV shows".to_string(),
+        }
+    }
+
+    /// Media type of results without their own, as in discover results
+    fn media_type(&self) -> MediaType {
+        match self {
+            SavedQuery::Search { .. } => MediaType::Movie,
+            SavedQuery::Discover { media_type, .. } => *media_type,
+        }
+    }
+
+    /// First page of results, most popular first
+    async fn run(&self, client: &dyn TmdbClient) -> Result<Vec<Movie>, TmdbError> {
+        let response = match self {
+            SavedQuery::Search { query } => client.search_content(query.trim(), 1).await?,
+            SavedQuery::Discover { media_type, genres, providers, region, networks, companies } => {
+                let filter = DiscoverFilter {
+                    genres: genres.clone(),
+                    providers: providers.clone(),
+                    region: region.as_deref().map(parse_region).and_then(Result::ok),
+                    networks: networks.clone(),
+                    companies: companies.clone(),
+                    ..DiscoverFilter::new(*media_type)
+                };
+                client.discover(&filter, 1).await?
+            }
+        };
+        Ok(response.results)
+    }
+}
+
+/// Body of `POST /api/searches`
+#[derive(Clone, Debug, Deserialize)]
+pub struct SavedSearchInput {
+    /// Shown in notifications; defaults to the query
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub query: SavedQuery,
+    /// Only titles rated at least this match, e.g. `7.5`
+    #[serde(default)]
+    pub min_vote: Option<f64>,
+}
+
+/// A search the session asked to be told about
+#[derive(Clone, Debug, Serialize)]
+pub struct SavedSearch {
+    pub id: u64,
+    pub name: String,
+    #[serde(flatten)]
+    pub query: SavedQuery,
+    pub min_vote: Option<f64>,
+    /// Unix timestamps, in seconds
+    pub created_at: u64,
+    pub last_checked_at: Option<u64>,
+    /// Titles already matched; `None` until the first run takes the baseline
+    #[serde(skip)]
+    seen: Option<HashSet<i64>>,
+}
+
+impl SavedSearch {
+    fn matches(&self, item: &Movie) -> bool {
+        self.min_vote.is_none_or(|min_vote| item.vote_average.is_some_and(|vote| vote >= min_vote))
+    }
+}
+
+/// New titles matching a saved search
+#[derive(Clone, Debug, Serialize)]
+pub struct Notification {
+    pub id: u64,
+    pub search_id: u64,
+    pub message: String,
+    pub titles: Vec<Movie>,
+    /// Unix timestamp, in seconds
+    pub created_at: u64,
+}
+
+#[derive(Default)]
+struct Inbox {
+    searches: Vec<SavedSearch>,
+    notifications: VecDeque<Notification>,
+    last_seen: Option<SystemTime>,
+    /// Ids of deleted searches with when they were deleted, oldest first
+    deleted: VecDeque<(u64, Stamp)>,
+    /// Delta syncs from before this get a full copy: the inbox was created
+    /// later or deletions since were forgotten
+    floor: Stamp,
+}
+
+/// Saved searches and their notifications per anonymous session, in memory.
+///
+/// [`SavedSearches::refresh`] re-runs every search and notifies about titles
+/// it didn't match before. The first run only records what already matches,
+/// so saving a search doesn't announce the whole catalog. Inboxes of
+/// sessions idle for longer than the TTL are discarded.
+///
+/// Ids double as sync revisions: they only grow, and searches don't change
+/// once saved, so a search is new to a client whose revision is below its id.
+pub struct SavedSearches {
+    ttl: Duration,
+    next_id: Mutex<u64>,
+    inboxes: Mutex<HashMap<SessionId, Inbox>>,
+}
+
+impl SavedSearches {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, next_id: Mutex::new(1), inboxes: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn save(&self, session: &SessionId, input: SavedSearchInput, now: SystemTime) -> Result<SavedSearch, String> {
+        input.query.validate()?;
+        if input.min_vote.is_some_and(|min_vote| !(0.0..=10.0).contains(&min_vote)) {
+            return Err("min_vote must be between 0 and 10".to_string());
+        }
+        let name = input
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| input.query.default_name());
+
+        let id = self.next_id();
+        self.update(session, now, |inbox| {
+            if inbox.searches.len() >= MAX_SAVED_SEARCHES {
+                return Err(format!("At most {} searches may be saved", MAX_SAVED_SEARCHES));
+            }
+            let search = SavedSearch {
+                id,
+                name,
+                query: input.query,
+                min_vote: input.min_vote,
+                created_at: unix_seconds(now),
+                last_checked_at: None,
+                seen: None,
+            };
+            inbox.searches.push(search.clone());
+            Ok(search)
+        })
+    }
+
+    /// The session's saved searches, oldest first
+    pub fn list(&self, session: &SessionId, now: SystemTime) -> Vec<SavedSearch> {
+        self.update(session, now, |inbox| inbox.searches.clone())
+    }
+
+    /// Deletes a saved search, returning whether it existed
+    pub fn delete(&self, session: &SessionId, id: u64, now: SystemTime) -> bool {
+        self.update(session, now, |inbox| {
+            let before = inbox.searches.len();
+            inbox.searches.retain(|search| search.id != id);
+            if inbox.searches.len() == before {
+                return false;
+            }
+            inbox.deleted.push_back((id, Stamp { revision: self.next_id(), at: unix_seconds(now) }));
+            if inbox.deleted.len() > TOMBSTONE_LIMIT
+                && let Some((_, stamp)) = inbox.deleted.pop_front()
+            {
+                inbox.floor = stamp;
+            }
+            true
+        })
+    }
+
+    /// Searches saved and ids deleted after `since`, with the current
+    /// revision. Everything is sent when `since` is unset or older than what
+    /// the session's inbox still knows.
+    pub fn changes_since(&self, session: &SessionId, since: Option<Since>, now: SystemTime) -> (Delta<SavedSearch, u64>, u64) {
+        self.update(session, now, |inbox| {
+            let current = self.current_revision();
+            let Some(since) = since.filter(|since| !inbox.floor.after(*since) && !since.is_ahead_of(current)) else {
+                return (Delta::full(inbox.searches.clone()), current);
+            };
+            let saved = |search: &SavedSearch| Stamp { revision: search.id, at: search.created_at };
+            let upserted = inbox.searches.iter().filter(|search| saved(search).after(since)).cloned().collect();
+            let deleted = inbox.deleted.iter().filter(|(_, stamp)| stamp.after(since)).map(|(id, _)| *id).collect();
+            (Delta { full: false, upserted, deleted }, current)
+        })
+    }
+
+    /// The session's notifications, newest first
+    pub fn notifications(&self, session: &SessionId, now: SystemTime) -> Vec<Notification> {
+        self.update(session, now, |inbox| inbox.notifications.iter().cloned().collect())
+    }
+
+    pub fn clear_notifications(&self, session: &SessionId, now: SystemTime) {
+        self.update(session, now, |inbox| inbox.notifications.clear());
+    }
+
+    /// Notifications of every session with an id above `after`, oldest
+    /// first. Unlike reading a session's inbox this doesn't keep it alive.
+    pub fn notifications_after(&self, after: u64) -> Vec<(SessionId, Notification)> {
+        let inboxes = self.inboxes.lock().unwrap();
+        let mut notifications: Vec<(SessionId, Notification)> = inboxes
+            .iter()
+            .flat_map(|(session, inbox)| {
+                inbox
+                    .notifications
+                    .iter()
+                    .filter(|notification| notification.id > after)
+                    .map(|notification| (session.clone(), notification.clone()))
+            })
+            .collect();
+        notifications.sort_by_key(|(_, notification)| notification.id);
+        notifications
+    }
+
+    /// Re-runs every saved search and returns how many notifications were
+    /// created. Searches shared by several sessions run once; searches whose
+    /// results can't be loaded are retried on the next run. Blocked titles
+    /// are never announced.
+    pub async fn refresh(&self, client: &dyn TmdbClient, blocklist: &Blocklist, now: SystemTime) -> usize {
+        let queries: HashSet<SavedQuery> = {
+            let mut inboxes = self.inboxes.lock().unwrap();
+            self.evict_expired(&mut inboxes, now);
+            inboxes.values().flat_map(|inbox| inbox.searches.iter().map(|search| search.query.clone())).collect()
+        };
+
+        let mut results = HashMap::new();
+        for query in queries {
+            match query.run(client).await {
+                Ok(mut items) => {
+                    blocklist.retain(&mut items, query.media_type());
+                    results.insert(query, items);
+                }
+                Err(error) => tracing::warn!(?query, %error, "saved search refresh failed"),
+            }
+        }
+
+        let mut created = 0;
+        let mut inboxes = self.inboxes.lock().unwrap();
+        for inbox in inboxes.values_mut() {
+            for search in &mut inbox.searches {
+                let Some(items) = results.get(&search.query) else {
+                    continue;
+                };
+                let matching: Vec<&Movie> = items.iter().filter(|item| search.matches(item)).collect();
+                search.last_checked_at = Some(unix_seconds(now));
+                let Some(seen) = &mut search.seen else {
+                    search.seen = Some(matching.iter().map(|item| item.id).collect());
+                    continue;
+                };
+
+                let new: Vec<Movie> = matching.into_iter().filter(|item| !seen.contains(&item.id)).cloned().collect();
+                if new.is_empty() {
+                    continue;
+                }
+                if seen.len() + new.len() > SEEN_LIMIT {
+                    seen.clear();
+                }
+                seen.extend(new.iter().map(|item| item.id));
+                let message = match new.len() {
+                    1 => format!("1 new match for '{}'", search.name),
+                    count => format!("{} new matches for '{}'", count, search.name),
+                };
+                inbox.notifications.push_front(Notification {
+                    id: self.next_id(),
+                    search_id: search.id,
+                    message,
+                    titles: new,
+                    created_at: unix_seconds(now),
+                });
+                inbox.notifications.truncate(NOTIFICATION_LIMIT);
+                created += 1;
+            }
+        }
+        created
+    }
+
+    fn next_id(&self) -> u64 {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        *next_id - 1
+    }
+
+    /// The last id handed out
+    fn current_revision(&self) -> u64 {
+        *self.next_id.lock().unwrap() - 1
+    }
+
+    fn update<T>(&self, session: &SessionId, now: SystemTime, apply: impl FnOnce(&mut Inbox) -> T) -> T {
+        let mut inboxes = self.inboxes.lock().unwrap();
+        self.evict_expired(&mut inboxes, now);
+
+        let inbox = inboxes
+            .entry(session.clone())
+            .or_insert_with(|| Inbox { floor: Stamp { revision: self.current_revision(), at: unix_seconds(now) }, ..Inbox::default() });
+        inbox.last_seen = Some(now);
+        apply(inbox)
+    }
+
+    fn evict_expired(&self, inboxes: &mut HashMap<SessionId, Inbox>, now: SystemTime) {
+        inboxes.retain(|_, inbox| {
+            inbox
+                .last_seen
+                .and_then(|last_seen| now.duration_since(last_seen).ok())
+                .is_none_or(|idle| idle <= self.ttl)
+        });
+    }
+}
+
+impl Default for SavedSearches {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_TTL)
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
@@ -0,0 +1,538 @@
+// src/services.rs
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::blocklist::Blocklist;
+use crate::error::TmdbError;
+use crate::lists::ResolvedList;
+use crate::logos::FALLBACK_LANGUAGE;
+use crate::models::{
+    AlternativeTitles, AvailabilityMatrix, BatchItemError, BatchTitleResult, BatchTitlesRequest, DiscoverFilter, Genre,
+    ImagesResponse, MediaType, NetworkDetails, ProviderList, Season, TitleDetails, TmdbResponse, VideoResponse, utc_date,
+};
+use crate::privacy::Tracker;
+use crate::problem::ApiError;
+use crate::providers::availability_matrix;
+use crate::push::PushDevice;
+use crate::saved_searches::{Notification, SavedSearch, SavedSearchInput};
+use crate::shadow::{ListRequest, ListRoute};
+use crate::session::{RecentActivity, RecentPick, RecentTitle, SessionId};
+use crate::state::AppState;
+use crate::sync::{Since, SyncResponse, SyncToken};
+use crate::taste::TasteProfile;
+use crate::viewing_order::{CollectionWhereToStart, ViewingOrder, WhereToStart};
+
+/// Upstream requests a single batch or viewing order lookup may have in flight
+const BATCH_CONCURRENCY: usize = 8;
+
+/// Titles and their metadata, read through the service's caches where it has them
+#[async_trait]
+pub trait CatalogService: Send + Sync {
+    async fn trending(&self, page: i32) -> Result<TmdbResponse, ApiError>;
+
+    /// Highest rated titles of all time
+    async fn top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, ApiError>;
+
+    /// Titles most popular right now
+    async fn popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, ApiError>;
+
+    /// Popular titles narrowed by `filter`; adult titles are only kept when
+    /// the filter includes them
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, ApiError>;
+
+    async fn details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, ApiError>;
+
+    async fn videos(&self, movie_id: i64) -> Result<VideoResponse, ApiError>;
+
+    async fn images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, ApiError>;
+
+    async fn alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, ApiError>;
+
+    /// One season of a TV show with its episodes
+    async fn season(&self, tv_id: i64, season_number: i32) -> Result<Season, ApiError>;
+
+    /// Aired episodes of a TV show in `order`, built from its seasons
+    async fn where_to_start(&self, tv_id: i64, order: ViewingOrder) -> Result<WhereToStart, ApiError>;
+
+    /// Released movies of a collection in release order
+    async fn collection_where_to_start(&self, id: i64) -> Result<CollectionWhereToStart, ApiError>;
+
+    async fn network(&self, id: i64) -> Result<NetworkDetails, ApiError>;
+
+    async fn curated_list(&self, slug: &str) -> Result<ResolvedList, ApiError>;
+
+    /// Watch providers of `region`, most prominent first
+    async fn providers(&self, region: &str) -> Result<ProviderList, ApiError>;
+
+    /// Titles of `provider` in `region`, latest release first, for
+    /// "New on <provider>" rows
+    async fn new_on(&self, media_type: MediaType, provider: i64, region: &str) -> Result<TmdbResponse, ApiError>;
+
+    /// Provider availability of a title across `regions`, one row per region
+    async fn availability(&self, media_type: MediaType, id: i64, regions: &[String]) -> Result<AvailabilityMatrix, ApiError>;
+
+    /// Details of many titles in request order; a title that fails to load
+    /// gets its own error entry instead of failing the whole batch
+    async fn batch(&self, request: BatchTitlesRequest) -> Vec<BatchTitleResult>;
+}
+
+/// Free-text search over the catalog
+#[async_trait]
+pub trait SearchService: Send + Sync {
+    /// Results for `query`, recorded in the session's history and, unless
+    /// the client opted out, in search analytics. Queries shorter than the
+    /// configured minimum get an empty page without asking TMDB. Adult
+    /// titles are only searched when `include_adult` is set.
+    async fn search(
+        &self,
+        query: &str,
+        page: i32,
+        include_adult: bool,
+        session: Option<&SessionId>,
+        tracker: &Tracker
+    ) -> Result<TmdbResponse, ApiError>;
+}
+
+/// What the service keeps for an anonymous session
+pub trait UserService: Send + Sync {
+    fn recent(&self, session: &SessionId) -> RecentActivity;
+    fn record_pick(&self, session: &SessionId, pick: RecentPick);
+    fn clear_recent(&self, session: &SessionId);
+
+    /// Records a title page view, counting `genres` towards the session's taste
+    fn record_view(&self, session: &SessionId, title: RecentTitle, genres: &[Genre]);
+    /// Titles whose detail pages the session viewed, most recent first
+    fn recent_views(&self, session: &SessionId, limit: usize) -> Vec<RecentTitle>;
+    fn clear_views(&self, session: &SessionId);
+
+    /// Genre affinity built from the session's title views
+    fn taste(&self, session: &SessionId) -> TasteProfile;
+
+    fn save_search(&self, session: &SessionId, input: SavedSearchInput) -> Result<SavedSearch, ApiError>;
+    fn saved_searches(&self, session: &SessionId) -> Vec<SavedSearch>;
+    fn delete_saved_search(&self, session: &SessionId, id: u64) -> Result<(), ApiError>;
+
+    /// New matches for the session's saved searches, newest first
+    fn notifications(&self, session: &SessionId) -> Vec<Notification>;
+    fn clear_notifications(&self, session: &SessionId);
+
+    /// Registers a device to receive the session's notifications as pushes,
+    /// returning whether it is new to the session
+    fn register_device(&self, session: &SessionId, device: PushDevice) -> Result<bool, ApiError>;
+    fn devices(&self, session: &SessionId) -> Vec<PushDevice>;
+    fn unregister_device(&self, session: &SessionId, token: &str) -> Result<(), ApiError>;
+
+    /// Curated lists and the session's saved searches changed since the
+    /// sync token `since`; everything when it is unset or from an earlier process
+    fn sync(&self, session: &SessionId, since: Option<&str>) -> Result<SyncResponse, ApiError>;
+}
+
+/// [`CatalogService`] over the state's TMDB client and caches
+#[derive(Clone)]
+pub struct TmdbCatalog {
+    state: AppState,
+}
+
+impl TmdbCatalog {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Fails requests for titles blocked by id before asking TMDB
+    fn check(&self, media_type: MediaType, id: i64) -> Result<(), ApiError> {
+        if self.state.blocklist.blocks_id(media_type, id) {
+            return Err(Blocklist::error());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CatalogService for TmdbCatalog {
+    async fn trending(&self, page: i32) -> Result<TmdbResponse, ApiError> {
+        let mut response = self.state.tmdb_client.get_trending(page).await?;
+        let upstream = self.state.shadow.sampled().then(|| response.clone());
+        self.state.blocklist.retain(&mut response.results, MediaType::Movie);
+        self.state.safe_search.retain(&mut response.results, false, "trending");
+        if let Some(upstream) = upstream {
+            let request = ListRequest { route: ListRoute::Trending, media_type: MediaType::Movie, include_adult: false };
+            self.state.shadow.compare(request, upstream, &response, self.state.clock.now());
+        }
+        Ok(response)
+    }
+
+    async fn top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, ApiError> {
+        let mut response = self.state.tmdb_client.get_top_rated(media_type, page).await?;
+        self.state.blocklist.retain(&mut response.results, media_type);
+        self.state.safe_search.retain(&mut response.results, false, "top_rated");
+        Ok(response)
+    }
+
+    async fn popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, ApiError> {
+        let mut response = self.state.tmdb_client.get_popular(media_type, page).await?;
+        self.state.blocklist.retain(&mut response.results, media_type);
+        self.state.safe_search.retain(&mut response.results, false, "popular");
+        Ok(response)
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, ApiError> {
+        let mut response = self.state.tmdb_client.discover(filter, page).await?;
+        let upstream = self.state.shadow.sampled().then(|| response.clone());
+        self.state.blocklist.retain(&mut response.results, filter.media_type);
+        self.state.safe_search.retain(&mut response.results, filter.include_adult, "discover");
+        if let Some(upstream) = upstream {
+            let request = ListRequest { route: ListRoute::Discover, media_type: filter.media_type, include_adult: filter.include_adult };
+            self.state.shadow.compare(request, upstream, &response, self.state.clock.now());
+        }
+        Ok(response)
+    }
+
+    async fn details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, ApiError> {
+        self.check(media_type, id)?;
+        let mut details = self.state.tmdb_client.get_title_details(media_type, id).await?;
+        if self.state.blocklist.blocks_details(media_type, &details) {
+            return Err(Blocklist::error());
+        }
+        self.state.metadata.complete(media_type, &mut details).await;
+        Ok(details)
+    }
+
+    async fn videos(&self, movie_id: i64) -> Result<VideoResponse, ApiError> {
+        self.check(MediaType::Movie, movie_id)?;
+        Ok(self.state.tmdb_client.get_movie_videos(movie_id).await?)
+    }
+
+    async fn images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, ApiError> {
+        self.check(media_type, id)?;
+        Ok(self.state.tmdb_client.get_images(media_type, id).await?)
+    }
+
+    async fn alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, ApiError> {
+        self.check(media_type, id)?;
+        Ok(self.state.tmdb_client.get_alternative_titles(media_type, id).await?)
+    }
+
+    async fn season(&self, tv_id: i64, season_number: i32) -> Result<Season, ApiError> {
+        self.check(MediaType::Tv, tv_id)?;
+        Ok(self.state.tmdb_client.get_tv_season(tv_id, season_number).await?)
+    }
+
+    async fn where_to_start(&self, tv_id: i64, order: ViewingOrder) -> Result<WhereToStart, ApiError> {
+        self.check(MediaType::Tv, tv_id)?;
+        let show = self.state.tmdb_client.get_title_details(MediaType::Tv, tv_id).await?;
+        if self.state.blocklist.blocks_details(MediaType::Tv, &show) {
+            return Err(Blocklist::error());
+        }
+        let today = utc_date(self.state.clock.now());
+
+        let permits = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+        let mut tasks = JoinSet::new();
+        for season_number in order.seasons_needed(&show.seasons, today) {
+            let (client, permits) = (self.state.tmdb_client.clone(), permits.clone());
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                client.get_tv_season(tv_id, season_number).await
+            });
+        }
+        let mut seasons = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(Ok(season)) => seasons.push(season),
+                // Listed seasons TMDB can't find are left out of the order
+                Ok(Err(TmdbError::NotFound)) => {}
+                Ok(Err(e)) => return Err(e.into()),
+                // A partial order would point viewers at the wrong episode
+                Err(e) => {
+                    tracing::error!(error = %e, tv_id, "season lookup task failed");
+                    return Err(ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", "Season lookup failed"));
+                }
+            }
+        }
+        Ok(WhereToStart::new(tv_id, order, &seasons, today))
+    }
+
+    async fn collection_where_to_start(&self, id: i64) -> Result<CollectionWhereToStart, ApiError> {
+        let mut collection = self.state.tmdb_client.get_collection(id).await?;
+        self.state.blocklist.retain(&mut collection.parts, MediaType::Movie);
+        self.state.safe_search.retain(&mut collection.parts, false, "collection");
+        Ok(CollectionWhereToStart::new(&collection, utc_date(self.state.clock.now())))
+    }
+
+    async fn network(&self, id: i64) -> Result<NetworkDetails, ApiError> {
+        Ok(NetworkDetails::from(self.state.tmdb_client.get_network(id).await?))
+    }
+
+    async fn curated_list(&self, slug: &str) -> Result<ResolvedList, ApiError> {
+        let (now, ttl) = (self.state.clock.now(), self.state.cache_policies.details);
+        let mut list = self
+            .state
+            .lists
+            .resolve(self.state.tmdb_client.clone(), slug, now, ttl)
+            .await
+            .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "not_found", "List not found"))?;
+        list.items.retain(|item| !self.state.blocklist.blocks_details(item.media_type, &item.details));
+        Ok(list)
+    }
+
+    async fn providers(&self, region: &str) -> Result<ProviderList, ApiError> {
+        let (now, ttl) = (self.state.clock.now(), self.state.cache_policies.provider_list);
+        Ok(self.state.provider_lists.lookup(self.state.tmdb_client.as_ref(), region, now, ttl).await?)
+    }
+
+    async fn new_on(&self, media_type: MediaType, provider: i64, region: &str) -> Result<TmdbResponse, ApiError> {
+        let (client, now, ttl) = (self.state.tmdb_client.as_ref(), self.state.clock.now(), self.state.cache_policies.new_on);
+        let mut response = self.state.new_on.lookup(client, media_type, provider, region, now, ttl).await?;
+        self.state.blocklist.retain(&mut response.results, media_type);
+        self.state.safe_search.retain(&mut response.results, false, "new_on");
+        Ok(response)
+    }
+
+    async fn availability(&self, media_type: MediaType, id: i64, regions: &[String]) -> Result<AvailabilityMatrix, ApiError> {
+        self.check(media_type, id)?;
+        let (client, now, ttl) = (self.state.tmdb_client.as_ref(), self.state.clock.now(), self.state.cache_policies.providers);
+        let regions = self.state.availability.lookup(client, media_type, id, regions, now, ttl).await?;
+        Ok(availability_matrix(id, regions))
+    }
+
+    async fn batch(&self, request: BatchTitlesRequest) -> Vec<BatchTitleResult> {
+        let state = &self.state;
+        let language = request
+            .language
+            .as_deref()
+            .map(|language| language.trim().to_lowercase())
+            .filter(|language| !language.is_empty())
+            .unwrap_or_else(|| FALLBACK_LANGUAGE.to_string());
+        let (now, logo_ttl) = (state.clock.now(), state.cache_policies.details);
+
+        let permits = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+        let mut tasks = JoinSet::new();
+        for (index, item) in request.items.iter().copied().enumerate() {
+            let (catalog, permits) = (self.clone(), permits.clone());
+            let (include_logo, language) = (request.include_logo, language.clone());
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let (media_type, id) = (item.media_type, item.id.get());
+                let details = catalog.details(media_type, id).await;
+                let logo = match details {
+                    Ok(_) if include_logo => {
+                        let client = catalog.state.tmdb_client.as_ref();
+                        catalog.state.logos.lookup(client, media_type, id, &language, now, logo_ttl).await
+                    }
+                    _ => None,
+                };
+                (index, details, logo)
+            });
+        }
+
+        let mut details = vec![None; request.items.len()];
+        let mut logos = vec![None; request.items.len()];
+        while let Some(Ok((index, result, logo))) = tasks.join_next().await {
+            details[index] = Some(result);
+            logos[index] = logo;
+        }
+
+        request
+            .items
+            .iter()
+            .zip(details)
+            .zip(logos)
+            .map(|((item, result), logo)| {
+                let (details, error) = match result {
+                    Some(Ok(details)) => (Some(details), None),
+                    Some(Err(error)) => {
+                        let message = error.detail.unwrap_or_default();
+                        (None, Some(BatchItemError { status: error.status.as_u16(), message }))
+                    }
+                    None => (None, Some(BatchItemError { status: 500, message: "Lookup failed".to_string() })),
+                };
+                let keywords = details.as_ref().map(|details| details.keywords.as_slice()).unwrap_or_default();
+                BatchTitleResult {
+                    media_type: item.media_type,
+                    id: item.id.get(),
+                    tags: state.tag_rules.tags(keywords),
+                    content_warnings: state.tag_rules.content_warnings(keywords),
+                    details,
+                    logo,
+                    has_trailer: None,
+                    providers: None,
+                    cast: None,
+                    error,
+                }
+            })
+            .collect()
+    }
+}
+
+/// [`SearchService`] over the state's TMDB client and session store
+#[derive(Clone)]
+pub struct TmdbSearch {
+    state: AppState,
+}
+
+impl TmdbSearch {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl SearchService for TmdbSearch {
+    async fn search(
+        &self,
+        query: &str,
+        page: i32,
+        include_adult: bool,
+        session: Option<&SessionId>,
+        tracker: &Tracker
+    ) -> Result<TmdbResponse, ApiError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(ApiError::new(StatusCode::BAD_REQUEST, "bad_request", "query must not be empty"));
+        }
+        // Too short to search meaningfully; clients keep showing suggestions instead
+        if query.chars().count() < self.state.min_search_query_chars {
+            return Ok(TmdbResponse { page, results: vec![], total_pages: 0 });
+        }
+
+        let started = Instant::now();
+        let client = &self.state.tmdb_client;
+        let mut response = if include_adult {
+            client.search_content_including_adult(query, page).await?
+        } else {
+            client.search_content(query, page).await?
+        };
+        let upstream = self.state.shadow.sampled().then(|| response.clone());
+        self.state.blocklist.retain(&mut response.results, MediaType::Movie);
+        self.state.safe_search.retain(&mut response.results, include_adult, "search");
+        if let Some(upstream) = upstream {
+            let request = ListRequest { route: ListRoute::Search, media_type: MediaType::Movie, include_adult };
+            self.state.shadow.compare(request, upstream, &response, self.state.clock.now());
+        }
+        let now = self.state.clock.now();
+        tracker.search(query, response.results.len(), started.elapsed(), now);
+        if let Some(session) = session {
+            self.state.sessions.record_search(session, query, now);
+        }
+        Ok(response)
+    }
+}
+
+/// [`UserService`] over the state's session store and saved searches
+#[derive(Clone)]
+pub struct SessionUsers {
+    state: AppState,
+}
+
+impl SessionUsers {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+impl UserService for SessionUsers {
+    fn recent(&self, session: &SessionId) -> RecentActivity {
+        self.state.sessions.recent(session, self.state.clock.now())
+    }
+
+    fn record_pick(&self, session: &SessionId, pick: RecentPick) {
+        self.state.sessions.record_pick(session, pick, self.state.clock.now());
+    }
+
+    fn clear_recent(&self, session: &SessionId) {
+        self.state.sessions.clear(session);
+    }
+
+    fn record_view(&self, session: &SessionId, title: RecentTitle, genres: &[Genre]) {
+        self.state.sessions.record_view(session, title, genres, self.state.clock.now());
+    }
+
+    fn recent_views(&self, session: &SessionId, limit: usize) -> Vec<RecentTitle> {
+        self.state.sessions.recent_views(session, limit, self.state.clock.now())
+    }
+
+    fn clear_views(&self, session: &SessionId) {
+        self.state.sessions.clear_views(session);
+    }
+
+    fn taste(&self, session: &SessionId) -> TasteProfile {
+        self.state.sessions.taste(session, self.state.clock.now())
+    }
+
+    fn save_search(&self, session: &SessionId, input: SavedSearchInput) -> Result<SavedSearch, ApiError> {
+        self.state
+            .saved_searches
+            .save(session, input, self.state.clock.now())
+            .map_err(|message| ApiError::new(StatusCode::BAD_REQUEST, "bad_request", message))
+    }
+
+    fn saved_searches(&self, session: &SessionId) -> Vec<SavedSearch> {
+        self.state.saved_searches.list(session, self.state.clock.now())
+    }
+
+    fn delete_saved_search(&self, session: &SessionId, id: u64) -> Result<(), ApiError> {
+        if self.state.saved_searches.delete(session, id, self.state.clock.now()) {
+            Ok(())
+        } else {
+            Err(ApiError::new(StatusCode::NOT_FOUND, "not_found", "Saved search not found"))
+        }
+    }
+
+    fn notifications(&self, session: &SessionId) -> Vec<Notification> {
+        self.state.saved_searches.notifications(session, self.state.clock.now())
+    }
+
+    fn clear_notifications(&self, session: &SessionId) {
+        self.state.saved_searches.clear_notifications(session, self.state.clock.now());
+    }
+
+    fn register_device(&self, session: &SessionId, device: PushDevice) -> Result<bool, ApiError> {
+        self.state
+            .push
+            .register(session, device, self.state.clock.now())
+            .map_err(|message| ApiError::new(StatusCode::BAD_REQUEST, "bad_request", message))
+    }
+
+    fn devices(&self, session: &SessionId) -> Vec<PushDevice> {
+        self.state.push.devices(session, self.state.clock.now())
+    }
+
+    fn unregister_device(&self, session: &SessionId, token: &str) -> Result<(), ApiError> {
+        if self.state.push.unregister(session, token) {
+            Ok(())
+        } else {
+            Err(ApiError::new(StatusCode::NOT_FOUND, "not_found", "Device not registered"))
+        }
+    }
+
+    fn sync(&self, session: &SessionId, since: Option<&str>) -> Result<SyncResponse, ApiError> {
+        // A bare number is a Unix timestamp, anything else a sync token
+        let (lists_since, searches_since) = match since.map(str::trim) {
+            None => (None, None),
+            Some(at) if !at.is_empty() && at.bytes().all(|byte| byte.is_ascii_digit()) => {
+                let at = at.parse().map_err(|_| ApiError::new(StatusCode::BAD_REQUEST, "bad_request", "invalid since timestamp"))?;
+                (Some(Since::Time(at)), Some(Since::Time(at)))
+            }
+            Some(token) => {
+                let token = token
+                    .parse::<SyncToken>()
+                    .map_err(|message| ApiError::new(StatusCode::BAD_REQUEST, "bad_request", message))?;
+                if token.epoch == self.state.sync_epoch {
+                    (Some(Since::Revision(token.lists)), Some(Since::Revision(token.searches)))
+                } else {
+                    (None, None)
+                }
+            }
+        };
+
+        let (lists, lists_revision) = self.state.lists.changes_since(lists_since);
+        let (saved_searches, searches_revision) = self
+            .state
+            .saved_searches
+            .changes_since(session, searches_since, self.state.clock.now());
+        let token = SyncToken { epoch: self.state.sync_epoch, lists: lists_revision, searches: searches_revision };
+        Ok(SyncResponse { sync_token: token.to_string(), lists, saved_searches })
+    }
+}
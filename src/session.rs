@@ -0,0 +1,340 @@
+// src/session.rs
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::models::{Genre, MediaType};
+use crate::state::AppState;
+use crate::taste::{GenreCounts, TasteProfile};
+
+/// Cookie holding the signed session token
+pub const SESSION_COOKIE: &str = "session";
+
+/// Header alternative to the cookie for non-browser clients; also set on
+/// responses that issue a new session
+pub const SESSION_HEADER: &str = "x-session-token";
+
+/// Sessions idle for longer than this are discarded
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Sessions kept in memory at most; when a new session would exceed it,
+/// expired sessions are dropped first, then the longest idle
+pub const DEFAULT_SESSION_CAPACITY: usize = 100_000;
+
+/// Number of entries kept per recent list
+const RECENT_LIMIT: usize = 20;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Identifies the anonymous session of the current request.
+///
+/// Inserted into request extensions by [`session_middleware`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SessionId(pub String);
+
+/// A title the user picked, e.g. opened or added from a row.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecentPick {
+    pub media_type: MediaType,
+    pub id: i64,
+    pub title: Option<String>,
+}
+
+/// Per-device recent activity, most recent first.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RecentActivity {
+    pub searches: Vec<String>,
+    pub picks: Vec<RecentPick>,
+}
+
+/// A title whose details the session viewed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecentTitle {
+    pub media_type: MediaType,
+    pub id: i64,
+    pub title: String,
+    pub poster_path: Option<String>,
+}
+
+#[derive(Default)]
+struct Session {
+    searches: VecDeque<String>,
+    picks: VecDeque<RecentPick>,
+    views: VecDeque<RecentTitle>,
+    taste: GenreCounts,
+    last_seen: Option<SystemTime>,
+}
+
+/// Issues and verifies signed session tokens and stores per-session data
+/// in memory, expiring sessions that have been idle longer than the TTL.
+///
+/// A token is `<session id>.<hex HMAC-SHA256 of the id>`, so clients cannot
+/// forge ids, and any replica sharing the secret can verify them.
+pub struct SessionStore {
+    secret: Vec<u8>,
+    ttl: Duration,
+    capacity: usize,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SessionStore {
+    pub fn new(secret: impl Into<Vec<u8>>, ttl: Duration) -> Self {
+        Self {
+            secret: secret.into(),
+            ttl,
+            capacity: DEFAULT_SESSION_CAPACITY,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keeps at most `capacity` sessions instead of [`DEFAULT_SESSION_CAPACITY`]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Creates a store with a random secret. Tokens will not survive a restart
+    /// or be accepted by other replicas; set `SESSION_SECRET` in production.
+    pub fn with_random_secret(ttl: Duration) -> Self {
+        let mut secret = [0u8; 32];
+        rand::rng().fill_bytes(&mut secret);
+        Self::new(secret.to_vec(), ttl)
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Generates a new session id and its signed token
+    pub fn issue(&self) -> (SessionId, String) {
+        let mut id = [0u8; 16];
+        rand::rng().fill_bytes(&mut id);
+        let id = to_hex(&id);
+        let token = format!("{}.{}", id, self.sign(&id));
+        (SessionId(id), token)
+    }
+
+    /// Returns the session id if the token's signature is valid
+    pub fn verify(&self, token: &str) -> Option<SessionId> {
+        let (id, signature) = token.split_once('.')?;
+        let signature = from_hex(signature)?;
+
+        let mut mac = self.mac();
+        mac.update(id.as_bytes());
+        mac.verify_slice(&signature).ok()?;
+
+        Some(SessionId(id.to_string()))
+    }
+
+    /// Records a search for the session, moving repeated queries to the front
+    pub fn record_search(&self, session: &SessionId, query: &str, now: SystemTime) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.update(session, now, |data| push_recent(&mut data.searches, query.to_string()));
+    }
+
+    /// Records a picked title for the session, moving repeated picks to the front
+    pub fn record_pick(&self, session: &SessionId, pick: RecentPick, now: SystemTime) {
+        self.update(session, now, |data| {
+            data.picks.retain(|p| (p.media_type, p.id) != (pick.media_type, pick.id));
+            push_recent(&mut data.picks, pick);
+        });
+    }
+
+    /// Records a title-detail view for the session, moving repeated views to
+    /// the front, and counts the title's genres towards the session's taste
+    pub fn record_view(&self, session: &SessionId, title: RecentTitle, genres: &[Genre], now: SystemTime) {
+        self.update(session, now, |data| {
+            data.views.retain(|t| (t.media_type, t.id) != (title.media_type, title.id));
+            push_recent(&mut data.views, title);
+            data.taste.record(genres);
+        });
+    }
+
+    /// Returns the session's genre affinity, or an empty profile if it expired
+    pub fn taste(&self, session: &SessionId, now: SystemTime) -> TasteProfile {
+        let sessions = self.sessions.lock().unwrap();
+
+        self.live(&sessions, session, now)
+            .map(|data| data.taste.profile())
+            .unwrap_or_default()
+    }
+
+    /// Returns up to `limit` distinct recently viewed titles, most recent first
+    pub fn recent_views(&self, session: &SessionId, limit: usize, now: SystemTime) -> Vec<RecentTitle> {
+        let sessions = self.sessions.lock().unwrap();
+
+        self.live(&sessions, session, now)
+            .map(|data| data.views.iter().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forgets the session's viewed titles and the taste built from them,
+    /// keeping searches and picks
+    pub fn clear_views(&self, session: &SessionId) {
+        if let Some(data) = self.sessions.lock().unwrap().get_mut(&session.0) {
+            data.views.clear();
+            data.taste = GenreCounts::default();
+        }
+    }
+
+    /// Returns the session's recent activity, or an empty set if it expired
+    pub fn recent(&self, session: &SessionId, now: SystemTime) -> RecentActivity {
+        let sessions = self.sessions.lock().unwrap();
+
+        self.live(&sessions, session, now)
+            .map(|data| RecentActivity {
+                searches: data.searches.iter().cloned().collect(),
+                picks: data.picks.iter().cloned().collect(),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Forgets all activity for the session
+    pub fn clear(&self, session: &SessionId) {
+        self.sessions.lock().unwrap().remove(&session.0);
+    }
+
+    fn update(&self, session: &SessionId, now: SystemTime, apply: impl FnOnce(&mut Session)) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if sessions.len() >= self.capacity && !sessions.contains_key(&session.0) {
+            self.make_room(&mut sessions, now);
+        }
+
+        let data = sessions.entry(session.0.clone()).or_default();
+        if !self.is_live(data, now) {
+            *data = Session::default();
+        }
+        data.last_seen = Some(now);
+        apply(data);
+    }
+
+    fn live<'a>(&self, sessions: &'a HashMap<String, Session>, session: &SessionId, now: SystemTime) -> Option<&'a Session> {
+        sessions.get(&session.0).filter(|data| self.is_live(data, now))
+    }
+
+    fn is_live(&self, data: &Session, now: SystemTime) -> bool {
+        data.last_seen
+            .and_then(|last_seen| now.duration_since(last_seen).ok())
+            .is_none_or(|idle| idle <= self.ttl)
+    }
+
+    /// Drops expired sessions, then the longest idle ones until a tenth of
+    /// the capacity is free, so a full store isn't swept on every new session
+    fn make_room(&self, sessions: &mut HashMap<String, Session>, now: SystemTime) {
+        sessions.retain(|_, data| self.is_live(data, now));
+
+        let keep = self.capacity - (self.capacity / 10).max(1);
+        if sessions.len() > keep {
+            let mut last_seen: Vec<Option<SystemTime>> = sessions.values().map(|data| data.last_seen).collect();
+            let drop = sessions.len() - keep;
+            let (_, &mut cutoff, _) = last_seen.select_nth_unstable(drop - 1);
+            sessions.retain(|_, data| data.last_seen > cutoff);
+        }
+    }
+
+    fn sign(&self, id: &str) -> String {
+        let mut mac = self.mac();
+        mac.update(id.as_bytes());
+        to_hex(&mac.finalize().into_bytes())
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length")
+    }
+}
+
+fn push_recent<T: PartialEq>(list: &mut VecDeque<T>, item: T) {
+    list.retain(|existing| *existing != item);
+    list.push_front(item);
+    list.truncate(RECENT_LIMIT);
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Attaches an anonymous session to every request.
+///
+/// A valid token from the `session` cookie or `X-Session-Token` header is
+/// reused; otherwise a new session is issued and returned both as a cookie
+/// and in the `X-Session-Token` header.
+pub async fn session_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next
+) -> Response {
+    let existing = session_token(&request).and_then(|token| state.sessions.verify(&token));
+
+    let (session, issued_token) = match existing {
+        Some(session) => (session, None),
+        None => {
+            let (session, token) = state.sessions.issue();
+            (session, Some(token))
+        }
+    };
+
+    request.extensions_mut().insert(session);
+    let mut response = next.run(request).await;
+
+    if let Some(token) = issued_token {
+        let cookie = format!(
+            "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax",
+            SESSION_COOKIE,
+            token,
+            state.sessions.ttl().as_secs()
+        );
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            headers.append(header::SET_COOKIE, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&token) {
+            headers.insert(SESSION_HEADER, value);
+        }
+    }
+
+    response
+}
+
+fn session_token(request: &Request) -> Option<String> {
+    let headers = request.headers();
+
+    headers
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| cookie_value(headers, SESSION_COOKIE))
+        .map(str::to_string)
+}
+
+/// Looks up a cookie by name across all `Cookie` headers
+pub fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, value)| *cookie_name == name && !value.is_empty())
+        .map(|(_, value)| value)
+}
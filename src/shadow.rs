@@ -0,0 +1,277 @@
+// src/shadow.rs
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use crate::models::{MediaType, TmdbResponse};
+use crate::problem::ApiError;
+
+/// Comparisons running at once; samples beyond that are skipped so shadow
+/// work never piles up behind a slow candidate
+const MAX_IN_FLIGHT: u32 = 16;
+
+/// Mismatches kept for `/admin/metrics/shadow`, oldest dropped first
+const RECENT_MISMATCHES: usize = 20;
+
+/// Differing JSON paths kept per mismatch
+const MAX_DIFFERENCES: usize = 10;
+
+/// List routes whose responses can be shadowed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListRoute {
+    Trending,
+    /// Discover and the routes built on it, such as network and company pages
+    Discover,
+    Search,
+}
+
+impl ListRoute {
+    pub const ALL: [ListRoute; 3] = [ListRoute::Trending, ListRoute::Discover, ListRoute::Search];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ListRoute::Trending => "trending",
+            ListRoute::Discover => "discover",
+            ListRoute::Search => "search",
+        }
+    }
+}
+
+/// What a list response was built for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ListRequest {
+    pub route: ListRoute,
+    /// Media type assumed for results TMDB doesn't label
+    pub media_type: MediaType,
+    pub include_adult: bool,
+}
+
+/// Turns a TMDB list response into the filtered list the catalog hands to
+/// the route: blocked and adult titles removed. The current pipeline lives in
+/// [`crate::services`]; a replacement implements this to be shadowed against
+/// it before taking over. Ranking, includes and device shaping happen later,
+/// in the handlers, and are not part of it.
+#[async_trait]
+pub trait ListPipeline: Send + Sync {
+    async fn normalize(&self, request: ListRequest, upstream: TmdbResponse) -> Result<TmdbResponse, ApiError>;
+}
+
+/// Shadowed comparisons of one route since startup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteShadowStats {
+    pub route: ListRoute,
+    pub compared: u64,
+    pub matched: u64,
+    pub mismatched: u64,
+    /// The candidate failed where the current pipeline succeeded
+    pub failed: u64,
+    /// Sampled while too many comparisons were running
+    pub skipped: u64,
+}
+
+/// One response the candidate built differently
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShadowMismatch {
+    pub route: ListRoute,
+    /// Unix timestamp in seconds
+    pub at: u64,
+    /// JSON paths that differ, e.g. `/results/3/title`, at most ten
+    pub differences: Vec<String>,
+}
+
+/// Body of `GET /admin/metrics/shadow`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShadowStatus {
+    /// Whether a candidate pipeline is installed
+    pub enabled: bool,
+    pub sample_rate: f64,
+    pub routes: Vec<RouteShadowStats>,
+    /// Latest mismatches, newest first
+    pub recent_mismatches: Vec<ShadowMismatch>,
+}
+
+/// Body of `PUT /admin/shadow`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShadowChange {
+    pub sample_rate: f64,
+}
+
+#[derive(Default)]
+struct Counters {
+    compared: AtomicU64,
+    matched: AtomicU64,
+    mismatched: AtomicU64,
+    failed: AtomicU64,
+    skipped: AtomicU64,
+}
+
+/// Runs a sample of list responses through a candidate [`ListPipeline`] as
+/// well and compares the outputs, so a rewrite can be checked on production
+/// traffic before it serves any.
+///
+/// The candidate gets the same TMDB response the current pipeline used, so
+/// shadowing costs no upstream calls. It runs in its own task once the
+/// catalog has filtered the list, and its output is only compared, never
+/// returned. Without a candidate nothing is sampled.
+pub struct ShadowTraffic {
+    candidate: Option<Arc<dyn ListPipeline>>,
+    sample_rate: Mutex<f64>,
+    in_flight: Arc<Semaphore>,
+    counters: [Counters; ListRoute::ALL.len()],
+    recent: Mutex<VecDeque<ShadowMismatch>>,
+}
+
+impl ShadowTraffic {
+    /// Shadows `sample_rate` of list responses, between 0 and 1, through `candidate`
+    pub fn new(candidate: Arc<dyn ListPipeline>, sample_rate: f64) -> Result<Self, String> {
+        Ok(Self { candidate: Some(candidate), sample_rate: Mutex::new(validate_rate(sample_rate)?), ..Self::default() })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.candidate.is_some()
+    }
+
+    /// Changes the share of responses shadowed from the next request on
+    pub fn set_sample_rate(&self, sample_rate: f64) -> Result<ShadowStatus, String> {
+        *self.sample_rate.lock().unwrap() = validate_rate(sample_rate)?;
+        tracing::warn!(target: "audit", sample_rate, "shadow sample rate changed");
+        Ok(self.status())
+    }
+
+    /// Whether to shadow the response being built; callers keep a copy of
+    /// the TMDB response for [`ShadowTraffic::compare`] only when it is
+    pub fn sampled(&self) -> bool {
+        self.candidate.is_some() && rand::random_bool(*self.sample_rate.lock().unwrap())
+    }
+
+    /// Builds `request` from `upstream` with the candidate in the background
+    /// and compares the result with `primary`, the catalog's filtered list
+    /// before the handler ranks, enriches or shapes it
+    pub fn compare(self: &Arc<Self>, request: ListRequest, upstream: TmdbResponse, primary: &TmdbResponse, now: SystemTime) {
+        let Some(candidate) = self.candidate.clone() else {
+            return;
+        };
+        let counters = &self.counters[request.route as usize];
+        let Ok(permit) = self.in_flight.clone().try_acquire_owned() else {
+            counters.skipped.fetch_add(1, Ordering::Relaxed);
+            return;
+        };
+        let primary = serde_json::to_value(primary).unwrap_or_default();
+        let shadow = self.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let outcome = candidate.normalize(request, upstream).await;
+            shadow.record(request.route, &primary, outcome, now);
+        });
+    }
+
+    /// Waits for running comparisons to finish
+    pub async fn idle(&self) {
+        let _all = self.in_flight.acquire_many(MAX_IN_FLIGHT).await;
+    }
+
+    pub fn status(&self) -> ShadowStatus {
+        ShadowStatus {
+            enabled: self.is_enabled(),
+            sample_rate: *self.sample_rate.lock().unwrap(),
+            routes: ListRoute::ALL
+                .iter()
+                .map(|&route| {
+                    let counters = &self.counters[route as usize];
+                    RouteShadowStats {
+                        route,
+                        compared: counters.compared.load(Ordering::Relaxed),
+                        matched: counters.matched.load(Ordering::Relaxed),
+                        mismatched: counters.mismatched.load(Ordering::Relaxed),
+                        failed: counters.failed.load(Ordering::Relaxed),
+                        skipped: counters.skipped.load(Ordering::Relaxed),
+                    }
+                })
+                .collect(),
+            recent_mismatches: self.recent.lock().unwrap().iter().rev().cloned().collect(),
+        }
+    }
+
+    fn record(&self, route: ListRoute, primary: &Value, outcome: Result<TmdbResponse, ApiError>, now: SystemTime) {
+        let counters = &self.counters[route as usize];
+        counters.compared.fetch_add(1, Ordering::Relaxed);
+        let candidate = match outcome {
+            Ok(candidate) => serde_json::to_value(&candidate).unwrap_or_default(),
+            Err(error) => {
+                counters.failed.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(route = route.as_str(), status = error.status.as_u16(), detail = error.detail.as_deref(), "shadow pipeline failed");
+                return;
+            }
+        };
+
+        let mut differences = Vec::new();
+        diff(primary, &candidate, &mut String::new(), &mut differences);
+        if differences.is_empty() {
+            counters.matched.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        counters.mismatched.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(route = route.as_str(), ?differences, "shadow pipeline output differs");
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() == RECENT_MISMATCHES {
+            recent.pop_front();
+        }
+        recent.push_back(ShadowMismatch {
+            route,
+            at: now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            differences,
+        });
+    }
+}
+
+impl Default for ShadowTraffic {
+    fn default() -> Self {
+        Self {
+            candidate: None,
+            sample_rate: Mutex::new(0.0),
+            in_flight: Arc::new(Semaphore::new(MAX_IN_FLIGHT as usize)),
+            counters: Default::default(),
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+fn validate_rate(sample_rate: f64) -> Result<f64, String> {
+    if (0.0..=1.0).contains(&sample_rate) {
+        Ok(sample_rate)
+    } else {
+        Err(format!("sample rate must be between 0 and 1, got {}", sample_rate))
+    }
+}
+
+/// Collects the JSON pointer paths at which `a` and `b` differ, up to
+/// [`MAX_DIFFERENCES`]
+fn diff(a: &Value, b: &Value, path: &mut String, differences: &mut Vec<String>) {
+    if differences.len() == MAX_DIFFERENCES || a == b {
+        return;
+    }
+    let len = path.len();
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for key in a.keys().chain(b.keys().filter(|key| !a.contains_key(*key))) {
+                path.push('/');
+                path.push_str(key);
+                diff(a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null), path, differences);
+                path.truncate(len);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                path.push_str(&format!("/{}", index));
+                diff(a.get(index).unwrap_or(&Value::Null), b.get(index).unwrap_or(&Value::Null), path, differences);
+                path.truncate(len);
+            }
+        }
+        _ => differences.push(if path.is_empty() { "/".to_string() } else { path.clone() }),
+    }
+}
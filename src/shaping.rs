@@ -0,0 +1,261 @@
+// src/shaping.rs
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use crate::models::{image_url, Movie, TitleDetails, TmdbResponse};
+use crate::problem::ApiError;
+use crate::safe_search::SafeSearch;
+use crate::state::AppState;
+
+/// Header alternative to the `device` query parameter
+pub const CLIENT_PROFILE_HEADER: &str = "x-client-profile";
+
+/// Words the built-in `kids` preset masks in overviews
+const DEFAULT_KIDS_WORDLIST: &[&str] = &[
+    "ass", "asshole", "bastard", "bitch", "crap", "damn", "fuck", "fucking", "hell", "shit",
+];
+
+/// What happens to an overview containing a listed word
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverviewAction {
+    /// Listed words are masked with asterisks
+    #[default]
+    Replace,
+    /// The whole overview is replaced by `null`
+    Drop,
+}
+
+/// Sanitizes overviews against a wordlist, e.g. for kids profiles.
+///
+/// Words are matched case-insensitively and whole, so `hell` doesn't touch
+/// `hello`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverviewFilter {
+    pub words: Vec<String>,
+    #[serde(default)]
+    pub action: OverviewAction,
+}
+
+impl OverviewFilter {
+    /// `overview` with listed words handled; `None` when it is dropped
+    pub fn apply(&self, overview: &str) -> Option<String> {
+        let listed: HashSet<String> = self.words.iter().map(|word| word.trim().to_lowercase()).collect();
+        let mut sanitized = String::with_capacity(overview.len());
+        let mut matched = false;
+        let mut word = String::new();
+        let mut flush = |word: &mut String, sanitized: &mut String| {
+            if listed.contains(&word.to_lowercase()) {
+                matched = true;
+                sanitized.extend(word.chars().map(|_| '*'));
+            } else {
+                sanitized.push_str(word);
+            }
+            word.clear();
+        };
+        for c in overview.chars() {
+            if c.is_alphanumeric() {
+                word.push(c);
+            } else {
+                flush(&mut word, &mut sanitized);
+                sanitized.push(c);
+            }
+        }
+        flush(&mut word, &mut sanitized);
+
+        match (matched, self.action) {
+            (true, OverviewAction::Drop) => None,
+            _ => Some(sanitized),
+        }
+    }
+}
+
+/// How list items are trimmed for one kind of device.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevicePreset {
+    /// Item fields left out of the response, e.g. `overview`
+    #[serde(default)]
+    pub omit: Vec<String>,
+    /// TMDB image size such as `w185`; when set, items get a full `poster_url`
+    #[serde(default)]
+    pub poster_size: Option<String>,
+    /// Leaves out titles TMDB flags as adult
+    #[serde(default)]
+    pub drop_adult: bool,
+    #[serde(default)]
+    pub overview_filter: Option<OverviewFilter>,
+}
+
+impl DevicePreset {
+    fn new(omit: &[&str], poster_size: &str) -> Self {
+        Self {
+            omit: omit.iter().map(|field| field.to_string()).collect(),
+            poster_size: Some(poster_size.to_string()),
+            drop_adult: false,
+            overview_filter: None,
+        }
+    }
+
+    /// Web sizing without adult titles or profanity in overviews
+    fn kids() -> Self {
+        let words = DEFAULT_KIDS_WORDLIST.iter().map(|word| word.to_string()).collect();
+        Self {
+            drop_adult: true,
+            overview_filter: Some(OverviewFilter { words, action: OverviewAction::Replace }),
+            ..Self::new(&[], "w500")
+        }
+    }
+
+    /// Drops adult titles and sanitizes overviews as the preset asks
+    pub fn retain(&self, items: &mut Vec<Movie>) {
+        if self.drop_adult {
+            items.retain(|item| !item.adult);
+        }
+        if let Some(filter) = &self.overview_filter {
+            for item in items.iter_mut() {
+                item.overview = item.overview.as_deref().and_then(|overview| filter.apply(overview));
+            }
+        }
+    }
+
+    /// Sanitizes the overview of a single title; adult titles are refused
+    /// when the preset drops them
+    pub fn check_details(&self, details: &mut TitleDetails) -> Result<(), ApiError> {
+        if self.drop_adult && details.adult {
+            return Err(SafeSearch::error());
+        }
+        if let Some(filter) = &self.overview_filter {
+            details.overview = details.overview.as_deref().and_then(|overview| filter.apply(overview));
+        }
+        Ok(())
+    }
+
+    /// Applies the preset to every item of a trending or search response
+    pub fn shape(&self, response: &TmdbResponse) -> Value {
+        let mut results = response.results.clone();
+        self.retain(&mut results);
+        let mut value = serde_json::to_value(TmdbResponse { page: response.page, results, total_pages: response.total_pages })
+            .unwrap_or_default();
+        let Some(items) = value.get_mut("results").and_then(Value::as_array_mut) else {
+            return value;
+        };
+
+        for item in items.iter_mut().filter_map(Value::as_object_mut) {
+            if let Some(size) = &self.poster_size {
+                let poster_url = item
+                    .get("poster_path")
+                    .and_then(Value::as_str)
+                    .map(|path| Value::String(image_url(size, path)));
+                item.insert("poster_url".to_string(), poster_url.unwrap_or(Value::Null));
+            }
+            for field in &self.omit {
+                item.remove(field);
+            }
+        }
+        value
+    }
+}
+
+/// Shaping presets by device name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevicePresets {
+    presets: HashMap<String, DevicePreset>,
+}
+
+impl DevicePresets {
+    /// Parses presets from JSON such as
+    /// `{"tv": {"omit": ["overview"], "poster_size": "w185"}}`.
+    /// Listed devices replace the built-in preset of the same name.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut presets = Self::default();
+        presets.presets.extend(serde_json::from_str::<HashMap<String, DevicePreset>>(json)?);
+        Ok(presets)
+    }
+
+    pub fn get(&self, device: &str) -> Option<&DevicePreset> {
+        self.presets.get(device)
+    }
+}
+
+impl Default for DevicePresets {
+    fn default() -> Self {
+        let presets = [
+            ("tv", DevicePreset::new(&["overview", "backdrop_path"], "w185")),
+            ("mobile", DevicePreset::new(&["backdrop_path"], "w342")),
+            ("web", DevicePreset::new(&[], "w500")),
+            ("kids", DevicePreset::kids()),
+        ];
+
+        Self {
+            presets: presets
+                .into_iter()
+                .map(|(device, preset)| (device.to_string(), preset))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeviceQuery {
+    device: Option<String>,
+}
+
+/// The caller's shaping preset, from `?device=` or the `X-Client-Profile` header.
+///
+/// Requests naming neither get responses unchanged; unknown devices are a 400.
+pub struct Device(pub Option<DevicePreset>);
+
+impl Device {
+    /// Serializes `response`, shaped for the device if one was given
+    pub fn body(&self, response: TmdbResponse) -> Value {
+        match &self.0 {
+            Some(preset) => preset.shape(&response),
+            None => serde_json::to_value(response).unwrap_or_default(),
+        }
+    }
+
+    /// Applies the preset to titles outside list pages, such as home rows
+    pub fn retain(&self, items: &mut Vec<Movie>) {
+        if let Some(preset) = &self.0 {
+            preset.retain(items);
+        }
+    }
+
+    /// Applies the preset to the details of one title, see [`DevicePreset::check_details`]
+    pub fn check_details(&self, details: &mut TitleDetails) -> Result<(), ApiError> {
+        match &self.0 {
+            Some(preset) => preset.check_details(details),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for Device {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let from_query = Query::<DeviceQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(query)| query.device);
+        let from_header = || {
+            parts
+                .headers
+                .get(CLIENT_PROFILE_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let Some(device) = from_query.or_else(from_header) else {
+            return Ok(Self(None));
+        };
+
+        match state.device_presets.get(device.trim()) {
+            Some(preset) => Ok(Self(Some(preset.clone()))),
+            None => Err((StatusCode::BAD_REQUEST, format!("unknown device '{}'", device.trim())).into_response()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// src/shutdown.rs
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::error::Elapsed;
+
+/// Time in-flight requests get to finish after a shutdown signal, unless
+/// `SHUTDOWN_GRACE_SECONDS` says otherwise
+pub const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Resolves on Ctrl+C or, on Unix, on the SIGTERM that Docker, Kubernetes
+/// and Cloud Run send before stopping a container
+pub async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("failed to listen for Ctrl+C");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Resolves once `stop` is set, so several tasks can wait on one signal
+pub async fn stopped(mut stop: watch::Receiver<bool>) {
+    // An error means the sender is gone, which only happens after shutdown
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+/// Runs `server` to completion, but once `shutdown` resolves gives it only
+/// `grace` more to drain before giving up
+pub async fn with_grace<F: Future>(server: F, shutdown: impl Future<Output = ()>, grace: Duration) -> Result<F::Output, Elapsed> {
+    tokio::pin!(server);
+    tokio::select! {
+        output = &mut server => return Ok(output),
+        _ = shutdown => {},
+    }
+    tokio::time::timeout(grace, server).await
+}
@@ -0,0 +1,188 @@
+// src/signing.rs
+use axum::{
+    body::{self, Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use crate::state::AppState;
+
+/// Base64 Ed25519 signature of the canonical response body
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// Id of the key in `/api/keys/public` that made `x-signature`
+pub const SIGNATURE_KEY_HEADER: &str = "x-signature-key";
+
+/// Bodies above this size, and streamed ones of unknown size, go out unsigned
+const MAX_SIGNED_BYTES: u64 = 1024 * 1024;
+
+/// An Ed25519 verification key in JWK form (RFC 8037)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicJwk {
+    pub kty: String,
+    pub crv: String,
+    pub alg: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub kid: String,
+    /// The public key, base64url without padding
+    pub x: String,
+    /// Retired keys no longer sign, but stay listed so responses cached
+    /// under them still verify
+    pub retired: bool,
+}
+
+/// Body of `GET /api/keys/public`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PublicKeySet {
+    pub keys: Vec<PublicJwk>,
+}
+
+struct SigningKey {
+    pair: Ed25519KeyPair,
+    kid: String,
+}
+
+/// Signs response bodies so consumers caching them, such as edge caches or
+/// offline clients, can check they weren't altered on the way.
+///
+/// Signatures are detached: the body is unchanged and the signature goes in
+/// `x-signature`, with the key id in `x-signature-key`. What's signed is the
+/// canonical body: JSON re-serialized with ordered keys and no whitespace,
+/// so re-encoding by a cache doesn't break it, and any other body as is.
+/// Without a key nothing is signed and no keys are published.
+#[derive(Default)]
+pub struct ResponseSigner {
+    key: Option<SigningKey>,
+    retired: Vec<PublicJwk>,
+}
+
+impl ResponseSigner {
+    /// Signs with the Ed25519 key derived from a 32-byte seed
+    pub fn from_seed(seed: &[u8]) -> Result<Self, String> {
+        let pair = Ed25519KeyPair::from_seed_unchecked(seed).map_err(|e| format!("invalid Ed25519 seed: {}", e))?;
+        let kid = key_id(pair.public_key().as_ref());
+        Ok(Self { key: Some(SigningKey { pair, kid }), retired: Vec::new() })
+    }
+
+    /// Like [`ResponseSigner::from_seed`] with the seed in standard base64
+    pub fn from_base64_seed(seed: &str) -> Result<Self, String> {
+        let seed = STANDARD.decode(seed.trim()).map_err(|e| format!("seed isn't base64: {}", e))?;
+        Self::from_seed(&seed)
+    }
+
+    /// Keeps publishing a previous key, given as a base64 32-byte public key
+    pub fn with_retired_key(mut self, public_key: &str) -> Result<Self, String> {
+        let public_key = STANDARD
+            .decode(public_key.trim())
+            .map_err(|e| format!("retired key isn't base64: {}", e))?;
+        if public_key.len() != 32 {
+            return Err(format!("retired key must be 32 bytes, got {}", public_key.len()));
+        }
+        self.retired.push(jwk(&public_key, key_id(&public_key), true));
+        Ok(self)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub fn key_id(&self) -> Option<&str> {
+        self.key.as_ref().map(|key| key.kid.as_str())
+    }
+
+    /// Base64 signature over the canonical form of `body`, unset without a key
+    pub fn sign(&self, body: &[u8], is_json: bool) -> Option<String> {
+        let key = self.key.as_ref()?;
+        Some(STANDARD.encode(key.pair.sign(&canonical_body(body, is_json)).as_ref()))
+    }
+
+    /// The current key followed by retired ones
+    pub fn public_keys(&self) -> PublicKeySet {
+        let current = self
+            .key
+            .as_ref()
+            .map(|key| jwk(key.pair.public_key().as_ref(), key.kid.clone(), false));
+        PublicKeySet { keys: current.into_iter().chain(self.retired.iter().cloned()).collect() }
+    }
+}
+
+/// What a signature covers: JSON bodies re-serialized through
+/// `serde_json::Value`, whose object keys are ordered, and other bodies as
+/// they are. Bodies that claim to be JSON but don't parse are taken as is.
+pub fn canonical_body(body: &[u8], is_json: bool) -> Cow<'_, [u8]> {
+    if is_json
+        && let Ok(value) = serde_json::from_slice::<serde_json::Value>(body)
+        && let Ok(canonical) = serde_json::to_vec(&value)
+    {
+        return Cow::Owned(canonical);
+    }
+    Cow::Borrowed(body)
+}
+
+/// Middleware adding `x-signature` and `x-signature-key` to responses with a
+/// body of known size, errors included. It sits outside the problem
+/// middleware so the body signed is the one sent.
+pub async fn signing_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let response = next.run(request).await;
+    let Some(kid) = state.signing.key_id() else {
+        return response;
+    };
+    let size = response.body().size_hint().exact();
+    if !size.is_some_and(|size| size > 0 && size <= MAX_SIGNED_BYTES) {
+        return response;
+    }
+
+    let is_json = is_json(&response);
+    let kid = HeaderValue::from_str(kid).expect("key ids are hex");
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, MAX_SIGNED_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    if let Some(signature) = state.signing.sign(&bytes, is_json)
+        && let Ok(signature) = HeaderValue::from_str(&signature)
+    {
+        parts.headers.insert(SIGNATURE_HEADER, signature);
+        parts.headers.insert(SIGNATURE_KEY_HEADER, kid);
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// `application/json` and `+json` types such as `application/problem+json`
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|essence| essence.trim() == "application/json" || essence.trim().ends_with("+json"))
+}
+
+/// First 8 bytes of the public key's SHA-256, in hex
+fn key_id(public_key: &[u8]) -> String {
+    Sha256::digest(public_key)[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn jwk(public_key: &[u8], kid: String, retired: bool) -> PublicJwk {
+    PublicJwk {
+        kty: "OKP".to_string(),
+        crv: "Ed25519".to_string(),
+        alg: "EdDSA".to_string(),
+        key_use: "sig".to_string(),
+        kid,
+        x: URL_SAFE_NO_PAD.encode(public_key),
+        retired,
+    }
+}
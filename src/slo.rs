@@ -0,0 +1,193 @@
+// src/slo.rs
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use crate::state::AppState;
+
+/// Short and long windows used for multi-window burn-rate alerts
+pub const SHORT_WINDOW: Duration = Duration::from_secs(5 * 60);
+pub const LONG_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Burn rate at which a 30-day error budget is gone in about two days
+pub const FAST_BURN_RATE: f64 = 14.4;
+
+/// Maximum samples kept per route; oldest samples are dropped first.
+const SAMPLE_CAPACITY: usize = 10_000;
+
+/// Objectives for one route, matched against the route template such as
+/// `/api/movie/{id}/videos`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Slo {
+    pub route: String,
+    /// Share of requests that must not fail with a 5xx, e.g. `0.999`
+    pub availability: f64,
+    /// Requests slower than this count against the latency objective
+    pub latency_ms: u64,
+    /// Share of requests that must finish within `latency_ms`, e.g. `0.99`
+    pub latency_target: f64,
+}
+
+/// Error-budget consumption over one window.
+///
+/// A burn rate of 1 spends the budget exactly as fast as the objective allows.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowCompliance {
+    pub window_secs: u64,
+    pub requests: usize,
+    pub availability: f64,
+    pub within_latency: f64,
+    pub availability_burn_rate: f64,
+    pub latency_burn_rate: f64,
+}
+
+/// Current compliance of one route against its objectives.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SloStatus {
+    pub slo: Slo,
+    pub short_window: WindowCompliance,
+    pub long_window: WindowCompliance,
+    /// True when both windows burn faster than [`FAST_BURN_RATE`]
+    pub alerting: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    at: SystemTime,
+    failed: bool,
+    slow: bool,
+}
+
+/// Configured route objectives plus rolling request samples per route.
+pub struct SloTracker {
+    slos: Vec<Slo>,
+    samples: Mutex<HashMap<String, VecDeque<Sample>>>,
+}
+
+impl SloTracker {
+    pub fn new(slos: Vec<Slo>) -> Self {
+        Self {
+            slos,
+            samples: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Parses objectives from a JSON array such as
+    /// `[{"route": "/api/search", "availability": 0.999, "latency_ms": 800, "latency_target": 0.99}]`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slos.is_empty()
+    }
+
+    /// Records a finished request; routes without an objective are ignored
+    pub fn record(&self, route: &str, status: u16, elapsed: Duration, at: SystemTime) {
+        let Some(slo) = self.slos.iter().find(|slo| slo.route == route) else {
+            return;
+        };
+        let sample = Sample {
+            at,
+            failed: status >= 500,
+            slow: elapsed > Duration::from_millis(slo.latency_ms),
+        };
+
+        let mut samples = self.samples.lock().unwrap();
+        let route_samples = samples.entry(slo.route.clone()).or_default();
+        while route_samples
+            .front()
+            .is_some_and(|oldest| at.duration_since(oldest.at).is_ok_and(|age| age > LONG_WINDOW))
+        {
+            route_samples.pop_front();
+        }
+        if route_samples.len() == SAMPLE_CAPACITY {
+            route_samples.pop_front();
+        }
+        route_samples.push_back(sample);
+    }
+
+    /// Compliance of every configured route as of `now`
+    pub fn status(&self, now: SystemTime) -> Vec<SloStatus> {
+        let samples = self.samples.lock().unwrap();
+
+        self.slos
+            .iter()
+            .map(|slo| {
+                let route_samples = samples.get(&slo.route);
+                let short_window = compliance(slo, route_samples, SHORT_WINDOW, now);
+                let long_window = compliance(slo, route_samples, LONG_WINDOW, now);
+                let burning = |window: &WindowCompliance| {
+                    window.availability_burn_rate.max(window.latency_burn_rate) > FAST_BURN_RATE
+                };
+                let alerting = burning(&short_window) && burning(&long_window);
+
+                SloStatus {
+                    slo: slo.clone(),
+                    short_window,
+                    long_window,
+                    alerting,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for SloTracker {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+fn compliance(slo: &Slo, samples: Option<&VecDeque<Sample>>, window: Duration, now: SystemTime) -> WindowCompliance {
+    let from = now.checked_sub(window).unwrap_or(SystemTime::UNIX_EPOCH);
+    let in_window = samples
+        .into_iter()
+        .flatten()
+        .filter(|sample| sample.at >= from && sample.at <= now);
+
+    let (mut requests, mut failed, mut slow) = (0, 0, 0);
+    for sample in in_window {
+        requests += 1;
+        failed += usize::from(sample.failed);
+        slow += usize::from(sample.slow);
+    }
+
+    let share = |count: usize| if requests == 0 { 0.0 } else { count as f64 / requests as f64 };
+    // A target of 100% leaves no budget; keep the rate finite so it still serializes
+    let burn_rate = |bad_share: f64, target: f64| bad_share / (1.0 - target).max(f64::EPSILON);
+
+    WindowCompliance {
+        window_secs: window.as_secs(),
+        requests,
+        availability: 1.0 - share(failed),
+        within_latency: 1.0 - share(slow),
+        availability_burn_rate: burn_rate(share(failed), slo.availability),
+        latency_burn_rate: burn_rate(share(slow), slo.latency_target),
+    }
+}
+
+/// Feeds every routed request into the state's [`SloTracker`].
+pub async fn slo_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    if state.slos.is_empty() {
+        return next.run(request).await;
+    }
+    let Some(route) = request.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string()) else {
+        return next.run(request).await;
+    };
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    state.slos.record(&route, response.status().as_u16(), started.elapsed(), state.clock.now());
+    response
+}
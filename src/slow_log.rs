@@ -0,0 +1,281 @@
+// src/slow_log.rs
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+use crate::clock::{Clock, SystemClock};
+use crate::error::TmdbError;
+use crate::etag::canonical_resource;
+use crate::hash::fnv1a;
+use crate::models::{AlternativeTitles, ChangesResponse, Collection, DiscoverFilter, ImagesResponse, Network, ProviderList, Season, TmdbList, WatchProvidersResponse, MediaType, TitleDetails, TmdbResponse, VideoResponse};
+use crate::state::AppState;
+use crate::tmdb_client::TmdbClient;
+use crate::upstream_metrics::{Operation, UpstreamMetrics};
+
+const DEFAULT_SLOW_REQUEST: Duration = Duration::from_millis(1000);
+const DEFAULT_SLOW_UPSTREAM: Duration = Duration::from_millis(500);
+
+/// Durations above which a request or TMDB call is logged as slow
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlowThresholds {
+    pub request: Duration,
+    pub upstream: Duration,
+}
+
+impl SlowThresholds {
+    /// Reads `SLOW_REQUEST_MS` and `SLOW_UPSTREAM_MS`, falling back to 1000ms and 500ms
+    pub fn from_env() -> Self {
+        let millis = |key: &str, default: Duration| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .map_or(default, Duration::from_millis)
+        };
+
+        Self {
+            request: millis("SLOW_REQUEST_MS", DEFAULT_SLOW_REQUEST),
+            upstream: millis("SLOW_UPSTREAM_MS", DEFAULT_SLOW_UPSTREAM),
+        }
+    }
+}
+
+impl Default for SlowThresholds {
+    fn default() -> Self {
+        Self {
+            request: DEFAULT_SLOW_REQUEST,
+            upstream: DEFAULT_SLOW_UPSTREAM,
+        }
+    }
+}
+
+/// Number of slow events seen since startup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlowEventCounts {
+    pub slow_requests: u64,
+    pub slow_upstream_calls: u64,
+}
+
+/// Thresholds plus counters for slow requests and slow TMDB calls.
+#[derive(Default)]
+pub struct SlowLog {
+    thresholds: SlowThresholds,
+    slow_requests: AtomicU64,
+    slow_upstream_calls: AtomicU64,
+}
+
+impl SlowLog {
+    pub fn new(thresholds: SlowThresholds) -> Self {
+        Self {
+            thresholds,
+            ..Self::default()
+        }
+    }
+
+    pub fn thresholds(&self) -> SlowThresholds {
+        self.thresholds
+    }
+
+    pub fn counts(&self) -> SlowEventCounts {
+        SlowEventCounts {
+            slow_requests: self.slow_requests.load(Ordering::Relaxed),
+            slow_upstream_calls: self.slow_upstream_calls.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Logs and counts a handler that ran longer than the request threshold
+    pub fn request_finished(&self, method: &str, route: &str, params_digest: u64, status: u16, elapsed: Duration) {
+        if elapsed <= self.thresholds.request {
+            return;
+        }
+        self.slow_requests.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            method,
+            route,
+            params_digest = %format!("{:016x}", params_digest),
+            status,
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = self.thresholds.request.as_millis() as u64,
+            "slow request"
+        );
+    }
+
+    /// Logs and counts a TMDB call that ran longer than the upstream threshold
+    pub fn upstream_finished(&self, operation: &str, params_digest: u64, ok: bool, elapsed: Duration) {
+        if elapsed <= self.thresholds.upstream {
+            return;
+        }
+        self.slow_upstream_calls.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            operation,
+            params_digest = %format!("{:016x}", params_digest),
+            ok,
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = self.thresholds.upstream.as_millis() as u64,
+            "slow TMDB call"
+        );
+    }
+}
+
+/// Times every request and reports slow ones to the state's [`SlowLog`].
+///
+/// Requests are identified by their route template and a digest of the path
+/// and sorted query, so search terms and ids never appear in the log.
+pub async fn slow_request_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path().to_string(), |path| path.as_str().to_string());
+    let params_digest = fnv1a(&[canonical_resource(&request).as_bytes()]);
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    state.slow_log.request_finished(&method, &route, params_digest, response.status().as_u16(), started.elapsed());
+    response
+}
+
+/// `TmdbClient` decorator that reports slow upstream calls to a [`SlowLog`]
+/// and counts every call per [`Operation`].
+///
+/// Each call runs in a `tmdb_call` span carrying its operation name.
+pub struct TimedTmdbClient<C> {
+    inner: C,
+    slow_log: Arc<SlowLog>,
+    metrics: Arc<UpstreamMetrics>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<C: TmdbClient> TimedTmdbClient<C> {
+    pub fn new(inner: C, slow_log: Arc<SlowLog>) -> Self {
+        Self {
+            inner,
+            slow_log,
+            metrics: Arc::new(UpstreamMetrics::new()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Reports call counts to `metrics`, typically the same metrics held by the state
+    pub fn with_metrics(mut self, metrics: Arc<UpstreamMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Stamps successful calls with `clock` rather than the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    async fn timed<T>(
+        &self,
+        operation: Operation,
+        params: &[&[u8]],
+        call: impl Future<Output = Result<T, TmdbError>>
+    ) -> Result<T, TmdbError> {
+        let started = Instant::now();
+        let result = call.instrument(tracing::info_span!("tmdb_call", operation = operation.as_str())).await;
+        let elapsed = started.elapsed();
+        self.metrics.record(operation, result.is_ok(), elapsed, self.clock.now());
+        self.slow_log.upstream_finished(operation.as_str(), fnv1a(params), result.is_ok(), elapsed);
+        result
+    }
+}
+
+#[async_trait]
+impl<C: TmdbClient> TmdbClient for TimedTmdbClient<C> {
+    async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.timed(Operation::Trending, &[&page.to_be_bytes()], self.inner.get_trending(page)).await
+    }
+
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), &page.to_be_bytes()];
+        self.timed(Operation::TopRated, params, self.inner.get_top_rated(media_type, page)).await
+    }
+
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), &page.to_be_bytes()];
+        self.timed(Operation::Popular, params, self.inner.get_popular(media_type, page)).await
+    }
+
+    async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let params: &[&[u8]] = &[query.as_bytes(), &page.to_be_bytes()];
+        self.timed(Operation::Search, params, self.inner.search_content(query, page)).await
+    }
+
+    async fn search_content_including_adult(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let params: &[&[u8]] = &[query.as_bytes(), &page.to_be_bytes(), b"adult"];
+        self.timed(Operation::Search, params, self.inner.search_content_including_adult(query, page)).await
+    }
+
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        self.timed(Operation::Videos, &[&movie_id.to_be_bytes()], self.inner.get_movie_videos(movie_id)).await
+    }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), &id.to_be_bytes()];
+        self.timed(Operation::Details, params, self.inner.get_title_details(media_type, id)).await
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), &id.to_be_bytes()];
+        self.timed(Operation::Images, params, self.inner.get_images(media_type, id)).await
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), &id.to_be_bytes()];
+        self.timed(Operation::AlternativeTitles, params, self.inner.get_alternative_titles(media_type, id)).await
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), &id.to_be_bytes()];
+        self.timed(Operation::WatchProviders, params, self.inner.get_watch_providers(media_type, id)).await
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let filter_key = format!("{:?}", filter);
+        let params: &[&[u8]] = &[filter_key.as_bytes(), &page.to_be_bytes()];
+        self.timed(Operation::Discover, params, self.inner.discover(filter, page)).await
+    }
+
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        self.timed(Operation::ProviderList, &[region.as_bytes()], self.inner.get_provider_list(region)).await
+    }
+
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        self.timed(Operation::Network, &[&id.to_be_bytes()], self.inner.get_network(id)).await
+    }
+
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        self.timed(Operation::List, &[&list_id.to_be_bytes()], self.inner.get_list(list_id)).await
+    }
+
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError> {
+        self.timed(Operation::Collection, &[&id.to_be_bytes()], self.inner.get_collection(id)).await
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        let params: &[&[u8]] = &[&tv_id.to_be_bytes(), &season_number.to_be_bytes()];
+        self.timed(Operation::Season, params, self.inner.get_tv_season(tv_id, season_number)).await
+    }
+
+    async fn get_changes(&self, media_type: MediaType, start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        let day = start_date.to_string();
+        let params: &[&[u8]] = &[media_type.as_str().as_bytes(), day.as_bytes(), &page.to_be_bytes()];
+        self.timed(Operation::Changes, params, self.inner.get_changes(media_type, start_date, page)).await
+    }
+}
@@ -0,0 +1,158 @@
+// src/startup.rs
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+use crate::app::AppOptions;
+use crate::compression::CompressionMode;
+use crate::mode::ServiceMode;
+use crate::redact::{redact, REDACTED};
+use crate::state::AppState;
+use crate::tmdb_client::DEFAULT_TIMEOUT;
+
+/// Environment variables holding credentials; only whether they are set is logged
+const SECRET_VARS: &[&str] = &["TMDB_API_KEY", "ADMIN_TOKEN", "SESSION_SECRET"];
+
+/// What was built, so a log line can be traced back to a commit
+#[derive(Clone, Debug, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// Set with `GIT_COMMIT` at build time, e.g. as a Docker build argument
+    pub commit: Option<&'static str>,
+    pub profile: &'static str,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: option_env!("GIT_COMMIT"),
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+        }
+    }
+}
+
+/// Effective settings after defaults, in seconds unless named otherwise
+#[derive(Clone, Debug, Serialize)]
+pub struct StartupSummary {
+    pub build: BuildInfo,
+    pub bind: Option<String>,
+    /// `tmdb`, or `dev-seed` when serving the bundled fixture catalog
+    pub upstream: &'static str,
+    pub tmdb_mirrors: Vec<String>,
+    pub tmdb_timeout_ms: u64,
+    pub tmdb_compression: CompressionMode,
+    pub mode: ServiceMode,
+    pub admin_routes: bool,
+    /// Whether the listener serves TLS
+    pub tls: bool,
+    /// Whether internal routes require a client certificate
+    pub client_certificates: bool,
+    pub profiling: bool,
+    pub frontend_dir: Option<String>,
+    /// `configured`, or `random` when sessions do not survive a restart
+    pub session_secret: &'static str,
+    /// Every cache lives in process memory and is lost on restart
+    pub cache_backend: &'static str,
+    pub cache_ttls: CacheTtls,
+    pub request_budget_capacity: u32,
+    pub request_budget_refill_per_sec: u32,
+    pub min_search_query_chars: usize,
+    pub home_rows: usize,
+    pub themed_rows: usize,
+    pub list_sync_interval: Option<String>,
+    pub shutdown_grace: Option<u64>,
+    /// `[REDACTED]` when set, `unset` otherwise
+    pub secrets: BTreeMap<&'static str, &'static str>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CacheTtls {
+    pub trending: u64,
+    pub search: u64,
+    pub videos: u64,
+    pub details: u64,
+    pub providers: u64,
+    pub provider_list: u64,
+    pub new_on: u64,
+    pub negative: u64,
+}
+
+impl StartupSummary {
+    /// Summarises `state` and `options`, reading the settings the state does
+    /// not keep through `lookup`, e.g. `std::env::var`
+    pub fn new(state: &AppState, options: &AppOptions, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let policies = &state.cache_policies;
+        let limits = state.budget.limits();
+        let is_set = |key: &str| lookup(key).is_some_and(|value| !value.is_empty());
+
+        Self {
+            build: BuildInfo::current(),
+            bind: None,
+            upstream: "tmdb",
+            tmdb_mirrors: state.mirrors.status().into_iter().map(|mirror| redact(&mirror.base_url)).collect(),
+            tmdb_timeout_ms: lookup("TMDB_TIMEOUT_MS")
+                .and_then(|millis| millis.parse().ok())
+                .unwrap_or(DEFAULT_TIMEOUT.as_millis() as u64),
+            tmdb_compression: state.compression.mode(),
+            mode: state.mode.mode(),
+            admin_routes: state.admin_token.is_some() || state.client_certs.is_enabled(),
+            tls: is_set("TLS_CERT"),
+            client_certificates: state.client_certs.is_enabled(),
+            profiling: options.profiling,
+            frontend_dir: options.frontend_dir.as_ref().map(|dir| dir.display().to_string()),
+            session_secret: if is_set("SESSION_SECRET") { "configured" } else { "random" },
+            cache_backend: "in-memory",
+            cache_ttls: CacheTtls {
+                trending: policies.trending.as_secs(),
+                search: policies.search.as_secs(),
+                videos: policies.videos.as_secs(),
+                details: policies.details.as_secs(),
+                providers: policies.providers.as_secs(),
+                provider_list: policies.provider_list.as_secs(),
+                new_on: policies.new_on.as_secs(),
+                negative: policies.negative.as_secs(),
+            },
+            request_budget_capacity: limits.capacity,
+            request_budget_refill_per_sec: limits.refill_per_sec,
+            min_search_query_chars: state.min_search_query_chars,
+            home_rows: state.home_layout.rows.len(),
+            themed_rows: state.themes.all().len(),
+            list_sync_interval: lookup("LIST_SYNC_INTERVAL"),
+            shutdown_grace: None,
+            secrets: SECRET_VARS
+                .iter()
+                .map(|key| (*key, if is_set(key) { REDACTED } else { "unset" }))
+                .collect(),
+        }
+    }
+
+    pub fn with_bind(mut self, bind: SocketAddr) -> Self {
+        self.bind = Some(bind.to_string());
+        self
+    }
+
+    pub fn with_shutdown_grace(mut self, grace: Duration) -> Self {
+        self.shutdown_grace = Some(grace.as_secs());
+        self
+    }
+
+    /// Marks the upstream as the bundled fixture catalog
+    pub fn with_dev_seed(mut self) -> Self {
+        self.upstream = "dev-seed";
+        self
+    }
+
+    /// Logs the banner with the summary as one structured `config` field
+    pub fn log(&self) {
+        let config = serde_json::to_string(self).unwrap_or_default();
+        tracing::info!(
+            version = self.build.version,
+            commit = self.build.commit.unwrap_or("unknown"),
+            config = %config,
+            "Starting netflix-service {} ({} build)",
+            self.build.version,
+            self.build.profile,
+        );
+    }
+}
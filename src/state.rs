@@ -1,8 +1,353 @@
 // src/state.rs
+use crate::analytics::{SearchAnalytics, TitleAnalytics};
+use crate::blocklist::Blocklist;
+use crate::budget::RequestBudget;
+use crate::cache_policy::CachePolicies;
+use crate::changes::ChangeFeed;
+use crate::clock::{Clock, SystemClock};
+use crate::compression::UpstreamCompression;
+use crate::enrich::EnrichmentCache;
+use crate::experiments::Experiments;
+use crate::health::UpstreamProbe;
+use crate::home::{DiversityLimits, HomeLayout, HomeRowCache, HomeRowMetrics};
+use crate::lists::CuratedLists;
+use crate::logos::LogoCache;
+use crate::metadata::MetadataSources;
+use crate::mirrors::Mirrors;
+use crate::mode::ModeSwitch;
+use crate::mtls::ClientCertPolicy;
+use crate::negative_cache::{NegativeCache, NegativeCacheTtls};
+use crate::new_on::NewOnCache;
+use crate::outage::OutageMonitor;
+use crate::providers::{AvailabilityCache, ProviderListCache};
+use crate::push::PushNotifier;
+use crate::quality::QualityPriors;
+use crate::ranking::RankingStrategies;
+use crate::safe_search::{AdultContentPolicy, SafeSearch};
+use crate::saved_searches::SavedSearches;
+use crate::services::{SessionUsers, TmdbCatalog, TmdbSearch};
+use crate::session::{SessionStore, DEFAULT_SESSION_TTL};
+use crate::shaping::DevicePresets;
+use crate::shadow::ShadowTraffic;
+use crate::signing::ResponseSigner;
+use crate::slo::SloTracker;
+use crate::slow_log::SlowLog;
+use crate::tags::TagRules;
+use crate::themes::ThemedRows;
 use crate::tmdb_client::TmdbClient;
+use crate::trailers::TrailerIndex;
+use crate::upstream_metrics::UpstreamMetrics;
+use rand::RngCore;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Clone)]
 pub struct AppState {
     pub tmdb_client: Arc<dyn TmdbClient>,
-}
\ No newline at end of file
+    pub clock: Arc<dyn Clock>,
+    pub search_analytics: Arc<SearchAnalytics>,
+    pub title_analytics: Arc<TitleAnalytics>,
+    pub experiments: Arc<Experiments>,
+    pub sessions: Arc<SessionStore>,
+    pub slow_log: Arc<SlowLog>,
+    pub negative_cache: Arc<NegativeCache>,
+    pub upstream_metrics: Arc<UpstreamMetrics>,
+    pub mirrors: Arc<Mirrors>,
+    /// Compression mode and byte counters of TMDB responses
+    pub compression: Arc<UpstreamCompression>,
+    /// Latest TMDB probe behind `/health/upstream`
+    pub upstream_probe: Arc<UpstreamProbe>,
+    /// Detects TMDB outages and switches to degraded mode while they last
+    pub outage: Arc<OutageMonitor>,
+    pub slos: Arc<SloTracker>,
+    pub budget: Arc<RequestBudget>,
+    pub cache_policies: Arc<CachePolicies>,
+    pub mode: Arc<ModeSwitch>,
+    pub device_presets: Arc<DevicePresets>,
+    /// Logo choices, kept for the `details` cache policy TTL
+    pub logos: Arc<LogoCache>,
+    pub tag_rules: Arc<TagRules>,
+    /// Watch providers per title and region, kept for the `providers` cache policy TTL
+    pub availability: Arc<AvailabilityCache>,
+    /// Provider lists per region, kept for the `provider_list` cache policy TTL
+    pub provider_lists: Arc<ProviderListCache>,
+    /// "New on <provider>" rows, kept for the `new_on` cache policy TTL and
+    /// refreshed by the scheduler
+    pub new_on: Arc<NewOnCache>,
+    pub quality_priors: Arc<QualityPriors>,
+    /// Ranking chains for list results, picked per request by the `ranking` experiment
+    pub ranking: Arc<RankingStrategies>,
+    /// Rows of `/api/home`, read on every request
+    pub home_layout: Arc<HomeLayout>,
+    /// Titles of non-list home rows, kept for each row's TTL
+    pub home_rows: Arc<HomeRowCache>,
+    pub home_row_metrics: Arc<HomeRowMetrics>,
+    pub home_diversity: Arc<DiversityLimits>,
+    /// Sources title details are completed from besides TMDB, per field group
+    pub metadata: Arc<MetadataSources>,
+    /// Editorial lists; resolved lists are kept for the `details` cache policy TTL
+    pub lists: Arc<CuratedLists>,
+    /// Rule-driven rows such as "Halloween picks"
+    pub themes: Arc<ThemedRows>,
+    /// Trailer badges for list items, kept for the `videos` cache policy TTL
+    pub trailers: Arc<TrailerIndex>,
+    /// Results of `?include=` enrichments, each kept for its include's cache policy TTL
+    pub enrichments: Arc<EnrichmentCache>,
+    /// Saved searches and their notifications, per session
+    pub saved_searches: Arc<SavedSearches>,
+    /// Devices registered for push notifications and the senders reaching them
+    pub push: Arc<PushNotifier>,
+    /// Titles hidden from every result and answered with `451` when requested directly
+    pub blocklist: Arc<Blocklist>,
+    /// Adult content policy and the count of adult titles it filtered
+    pub safe_search: Arc<SafeSearch>,
+    /// Polls TMDB for changed titles and drops their cached data
+    pub changes: Arc<ChangeFeed>,
+    /// Signs response bodies when a signing key is configured
+    pub signing: Arc<ResponseSigner>,
+    /// Client certificate roles required on internal routes, when configured
+    pub client_certs: Arc<ClientCertPolicy>,
+    /// Compares a candidate list pipeline against the current one, when installed
+    pub shadow: Arc<ShadowTraffic>,
+    /// Trimmed queries shorter than this return no results without calling TMDB
+    pub min_search_query_chars: usize,
+    /// When the state was built, for uptime
+    pub started: Instant,
+    /// Random id of this process, so sync tokens of an earlier one are recognized
+    pub sync_epoch: u64,
+    /// Bearer token required for `/admin` routes; admin routes are disabled when unset
+    pub admin_token: Option<String>,
+    /// Scheme and host absolute links such as sitemap URLs are built from, without a trailing slash
+    pub public_base_url: String,
+}
+
+impl AppState {
+    pub fn new(tmdb_client: Arc<dyn TmdbClient>) -> Self {
+        Self {
+            tmdb_client,
+            clock: Arc::new(SystemClock),
+            search_analytics: Arc::new(SearchAnalytics::new()),
+            title_analytics: Arc::new(TitleAnalytics::new()),
+            experiments: Arc::new(Experiments::default()),
+            sessions: Arc::new(SessionStore::with_random_secret(DEFAULT_SESSION_TTL)),
+            slow_log: Arc::new(SlowLog::default()),
+            negative_cache: Arc::new(NegativeCache::new(NegativeCacheTtls::default(), Arc::new(SystemClock))),
+            upstream_metrics: Arc::new(UpstreamMetrics::new()),
+            mirrors: Arc::new(Mirrors::new(Vec::new(), Arc::new(SystemClock))),
+            compression: Arc::new(UpstreamCompression::default()),
+            upstream_probe: Arc::new(UpstreamProbe::default()),
+            outage: Arc::new(OutageMonitor::new()),
+            slos: Arc::new(SloTracker::default()),
+            budget: Arc::new(RequestBudget::default()),
+            cache_policies: Arc::new(CachePolicies::default()),
+            mode: Arc::new(ModeSwitch::new()),
+            device_presets: Arc::new(DevicePresets::default()),
+            logos: Arc::new(LogoCache::new()),
+            tag_rules: Arc::new(TagRules::default()),
+            availability: Arc::new(AvailabilityCache::new()),
+            provider_lists: Arc::new(ProviderListCache::new()),
+            new_on: Arc::new(NewOnCache::new()),
+            quality_priors: Arc::new(QualityPriors::default()),
+            ranking: Arc::new(RankingStrategies::default()),
+            home_layout: Arc::new(HomeLayout::default()),
+            home_rows: Arc::new(HomeRowCache::new()),
+            home_row_metrics: Arc::new(HomeRowMetrics::new()),
+            home_diversity: Arc::new(DiversityLimits::default()),
+            metadata: Arc::new(MetadataSources::default()),
+            lists: Arc::new(CuratedLists::new()),
+            themes: Arc::new(ThemedRows::new()),
+            trailers: Arc::new(TrailerIndex::new()),
+            enrichments: Arc::new(EnrichmentCache::new()),
+            saved_searches: Arc::new(SavedSearches::default()),
+            push: Arc::new(PushNotifier::default()),
+            blocklist: Arc::new(Blocklist::default()),
+            safe_search: Arc::new(SafeSearch::default()),
+            changes: Arc::new(ChangeFeed::new()),
+            signing: Arc::new(ResponseSigner::default()),
+            client_certs: Arc::new(ClientCertPolicy::default()),
+            shadow: Arc::new(ShadowTraffic::default()),
+            min_search_query_chars: 1,
+            started: Instant::now(),
+            sync_epoch: rand::rng().next_u64(),
+            admin_token: None,
+            public_base_url: "http://localhost:8080".to_string(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_experiments(mut self, experiments: Experiments) -> Self {
+        self.experiments = Arc::new(experiments);
+        self
+    }
+
+    pub fn with_sessions(mut self, sessions: SessionStore) -> Self {
+        self.sessions = Arc::new(sessions);
+        self
+    }
+
+    /// Shares `slow_log` with the state, typically the same log a
+    /// [`TimedTmdbClient`](crate::slow_log::TimedTmdbClient) reports to
+    pub fn with_slow_log(mut self, slow_log: Arc<SlowLog>) -> Self {
+        self.slow_log = slow_log;
+        self
+    }
+
+    /// Shares `negative_cache` with the state, typically the same cache a
+    /// [`NegativeCachingTmdbClient`](crate::negative_cache::NegativeCachingTmdbClient) uses
+    pub fn with_negative_cache(mut self, negative_cache: Arc<NegativeCache>) -> Self {
+        self.negative_cache = negative_cache;
+        self
+    }
+
+    /// Shares `upstream_metrics` with the state, typically the same metrics a
+    /// [`TimedTmdbClient`](crate::slow_log::TimedTmdbClient) records to
+    pub fn with_upstream_metrics(mut self, upstream_metrics: Arc<UpstreamMetrics>) -> Self {
+        self.upstream_metrics = upstream_metrics;
+        self
+    }
+
+    /// Shares `mirrors` with the state, typically the same mirrors a
+    /// [`RealTmdbClient`](crate::tmdb_client::RealTmdbClient) fails over between
+    pub fn with_mirrors(mut self, mirrors: Arc<Mirrors>) -> Self {
+        self.mirrors = mirrors;
+        self
+    }
+
+    /// Shares `compression` with the state, typically the same one a
+    /// [`RealTmdbClient`](crate::tmdb_client::RealTmdbClient) decodes with
+    pub fn with_compression(mut self, compression: Arc<UpstreamCompression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Shares `mode` with the state, typically the same switch a
+    /// [`ModeAwareTmdbClient`](crate::mode::ModeAwareTmdbClient) consults
+    pub fn with_mode_switch(mut self, mode: Arc<ModeSwitch>) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_outage_monitor(mut self, outage: OutageMonitor) -> Self {
+        self.outage = Arc::new(outage);
+        self
+    }
+
+    pub fn with_slos(mut self, slos: SloTracker) -> Self {
+        self.slos = Arc::new(slos);
+        self
+    }
+
+    pub fn with_budget(mut self, budget: RequestBudget) -> Self {
+        self.budget = Arc::new(budget);
+        self
+    }
+
+    pub fn with_cache_policies(mut self, cache_policies: CachePolicies) -> Self {
+        self.cache_policies = Arc::new(cache_policies);
+        self
+    }
+
+    pub fn with_device_presets(mut self, device_presets: DevicePresets) -> Self {
+        self.device_presets = Arc::new(device_presets);
+        self
+    }
+
+    pub fn with_tag_rules(mut self, tag_rules: TagRules) -> Self {
+        self.tag_rules = Arc::new(tag_rules);
+        self
+    }
+
+    pub fn with_quality_priors(mut self, quality_priors: QualityPriors) -> Self {
+        self.quality_priors = Arc::new(quality_priors);
+        self
+    }
+
+    pub fn with_ranking_strategies(mut self, ranking: RankingStrategies) -> Self {
+        self.ranking = Arc::new(ranking);
+        self
+    }
+
+    /// Replaces the home layout; cached rows from an earlier layout are dropped
+    pub fn with_home_layout(mut self, layout: HomeLayout) -> Self {
+        self.home_layout = Arc::new(layout);
+        self.home_rows = Arc::new(HomeRowCache::new());
+        self
+    }
+
+    pub fn with_home_diversity(mut self, limits: DiversityLimits) -> Self {
+        self.home_diversity = Arc::new(limits);
+        self
+    }
+
+    pub fn with_metadata_sources(mut self, sources: MetadataSources) -> Self {
+        self.metadata = Arc::new(sources);
+        self
+    }
+
+    pub fn with_blocklist(mut self, blocklist: Blocklist) -> Self {
+        self.blocklist = Arc::new(blocklist);
+        self
+    }
+
+    pub fn with_themes(mut self, themes: ThemedRows) -> Self {
+        self.themes = Arc::new(themes);
+        self
+    }
+
+    pub fn with_push(mut self, push: PushNotifier) -> Self {
+        self.push = Arc::new(push);
+        self
+    }
+
+    pub fn with_response_signer(mut self, signer: ResponseSigner) -> Self {
+        self.signing = Arc::new(signer);
+        self
+    }
+
+    pub fn with_client_cert_policy(mut self, policy: ClientCertPolicy) -> Self {
+        self.client_certs = Arc::new(policy);
+        self
+    }
+
+    pub fn with_shadow_traffic(mut self, shadow: ShadowTraffic) -> Self {
+        self.shadow = Arc::new(shadow);
+        self
+    }
+
+    pub fn with_adult_content_policy(mut self, policy: AdultContentPolicy) -> Self {
+        self.safe_search = Arc::new(SafeSearch::new(policy));
+        self
+    }
+
+    pub fn with_min_search_query_chars(mut self, chars: usize) -> Self {
+        self.min_search_query_chars = chars;
+        self
+    }
+
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    pub fn with_public_base_url(mut self, url: impl Into<String>) -> Self {
+        self.public_base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Services handlers go through instead of the TMDB client and stores
+    pub fn catalog(&self) -> TmdbCatalog {
+        TmdbCatalog::new(self.clone())
+    }
+
+    pub fn search(&self) -> TmdbSearch {
+        TmdbSearch::new(self.clone())
+    }
+
+    pub fn users(&self) -> SessionUsers {
+        SessionUsers::new(self.clone())
+    }
+}
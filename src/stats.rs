@@ -0,0 +1,58 @@
+// src/stats.rs
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::models::utc_date;
+use crate::startup::BuildInfo;
+use crate::state::AppState;
+use crate::upstream_metrics::Operation;
+
+/// Path of the public stats, also served during maintenance
+pub const PUBLIC_PATH: &str = "/api/stats/public";
+
+/// Aggregates integrators may show, e.g. "data as of": nothing here names a
+/// client, a title or a setting
+#[derive(Clone, Debug, Serialize)]
+pub struct PublicStats {
+    pub api_version: &'static str,
+    /// Unix timestamp, in seconds
+    pub started_at: u64,
+    pub uptime_seconds: u64,
+    /// When trending or discover titles were last loaded from TMDB, as a Unix
+    /// timestamp; `None` until the first load
+    pub catalog_snapshot_at: Option<u64>,
+    /// UTC date of `catalog_snapshot_at`
+    pub catalog_snapshot_date: Option<NaiveDate>,
+    /// Distinct titles with availability, logos or enrichments in memory
+    pub cached_titles: usize,
+}
+
+impl PublicStats {
+    pub fn collect(state: &AppState) -> Self {
+        let now = state.clock.now();
+        let uptime = state.started.elapsed();
+        let snapshot = [Operation::Trending, Operation::Discover]
+            .into_iter()
+            .filter_map(|operation| state.upstream_metrics.last_success(operation))
+            .max();
+
+        let mut titles = HashSet::new();
+        titles.extend(state.availability.titles(now));
+        titles.extend(state.logos.titles(now));
+        titles.extend(state.enrichments.titles(now));
+
+        Self {
+            api_version: BuildInfo::current().version,
+            started_at: unix_seconds(now.checked_sub(uptime).unwrap_or(UNIX_EPOCH)),
+            uptime_seconds: uptime.as_secs(),
+            catalog_snapshot_at: snapshot.map(unix_seconds),
+            catalog_snapshot_date: snapshot.map(utc_date),
+            cached_titles: titles.len(),
+        }
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
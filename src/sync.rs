@@ -0,0 +1,105 @@
+// src/sync.rs
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use crate::lists::CuratedList;
+use crate::saved_searches::SavedSearch;
+
+/// What changed in one synced collection
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Delta<T, K> {
+    /// `upserted` is the whole collection and replaces the client's copy,
+    /// e.g. on a first sync or when the changes since the token are gone
+    pub full: bool,
+    /// Entries added or changed, in full
+    pub upserted: Vec<T>,
+    /// Keys of entries removed
+    pub deleted: Vec<K>,
+}
+
+impl<T, K> Delta<T, K> {
+    pub fn full(entries: Vec<T>) -> Self {
+        Self { full: true, upserted: entries, deleted: Vec::new() }
+    }
+}
+
+/// Where a client's copy of one collection is up to, from `?since=`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Since {
+    /// A revision from a sync token; later revisions are new to the client
+    Revision(u64),
+    /// A Unix timestamp in seconds; changes made at or after it are sent
+    Time(u64),
+}
+
+impl Since {
+    /// Whether this is a revision the collection hasn't reached, e.g. from a
+    /// token of an earlier process that happened to share the epoch
+    pub fn is_ahead_of(self, current: u64) -> bool {
+        matches!(self, Since::Revision(revision) if revision > current)
+    }
+}
+
+/// When an entry of a synced collection changed, both as a revision and as
+/// a Unix timestamp in seconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stamp {
+    pub revision: u64,
+    pub at: u64,
+}
+
+impl Stamp {
+    /// Whether the change is new to a client whose copy is at `since`
+    pub fn after(self, since: Since) -> bool {
+        match since {
+            Since::Revision(revision) => self.revision > revision,
+            Since::Time(at) => self.at >= at,
+        }
+    }
+}
+
+/// Body of `GET /api/sync`
+#[derive(Clone, Debug, Serialize)]
+pub struct SyncResponse {
+    /// Pass as `?since=` on the next sync; unlike a timestamp it can't miss
+    /// changes made within the same second
+    pub sync_token: String,
+    /// Curated lists, keyed by slug
+    pub lists: Delta<CuratedList, String>,
+    /// The session's saved searches, keyed by id
+    pub saved_searches: Delta<SavedSearch, u64>,
+}
+
+/// Where a client's copy is up to: the revisions of curated lists and saved
+/// searches it has seen. Revisions only grow while the process runs; the
+/// epoch tells tokens of an earlier process apart, since the stores are in
+/// memory and start over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyncToken {
+    pub epoch: u64,
+    pub lists: u64,
+    pub searches: u64,
+}
+
+impl fmt::Display for SyncToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}.{}.{}", self.epoch, self.lists, self.searches)
+    }
+}
+
+impl FromStr for SyncToken {
+    type Err = String;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid sync token '{}'", token);
+        let mut parts = token.trim().split('.');
+        let (Some(epoch), Some(lists), Some(searches), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        Ok(SyncToken {
+            epoch: u64::from_str_radix(epoch, 16).map_err(|_| invalid())?,
+            lists: lists.parse().map_err(|_| invalid())?,
+            searches: searches.parse().map_err(|_| invalid())?,
+        })
+    }
+}
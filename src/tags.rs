@@ -0,0 +1,95 @@
+// src/tags.rs
+use std::collections::HashMap;
+use crate::models::Keyword;
+
+/// TMDB keywords shown as tags, with the label used for the badge
+const DEFAULT_TAGS: &[(&str, &str)] = &[
+    ("based on novel or book", "based on a book"),
+    ("based on comic", "based on a comic"),
+    ("based on true story", "based on a true story"),
+    ("biography", "biopic"),
+    ("post-apocalyptic future", "post-apocalyptic"),
+    ("dystopia", "dystopian"),
+    ("time travel", "time travel"),
+    ("remake", "remake"),
+    ("sequel", "sequel"),
+];
+
+/// TMDB keywords that warrant a content warning, with the warning shown
+const DEFAULT_CONTENT_WARNINGS: &[(&str, &str)] = &[
+    ("suicide", "suicide"),
+    ("self-harm", "self-harm"),
+    ("rape", "sexual violence"),
+    ("sexual abuse", "sexual violence"),
+    ("drug addiction", "drug use"),
+    ("animal cruelty", "animal harm"),
+    ("gore", "gore"),
+];
+
+/// Maps TMDB keywords to display tags and content warnings for UI badges.
+///
+/// Keywords are matched case-insensitively; keywords without a mapping are
+/// ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagRules {
+    tags: HashMap<String, String>,
+    content_warnings: HashMap<String, String>,
+}
+
+impl TagRules {
+    /// Parses content warnings such as `suicide=suicide,rape=sexual violence`,
+    /// replacing the built-in list; an empty list disables content warnings
+    pub fn parse_content_warnings(spec: &str) -> Result<Self, String> {
+        let mut content_warnings = HashMap::new();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (keyword, warning) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected keyword=warning, got '{}'", entry))?;
+            let (keyword, warning) = (keyword.trim(), warning.trim());
+            if keyword.is_empty() || warning.is_empty() {
+                return Err(format!("expected keyword=warning, got '{}'", entry));
+            }
+            content_warnings.insert(keyword.to_lowercase(), warning.to_string());
+        }
+
+        Ok(Self { content_warnings, ..Self::default() })
+    }
+
+    /// Tags for `keywords`, in keyword order and without duplicates
+    pub fn tags(&self, keywords: &[Keyword]) -> Vec<String> {
+        labels(&self.tags, keywords)
+    }
+
+    /// Content warnings for `keywords`, in keyword order and without duplicates
+    pub fn content_warnings(&self, keywords: &[Keyword]) -> Vec<String> {
+        labels(&self.content_warnings, keywords)
+    }
+}
+
+impl Default for TagRules {
+    fn default() -> Self {
+        let table = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(keyword, label)| (keyword.to_string(), label.to_string()))
+                .collect()
+        };
+
+        Self {
+            tags: table(DEFAULT_TAGS),
+            content_warnings: table(DEFAULT_CONTENT_WARNINGS),
+        }
+    }
+}
+
+fn labels(table: &HashMap<String, String>, keywords: &[Keyword]) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for keyword in keywords {
+        if let Some(label) = table.get(&keyword.name.trim().to_lowercase())
+            && !labels.contains(label)
+        {
+            labels.push(label.clone());
+        }
+    }
+    labels
+}
@@ -0,0 +1,68 @@
+// src/taste.rs
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::models::Genre;
+
+/// How strongly a session leans towards one genre
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenreAffinity {
+    pub id: i64,
+    pub name: String,
+    /// Share of the session's genre signal, between 0 and 1
+    pub weight: f64,
+}
+
+/// A session's genre affinity, as served by `/api/me/taste`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TasteProfile {
+    /// Title views the profile is built from
+    pub views: u32,
+    /// Highest affinity first; weights add up to 1
+    pub genres: Vec<GenreAffinity>,
+}
+
+impl TasteProfile {
+    /// Sum of the affinities of `genre_ids`, 0 for genres the session never viewed
+    pub fn affinity(&self, genre_ids: &[i64]) -> f64 {
+        self.genres
+            .iter()
+            .filter(|genre| genre_ids.contains(&genre.id))
+            .map(|genre| genre.weight)
+            .sum()
+    }
+}
+
+/// Running genre counts for one session, updated on every title view.
+#[derive(Clone, Debug, Default)]
+pub struct GenreCounts {
+    views: u32,
+    counts: HashMap<i64, (String, u32)>,
+}
+
+impl GenreCounts {
+    /// Counts one view of a title with `genres`
+    pub fn record(&mut self, genres: &[Genre]) {
+        self.views += 1;
+        for genre in genres {
+            let entry = self.counts.entry(genre.id).or_insert_with(|| (genre.name.clone(), 0));
+            entry.1 += 1;
+        }
+    }
+
+    /// Normalised profile; ties are ordered by genre id so responses are stable
+    pub fn profile(&self) -> TasteProfile {
+        let total: u32 = self.counts.values().map(|(_, count)| count).sum();
+        let mut genres: Vec<GenreAffinity> = self
+            .counts
+            .iter()
+            .map(|(&id, (name, count))| GenreAffinity {
+                id,
+                name: name.clone(),
+                weight: (*count as f64 / total as f64 * 1000.0).round() / 1000.0,
+            })
+            .collect();
+        genres.sort_by(|a, b| b.weight.total_cmp(&a.weight).then(a.id.cmp(&b.id)));
+
+        TasteProfile { views: self.views, genres }
+    }
+}
@@ -0,0 +1,204 @@
+// src/themes.rs
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::lists::validate_slug;
+use crate::models::{DiscoverFilter, DiscoverSort, MediaType};
+
+/// Upper bound on ids per rule, keeping discover URLs short
+pub const MAX_RULE_IDS: usize = 20;
+
+/// What titles a themed row shows, compiled into a TMDB discover query.
+///
+/// Each id list matches titles linked to any of its ids, and the lists
+/// narrow each other: `{"keywords": [3335], "genres": [27]}` is horror
+/// titles tagged "halloween".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeRules {
+    pub media_type: MediaType,
+    /// TMDB keyword ids
+    #[serde(default)]
+    pub keywords: Vec<i64>,
+    /// TMDB genre ids
+    #[serde(default)]
+    pub genres: Vec<i64>,
+    /// Production company ids
+    #[serde(default)]
+    pub companies: Vec<i64>,
+    /// First release or air day included
+    #[serde(default)]
+    pub released_from: Option<NaiveDate>,
+    /// Last release or air day included
+    #[serde(default)]
+    pub released_until: Option<NaiveDate>,
+    #[serde(default)]
+    pub sort: DiscoverSort,
+}
+
+impl ThemeRules {
+    /// Checks that the rules narrow the catalog at all and that every id
+    /// and date range is usable
+    pub fn validate(&self) -> Result<(), String> {
+        for (rule, ids) in [("keywords", &self.keywords), ("genres", &self.genres), ("companies", &self.companies)] {
+            if ids.len() > MAX_RULE_IDS {
+                return Err(format!("{} may list at most {} ids", rule, MAX_RULE_IDS));
+            }
+            if let Some(id) = ids.iter().find(|id| **id <= 0) {
+                return Err(format!("invalid {} id {}", rule, id));
+            }
+        }
+        if let (Some(from), Some(until)) = (self.released_from, self.released_until)
+            && from > until
+        {
+            return Err("released_from must not be after released_until".to_string());
+        }
+        let narrows = !self.keywords.is_empty()
+            || !self.genres.is_empty()
+            || !self.companies.is_empty()
+            || self.released_from.is_some()
+            || self.released_until.is_some();
+        if !narrows {
+            return Err("rules need at least one keyword, genre, company or release date".to_string());
+        }
+        Ok(())
+    }
+
+    /// The discover query the rules compile into
+    pub fn filter(&self) -> DiscoverFilter {
+        DiscoverFilter {
+            keywords: self.keywords.clone(),
+            genres: self.genres.clone(),
+            companies: self.companies.clone(),
+            released_from: self.released_from,
+            released_by: self.released_until,
+            sort: self.sort,
+            ..DiscoverFilter::new(self.media_type)
+        }
+    }
+}
+
+/// Body of `PUT /admin/themes/{slug}`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThemeInput {
+    pub title: String,
+    #[serde(flatten)]
+    pub rules: ThemeRules,
+    /// First day the row is shown, in UTC; shown right away when unset
+    #[serde(default)]
+    pub live_from: Option<NaiveDate>,
+    /// Last day the row is shown, in UTC; shown until deleted when unset
+    #[serde(default)]
+    pub live_until: Option<NaiveDate>,
+}
+
+/// A themed row such as "Halloween picks", as stored
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThemedRow {
+    pub slug: String,
+    pub title: String,
+    #[serde(flatten)]
+    pub rules: ThemeRules,
+    pub live_from: Option<NaiveDate>,
+    pub live_until: Option<NaiveDate>,
+    /// Unix timestamp of the last change, in seconds
+    pub updated_at: u64,
+}
+
+impl ThemedRow {
+    /// Whether the row is shown on `today`
+    pub fn is_live(&self, today: NaiveDate) -> bool {
+        self.live_from.is_none_or(|from| from <= today) && self.live_until.is_none_or(|until| today <= until)
+    }
+}
+
+/// One entry of `THEMED_ROWS`
+#[derive(Deserialize)]
+struct ThemeConfig {
+    slug: String,
+    #[serde(flatten)]
+    input: ThemeInput,
+}
+
+/// Rule-driven rows, so marketing can launch seasonal rows without a
+/// deploy. Loaded from config at startup and edited through the admin API;
+/// edits are kept in memory until the next restart.
+#[derive(Default)]
+pub struct ThemedRows {
+    rows: Mutex<BTreeMap<String, ThemedRow>>,
+}
+
+impl ThemedRows {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses and checks rows such as
+    /// `[{"slug": "halloween", "title": "Halloween picks", "media_type": "movie", "keywords": [3335]}]`
+    pub fn from_json(json: &str, now: SystemTime) -> Result<Self, String> {
+        let configs: Vec<ThemeConfig> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let themes = Self::new();
+        for config in configs {
+            let (_, created) = themes
+                .put(&config.slug, config.input, now)
+                .map_err(|e| format!("theme '{}': {}", config.slug, e))?;
+            if !created {
+                return Err(format!("duplicate theme slug '{}'", config.slug));
+            }
+        }
+        Ok(themes)
+    }
+
+    /// All rows, live or not, ordered by slug
+    pub fn all(&self) -> Vec<ThemedRow> {
+        self.rows.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn get(&self, slug: &str) -> Option<ThemedRow> {
+        self.rows.lock().unwrap().get(slug).cloned()
+    }
+
+    /// Rows shown on `today`, ordered by slug
+    pub fn live(&self, today: NaiveDate) -> Vec<ThemedRow> {
+        self.rows.lock().unwrap().values().filter(|row| row.is_live(today)).cloned().collect()
+    }
+
+    /// Discover query of the row at `slug` if it is shown on `today`
+    pub fn live_filter(&self, slug: &str, today: NaiveDate) -> Option<DiscoverFilter> {
+        self.get(slug).filter(|row| row.is_live(today)).map(|row| row.rules.filter())
+    }
+
+    /// Creates or replaces the row at `slug`.
+    ///
+    /// Returns the stored row and whether it is new.
+    pub fn put(&self, slug: &str, input: ThemeInput, now: SystemTime) -> Result<(ThemedRow, bool), String> {
+        validate_slug(slug)?;
+        let title = input.title.trim();
+        if title.is_empty() {
+            return Err("title must not be empty".to_string());
+        }
+        input.rules.validate()?;
+        if let (Some(from), Some(until)) = (input.live_from, input.live_until)
+            && from > until
+        {
+            return Err("live_from must not be after live_until".to_string());
+        }
+
+        let row = ThemedRow {
+            slug: slug.to_string(),
+            title: title.to_string(),
+            rules: input.rules,
+            live_from: input.live_from,
+            live_until: input.live_until,
+            updated_at: now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        };
+        let created = self.rows.lock().unwrap().insert(slug.to_string(), row.clone()).is_none();
+        Ok((row, created))
+    }
+
+    /// Removes the row at `slug`, returning whether it existed
+    pub fn delete(&self, slug: &str) -> bool {
+        self.rows.lock().unwrap().remove(slug).is_some()
+    }
+}
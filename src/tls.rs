@@ -0,0 +1,205 @@
+// src/tls.rs
+use axum::{
+    extract::{connect_info::Connected, ConnectInfo, Request},
+    middleware::Next,
+    response::Response,
+    serve::{IncomingStream, Listener},
+};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, CertificateRevocationListDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use crate::mtls::ClientIdentity;
+
+/// How often certificates, the CA bundle and revocation lists are read again
+/// when `TLS_RELOAD_INTERVAL` isn't set
+pub const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Connections that haven't finished the handshake by then are dropped
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Finished handshakes waiting for the server to pick them up
+const ACCEPT_QUEUE: usize = 128;
+
+/// Where the listener's certificate and the client certificate trust
+/// settings are read from, all PEM files
+#[derive(Clone, Debug)]
+pub struct TlsSettings {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// CAs client certificates must chain to; without it no client
+    /// certificates are asked for
+    pub client_ca_path: Option<PathBuf>,
+    /// Revocation lists checked against client certificates
+    pub crl_paths: Vec<PathBuf>,
+}
+
+impl TlsSettings {
+    /// Reads the files and builds a server config. Client certificates are
+    /// optional at the TLS level so public routes keep working without one;
+    /// internal routes require them through [`crate::mtls::ClientCertPolicy`].
+    pub fn load(&self) -> Result<Arc<ServerConfig>, String> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let certs = CertificateDer::pem_file_iter(&self.cert_path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("reading {}: {}", self.cert_path.display(), e))?;
+        let key = PrivateKeyDer::from_pem_file(&self.key_path).map_err(|e| format!("reading {}: {}", self.key_path.display(), e))?;
+
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?;
+        let builder = match &self.client_ca_path {
+            Some(ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for ca in CertificateDer::pem_file_iter(ca_path).map_err(|e| format!("reading {}: {}", ca_path.display(), e))? {
+                    let ca = ca.map_err(|e| format!("reading {}: {}", ca_path.display(), e))?;
+                    roots.add(ca).map_err(|e| format!("CA in {}: {}", ca_path.display(), e))?;
+                }
+                let mut crls = Vec::new();
+                for crl_path in &self.crl_paths {
+                    let crl = CertificateRevocationListDer::from_pem_file(crl_path)
+                        .map_err(|e| format!("reading {}: {}", crl_path.display(), e))?;
+                    crls.push(crl);
+                }
+                let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                    .with_crls(crls)
+                    .only_check_end_entity_revocation()
+                    .allow_unauthenticated()
+                    .build()
+                    .map_err(|e| e.to_string())?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder.with_single_cert(certs, key).map_err(|e| e.to_string())?;
+        // The server speaks HTTP/1.1 only
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+/// The server config new connections are accepted with, swapped on reload
+#[derive(Clone)]
+pub struct TlsReloader {
+    settings: TlsSettings,
+    current: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl TlsReloader {
+    /// Reads the files again. On failure the previous config stays in use,
+    /// so a half-written certificate never takes the listener down.
+    pub fn reload(&self) -> Result<(), String> {
+        let config = self.settings.load()?;
+        *self.current.write().unwrap() = config;
+        Ok(())
+    }
+}
+
+/// A TCP listener serving TLS. Handshakes run in their own tasks, so a
+/// slow client never holds up others.
+pub struct TlsListener {
+    local_addr: SocketAddr,
+    handshaken: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, settings: TlsSettings) -> Result<(Self, TlsReloader), String> {
+        let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+        let reloader = TlsReloader { current: Arc::new(RwLock::new(settings.load()?)), settings };
+        let (sender, handshaken) = mpsc::channel(ACCEPT_QUEUE);
+
+        let current = reloader.current.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, remote_addr) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(error) => {
+                        // Usually too many open files; give connections time to close
+                        tracing::error!(%error, "accept failed");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                let acceptor = TlsAcceptor::from(current.read().unwrap().clone());
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = sender.send((stream, remote_addr)).await;
+                        }
+                        Ok(Err(error)) => tracing::debug!(%error, %remote_addr, "TLS handshake failed"),
+                        Err(_) => tracing::debug!(%remote_addr, "TLS handshake timed out"),
+                    }
+                });
+            }
+        });
+
+        Ok((Self { local_addr, handshaken }, reloader))
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.handshaken.recv().await {
+            Some(accepted) => accepted,
+            // The accept task never stops, but if it did no connection would come again
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Connection info of a TLS connection: the client's address and, when it
+/// presented a verified certificate, who it belongs to
+#[derive(Clone, Debug)]
+pub struct TlsPeer {
+    pub addr: SocketAddr,
+    pub identity: Option<ClientIdentity>,
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for TlsPeer {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        let identity = stream
+            .io()
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|chain| chain.first())
+            .and_then(|certificate| match ClientIdentity::from_der(certificate) {
+                Ok(identity) => Some(identity),
+                Err(error) => {
+                    tracing::warn!(%error, "unreadable client certificate");
+                    None
+                }
+            });
+        TlsPeer { addr: *stream.remote_addr(), identity }
+    }
+}
+
+/// Hands the peer of a TLS connection to the rest of the app the way plain
+/// connections do: its address as `ConnectInfo<SocketAddr>`, for per-client
+/// budgets, and its certificate's [`ClientIdentity`]
+pub async fn peer_middleware(mut request: Request, next: Next) -> Response {
+    if let Some(ConnectInfo(peer)) = request.extensions().get::<ConnectInfo<TlsPeer>>().cloned() {
+        request.extensions_mut().insert(ConnectInfo(peer.addr));
+        if let Some(identity) = peer.identity {
+            request.extensions_mut().insert(identity);
+        }
+    }
+    next.run(request).await
+}
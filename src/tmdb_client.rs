@@ -1,6 +1,17 @@
+use crate::clock::SystemClock;
+use crate::compression::UpstreamCompression;
 use crate::error::TmdbError;
-use crate::models::{TmdbResponse, VideoResponse};
+use crate::mirrors::Mirrors;
+use crate::models::{AlternativeTitles, ChangesResponse, Collection, DiscoverFilter, ImagesResponse, Network, ProviderList, Season, TmdbList, WatchProvidersResponse, MediaType, TitleDetails, TmdbResponse, VideoResponse};
 use async_trait::async_trait;
+use chrono::NaiveDate;
+use reqwest::header;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long one TMDB request may take before it fails as a network error
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Trait defining the contract for TMDB API operations.
 ///
@@ -21,6 +32,26 @@ pub trait TmdbClient: Send + Sync {
     /// Returns `TmdbError` if the request fails or response cannot be parsed
     async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError>;
 
+    /// Fetches the highest rated movies or TV shows of all time
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether to list movies or TV shows
+    /// * `page` - Page number (1-indexed)
+    ///
+    /// # Errors
+    /// Returns `TmdbError` if the request fails or response cannot be parsed
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError>;
+
+    /// Fetches the movies or TV shows most popular right now
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether to list movies or TV shows
+    /// * `page` - Page number (1-indexed)
+    ///
+    /// # Errors
+    /// Returns `TmdbError` if the request fails or response cannot be parsed
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError>;
+
     /// Searches for content (movies/TV shows) by query string
     ///
     /// # Arguments
@@ -31,6 +62,13 @@ pub trait TmdbClient: Send + Sync {
     /// Returns `TmdbError` if the request fails or response cannot be parsed
     async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError>;
 
+    /// Like `search_content`, but lets TMDB return titles flagged as adult.
+    /// Only called where the adult content policy allows it; clients without
+    /// adult titles keep the safe search.
+    async fn search_content_including_adult(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.search_content(query, page).await
+    }
+
     /// Fetches videos (trailers, teasers, etc.) for a specific movie
     ///
     /// # Arguments
@@ -39,76 +77,404 @@ pub trait TmdbClient: Send + Sync {
     /// # Errors
     /// Returns `TmdbError::NotFound` if movie doesn't exist
     /// Returns other `TmdbError` variants for request/parse failures
-    async fn get_movie_videos(&self, movie_id: i32) -> Result<VideoResponse, TmdbError>;
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError>;
+
+    /// Fetches full details for a movie or TV show
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether `id` refers to a movie or a TV show
+    /// * `id` - TMDB movie or TV show ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the title doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError>;
+
+    /// Fetches posters, backdrops and logos for a movie or TV show, in all languages
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether `id` refers to a movie or a TV show
+    /// * `id` - TMDB movie or TV show ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the title doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError>;
+
+    /// Fetches the per-country alternative titles of a movie or TV show
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether `id` refers to a movie or a TV show
+    /// * `id` - TMDB movie or TV show ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the title doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError>;
+
+    /// Fetches where a movie or TV show can be watched, for every region at once
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether `id` refers to a movie or a TV show
+    /// * `id` - TMDB movie or TV show ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the title doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError>;
+
+    /// Browses popular titles matching `filter`
+    ///
+    /// # Arguments
+    /// * `filter` - Media type and provider filters
+    /// * `page` - Page number for pagination
+    ///
+    /// # Errors
+    /// Returns `TmdbError` variants for request/parse failures
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError>;
+
+    /// Lists the watch providers TMDB knows for movies in a region
+    ///
+    /// # Arguments
+    /// * `region` - ISO 3166-1 country code such as `US`
+    ///
+    /// # Errors
+    /// Returns `TmdbError` variants for request/parse failures
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError>;
+
+    /// Fetches a TV network such as HBO
+    ///
+    /// # Arguments
+    /// * `id` - TMDB network ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the network doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError>;
+
+    /// Fetches a public list made by a TMDB user
+    ///
+    /// # Arguments
+    /// * `list_id` - TMDB list ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the list doesn't exist or is private
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError>;
+
+    /// Fetches a movie collection, such as a franchise, with its movies
+    ///
+    /// # Arguments
+    /// * `id` - TMDB collection ID
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the collection doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError>;
+
+    /// Fetches one season of a TV show with its episodes
+    ///
+    /// # Arguments
+    /// * `tv_id` - TMDB TV show ID
+    /// * `season_number` - Season number, `0` for specials
+    ///
+    /// # Errors
+    /// Returns `TmdbError::NotFound` if the show or season doesn't exist
+    /// Returns other `TmdbError` variants for request/parse failures
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError>;
+
+    /// Lists titles of one media type whose data changed on TMDB since
+    /// `start_date`, up to today
+    ///
+    /// # Arguments
+    /// * `media_type` - Whether to list changed movies or TV shows
+    /// * `start_date` - First day of the window, at most 14 days ago
+    /// * `page` - Page number (1-indexed)
+    ///
+    /// # Errors
+    /// Returns `TmdbError` variants for request/parse failures
+    async fn get_changes(&self, media_type: MediaType, start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError>;
+}
+
+/// Lets a shared client be wrapped by decorators such as `TimedTmdbClient`
+#[async_trait]
+impl<T: TmdbClient + ?Sized> TmdbClient for Arc<T> {
+    async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
+        (**self).get_trending(page).await
+    }
+
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        (**self).get_top_rated(media_type, page).await
+    }
+
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        (**self).get_popular(media_type, page).await
+    }
+
+    async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        (**self).search_content(query, page).await
+    }
+
+    async fn search_content_including_adult(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        (**self).search_content_including_adult(query, page).await
+    }
+
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        (**self).get_movie_videos(movie_id).await
+    }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        (**self).get_title_details(media_type, id).await
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        (**self).get_images(media_type, id).await
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        (**self).get_alternative_titles(media_type, id).await
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        (**self).get_watch_providers(media_type, id).await
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        (**self).discover(filter, page).await
+    }
+
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        (**self).get_provider_list(region).await
+    }
+
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        (**self).get_network(id).await
+    }
+
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        (**self).get_list(list_id).await
+    }
+
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError> {
+        (**self).get_collection(id).await
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        (**self).get_tv_season(tv_id, season_number).await
+    }
+
+    async fn get_changes(&self, media_type: MediaType, start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        (**self).get_changes(media_type, start_date, page).await
+    }
 }
 
 pub struct RealTmdbClient {
     api_key: String,
     client: reqwest::Client,
+    mirrors: Arc<Mirrors>,
+    compression: Arc<UpstreamCompression>,
 }
 
 impl RealTmdbClient {
     pub fn new(api_key: String) -> Self {
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            client: http_client(DEFAULT_TIMEOUT),
+            mirrors: Arc::new(Mirrors::new(Vec::new(), Arc::new(SystemClock))),
+            compression: Arc::new(UpstreamCompression::default()),
+        }
+    }
+
+    /// Fails requests that take longer than `timeout`, which then fail over
+    /// to the next mirror like any other network error
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = http_client(timeout);
+        self
+    }
+
+    /// Sends requests to `mirrors` instead of only the official TMDB endpoint
+    pub fn with_mirrors(mut self, mirrors: Arc<Mirrors>) -> Self {
+        self.mirrors = mirrors;
+        self
+    }
+
+    /// Shares the compression mode and byte counters with the admin API
+    pub fn with_compression(mut self, compression: Arc<UpstreamCompression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// GETs `path` from the first reachable mirror and parses the JSON body.
+    ///
+    /// Only failures to get a response fail over to the next mirror; HTTP
+    /// error statuses are returned as they are.
+    async fn get<T: DeserializeOwned>(&self, path: &str, params: &[(&str, &str)]) -> Result<T, TmdbError> {
+        let mut last_error = TmdbError::NetworkError("no TMDB base URL configured".to_string());
+
+        for index in self.mirrors.attempt_order() {
+            let url = format!("{}{}", self.mirrors.base_url(index), path);
+            let sent = self
+                .client
+                .get(&url)
+                .query(&[("api_key", self.api_key.as_str())])
+                .query(params)
+                .header(header::ACCEPT_ENCODING, self.compression.mode().accept_encoding())
+                .send()
+                .await;
+
+            let response = match sent {
+                Ok(response) => {
+                    self.mirrors.record_success(index);
+                    response
+                }
+                Err(error) => {
+                    self.mirrors.record_failure(index);
+                    last_error = error.into();
+                    continue;
+                }
+            };
+
+            // Decoded here rather than by reqwest so the bytes on the wire can
+            // be counted
+            let status = response.status();
+            let content_encoding = response
+                .headers()
+                .get(header::CONTENT_ENCODING)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let body = response.bytes().await;
+
+            if !status.is_success() {
+                let body = body
+                    .ok()
+                    .and_then(|body| self.compression.decode(content_encoding.as_deref(), &body).ok())
+                    .map(|body| String::from_utf8_lossy(&body).into_owned())
+                    .unwrap_or_default();
+                return Err(TmdbError::from_status(status, body));
+            }
+
+            // Parsed here rather than by reqwest so malformed bodies are parse
+            // errors, not network errors
+            let body = self
+                .compression
+                .decode(content_encoding.as_deref(), &body?)
+                .map_err(TmdbError::ParseError)?;
+            return Ok(serde_json::from_slice(&body)?);
         }
+
+        Err(last_error)
+    }
+
+    async fn search(&self, query: &str, page: i32, include_adult: bool) -> Result<TmdbResponse, TmdbError> {
+        let params = [("query", query), ("page", &page.to_string()), ("include_adult", &include_adult.to_string())];
+        self.get("/search/multi", &params).await
     }
+
+    /// A page of one of TMDB's per-media-type lists such as `/movie/popular`
+    async fn chart(&self, chart: &str, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let path = format!("/{}/{}", media_type.as_str(), chart);
+        let mut response: TmdbResponse = self.get(&path, &[("page", &page.to_string())]).await?;
+        // Like discover items, chart items don't carry a media type
+        for item in &mut response.results {
+            item.media_type.get_or_insert_with(|| media_type.as_str().to_string());
+        }
+        Ok(response)
+    }
+}
+
+fn http_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("TMDB HTTP client should build")
 }
 
 #[async_trait]
 impl TmdbClient for RealTmdbClient {
     async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
-        let url = format!(
-            "https://api.themoviedb.org/3/trending/all/week?api_key={}&page={}",
-            self.api_key, page
-        );
-
-        let response = self.client.get(&url).send().await?;
+        self.get("/trending/all/week", &[("page", &page.to_string())]).await
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(TmdbError::from_status(status, body));
-        }
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.chart("top_rated", media_type, page).await
+    }
 
-        let data = response.json::<TmdbResponse>().await?;
-        Ok(data)
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.chart("popular", media_type, page).await
     }
 
     async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
-        let url = format!(
-            "https://api.themoviedb.org/3/search/multi?api_key={}&query={}&page={}&include_adult=false",
-            self.api_key, query, page
-        );
+        self.search(query, page, false).await
+    }
 
-        let response = self.client.get(&url).send().await?;
+    async fn search_content_including_adult(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.search(query, page, true).await
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(TmdbError::from_status(status, body));
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        self.get(&format!("/movie/{}/videos", movie_id), &[]).await
+    }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        // Alternative titles, keywords, credits and external ids ride along so details stay a single call
+        let params = [("append_to_response", "alternative_titles,keywords,credits,external_ids")];
+        self.get(&format!("/{}/{}", media_type.as_str(), id), &params).await
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        self.get(&format!("/{}/{}/images", media_type.as_str(), id), &[]).await
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        self.get(&format!("/{}/{}/alternative_titles", media_type.as_str(), id), &[]).await
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        self.get(&format!("/{}/{}/watch/providers", media_type.as_str(), id), &[]).await
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let filter_params = filter.tmdb_params();
+        let page = page.to_string();
+        let include_adult = filter.include_adult.to_string();
+        let mut params = vec![("page", page.as_str()), ("include_adult", include_adult.as_str())];
+        params.extend(filter_params.iter().map(|(key, value)| (*key, value.as_str())));
+
+        let mut response: TmdbResponse = self.get(&format!("/discover/{}", filter.media_type.as_str()), &params).await?;
+        // Discover items don't carry a media type; clients rely on it like on trending items
+        for item in &mut response.results {
+            item.media_type.get_or_insert_with(|| filter.media_type.as_str().to_string());
         }
+        Ok(response)
+    }
 
-        let data = response.json::<TmdbResponse>().await?;
-        Ok(data)
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        self.get("/watch/providers/movie", &[("watch_region", region)]).await
     }
 
-    async fn get_movie_videos(&self, movie_id: i32) -> Result<VideoResponse, TmdbError> {
-        let url = format!(
-            "https://api.themoviedb.org/3/movie/{}/videos?api_key={}",
-            movie_id, self.api_key
-        );
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        self.get(&format!("/network/{}", id), &[]).await
+    }
 
-        let response = self.client.get(&url).send().await?;
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        self.get(&format!("/list/{}", list_id), &[]).await
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(TmdbError::from_status(status, body));
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError> {
+        let mut collection: Collection = self.get(&format!("/collection/{}", id), &[]).await?;
+        for part in &mut collection.parts {
+            part.media_type.get_or_insert_with(|| MediaType::Movie.as_str().to_string());
         }
+        Ok(collection)
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        self.get(&format!("/tv/{}/season/{}", tv_id, season_number), &[]).await
+    }
 
-        let data = response.json::<VideoResponse>().await?;
-        Ok(data)
+    async fn get_changes(&self, media_type: MediaType, start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        let (start_date, page) = (start_date.to_string(), page.to_string());
+        let params = [("start_date", start_date.as_str()), ("page", page.as_str())];
+        self.get(&format!("/{}/changes", media_type.as_str()), &params).await
     }
 }
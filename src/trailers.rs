@@ -0,0 +1,76 @@
+// src/trailers.rs
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::error::TmdbError;
+use crate::state::AppState;
+
+/// Video lookups a background batch runs at once, to stay well inside TMDB's rate limit
+const BATCH_CONCURRENCY: usize = 4;
+
+/// Whether movies have a trailer, filled in by background batches so list
+/// routes never wait on a video lookup per item
+#[derive(Default)]
+pub struct TrailerIndex {
+    known: Mutex<HashMap<i64, (bool, SystemTime)>>,
+    /// Movies a batch is looking up right now
+    pending: Mutex<HashSet<i64>>,
+}
+
+impl TrailerIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stored answer for `movie_id` if younger than `ttl` at `now`
+    pub fn get(&self, movie_id: i64, now: SystemTime, ttl: Duration) -> Option<bool> {
+        let known = self.known.lock().unwrap();
+        let (has_trailer, stored_at) = known.get(&movie_id)?;
+        (now.duration_since(*stored_at).unwrap_or_default() < ttl).then_some(*has_trailer)
+    }
+
+    pub fn store(&self, movie_id: i64, has_trailer: bool, now: SystemTime) {
+        self.known.lock().unwrap().insert(movie_id, (has_trailer, now));
+    }
+
+    /// Forgets the stored answer, so the next list showing the movie looks it up again
+    pub fn invalidate(&self, movie_id: i64) -> bool {
+        self.known.lock().unwrap().remove(&movie_id).is_some()
+    }
+
+    /// Marks `movie_ids` as being looked up, returning those no batch has claimed yet
+    pub fn claim(&self, movie_ids: impl IntoIterator<Item = i64>) -> Vec<i64> {
+        let mut pending = self.pending.lock().unwrap();
+        movie_ids.into_iter().filter(|id| pending.insert(*id)).collect()
+    }
+
+    pub fn release(&self, movie_id: i64) {
+        self.pending.lock().unwrap().remove(&movie_id);
+    }
+}
+
+/// Looks up the videos of `movie_ids` a few at a time and records whether
+/// each has a playable trailer. Movies TMDB doesn't know have none; other
+/// failures are left unrecorded so a later request retries them.
+pub async fn look_up(state: AppState, movie_ids: Vec<i64>) {
+    let permits = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+    let mut lookups = JoinSet::new();
+    for movie_id in movie_ids {
+        let (state, permits) = (state.clone(), permits.clone());
+        lookups.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let has_trailer = match state.tmdb_client.get_movie_videos(movie_id).await {
+                Ok(videos) => Some(videos.best_trailer().is_some()),
+                Err(TmdbError::NotFound) => Some(false),
+                Err(_) => None,
+            };
+            if let Some(has_trailer) = has_trailer {
+                state.trailers.store(movie_id, has_trailer, state.clock.now());
+            }
+            state.trailers.release(movie_id);
+        });
+    }
+    while lookups.join_next().await.is_some() {}
+}
@@ -0,0 +1,138 @@
+// src/upstream_metrics.rs
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Logical TMDB operation an upstream call belongs to, used as its metrics
+/// label and trace field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Trending,
+    Search,
+    Videos,
+    Details,
+    Images,
+    AlternativeTitles,
+    WatchProviders,
+    Discover,
+    ProviderList,
+    Network,
+    List,
+    Changes,
+    Season,
+    TopRated,
+    Popular,
+    Collection,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 16] = [
+        Operation::Trending,
+        Operation::Search,
+        Operation::Videos,
+        Operation::Details,
+        Operation::Images,
+        Operation::AlternativeTitles,
+        Operation::WatchProviders,
+        Operation::Discover,
+        Operation::ProviderList,
+        Operation::Network,
+        Operation::List,
+        Operation::Changes,
+        Operation::Season,
+        Operation::TopRated,
+        Operation::Popular,
+        Operation::Collection,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Trending => "trending",
+            Operation::Search => "search",
+            Operation::Videos => "videos",
+            Operation::Details => "details",
+            Operation::Images => "images",
+            Operation::AlternativeTitles => "alternative_titles",
+            Operation::WatchProviders => "watch_providers",
+            Operation::Discover => "discover",
+            Operation::ProviderList => "provider_list",
+            Operation::Network => "network",
+            Operation::List => "list",
+            Operation::Changes => "changes",
+            Operation::Season => "season",
+            Operation::TopRated => "top_rated",
+            Operation::Popular => "popular",
+            Operation::Collection => "collection",
+        }
+    }
+}
+
+/// Upstream usage for one operation since startup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OperationStats {
+    pub operation: Operation,
+    pub calls: u64,
+    pub errors: u64,
+    pub average_latency_ms: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    calls: AtomicU64,
+    errors: AtomicU64,
+    total_latency_ms: AtomicU64,
+    /// Unix seconds of the last successful call; 0 before the first
+    last_ok_secs: AtomicU64,
+}
+
+/// Per-operation counters for TMDB calls, showing which feature consumes the quota.
+#[derive(Default)]
+pub struct UpstreamMetrics {
+    counters: [Counters; Operation::ALL.len()],
+}
+
+impl UpstreamMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one TMDB call that finished `at`
+    pub fn record(&self, operation: Operation, ok: bool, elapsed: Duration, at: SystemTime) {
+        let counters = &self.counters[operation as usize];
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+        if ok {
+            let secs = at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            counters.last_ok_secs.fetch_max(secs, Ordering::Relaxed);
+        } else {
+            counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        counters.total_latency_ms.fetch_add(elapsed.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// When a call of `operation` last succeeded
+    pub fn last_success(&self, operation: Operation) -> Option<SystemTime> {
+        match self.counters[operation as usize].last_ok_secs.load(Ordering::Relaxed) {
+            0 => None,
+            secs => Some(UNIX_EPOCH + Duration::from_secs(secs)),
+        }
+    }
+
+    /// Stats for every operation, in [`Operation::ALL`] order
+    pub fn stats(&self) -> Vec<OperationStats> {
+        Operation::ALL
+            .iter()
+            .map(|&operation| {
+                let counters = &self.counters[operation as usize];
+                let calls = counters.calls.load(Ordering::Relaxed);
+                let total_latency_ms = counters.total_latency_ms.load(Ordering::Relaxed);
+                OperationStats {
+                    operation,
+                    calls,
+                    errors: counters.errors.load(Ordering::Relaxed),
+                    average_latency_ms: total_latency_ms.checked_div(calls).unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,154 @@
+// src/versioning.rs
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{Map, Value};
+use crate::models::{MediaType, TmdbResponse};
+use crate::problem::ApiError;
+use crate::shaping::Device;
+use crate::state::AppState;
+
+/// Media type opting into the normalized v2 list format
+pub const V2_MEDIA_TYPE: &str = "application/vnd.netflix-service.v2+json";
+
+/// Vendor media types name their version between these
+const VENDOR_PREFIX: &str = "application/vnd.netflix-service.v";
+const VENDOR_SUFFIX: &str = "+json";
+
+/// Response format a client asked for in `Accept`, so clients can move to the
+/// normalized format one request at a time while the URLs stay the same.
+///
+/// Requests without a vendor media type get v1, the format list routes have
+/// always returned. Versions the service doesn't know answer `406`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    #[default]
+    V1,
+    /// Normalized list items: one `title` for movies and shows, an explicit
+    /// `media_type` and the vote fields grouped under `rating`
+    V2,
+}
+
+impl ApiVersion {
+    /// Highest version listed in `Accept` without `q=0`
+    pub fn from_accept<'a>(accept: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        let mut version = None;
+        for range in accept.into_iter().flat_map(|value| value.split(',')) {
+            let mut params = range.split(';').map(str::trim);
+            let media = params.next().unwrap_or_default().to_ascii_lowercase();
+            let Some(number) = media.strip_prefix(VENDOR_PREFIX).and_then(|rest| rest.strip_suffix(VENDOR_SUFFIX)) else {
+                continue;
+            };
+            let refused = params.any(|param| param.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0));
+            if refused {
+                continue;
+            }
+            let listed = match number {
+                "1" => ApiVersion::V1,
+                "2" => ApiVersion::V2,
+                other => return Err(format!("unknown API version '{}', expected 1 or 2", other)),
+            };
+            version = version.max(Some(listed));
+        }
+        Ok(version.unwrap_or_default())
+    }
+
+    /// Serializes a list page, `body` being its v1 JSON after device
+    /// shaping, in this version. Items TMDB didn't label get `media_type`.
+    pub fn list_response(self, body: Value, media_type: MediaType) -> Response {
+        match self {
+            ApiVersion::V1 => Json(body).into_response(),
+            ApiVersion::V2 => {
+                let mut response = Json(v2_list(body, media_type)).into_response();
+                response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(V2_MEDIA_TYPE));
+                response
+            }
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let accept = parts.headers.get_all(header::ACCEPT).iter().filter_map(|value| value.to_str().ok());
+        ApiVersion::from_accept(accept).map_err(|message| ApiError::new(StatusCode::NOT_ACCEPTABLE, "not_acceptable", message))
+    }
+}
+
+/// How a list route serializes its page: the caller's device shaping, then
+/// the caller's version
+pub struct ListFormat {
+    pub device: Device,
+    pub version: ApiVersion,
+}
+
+impl ListFormat {
+    pub fn respond(&self, response: TmdbResponse, media_type: MediaType) -> Response {
+        self.version.list_response(self.device.body(response), media_type)
+    }
+}
+
+impl FromRequestParts<AppState> for ListFormat {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let device = Device::from_request_parts(parts, state).await?;
+        let version = ApiVersion::from_request_parts(parts, state).await.map_err(IntoResponse::into_response)?;
+        Ok(Self { device, version })
+    }
+}
+
+/// `{"page", "results", "total_pages"}` to `{"items", "pagination": {"page", "total_pages"}}`
+fn v2_list(body: Value, media_type: MediaType) -> Value {
+    let Value::Object(mut page) = body else {
+        return body;
+    };
+    let items: Vec<Value> = match page.remove("results") {
+        Some(Value::Array(items)) => items.into_iter().map(|item| v2_item(item, media_type)).collect(),
+        _ => Vec::new(),
+    };
+    let mut pagination = Map::new();
+    for key in ["page", "total_pages"] {
+        pagination.insert(key.to_string(), page.remove(key).unwrap_or(Value::Null));
+    }
+    let mut list = Map::new();
+    list.insert("items".to_string(), Value::Array(items));
+    list.insert("pagination".to_string(), Value::Object(pagination));
+    Value::Object(list)
+}
+
+/// Folds `name` into `title` and the vote fields into `rating`. Fields a
+/// device preset left out stay out.
+fn v2_item(item: Value, media_type: MediaType) -> Value {
+    let Value::Object(mut item) = item else {
+        return item;
+    };
+    let name = item.remove("name").filter(|name| !name.is_null());
+    match item.get("title") {
+        Some(title) if !title.is_null() => {}
+        _ => {
+            item.insert("title".to_string(), name.unwrap_or(Value::Null));
+        }
+    }
+    if item.get("media_type").is_none_or(Value::is_null) {
+        item.insert("media_type".to_string(), Value::String(media_type.as_str().to_string()));
+    }
+
+    let mut rating = Map::new();
+    for (from, to) in [
+        ("vote_average", "average"),
+        ("vote_average_rounded", "rounded"),
+        ("vote_count", "count"),
+        ("quality_score", "quality_score"),
+    ] {
+        if let Some(value) = item.remove(from) {
+            rating.insert(to.to_string(), value);
+        }
+    }
+    item.insert("rating".to_string(), Value::Object(rating));
+    Value::Object(item)
+}
@@ -0,0 +1,139 @@
+// src/viewing_order.rs
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::models::{Collection, Season, SeasonSummary};
+
+/// Most seasons fetched for one viewing order, lowest numbers first
+pub const MAX_SEASONS: usize = 50;
+
+/// How a show's episodes or a collection's movies are lined up for the
+/// where-to-start routes.
+///
+/// Only episodes and movies out by today are listed; TMDB leaves the date of
+/// unscheduled ones unset, so those count as unreleased.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewingOrder {
+    /// Order of first broadcast, with specials where they aired
+    #[default]
+    Release,
+    /// Story order: seasons and episodes by number, which TMDB keeps even for
+    /// shows broadcast out of order. Specials are left out because TMDB
+    /// doesn't place them in the story.
+    Chronological,
+}
+
+impl ViewingOrder {
+    /// Numbers of the seasons the order is built from: those aired by
+    /// `today`, lowest first, at most [`MAX_SEASONS`]
+    pub fn seasons_needed(self, seasons: &[SeasonSummary], today: NaiveDate) -> Vec<i32> {
+        let mut numbers: Vec<i32> = seasons
+            .iter()
+            .filter(|season| self == ViewingOrder::Release || season.season_number > 0)
+            .filter(|season| aired(season.air_date, today))
+            .map(|season| season.season_number)
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers.truncate(MAX_SEASONS);
+        numbers
+    }
+
+    /// Aired episodes of `seasons` in this order
+    pub fn arrange(self, seasons: &[Season], today: NaiveDate) -> Vec<ViewingEntry> {
+        let mut entries: Vec<ViewingEntry> = seasons
+            .iter()
+            .filter(|season| self == ViewingOrder::Release || season.season_number > 0)
+            .flat_map(|season| {
+                season.episodes.iter().map(|episode| ViewingEntry {
+                    season_number: season.season_number,
+                    episode_number: episode.episode_number,
+                    name: episode.name.clone(),
+                    air_date: episode.air_date,
+                })
+            })
+            .filter(|entry| aired(entry.air_date, today))
+            .collect();
+        match self {
+            // A special airing the same day as an episode comes after it
+            ViewingOrder::Release => entries.sort_by_key(|entry| {
+                (entry.air_date, entry.season_number == 0, entry.season_number, entry.episode_number)
+            }),
+            ViewingOrder::Chronological => entries.sort_by_key(|entry| (entry.season_number, entry.episode_number)),
+        }
+        entries
+    }
+}
+
+fn aired(air_date: Option<NaiveDate>, today: NaiveDate) -> bool {
+    air_date.is_some_and(|date| date <= today)
+}
+
+/// One episode of a viewing order
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ViewingEntry {
+    /// `0` for specials
+    pub season_number: i32,
+    pub episode_number: i32,
+    pub name: String,
+    pub air_date: Option<NaiveDate>,
+}
+
+/// Suggested viewing order of a TV show, as served by `/api/tv/{id}/where_to_start`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WhereToStart {
+    pub id: i64,
+    pub order: ViewingOrder,
+    /// First episode to watch; unset when nothing has aired yet
+    pub start: Option<ViewingEntry>,
+    pub episodes: Vec<ViewingEntry>,
+}
+
+impl WhereToStart {
+    pub fn new(id: i64, order: ViewingOrder, seasons: &[Season], today: NaiveDate) -> Self {
+        let episodes = order.arrange(seasons, today);
+        Self { id, order, start: episodes.first().cloned(), episodes }
+    }
+}
+
+/// One movie of a collection's viewing order
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CollectionEntry {
+    pub id: i64,
+    pub title: String,
+    pub release_date: Option<NaiveDate>,
+}
+
+/// Suggested viewing order of a movie collection, as served by
+/// `/api/collection/{id}/where_to_start`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CollectionWhereToStart {
+    pub id: i64,
+    pub name: String,
+    /// Always [`ViewingOrder::Release`], since TMDB keeps no story order for
+    /// collections; present so clients read both routes alike
+    pub order: ViewingOrder,
+    /// First movie to watch; unset when none is out yet
+    pub start: Option<CollectionEntry>,
+    pub movies: Vec<CollectionEntry>,
+}
+
+impl CollectionWhereToStart {
+    /// Released movies of `collection` in release order. TMDB keeps no story
+    /// order for collections, so that is the only order the data allows.
+    pub fn new(collection: &Collection, today: NaiveDate) -> Self {
+        let mut movies: Vec<CollectionEntry> = collection
+            .parts
+            .iter()
+            .filter(|part| aired(part.release_date, today))
+            .map(|part| CollectionEntry {
+                id: part.id,
+                title: part.title.clone().unwrap_or_default(),
+                release_date: part.release_date,
+            })
+            .collect();
+        movies.sort_by_key(|movie| (movie.release_date, movie.id));
+        let start = movies.first().cloned();
+        Self { id: collection.id, name: collection.name.clone(), order: ViewingOrder::Release, start, movies }
+    }
+}
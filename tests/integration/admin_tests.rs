@@ -0,0 +1,468 @@
+use axum::{middleware, routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{admin, analytics::{SearchReport, TitleCount}, clock::MockClock, compression::{CompressionMode, CompressionStatus}, error::TmdbError, experiments::{self, Experiments, VariantExposures}, handlers, lists::{CuratedList, CuratedListInput, ResolvedList}, models::{MediaType, TmdbList, TmdbResponse}, pages, slo::{self, Slo, SloStatus, SloTracker}, state::AppState};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+const TOKEN: &str = "secret-token";
+
+fn create_test_app_with_client(client: MockTmdbClient) -> Router {
+    let state = AppState::new(Arc::new(client)).with_admin_token(TOKEN);
+
+    Router::new()
+        .route("/api/search", get(handlers::search_content))
+        .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
+        .route("/title/{media_type}/{id}", get(pages::title_page))
+        .route("/api/lists/{slug}", get(handlers::get_curated_list))
+        .nest("/admin", admin::router(state.clone()))
+        .with_state(state)
+}
+
+fn create_test_app() -> Router {
+    create_test_app_with_client(MockTmdbClient::new())
+}
+
+#[tokio::test]
+async fn test_admin_requires_token() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let missing = server.get("/admin/analytics/searches").await;
+    let wrong = server
+        .get("/admin/analytics/searches")
+        .authorization_bearer("wrong")
+        .await;
+
+    assert_eq!(missing.status_code(), 401);
+    assert_eq!(wrong.status_code(), 401);
+}
+
+#[tokio::test]
+async fn test_admin_disabled_without_configured_token() {
+    let state = AppState::new(Arc::new(MockTmdbClient::new()));
+    let app = Router::new()
+        .nest("/admin", admin::router(state.clone()))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/admin/analytics/searches")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_search_analytics_report() {
+    let mock_client = MockTmdbClient::builder()
+        .with_search_response("zzzz", 1, Ok(TmdbResponse { page: 1, total_pages: 0, results: vec![] }))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    server.get("/api/search?query=Matrix").await;
+    server.get("/api/search?query=%20the%20matrix").await;
+    server.get("/api/search?query=the%20MATRIX").await;
+    server.get("/api/search?query=zzzz").await;
+
+    let response = server
+        .get("/admin/analytics/searches")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let report: SearchReport = response.json();
+    assert_eq!(report.total_searches, 4);
+    assert_eq!(report.zero_result_searches, 1);
+    assert_eq!(report.top_queries[0].query, "the matrix");
+    assert_eq!(report.top_queries[0].count, 2);
+    assert_eq!(report.top_zero_result_queries.len(), 1);
+    assert_eq!(report.top_zero_result_queries[0].query, "zzzz");
+}
+
+#[tokio::test]
+async fn test_search_analytics_time_range() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    server.get("/api/search?query=matrix").await;
+
+    let response = server
+        .get("/admin/analytics/searches?from=0&to=1")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    let report: SearchReport = response.json();
+    assert_eq!(report.total_searches, 0);
+}
+
+#[tokio::test]
+async fn test_top_titles_from_traffic() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    server.get("/api/movie/603/videos").await;
+    server.get("/title/movie/603").await;
+    server.get("/title/tv/1399").await;
+
+    let response = server
+        .get("/admin/analytics/titles/top?window=7d")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let top: Vec<TitleCount> = response.json();
+    assert_eq!(top.len(), 2);
+    assert_eq!((top[0].media_type, top[0].id, top[0].count), (MediaType::Movie, 603, 2));
+    assert_eq!((top[1].media_type, top[1].id, top[1].count), (MediaType::Tv, 1399, 1));
+}
+
+#[tokio::test]
+async fn test_top_titles_rejects_bad_window() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/admin/analytics/titles/top?window=week")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    assert_eq!(response.status_code(), 400);
+}
+
+fn create_test_app_with_experiments() -> Router {
+    let experiments = Experiments::from_json(r#"[{"name": "hero_row", "variants": ["control", "large"]}]"#).unwrap();
+    let state = AppState::new(Arc::new(MockTmdbClient::new()))
+        .with_admin_token(TOKEN)
+        .with_experiments(experiments);
+
+    Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .nest("/admin", admin::router(state.clone()))
+        .layer(middleware::from_fn_with_state(state.clone(), experiments::experiments_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_experiment_assignment_header() {
+    let server = TestServer::new(create_test_app_with_experiments()).unwrap();
+
+    let first = server.get("/api/trending").add_header("x-anonymous-id", "device-1").await;
+    let second = server.get("/api/trending").add_header("cookie", "theme=dark; anon_id=device-1").await;
+
+    let header = first.header("x-experiments");
+    let header = header.to_str().unwrap();
+    assert!(header == "hero_row=control" || header == "hero_row=large");
+    // The cookie identifies the same subject as the header
+    assert_eq!(second.header("x-experiments"), first.header("x-experiments"));
+}
+
+#[tokio::test]
+async fn test_no_assignment_without_subject() {
+    let server = TestServer::new(create_test_app_with_experiments()).unwrap();
+
+    let response = server.get("/api/trending").await;
+
+    assert!(response.maybe_header("x-experiments").is_none());
+}
+
+#[tokio::test]
+async fn test_experiment_exposures_report() {
+    let server = TestServer::new(create_test_app_with_experiments()).unwrap();
+
+    server.get("/api/trending").add_header("x-user-id", "user-1").await;
+    server.get("/api/trending").add_header("x-user-id", "user-1").await;
+
+    let response = server
+        .get("/admin/analytics/experiments")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    let counts: Vec<VariantExposures> = response.json();
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].experiment, "hero_row");
+    assert_eq!(counts[0].exposures, 2);
+}
+
+#[tokio::test]
+async fn test_do_not_track_skips_analytics() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    server.get("/api/search?query=matrix").add_header("dnt", "1").await;
+    server.get("/api/movie/603/videos").add_header("sec-gpc", "1").await;
+    server.get("/title/movie/603").add_header("dnt", "1").await;
+
+    let searches: SearchReport = server
+        .get("/admin/analytics/searches")
+        .authorization_bearer(TOKEN)
+        .await
+        .json();
+    let titles: Vec<TitleCount> = server
+        .get("/admin/analytics/titles/top")
+        .authorization_bearer(TOKEN)
+        .await
+        .json();
+
+    assert_eq!(searches.total_searches, 0);
+    assert!(titles.is_empty());
+}
+
+#[tokio::test]
+async fn test_do_not_track_skips_exposures_but_keeps_assignment() {
+    let server = TestServer::new(create_test_app_with_experiments()).unwrap();
+
+    let response = server
+        .get("/api/trending")
+        .add_header("x-user-id", "user-1")
+        .add_header("dnt", "1")
+        .await;
+
+    assert!(response.maybe_header("x-experiments").is_some());
+
+    let counts: Vec<VariantExposures> = server
+        .get("/admin/analytics/experiments")
+        .authorization_bearer(TOKEN)
+        .await
+        .json();
+    assert!(counts.is_empty());
+}
+
+#[tokio::test]
+async fn test_slo_status_tracks_route_failures() {
+    let mock_client = MockTmdbClient::builder()
+        .with_trending_error(1, TmdbError::ServerError(503))
+        .build();
+    let slos = SloTracker::new(vec![Slo {
+        route: "/api/trending".to_string(),
+        availability: 0.999,
+        latency_ms: 60_000,
+        latency_target: 0.99,
+    }]);
+    let state = AppState::new(Arc::new(mock_client))
+        .with_slos(slos)
+        .with_admin_token(TOKEN);
+    let app = Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .nest("/admin", admin::router(state.clone()))
+        .layer(middleware::from_fn_with_state(state.clone(), slo::slo_middleware))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    server.get("/api/trending").add_query_param("page", 1).await;
+    server.get("/api/trending").add_query_param("page", 2).await.assert_status_ok();
+
+    let status = server
+        .get("/admin/slo")
+        .authorization_bearer(TOKEN)
+        .await
+        .json::<Vec<SloStatus>>();
+
+    assert_eq!(status.len(), 1);
+    assert_eq!(status[0].short_window.requests, 2);
+    assert!((status[0].short_window.availability - 0.5).abs() < 1e-9);
+    assert!(status[0].alerting);
+}
+
+#[tokio::test]
+async fn test_top_titles_window_follows_clock() {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+    let state = AppState::new(Arc::new(MockTmdbClient::new()))
+        .with_clock(clock.clone())
+        .with_admin_token(TOKEN);
+    let app = Router::new()
+        .route("/title/{media_type}/{id}", get(pages::title_page))
+        .nest("/admin", admin::router(state.clone()))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    server.get("/title/movie/603").await;
+    clock.advance(Duration::from_secs(2 * 60 * 60));
+    server.get("/title/tv/1399").await;
+
+    let top: Vec<TitleCount> = server
+        .get("/admin/analytics/titles/top?window=1h")
+        .authorization_bearer(TOKEN)
+        .await
+        .json();
+
+    assert_eq!(top.len(), 1);
+    assert_eq!((top[0].media_type, top[0].id), (MediaType::Tv, 1399));
+}
+
+#[tokio::test]
+async fn test_curated_list_crud() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let body = json!({
+        "title": "Staff picks",
+        "description": "What the team is watching",
+        "items": [{ "media_type": "movie", "id": 603 }, { "media_type": "tv", "id": 1399 }]
+    });
+
+    let created = server.put("/admin/lists/staff-picks").authorization_bearer(TOKEN).json(&body).await;
+    assert_eq!(created.status_code(), 201);
+    let replaced = server
+        .put("/admin/lists/staff-picks")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "title": "Staff picks", "items": [{ "media_type": "movie", "id": 27205 }] }))
+        .await;
+    assert_eq!(replaced.status_code(), 200);
+
+    let list: CuratedList = server.get("/admin/lists/staff-picks").authorization_bearer(TOKEN).await.json();
+    assert_eq!(list.items.len(), 1);
+    assert_eq!(list.items[0].id.get(), 27205);
+    assert!(list.description.is_none());
+
+    let all: Vec<CuratedList> = server.get("/admin/lists").authorization_bearer(TOKEN).await.json();
+    assert_eq!(all.len(), 1);
+
+    let deleted = server.delete("/admin/lists/staff-picks").authorization_bearer(TOKEN).await;
+    assert_eq!(deleted.status_code(), 204);
+    let missing = server.get("/admin/lists/staff-picks").authorization_bearer(TOKEN).await;
+    assert_eq!(missing.status_code(), 404);
+    let public = server.get("/api/lists/staff-picks").await;
+    assert_eq!(public.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_curated_list_rejects_invalid_input() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let items: Vec<_> = (1..=51).map(|id| json!({ "media_type": "movie", "id": id })).collect();
+
+    let bad_slug = server
+        .put("/admin/lists/Staff_Picks")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "title": "Staff picks", "items": [] }))
+        .await;
+    let blank_title = server
+        .put("/admin/lists/staff-picks")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "title": "  ", "items": [] }))
+        .await;
+    let too_many = server
+        .put("/admin/lists/staff-picks")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "title": "Staff picks", "items": items }))
+        .await;
+    let unauthorized = server
+        .put("/admin/lists/staff-picks")
+        .json(&json!({ "title": "Staff picks", "items": [] }))
+        .await;
+
+    assert_eq!(bad_slug.status_code(), 400);
+    assert_eq!(blank_title.status_code(), 400);
+    assert_eq!(too_many.status_code(), 400);
+    assert_eq!(unauthorized.status_code(), 401);
+}
+
+#[tokio::test]
+async fn test_public_list_resolves_and_caches_titles() {
+    let mock_client = Arc::new(MockTmdbClient::builder().with_details_error(MediaType::Movie, 404, TmdbError::NotFound).build());
+    let state = AppState::new(mock_client.clone()).with_admin_token(TOKEN);
+    let app = Router::new()
+        .route("/api/lists/{slug}", get(handlers::get_curated_list))
+        .nest("/admin", admin::router(state.clone()))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+    let body = json!({
+        "title": "Staff picks",
+        "items": [{ "media_type": "tv", "id": 1399 }, { "media_type": "movie", "id": 404 }, { "media_type": "movie", "id": 603 }]
+    });
+    server.put("/admin/lists/staff-picks").authorization_bearer(TOKEN).json(&body).await;
+
+    let list: ResolvedList = server.get("/api/lists/staff-picks").await.json();
+    let ids: Vec<(MediaType, i64)> = list.items.iter().map(|item| (item.media_type, item.id)).collect();
+    assert_eq!(list.title, "Staff picks");
+    assert_eq!(ids, [(MediaType::Tv, 1399), (MediaType::Movie, 603)]);
+    assert_eq!(mock_client.calls(), 3);
+
+    server.get("/api/lists/staff-picks").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 3);
+
+    // Editing the list drops the cached resolution
+    server.put("/admin/lists/staff-picks").authorization_bearer(TOKEN).json(&body).await;
+    server.get("/api/lists/staff-picks").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 6);
+}
+
+#[tokio::test]
+async fn test_import_tmdb_list() {
+    let mock_client = MockTmdbClient::builder().with_list_response(404, Err(TmdbError::NotFound)).build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let imported = server
+        .post("/admin/lists/nineties/import")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "tmdb_list_id": 8_001 }))
+        .await;
+    assert_eq!(imported.status_code(), 201);
+    let list: CuratedList = imported.json();
+    let ids: Vec<(MediaType, i64)> = list.items.iter().map(|item| (item.media_type, item.id.get())).collect();
+    assert_eq!(list.title, "Best of the 90s");
+    assert_eq!(list.tmdb_list_id, Some(8_001));
+    assert_eq!(ids, [(MediaType::Movie, 603), (MediaType::Tv, 1399), (MediaType::Movie, 550)]);
+
+    let missing = server
+        .post("/admin/lists/missing/import")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "tmdb_list_id": 404 }))
+        .await;
+    assert_eq!(missing.status_code(), 404);
+
+    // Editing an imported list by hand unlinks it
+    let edited: CuratedList = server
+        .put("/admin/lists/nineties")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "title": "Nineties", "items": [] }))
+        .await
+        .json();
+    assert!(edited.tmdb_list_id.is_none());
+}
+
+#[tokio::test]
+async fn test_sync_reimports_linked_lists() {
+    let mock_client = MockTmdbClient::builder().with_list_response(404, Err(TmdbError::NotFound)).build();
+    let lists = AppState::new(Arc::new(MockTmdbClient::new())).lists;
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let stale = TmdbList { name: "Old name".to_string(), description: None, items: Vec::new() };
+    lists.import("nineties", 8_001, stale.clone(), UNIX_EPOCH).unwrap();
+    lists.import("gone", 404, stale, UNIX_EPOCH).unwrap();
+    lists.put("staff-picks", CuratedListInput { title: "Staff picks".to_string(), description: None, items: Vec::new() }, UNIX_EPOCH).unwrap();
+
+    assert_eq!(lists.sync(&mock_client, now).await, 1);
+
+    let synced = lists.get("nineties").unwrap();
+    assert_eq!(synced.title, "Best of the 90s");
+    assert_eq!(synced.items.len(), 3);
+    assert_eq!(synced.updated_at, 1_700_000_000);
+    // Lists whose TMDB list failed to load are left as they were
+    assert_eq!(lists.get("gone").unwrap().title, "Old name");
+    assert_eq!(lists.get("staff-picks").unwrap().updated_at, 0);
+}
+
+#[tokio::test]
+async fn test_compression_mode_switches_at_runtime() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let status = server
+        .put("/admin/compression")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "mode": "off" }))
+        .await
+        .json::<CompressionStatus>();
+    assert_eq!(status.mode, CompressionMode::Off);
+
+    let metrics = server
+        .get("/admin/metrics/compression")
+        .authorization_bearer(TOKEN)
+        .await
+        .json::<CompressionStatus>();
+    assert_eq!(metrics.mode, CompressionMode::Off);
+    assert_eq!(metrics.compressed_bytes, 0);
+
+    server
+        .put("/admin/compression")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "mode": "zstd" }))
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+}
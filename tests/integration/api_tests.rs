@@ -1,34 +1,52 @@
-use axum::{routing::get, Router};
+use axum::{middleware, routing::{get, post}, Router};
 use axum_test::TestServer;
 use super::mock_tmdb_client::MockTmdbClient;
-use netflix_service::{error::TmdbError, handlers, models, state::AppState};
+use netflix_service::{clock::{Clock, MockClock}, error::TmdbError, experiments::{self, Experiments}, handlers, home::{HomeLayout, HomePage, RowKind, RowStatus}, lists::CuratedListInput, models, ranking::RankingStrategies, negative_cache::{NegativeCache, NegativeCacheTtls, NegativeCachingTmdbClient}, state::AppState};
+use serde_json::json;
 use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
 
 fn create_test_app() -> Router {
-    let tmdb_client = Arc::new(MockTmdbClient::new());
-
-    let state = AppState {
-        tmdb_client,
-    };
+    let state = AppState::new(Arc::new(MockTmdbClient::new()));
 
     Router::new()
         .route("/", get(handlers::root))
         .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/top_rated", get(handlers::get_top_rated))
+        .route("/api/popular", get(handlers::get_popular))
         .route("/api/search", get(handlers::search_content))
         .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
+        .route("/api/movie/{id}/images", get(handlers::get_movie_images))
+        .route("/api/movie/{id}/alternative-titles", get(handlers::get_movie_alternative_titles))
+        .route("/api/movie/{id}/availability", get(handlers::get_movie_availability))
+        .route("/api/discover", get(handlers::discover))
+        .route("/api/providers", get(handlers::get_providers))
+        .route("/api/tv/{id}/season/{season_number}", get(handlers::get_tv_season))
+        .route("/api/network/{id}", get(handlers::get_network))
+        .route("/api/network/{id}/titles", get(handlers::get_network_titles))
+        .route("/api/company/{id}/titles", get(handlers::get_company_titles))
+        .route("/oembed", get(handlers::get_oembed))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
         .with_state(state)
 }
 
 fn create_test_app_with_client(client: MockTmdbClient) -> Router {
-    let state = AppState {
-        tmdb_client: Arc::new(client),
-    };
+    let state = AppState::new(Arc::new(client));
 
     Router::new()
         .route("/", get(handlers::root))
         .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/top_rated", get(handlers::get_top_rated))
+        .route("/api/popular", get(handlers::get_popular))
         .route("/api/search", get(handlers::search_content))
         .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
+        .route("/api/movie/{id}/images", get(handlers::get_movie_images))
+        .route("/api/movie/{id}/alternative-titles", get(handlers::get_movie_alternative_titles))
+        .route("/api/tv/{id}/season/{season_number}", get(handlers::get_tv_season))
+        .route("/api/tv/{id}/where_to_start", get(handlers::get_where_to_start))
+        .route("/api/collection/{id}/where_to_start", get(handlers::get_collection_where_to_start))
+        .route("/oembed", get(handlers::get_oembed))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
         .with_state(state)
 }
 
@@ -111,6 +129,50 @@ async fn test_search_content_requires_query() {
     assert_ne!(response.status_code(), 200);
 }
 
+#[tokio::test]
+async fn test_search_rejects_blank_query() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    for query in ["", "%20%20", "%09"] {
+        let response = server.get(&format!("/api/search?query={}", query)).await;
+
+        assert_eq!(response.status_code(), 400, "query {:?}", query);
+        assert_eq!(response.text(), "query must not be empty");
+    }
+}
+
+#[tokio::test]
+async fn test_search_trims_query() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/api/search?query=%20avengers%20").await;
+
+    assert_eq!(response.status_code(), 200);
+    let body: models::TmdbResponse = response.json();
+    assert_eq!(body.results[0].title.as_deref(), Some("Search Result for 'avengers'"));
+}
+
+#[tokio::test]
+async fn test_search_short_query_skips_upstream() {
+    let mock_client = MockTmdbClient::builder()
+        .with_search_error("a", 1, TmdbError::ServerError(500))
+        .build();
+    let state = AppState::new(Arc::new(mock_client)).with_min_search_query_chars(2);
+    let app = Router::new()
+        .route("/api/search", get(handlers::search_content))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/api/search?query=a").await;
+
+    assert_eq!(response.status_code(), 200);
+    let body: models::TmdbResponse = response.json();
+    assert!(body.results.is_empty());
+    assert_eq!(body.total_pages, 0);
+}
+
 #[tokio::test]
 async fn test_get_movie_videos_endpoint() {
     let app = create_test_app();
@@ -269,6 +331,32 @@ async fn test_movie_videos_not_found() {
     assert_eq!(response.text(), "Resource not found");
 }
 
+#[tokio::test]
+async fn test_get_movie_videos_out_of_range_id() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    for id in ["-5", "0"] {
+        let response = server.get(&format!("/api/movie/{}/videos", id)).await;
+
+        assert_eq!(response.status_code(), 404, "id {}", id);
+        assert_eq!(response.text(), "Resource not found");
+    }
+}
+
+#[tokio::test]
+async fn test_get_movie_videos_malformed_id() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    for id in ["abc", "99999999999999999999"] {
+        let response = server.get(&format!("/api/movie/{}/videos", id)).await;
+
+        assert_eq!(response.status_code(), 400, "id {}", id);
+        assert!(response.text().starts_with("Invalid URL"));
+    }
+}
+
 // ========== Custom Response Tests ==========
 
 #[tokio::test]
@@ -284,8 +372,15 @@ async fn test_custom_trending_response() {
             poster_path: None,
             backdrop_path: None,
             vote_average: Some(10.0),
+            vote_count: Some(1200),
+            quality_score: None,
+            has_trailer: None,
+            providers: None,
+            cast: None,
+            genre_ids: Vec::new(),
             release_date: None,
             media_type: Some("movie".to_string()),
+            adult: false,
         }],
     };
 
@@ -347,3 +442,1174 @@ async fn test_specific_page_override() {
     assert_eq!(response1.status_code(), 429);
     assert_eq!(response3.status_code(), 200);
 }
+
+// ========== oEmbed Tests ==========
+
+#[tokio::test]
+async fn test_oembed_for_title_url() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/title/movie/550")
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let body: models::OembedResponse = response.json();
+    assert_eq!(body.version, "1.0");
+    assert_eq!(body.r#type, "video");
+    assert_eq!(body.title, "Test Movie 1");
+    assert_eq!((body.width, body.height), (560, 315));
+    assert!(body.html.contains("https://www.youtube.com/embed/abc123xyz"));
+}
+
+#[tokio::test]
+async fn test_oembed_respects_max_size() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/title/movie/550")
+        .add_query_param("maxwidth", "320")
+        .await;
+
+    let body: models::OembedResponse = response.json();
+    assert_eq!((body.width, body.height), (320, 180));
+}
+
+#[tokio::test]
+async fn test_oembed_with_huge_max_height_keeps_default_size() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/title/movie/550")
+        .add_query_param("maxheight", u32::MAX.to_string())
+        .await;
+
+    response.assert_status_ok();
+    let body: models::OembedResponse = response.json();
+    assert_eq!((body.width, body.height), (560, 315));
+}
+
+#[tokio::test]
+async fn test_oembed_unknown_url() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/browse")
+        .await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_oembed_out_of_range_id() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/title/movie/-5")
+        .await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_oembed_without_trailer() {
+    let mock_client = MockTmdbClient::builder()
+        .with_video_response(550, Ok(models::VideoResponse { id: 550, results: vec![] }))
+        .build();
+
+    let app = create_test_app_with_client(mock_client);
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/title/movie/550")
+        .await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_oembed_xml_not_implemented() {
+    let app = create_test_app();
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/oembed")
+        .add_query_param("url", "https://example.com/title/movie/550")
+        .add_query_param("format", "xml")
+        .await;
+
+    assert_eq!(response.status_code(), 501);
+}
+
+#[tokio::test]
+async fn test_get_movie_videos_id_beyond_i32() {
+    let large_id = i32::MAX as i64 + 1;
+    let mock_client = MockTmdbClient::builder()
+        .with_video_response(large_id, Ok(models::VideoResponse { id: large_id, results: vec![] }))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let response = server.get(&format!("/api/movie/{}/videos", large_id)).await;
+
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<models::VideoResponse>().id, large_id);
+}
+
+#[tokio::test]
+async fn test_titles_batch_keeps_order_and_reports_item_errors() {
+    let mock_client = MockTmdbClient::builder()
+        .with_details_error(models::MediaType::Movie, 404, TmdbError::NotFound)
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let response = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [
+            { "media_type": "movie", "id": 603 },
+            { "media_type": "movie", "id": 404 },
+            { "media_type": "tv", "id": 1399 }
+        ] }))
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+    let results: Vec<models::BatchTitleResult> = response.json();
+    assert_eq!(results.len(), 3);
+
+    assert_eq!(results[0].id, 603);
+    assert_eq!(results[0].details.as_ref().unwrap().display_title(), "Test Movie 1");
+    assert!(results[0].error.is_none());
+
+    assert_eq!(results[1].id, 404);
+    assert!(results[1].details.is_none());
+    let error = results[1].error.as_ref().unwrap();
+    assert_eq!((error.status, error.message.as_str()), (404, "Resource not found"));
+
+    assert_eq!((results[2].media_type, results[2].id), (models::MediaType::Tv, 1399));
+    assert_eq!(results[2].details.as_ref().unwrap().display_title(), "Test Show 1");
+}
+
+#[tokio::test]
+async fn test_titles_batch_includes_cached_logos_on_request() {
+    let mock_client = Arc::new(
+        MockTmdbClient::builder()
+            .with_details_error(models::MediaType::Movie, 404, TmdbError::NotFound)
+            .build(),
+    );
+    let state = AppState::new(mock_client.clone());
+    let app = Router::new()
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+    let body = json!({
+        "items": [{ "media_type": "movie", "id": 603 }, { "media_type": "movie", "id": 404 }],
+        "include_logo": true,
+        "language": "DE"
+    });
+
+    let results: Vec<models::BatchTitleResult> = server.post("/api/titles/batch").json(&body).await.json();
+    assert_eq!(
+        results[0].logo.as_ref().map(|logo| logo.url.as_str()),
+        Some("https://image.tmdb.org/t/p/original/logo_de.png")
+    );
+    assert!(results[1].logo.is_none());
+    // Details for both titles plus images for the one that exists
+    assert_eq!(mock_client.calls(), 3);
+
+    server.post("/api/titles/batch").json(&body).await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 5);
+}
+
+#[tokio::test]
+async fn test_titles_batch_omits_logo_by_default() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 603 }] }))
+        .await;
+
+    assert!(response.json::<serde_json::Value>()[0].get("logo").is_none());
+}
+
+#[tokio::test]
+async fn test_titles_batch_rejects_too_many_items() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let items: Vec<_> = (1..=handlers::MAX_BATCH_ITEMS as i64 + 1)
+        .map(|id| json!({ "media_type": "movie", "id": id }))
+        .collect();
+
+    let response = server.post("/api/titles/batch").json(&json!({ "items": items })).await;
+
+    assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+async fn test_titles_batch_rejects_invalid_ids() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": -5 }] }))
+        .await;
+
+    assert_eq!(response.status_code(), 422);
+}
+
+#[tokio::test]
+async fn test_not_found_videos_are_negatively_cached() {
+    let mock_client = Arc::new(
+        MockTmdbClient::builder()
+            .with_video_error(404, TmdbError::NotFound)
+            .build(),
+    );
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    let cache = Arc::new(NegativeCache::new(NegativeCacheTtls::default(), clock.clone()));
+    let client = NegativeCachingTmdbClient::new(mock_client.clone(), cache.clone());
+    let state = AppState::new(Arc::new(client)).with_negative_cache(cache);
+    let app = Router::new()
+        .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
+        .route("/api/movie/{id}/images", get(handlers::get_movie_images))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    for _ in 0..3 {
+        let response = server.get("/api/movie/404/videos").await;
+        assert_eq!(response.status_code(), 404);
+        assert_eq!(response.text(), "Resource not found");
+    }
+    assert_eq!(mock_client.calls(), 1);
+
+    // Found titles are never cached
+    server.get("/api/movie/550/videos").await.assert_status_ok();
+    server.get("/api/movie/550/videos").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 3);
+
+    clock.advance(Duration::from_secs(31));
+    server.get("/api/movie/404/videos").await;
+    assert_eq!(mock_client.calls(), 4);
+}
+
+#[tokio::test]
+async fn test_trending_shaped_for_tv() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/trending").add_query_param("device", "tv").await;
+    response.assert_status_ok();
+
+    let body = response.json::<serde_json::Value>();
+    let item = &body["results"][0];
+    assert!(item.get("overview").is_none());
+    assert_eq!(item["poster_url"], "https://image.tmdb.org/t/p/w185/test1.jpg");
+}
+
+#[tokio::test]
+async fn test_search_shaped_by_client_profile_header() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/api/search")
+        .add_query_param("query", "matrix")
+        .add_header("x-client-profile", "mobile")
+        .await;
+    response.assert_status_ok();
+
+    let item = &response.json::<serde_json::Value>()["results"][0];
+    assert!(item.get("backdrop_path").is_none());
+    assert_eq!(item["overview"], "Matching content");
+    assert_eq!(item["poster_url"], "https://image.tmdb.org/t/p/w342/search.jpg");
+}
+
+#[tokio::test]
+async fn test_kids_profile_hides_adult_titles_and_profanity() {
+    let trending: models::TmdbResponse = serde_json::from_value(json!({
+        "page": 1,
+        "total_pages": 1,
+        "results": [
+            { "id": 1, "title": "Family Film", "overview": "A damn fine adventure", "adult": false },
+            { "id": 2, "title": "Adult Film", "overview": "Not for kids", "adult": true }
+        ]
+    }))
+    .unwrap();
+    let client = MockTmdbClient::builder().with_trending_response(1, Ok(trending)).build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    let body = server.get("/api/trending").add_header("x-client-profile", "kids").await.json::<serde_json::Value>();
+    let items = body["results"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["overview"], "A **** fine adventure");
+    assert!(items[0].get("adult").is_none());
+
+    // Other profiles see overviews as they are; the adult title is still
+    // left out by the default adult content policy
+    let body = server.get("/api/trending").await.json::<serde_json::Value>();
+    assert_eq!(body["results"].as_array().unwrap().len(), 1);
+    assert_eq!(body["results"][0]["overview"], "A damn fine adventure");
+}
+
+#[tokio::test]
+async fn test_kids_profile_applies_to_home_rows_and_batch() {
+    let trending: models::TmdbResponse = serde_json::from_value(json!({
+        "page": 1,
+        "total_pages": 1,
+        "results": [{ "id": 1, "title": "Family Film", "overview": "A damn fine adventure" }]
+    }))
+    .unwrap();
+    let adult: models::TitleDetails = serde_json::from_value(json!({ "id": 2, "title": "Adult Film", "adult": true })).unwrap();
+    let client = MockTmdbClient::builder()
+        .with_trending_response(1, Ok(trending))
+        .with_details_response(models::MediaType::Movie, 2, Ok(adult))
+        .build();
+    let app = Router::new()
+        .route("/api/home", get(handlers::get_home))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
+        .with_state(AppState::new(Arc::new(client)));
+    let server = TestServer::new(app).unwrap();
+
+    let home: HomePage = server.get("/api/home?device=kids").await.json();
+    let trending_row = home.rows.iter().find(|row| row.id == "trending").unwrap();
+    assert_eq!(trending_row.items[0].overview.as_deref(), Some("A **** fine adventure"));
+
+    let results: Vec<models::BatchTitleResult> = server
+        .post("/api/titles/batch")
+        .add_header("x-client-profile", "kids")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 2 }, { "media_type": "movie", "id": 603 }] }))
+        .await
+        .json();
+    assert!(results[0].details.is_none());
+    assert_eq!(results[0].error.as_ref().unwrap().status, 403);
+    assert!(results[1].details.is_some());
+
+    // Without a preset the batch returns the title as before
+    let results: Vec<models::BatchTitleResult> = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 2 }] }))
+        .await
+        .json();
+    assert!(results[0].details.is_some());
+}
+
+#[tokio::test]
+async fn test_unknown_device_is_rejected() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/trending").add_query_param("device", "fridge").await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "unknown device 'fridge'");
+}
+
+#[tokio::test]
+async fn test_responses_unshaped_without_device() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let item = server.get("/api/trending").await.json::<serde_json::Value>()["results"][0].clone();
+    assert_eq!(item["overview"], "A great test movie");
+    assert!(item.get("poster_url").is_none());
+}
+
+#[tokio::test]
+async fn test_movie_images_grouped_with_full_urls() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/movie/603/images").await;
+    response.assert_status_ok();
+
+    let images = response.json::<models::TitleImages>();
+    assert_eq!(images.id, 603);
+    assert_eq!(images.posters.len(), 2);
+    assert_eq!(images.backdrops.len(), 1);
+    assert_eq!(images.logos[0].url, "https://image.tmdb.org/t/p/original/logo_en.png");
+    assert_eq!(images.logos[0].language.as_deref(), Some("en"));
+}
+
+#[tokio::test]
+async fn test_movie_images_language_filter() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let images = server
+        .get("/api/movie/603/images")
+        .add_query_param("language", "EN,null")
+        .await
+        .json::<models::TitleImages>();
+
+    let urls = |links: &[models::ImageLink]| links.iter().map(|link| link.url.clone()).collect::<Vec<_>>();
+    assert_eq!(urls(&images.posters), vec!["https://image.tmdb.org/t/p/original/poster_en.jpg"]);
+    assert_eq!(urls(&images.backdrops), vec!["https://image.tmdb.org/t/p/original/backdrop_text_free.jpg"]);
+    assert_eq!(images.logos.len(), 1);
+}
+
+#[tokio::test]
+async fn test_movie_images_not_found() {
+    let client = MockTmdbClient::builder()
+        .with_images_response(models::MediaType::Movie, 999, Err(TmdbError::NotFound))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    server.get("/api/movie/999/images").await.assert_status_not_found();
+    server.get("/api/movie/-1/images").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_movie_alternative_titles() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/movie/603/alternative-titles").await;
+    response.assert_status_ok();
+
+    let titles = response.json::<models::AlternativeTitles>();
+    assert_eq!(titles.id, 603);
+    assert_eq!(titles.titles.len(), 3);
+}
+
+#[tokio::test]
+async fn test_movie_alternative_titles_country_filter() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let titles = server
+        .get("/api/movie/603/alternative-titles")
+        .add_query_param("country", "de")
+        .await
+        .json::<models::AlternativeTitles>();
+
+    assert_eq!(titles.titles.len(), 1);
+    assert_eq!(titles.titles[0].title, "Testfilm 1");
+}
+
+#[tokio::test]
+async fn test_movie_alternative_titles_not_found() {
+    let client = MockTmdbClient::builder()
+        .with_alternative_titles_response(models::MediaType::Movie, 999, Err(TmdbError::NotFound))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    server.get("/api/movie/999/alternative-titles").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_titles_batch_includes_original_language_metadata() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 603 }] }))
+        .await;
+
+    let details = &response.json::<serde_json::Value>()[0]["details"];
+    assert_eq!(details["original_title"], "Película de Prueba 1");
+    assert_eq!(details["original_language"], "es");
+    assert_eq!(details["alternative_titles"][0]["iso_3166_1"], "IT");
+}
+
+#[tokio::test]
+async fn test_titles_batch_includes_keyword_tags_and_warnings() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let results: Vec<models::BatchTitleResult> = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 603 }] }))
+        .await
+        .json();
+
+    assert_eq!(results[0].tags, ["based on a book"]);
+    assert_eq!(results[0].content_warnings, ["gore"]);
+}
+
+#[tokio::test]
+async fn test_titles_batch_omits_tags_for_failed_items() {
+    let client = MockTmdbClient::builder()
+        .with_details_error(models::MediaType::Movie, 404, TmdbError::NotFound)
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    let response = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 404 }] }))
+        .await;
+
+    let item = &response.json::<serde_json::Value>()[0];
+    assert!(item.get("tags").is_none());
+    assert!(item.get("content_warnings").is_none());
+}
+
+#[tokio::test]
+async fn test_movie_availability_matrix() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/api/movie/603/availability")
+        .add_query_param("regions", "us,GB,DE,US")
+        .await;
+    response.assert_status_ok();
+
+    let matrix = response.json::<models::AvailabilityMatrix>();
+    let names: Vec<&str> = matrix.providers.iter().map(|provider| provider.name.as_str()).collect();
+    assert_eq!(names, ["Netflix", "Apple TV"]);
+    assert_eq!(matrix.providers[0].logo_url.as_deref(), Some("https://image.tmdb.org/t/p/w92/provider_8.jpg"));
+    assert_eq!(matrix.regions.keys().collect::<Vec<_>>(), ["DE", "GB", "US"]);
+    assert_eq!(matrix.regions["US"].flatrate, [8]);
+    assert_eq!(matrix.regions["US"].rent, [2]);
+    assert_eq!(matrix.regions["GB"].flatrate, [8]);
+    assert_eq!(matrix.regions["DE"], models::RegionAvailability::default());
+}
+
+#[tokio::test]
+async fn test_movie_availability_cached_per_region() {
+    let mock_client = Arc::new(MockTmdbClient::new());
+    let state = AppState::new(mock_client.clone());
+    let app = Router::new()
+        .route("/api/movie/{id}/availability", get(handlers::get_movie_availability))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    server.get("/api/movie/603/availability").add_query_param("regions", "US,GB").await.assert_status_ok();
+    server.get("/api/movie/603/availability").add_query_param("regions", "GB").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 1);
+
+    server.get("/api/movie/603/availability").add_query_param("regions", "GB,DE").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 2);
+}
+
+#[tokio::test]
+async fn test_movie_availability_rejects_bad_regions() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/movie/603/availability").add_query_param("regions", "US,USA").await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "invalid region 'USA'");
+
+    server.get("/api/movie/603/availability").await.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_movie_availability_not_found() {
+    let client = MockTmdbClient::builder()
+        .with_watch_providers_response(models::MediaType::Movie, 999, Err(TmdbError::NotFound))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    server.get("/api/movie/999/availability").add_query_param("regions", "US").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_discover_filters_by_providers() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/api/discover")
+        .add_query_param("providers", "8, 9")
+        .add_query_param("region", "us")
+        .add_query_param("page", "2")
+        .await;
+    response.assert_status_ok();
+
+    let body = response.json::<models::TmdbResponse>();
+    assert_eq!(body.page, 2);
+    assert_eq!(body.results[0].title.as_deref(), Some("Streaming on [8, 9] in US"));
+    assert_eq!(body.results[0].media_type.as_deref(), Some("movie"));
+}
+
+#[tokio::test]
+async fn test_discover_without_filters() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let body = server.get("/api/discover").await.json::<models::TmdbResponse>();
+    assert_eq!(body.results[0].title.as_deref(), Some("Streaming on [] in anywhere"));
+}
+
+#[tokio::test]
+async fn test_discover_rejects_bad_filters() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/discover").add_query_param("providers", "8").await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "region is required when filtering by providers");
+
+    let response = server
+        .get("/api/discover")
+        .add_query_param("providers", "netflix")
+        .add_query_param("region", "US")
+        .await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "invalid provider id 'netflix'");
+}
+
+#[tokio::test]
+async fn test_top_rated_defaults_to_movies() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let movies: models::TmdbResponse = server.get("/api/top_rated").await.json();
+    assert_eq!(movies.page, 1);
+    assert_eq!(movies.results[0].title.as_deref(), Some("Top Rated 1"));
+    assert_eq!(movies.results[1].vote_average, Some(8.6));
+
+    let shows: models::TmdbResponse = server
+        .get("/api/top_rated")
+        .add_query_param("media_type", "tv")
+        .add_query_param("page", "2")
+        .await
+        .json();
+    assert_eq!(shows.page, 2);
+    assert_eq!(shows.results[0].name.as_deref(), Some("Top Rated 1"));
+    assert_eq!(shows.results[0].media_type.as_deref(), Some("tv"));
+}
+
+#[tokio::test]
+async fn test_top_rated_rejects_unknown_media_types() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    server.get("/api/top_rated").add_query_param("media_type", "person").await.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_popular_defaults_to_movies() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let movies: models::TmdbResponse = server.get("/api/popular").await.json();
+    assert_eq!(movies.page, 1);
+    assert_eq!(movies.results[0].title.as_deref(), Some("Popular 1"));
+    assert_eq!(movies.results[0].media_type.as_deref(), Some("movie"));
+}
+
+#[tokio::test]
+async fn test_popular_pages_through_tv_shows() {
+    let page = models::TmdbResponse { page: 3, total_pages: 3, results: Vec::new() };
+    let client = MockTmdbClient::builder()
+        .with_popular_sequence(models::MediaType::Tv, 3, vec![Ok(page), Err(TmdbError::ServerError(500))])
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+    let request = || server.get("/api/popular").add_query_param("media_type", "tv").add_query_param("page", "3");
+
+    let shows: models::TmdbResponse = request().await.json();
+    assert_eq!((shows.page, shows.total_pages), (3, 3));
+    assert_eq!(request().await.status_code(), 502);
+}
+
+#[tokio::test]
+async fn test_discover_movie_maps_tmdb_parameters() {
+    let filter = models::DiscoverFilter {
+        genres: vec![28, 12],
+        all_genres: true,
+        release_year: Some(1999),
+        min_vote_tenths: Some(75),
+        sort: models::DiscoverSort::TopRated,
+        ..models::DiscoverFilter::new(models::MediaType::Movie)
+    };
+    let page = models::TmdbResponse { page: 2, total_pages: 2, results: Vec::new() };
+    let client = MockTmdbClient::builder().with_discover_sequence(filter, 2, vec![Ok(page)]).build();
+    let app = Router::new()
+        .route("/api/discover/movie", get(handlers::discover))
+        .with_state(AppState::new(Arc::new(client)));
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .get("/api/discover/movie")
+        .add_query_param("with_genres", "28,12")
+        .add_query_param("primary_release_year", "1999")
+        .add_query_param("vote_average.gte", "7.5")
+        .add_query_param("sort_by", "vote_average.desc")
+        .add_query_param("page", "2")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<models::TmdbResponse>().total_pages, 2);
+
+    let response = server.get("/api/discover/movie").add_query_param("sort_by", "revenue.desc").await;
+    response.assert_status_bad_request();
+    let response = server.get("/api/discover/movie").add_query_param("with_genres", "28,12|16").await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_providers_list_sorted_with_logos() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/providers").add_query_param("region", "US").await;
+    response.assert_status_ok();
+
+    let providers = response.json::<Vec<models::ProviderSummary>>();
+    let ids: Vec<i64> = providers.iter().map(|provider| provider.id).collect();
+    assert_eq!(ids, [8, 9, 337]);
+    assert_eq!(providers[0].name, "Netflix");
+    assert_eq!(providers[0].logo_url.as_deref(), Some("https://image.tmdb.org/t/p/w92/provider_8.jpg"));
+}
+
+#[tokio::test]
+async fn test_providers_require_valid_region() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    server.get("/api/providers").await.assert_status_bad_request();
+    let response = server.get("/api/providers").add_query_param("region", "USA").await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "invalid region 'USA'");
+}
+
+#[tokio::test]
+async fn test_providers_list_cached_per_region_for_a_day() {
+    let mock_client = Arc::new(MockTmdbClient::new());
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    let state = AppState::new(mock_client.clone()).with_clock(clock.clone());
+    let app = Router::new()
+        .route("/api/providers", get(handlers::get_providers))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    server.get("/api/providers").add_query_param("region", "US").await.assert_status_ok();
+    server.get("/api/providers").add_query_param("region", "us").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 1);
+
+    server.get("/api/providers").add_query_param("region", "GB").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 2);
+
+    clock.advance(Duration::from_secs(24 * 60 * 60));
+    server.get("/api/providers").add_query_param("region", "US").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 3);
+}
+
+#[tokio::test]
+async fn test_providers_list_failures_not_cached() {
+    let mock_client = Arc::new(
+        MockTmdbClient::builder()
+            .with_provider_list_response("US", Err(TmdbError::NetworkError("timed out".to_string())))
+            .build(),
+    );
+    let app = Router::new()
+        .route("/api/providers", get(handlers::get_providers))
+        .with_state(AppState::new(mock_client.clone()));
+    let server = TestServer::new(app).unwrap();
+
+    for _ in 0..2 {
+        server.get("/api/providers").add_query_param("region", "US").await.assert_status_service_unavailable();
+    }
+    assert_eq!(mock_client.calls(), 2);
+}
+
+#[tokio::test]
+async fn test_network_titles_are_tv_shows() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/network/49/titles").add_query_param("page", "2").await;
+    response.assert_status_ok();
+
+    let body = response.json::<models::TmdbResponse>();
+    assert_eq!(body.page, 2);
+    assert_eq!(body.results[0].title.as_deref(), Some("On network 49"));
+    assert_eq!(body.results[0].media_type.as_deref(), Some("tv"));
+}
+
+#[tokio::test]
+async fn test_company_titles_are_movies() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let body = server.get("/api/company/420/titles").await.json::<models::TmdbResponse>();
+    assert_eq!(body.results[0].title.as_deref(), Some("By company 420"));
+    assert_eq!(body.results[0].media_type.as_deref(), Some("movie"));
+
+    server.get("/api/company/0/titles").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_new_on_requires_provider_and_region() {
+    let app = Router::new()
+        .route("/api/rows/new_on", get(handlers::get_new_on))
+        .with_state(AppState::new(Arc::new(MockTmdbClient::new())));
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/api/rows/new_on").add_query_param("region", "US").await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "provider is required");
+    let response = server.get("/api/rows/new_on").add_query_param("provider", "8").await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "region is required");
+    let response = server.get("/api/rows/new_on").add_query_param("provider", "0").add_query_param("region", "US").await;
+    assert_eq!(response.text(), "invalid provider id '0'");
+
+    let body = server
+        .get("/api/rows/new_on")
+        .add_query_param("provider", "8")
+        .add_query_param("region", "us")
+        .add_query_param("media_type", "tv")
+        .await
+        .json::<models::TmdbResponse>();
+    assert_eq!(body.results[0].title.as_deref(), Some("Streaming on [8] in US"));
+    assert_eq!(body.results[0].media_type.as_deref(), Some("tv"));
+}
+
+#[tokio::test]
+async fn test_new_on_rows_cached_and_refreshed_per_provider_and_region() {
+    let mock_client = Arc::new(MockTmdbClient::new());
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    let state = AppState::new(mock_client.clone()).with_clock(clock.clone());
+    let app = Router::new()
+        .route("/api/rows/new_on", get(handlers::get_new_on))
+        .with_state(state.clone());
+    let server = TestServer::new(app).unwrap();
+    let row = |provider: &'static str, region: &'static str| {
+        server.get("/api/rows/new_on").add_query_param("provider", provider).add_query_param("region", region)
+    };
+
+    row("8", "US").await.assert_status_ok();
+    row("8", "US").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 1);
+    row("8", "GB").await.assert_status_ok();
+    row("337", "US").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 3);
+
+    // The scheduler refetches cached rows and keeps them for another TTL
+    clock.advance(Duration::from_secs(5 * 60 * 60));
+    let ttl = state.cache_policies.new_on;
+    assert_eq!(state.new_on.refresh(mock_client.as_ref(), clock.now(), ttl).await, 3);
+    assert_eq!(mock_client.calls(), 6);
+    clock.advance(Duration::from_secs(2 * 60 * 60));
+    row("8", "US").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 6);
+
+    // Rows nobody asked for within a TTL are dropped instead of refreshed
+    clock.advance(ttl);
+    assert_eq!(state.new_on.refresh(mock_client.as_ref(), clock.now(), ttl).await, 0);
+    row("8", "US").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 7);
+}
+
+#[tokio::test]
+async fn test_new_on_rows_not_requested_stop_refreshing() {
+    let mock_client = Arc::new(MockTmdbClient::new());
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    let state = AppState::new(mock_client.clone()).with_clock(clock.clone());
+    let app = Router::new()
+        .route("/api/rows/new_on", get(handlers::get_new_on))
+        .with_state(state.clone());
+    let server = TestServer::new(app).unwrap();
+
+    server.get("/api/rows/new_on").add_query_param("provider", "8").add_query_param("region", "US").await.assert_status_ok();
+
+    // Hourly refreshes keep the row fresh, but don't count as requests
+    let ttl = state.cache_policies.new_on;
+    let hours = ttl.as_secs() / 3600;
+    for _ in 1..hours {
+        clock.advance(Duration::from_secs(60 * 60));
+        assert_eq!(state.new_on.refresh(mock_client.as_ref(), clock.now(), ttl).await, 1);
+    }
+    clock.advance(Duration::from_secs(60 * 60));
+    assert_eq!(state.new_on.refresh(mock_client.as_ref(), clock.now(), ttl).await, 0);
+    assert_eq!(state.new_on.refresh(mock_client.as_ref(), clock.now(), ttl).await, 0);
+    assert_eq!(mock_client.calls(), hours as usize);
+}
+
+#[tokio::test]
+async fn test_network_details_with_logo() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/network/49").await;
+    response.assert_status_ok();
+
+    let network = response.json::<models::NetworkDetails>();
+    assert_eq!(network.name, "HBO");
+    assert_eq!(network.logo_url.as_deref(), Some("https://image.tmdb.org/t/p/w300/hbo.png"));
+    assert_eq!(network.homepage, None);
+}
+
+#[tokio::test]
+async fn test_network_not_found() {
+    let client = MockTmdbClient::builder()
+        .with_network_response(999, Err(TmdbError::NotFound))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    server.get("/api/network/999").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_tv_season_lists_episodes() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/tv/1399/season/1").await;
+    response.assert_status_ok();
+
+    let season = response.json::<models::Season>();
+    assert_eq!(season.season_number, 1);
+    assert_eq!(season.episodes.iter().map(|episode| episode.episode_number).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(season.episodes[0].name, "Winter Is Coming");
+    assert_eq!(season.episodes[0].still_path.as_deref(), Some("/still1.jpg"));
+    assert_eq!(response.json::<serde_json::Value>()["episodes"][0]["air_date"], "2011-04-17");
+    assert_eq!(season.episodes[1].air_date, None);
+}
+
+#[tokio::test]
+async fn test_tv_season_not_found() {
+    let client = MockTmdbClient::builder()
+        .with_season_response(1399, 9, Err(TmdbError::NotFound))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    server.get("/api/tv/1399/season/9").await.assert_status_not_found();
+    server.get("/api/tv/0/season/1").await.assert_status_not_found();
+    server.get("/api/tv/1399/season/-1").await.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_where_to_start_orders_aired_episodes() {
+    let premiere = models::Episode {
+        id: 249260,
+        episode_number: 1,
+        name: "The North Remembers".to_string(),
+        overview: None,
+        air_date: "2012-04-01".parse().ok(),
+        still_path: None,
+        runtime: None,
+    };
+    let second_season = models::Season {
+        id: 3625,
+        season_number: 2,
+        name: "Season 2".to_string(),
+        overview: None,
+        air_date: "2012-04-01".parse().ok(),
+        poster_path: None,
+        episodes: vec![premiere],
+    };
+    let client = MockTmdbClient::builder()
+        .with_season_response(1399, 0, Err(TmdbError::NotFound))
+        .with_season_response(1399, 2, Ok(second_season))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    let body = server.get("/api/tv/1399/where_to_start").await.json::<serde_json::Value>();
+    assert_eq!(body["order"], "release");
+    assert_eq!(body["start"]["name"], "Winter Is Coming");
+    let names: Vec<&str> = body["episodes"].as_array().unwrap().iter().map(|entry| entry["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["Winter Is Coming", "The North Remembers"]);
+
+    let body = server.get("/api/tv/1399/where_to_start").add_query_param("order", "chronological").await.json::<serde_json::Value>();
+    assert_eq!(body["order"], "chronological");
+    assert_eq!(body["episodes"][1]["season_number"], 2);
+
+    server.get("/api/tv/1399/where_to_start").add_query_param("order", "random").await.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_where_to_start_propagates_season_failures() {
+    let client = MockTmdbClient::builder()
+        .with_season_response(1399, 1, Err(TmdbError::ServerError(503)))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    assert_eq!(server.get("/api/tv/1399/where_to_start").await.status_code(), 502);
+    server.get("/api/tv/0/where_to_start").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_collection_where_to_start_uses_release_order() {
+    let client = MockTmdbClient::builder().with_collection_response(99, Err(TmdbError::NotFound)).build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    let body = server.get("/api/collection/2344/where_to_start").await.json::<serde_json::Value>();
+    assert_eq!(body["order"], "release");
+    assert_eq!(body["start"]["title"], "The Matrix");
+    let ids: Vec<i64> = body["movies"].as_array().unwrap().iter().map(|movie| movie["id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, [603, 604, 624860]);
+
+    server.get("/api/collection/99/where_to_start").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_trending_includes_vote_count_and_quality_score() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let item = server.get("/api/trending").await.json::<serde_json::Value>()["results"][0].clone();
+    assert_eq!(item["vote_count"], 1200);
+    assert_eq!(item["quality_score"], 8.16);
+}
+
+#[tokio::test]
+async fn test_list_sorted_by_quality() {
+    let results = serde_json::from_value::<Vec<models::Movie>>(json!([
+        { "id": 1, "title": "Hyped", "vote_average": 9.8, "vote_count": 4 },
+        { "id": 2, "title": "Classic", "vote_average": 8.6, "vote_count": 30000 }
+    ]))
+    .unwrap();
+    let client = MockTmdbClient::builder()
+        .with_default_trending(Ok(models::TmdbResponse { page: 1, results, total_pages: 1 }))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(client)).unwrap();
+
+    let default_order = server.get("/api/trending").await.json::<models::TmdbResponse>();
+    assert_eq!(default_order.results[0].id, 1);
+
+    let by_quality = server
+        .get("/api/trending")
+        .add_query_param("sort", "quality")
+        .await
+        .json::<models::TmdbResponse>();
+    assert_eq!(by_quality.results[0].id, 2);
+
+    server.get("/api/trending").add_query_param("sort", "loudest").await.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_ranking_experiment_picks_strategy() {
+    let experiments = Experiments::from_json(r#"[{"name": "ranking", "variants": ["fresh"]}]"#).unwrap();
+    let strategies = RankingStrategies::from_json(r#"{"fresh": "recency=1"}"#).unwrap();
+    let state = AppState::new(Arc::new(MockTmdbClient::new()))
+        .with_experiments(experiments)
+        .with_ranking_strategies(strategies);
+    let app = Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/top_rated", get(handlers::get_top_rated))
+        .layer(middleware::from_fn_with_state(state.clone(), experiments::experiments_middleware))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+    let ids = |response: models::TmdbResponse| response.results.iter().map(|item| item.id).collect::<Vec<_>>();
+
+    let enrolled = server.get("/api/trending").add_header("x-user-id", "user-1").await.json();
+    let anonymous = server.get("/api/trending").await.json();
+    let sorted = server.get("/api/trending?sort=quality").add_header("x-user-id", "user-1").await.json();
+
+    // The show is the newer of the two trending titles
+    assert_eq!(ids(enrolled), [456, 123]);
+    assert_eq!(ids(anonymous), [123, 456]);
+    // An explicit sort isn't reordered by the experiment
+    assert_eq!(ids(sorted), [123, 456]);
+}
+
+#[tokio::test]
+async fn test_home_rows_skip_repeated_titles() {
+    let trending = models::TmdbResponse {
+        page: 1,
+        total_pages: 1,
+        results: vec![models::Movie {
+            id: 321,
+            title: Some("Blockbuster".to_string()),
+            name: None,
+            overview: None,
+            poster_path: None,
+            backdrop_path: None,
+            vote_average: Some(7.9),
+            vote_count: Some(5000),
+            quality_score: None,
+            has_trailer: None,
+            providers: None,
+            cast: None,
+            genre_ids: vec![28],
+            release_date: None,
+            media_type: Some("movie".to_string()),
+            adult: false,
+        }],
+    };
+    let mock_client = MockTmdbClient::builder().with_trending_response(1, Ok(trending)).build();
+    let state = AppState::new(Arc::new(mock_client));
+    let app = Router::new()
+        .route("/api/home", get(handlers::get_home))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/api/home").await;
+
+    assert_eq!(response.status_code(), 200);
+    let home: HomePage = response.json();
+    let rows: Vec<(&str, Vec<i64>)> = home
+        .rows
+        .iter()
+        .map(|row| (row.id.as_str(), row.items.iter().map(|item| item.id).collect()))
+        .collect();
+    // The trending blockbuster is also the top discovered movie; the TV show with the same id is a different title
+    assert_eq!(rows, [("trending", vec![321]), ("popular_movies", vec![]), ("popular_tv", vec![321])]);
+    assert!(home.rows[0].items[0].quality_score.is_some());
+}
+
+#[tokio::test]
+async fn test_home_keeps_rows_when_one_fails() {
+    let mock_client = MockTmdbClient::builder().with_trending_error(1, TmdbError::ServerError(500)).build();
+    let state = AppState::new(Arc::new(mock_client));
+    let app = Router::new()
+        .route("/api/home", get(handlers::get_home))
+        .with_state(state.clone());
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/api/home").await;
+
+    assert_eq!(response.status_code(), 200);
+    let home: HomePage = response.json();
+    let rows: Vec<(&str, RowStatus, Option<&str>, usize)> = home
+        .rows
+        .iter()
+        .map(|row| (row.id.as_str(), row.status, row.error.as_deref(), row.items.len()))
+        .collect();
+    assert_eq!(rows, [
+        ("trending", RowStatus::Error, Some("Upstream server error"), 0),
+        ("popular_movies", RowStatus::Ok, None, 1),
+        ("popular_tv", RowStatus::Ok, None, 1),
+    ]);
+    let failures: Vec<(String, u64)> = state.home_row_metrics.stats().into_iter().map(|stats| (stats.row, stats.errors)).collect();
+    assert_eq!(failures, [("popular_movies".to_string(), 0), ("popular_tv".to_string(), 0), ("trending".to_string(), 1)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_slow_home_row_times_out() {
+    let mock_client = MockTmdbClient::builder().with_latency(Duration::from_secs(10)).build();
+    let layout = HomeLayout::from_json(&json!({
+        "rows": [
+            { "id": "trending", "title": "Trending now", "source": "trending", "timeout": "1s" },
+            { "id": "gone", "title": "Deleted list", "source": "curated_list", "slug": "gone" }
+        ]
+    }).to_string()).unwrap();
+    let state = AppState::new(Arc::new(mock_client)).with_home_layout(layout);
+    let app = Router::new()
+        .route("/api/home", get(handlers::get_home))
+        .with_state(state.clone());
+    let server = TestServer::new(app).unwrap();
+
+    let home: HomePage = server.get("/api/home").await.json();
+
+    assert_eq!(home.rows[0].status, RowStatus::Error);
+    assert_eq!(home.rows[0].error.as_deref(), Some("Row timed out"));
+    assert_eq!(home.rows[1].status, RowStatus::Ok);
+    assert_eq!(state.home_row_metrics.stats()[1].timeouts, 1);
+}
+
+#[tokio::test]
+async fn test_home_follows_configured_layout() {
+    let mock_client = Arc::new(MockTmdbClient::new());
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    let layout = HomeLayout::from_json(&json!({
+        "rows": [
+            { "id": "picks", "title": "Staff picks", "kind": "hero", "source": "curated_list", "slug": "staff-picks" },
+            { "id": "dramas", "title": "Dramas", "kind": "ranked", "source": "genre", "media_type": "movie", "genre": 18, "ttl": "1m" },
+            { "id": "gone", "title": "Deleted list", "source": "curated_list", "slug": "gone" }
+        ]
+    }).to_string()).unwrap();
+    let state = AppState::new(mock_client.clone())
+        .with_clock(clock.clone())
+        .with_home_layout(layout);
+    let picks = CuratedListInput {
+        title: "Staff picks".to_string(),
+        description: None,
+        items: vec![models::TitleRef { media_type: models::MediaType::Tv, id: models::TmdbId::try_from(1399).unwrap() }],
+    };
+    state.lists.put("staff-picks", picks, UNIX_EPOCH).unwrap();
+    let app = Router::new()
+        .route("/api/home", get(handlers::get_home))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    let home: HomePage = server.get("/api/home").await.json();
+    let rows: Vec<(&str, RowKind, usize)> = home.rows.iter().map(|row| (row.id.as_str(), row.kind, row.items.len())).collect();
+    assert_eq!(rows, [("picks", RowKind::Hero, 1), ("dramas", RowKind::Ranked, 1), ("gone", RowKind::Carousel, 0)]);
+    assert_eq!(home.rows[0].items[0].media_type.as_deref(), Some("tv"));
+    assert_eq!(home.rows[1].items[0].title.as_deref(), Some("In genre 18"));
+    assert_eq!(mock_client.calls(), 2);
+
+    // Both rows are served from cache until the genre row's TTL passes
+    server.get("/api/home").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 2);
+    clock.advance(Duration::from_secs(61));
+    server.get("/api/home").await.assert_status_ok();
+    assert_eq!(mock_client.calls(), 3);
+}
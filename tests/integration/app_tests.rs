@@ -0,0 +1,89 @@
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{test_app, AppOptions, AppStateOverrides}, budget::{BudgetLimits, RequestBudget}, dev_client::DevTmdbClient, error::TmdbError, upstream_metrics::Operation};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const TOKEN: &str = "test-admin-token";
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client).with_admin_token(TOKEN))).unwrap()
+}
+
+#[tokio::test]
+async fn test_responses_pass_through_the_production_layers() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+
+    let response = server.get("/api/trending").add_header("origin", "http://example.com").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("access-control-allow-origin"), "*");
+    assert!(response.header("etag").to_str().unwrap().starts_with('"'));
+    assert!(response.maybe_header("cache-control").is_some());
+}
+
+#[tokio::test]
+async fn test_admin_routes_need_the_token() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+
+    server.get("/admin/mode").await.assert_status_unauthorized();
+    server.get("/admin/mode").authorization_bearer(TOKEN).await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_fake_client_sits_behind_the_upstream_decorators() {
+    let client = Arc::new(MockTmdbClient::builder().with_video_error(404, TmdbError::NotFound).build());
+    let server = create_test_server(client.clone());
+
+    server.get("/api/movie/404/videos").await.assert_status_not_found();
+    server.get("/api/movie/404/videos").await.assert_status_not_found();
+
+    // The second miss is answered by the negative cache
+    assert_eq!(client.calls_to(Operation::Videos), 1);
+    let upstream = server.get("/admin/metrics/upstream").authorization_bearer(TOKEN).await.json::<Value>();
+    assert!(upstream.to_string().contains("videos"));
+}
+
+#[tokio::test]
+async fn test_maintenance_mode_applies_to_the_whole_app() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+
+    server
+        .put("/admin/mode")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "mode": "maintenance" }))
+        .await
+        .assert_status_ok();
+
+    server.get("/api/trending").await.assert_status_service_unavailable();
+}
+
+#[tokio::test]
+async fn test_overrides_configure_state_and_options() {
+    let limits = BudgetLimits { capacity: 0, refill_per_sec: 0 };
+    let app = test_app(
+        AppStateOverrides::new(Arc::new(MockTmdbClient::new()))
+            .with_state(move |state| state.with_budget(RequestBudget::new(limits)))
+            .with_admin_token(TOKEN)
+            .with_options(AppOptions { profiling: true, ..AppOptions::default() }),
+    );
+    let server = TestServer::new(app).unwrap();
+
+    let batch = json!({ "items": [{ "media_type": "movie", "id": 603 }] });
+    server.post("/api/titles/batch").json(&batch).await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    server.get("/debug/pprof/allocs").authorization_bearer(TOKEN).await.assert_status_ok();
+    // Profiling is off unless the options switch it on
+    let unmounted = create_test_server(Arc::new(MockTmdbClient::new()));
+    unmounted.get("/debug/pprof/allocs").authorization_bearer(TOKEN).await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_dev_catalog_serves_the_app_offline() {
+    let server = TestServer::new(test_app(AppStateOverrides::new(Arc::new(DevTmdbClient::new())))).unwrap();
+
+    let home = server.get("/api/home").await.json::<Value>();
+    assert!(home.to_string().contains("The Matrix"));
+    server.get("/api/movie/603/videos").await.assert_status_ok();
+    server.get("/title/tv/1399").await.assert_status_ok();
+}
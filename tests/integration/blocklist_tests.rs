@@ -0,0 +1,87 @@
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::app::{test_app, AppStateOverrides};
+use netflix_service::upstream_metrics::Operation;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const TOKEN: &str = "test-admin-token";
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client).with_admin_token(TOKEN))).unwrap()
+}
+
+async fn set_blocklist(server: &TestServer, rules: Value) {
+    server.put("/admin/blocklist").authorization_bearer(TOKEN).json(&rules).await.assert_status_ok();
+}
+
+fn ids(body: &Value) -> Vec<i64> {
+    body["results"].as_array().unwrap().iter().map(|item| item["id"].as_i64().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_blocked_titles_leave_lists_and_answer_451() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+    assert_eq!(ids(&server.get("/api/trending").await.json::<Value>()), [123, 456]);
+
+    set_blocklist(&server, json!({ "titles": [{ "media_type": "movie", "id": 123 }] })).await;
+
+    assert_eq!(ids(&server.get("/api/trending").await.json::<Value>()), [456]);
+    server.get("/title/movie/123").await.assert_status(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+    let videos = server.get("/api/movie/123/videos").await;
+    videos.assert_status(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+    assert_eq!(client.calls_to(Operation::Videos), 0);
+    // Blocks are per media type
+    server.get("/title/tv/123").await.assert_status_ok();
+
+    set_blocklist(&server, json!({})).await;
+    assert_eq!(ids(&server.get("/api/trending").await.json::<Value>()), [123, 456]);
+}
+
+#[tokio::test]
+async fn test_keyword_rules_match_whole_words() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+
+    set_blocklist(&server, json!({ "keywords": ["match", "Gore"] })).await;
+    assert_eq!(ids(&server.get("/api/search").add_query_param("query", "x").await.json::<Value>()), [789]);
+    // Found among the title's TMDB keywords
+    server.get("/title/movie/5").await.assert_status(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+
+    set_blocklist(&server, json!({ "keywords": ["matching content"] })).await;
+    assert!(ids(&server.get("/api/search").add_query_param("query", "x").await.json::<Value>()).is_empty());
+}
+
+#[tokio::test]
+async fn test_blocked_batch_items_fail_alone() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+    set_blocklist(&server, json!({ "titles": [{ "media_type": "movie", "id": 123 }] })).await;
+
+    let results = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 123 }, { "media_type": "movie", "id": 7 }] }))
+        .await
+        .json::<Value>();
+
+    assert_eq!(results[0]["error"]["status"], 451);
+    assert_eq!(results[1]["details"]["id"], 7);
+}
+
+#[tokio::test]
+async fn test_invalid_rules_keep_the_current_blocklist() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+    let rules = json!({ "titles": [{ "media_type": "tv", "id": 456 }], "keywords": ["banned"] });
+    set_blocklist(&server, rules.clone()).await;
+
+    server
+        .put("/admin/blocklist")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "keywords": [" -- "] }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let current = server.get("/admin/blocklist").authorization_bearer(TOKEN).await.json::<Value>();
+    assert_eq!(current, rules);
+    server.get("/admin/blocklist").await.assert_status(StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,38 @@
+use axum::{http::{header, StatusCode}, middleware, routing::{get, post}, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{budget::{self, BudgetLimits, RequestBudget}, handlers, session::{self, SESSION_HEADER}, state::AppState};
+use serde_json::json;
+use std::sync::Arc;
+
+fn create_test_app(limits: BudgetLimits) -> Router {
+    let state = AppState::new(Arc::new(MockTmdbClient::new())).with_budget(RequestBudget::new(limits));
+
+    Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
+        .layer(middleware::from_fn_with_state(state.clone(), budget::budget_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), session::session_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_batch_requests_are_limited_per_session() {
+    let server = TestServer::new(create_test_app(BudgetLimits { capacity: 20, refill_per_sec: 0 })).unwrap();
+    let body = json!({ "items": [{ "media_type": "movie", "id": 603 }] });
+
+    let first = server.post("/api/titles/batch").json(&body).await;
+    first.assert_status_ok();
+    let token = first.header(SESSION_HEADER).to_str().unwrap().to_string();
+
+    let batch = || server.post("/api/titles/batch").add_header(SESSION_HEADER, token.as_str()).json(&body);
+    batch().await.assert_status_ok();
+
+    let refused = batch().await;
+    refused.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(refused.header(header::RETRY_AFTER), "60");
+
+    // Free routes and other sessions are unaffected
+    server.get("/api/trending").add_header(SESSION_HEADER, token.as_str()).await.assert_status_ok();
+    server.post("/api/titles/batch").json(&body).await.assert_status_ok();
+}
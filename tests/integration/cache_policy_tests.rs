@@ -0,0 +1,57 @@
+use axum::{http::{header, StatusCode}, middleware, routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{cache_policy::{self, CachePolicies}, error::TmdbError, etag, handlers, state::AppState};
+use std::sync::Arc;
+
+fn create_test_app(client: MockTmdbClient, policies: CachePolicies) -> Router {
+    let state = AppState::new(Arc::new(client)).with_cache_policies(policies);
+
+    Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/search", get(handlers::search_content))
+        .route("/api/session/recent", get(handlers::get_session_recent))
+        .layer(middleware::from_fn(etag::etag_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), cache_policy::cache_control_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_route_ttls_set_cache_control() {
+    let server = TestServer::new(create_test_app(MockTmdbClient::new(), CachePolicies::default())).unwrap();
+
+    let trending = server.get("/api/trending").await;
+    assert_eq!(trending.header(header::CACHE_CONTROL), "private, max-age=300");
+
+    let search = server.get("/api/search").add_query_param("query", "matrix").await;
+    assert_eq!(search.header(header::CACHE_CONTROL), "private, max-age=60");
+}
+
+#[tokio::test]
+async fn test_not_modified_keeps_cache_control() {
+    let server = TestServer::new(create_test_app(MockTmdbClient::new(), CachePolicies::default())).unwrap();
+
+    let etag = server.get("/api/trending").await.header(header::ETAG);
+    let response = server.get("/api/trending").add_header(header::IF_NONE_MATCH, etag).await;
+
+    response.assert_status(StatusCode::NOT_MODIFIED);
+    assert_eq!(response.header(header::CACHE_CONTROL), "private, max-age=300");
+}
+
+#[tokio::test]
+async fn test_no_cache_control_without_policy_or_on_errors() {
+    let client = MockTmdbClient::builder()
+        .with_trending_error(1, TmdbError::ServerError(503))
+        .build();
+    let policies = CachePolicies::parse("search=0s").unwrap();
+    let server = TestServer::new(create_test_app(client, policies)).unwrap();
+
+    let failed = server.get("/api/trending").await;
+    assert!(failed.maybe_header(header::CACHE_CONTROL).is_none());
+
+    let disabled = server.get("/api/search").add_query_param("query", "matrix").await;
+    assert!(disabled.maybe_header(header::CACHE_CONTROL).is_none());
+
+    let uncached = server.get("/api/session/recent").await;
+    assert!(uncached.maybe_header(header::CACHE_CONTROL).is_none());
+}
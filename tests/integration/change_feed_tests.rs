@@ -0,0 +1,66 @@
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::error::TmdbError;
+use netflix_service::models::{ChangedTitle, ChangesResponse, MediaType};
+use netflix_service::state::AppState;
+use netflix_service::upstream_metrics::Operation;
+use std::sync::Arc;
+use std::time::Duration;
+
+const TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn changed(ids: &[i64]) -> Result<ChangesResponse, TmdbError> {
+    Ok(ChangesResponse { results: ids.iter().map(|id| ChangedTitle { id: *id }).collect(), page: 1, total_pages: 1 })
+}
+
+#[tokio::test]
+async fn test_poll_invalidates_changed_titles() {
+    let client = Arc::new(
+        MockTmdbClient::builder()
+            .with_changes_response(MediaType::Movie, changed(&[603, 9999]))
+            .with_changes_response(MediaType::Tv, changed(&[1399]))
+            .build()
+    );
+    let state = AppState::new(client.clone());
+    let now = state.clock.now();
+    state.trailers.store(603, true, now);
+    state.trailers.store(550, true, now);
+    state.logos.store(MediaType::Tv, 1399, "en", None, now, TTL);
+    state.logos.store(MediaType::Movie, 1399, "en", None, now, TTL);
+
+    assert_eq!(state.changes.poll(&state).await.unwrap(), 2);
+
+    assert_eq!(state.trailers.get(603, now, TTL), None);
+    assert_eq!(state.trailers.get(550, now, TTL), Some(true));
+    assert!(state.logos.get(MediaType::Tv, 1399, "en", now).is_none());
+    // Same id, other media type
+    assert!(state.logos.get(MediaType::Movie, 1399, "en", now).is_some());
+    assert_eq!(client.calls_to(Operation::Changes), 2);
+
+    // The first poll read from yesterday, so the next one reads today's
+    // changes afresh; after that, handled titles are skipped
+    state.trailers.store(603, false, now);
+    assert_eq!(state.changes.poll(&state).await.unwrap(), 1);
+    state.trailers.store(603, false, now);
+    assert_eq!(state.changes.poll(&state).await.unwrap(), 0);
+    assert_eq!(state.trailers.get(603, now, TTL), Some(false));
+
+    let stats = state.changes.stats();
+    assert_eq!((stats.changed, stats.invalidated), (6, 3));
+    assert!(stats.last_polled.is_some());
+}
+
+#[tokio::test]
+async fn test_failed_poll_keeps_caches() {
+    let client = MockTmdbClient::builder()
+        .with_changes_response(MediaType::Movie, changed(&[603]))
+        .with_changes_response(MediaType::Tv, Err(TmdbError::ServerError(503)))
+        .build();
+    let state = AppState::new(Arc::new(client));
+    let now = state.clock.now();
+    state.trailers.store(603, true, now);
+
+    assert!(matches!(state.changes.poll(&state).await, Err(TmdbError::ServerError(503))));
+
+    assert_eq!(state.trailers.get(603, now, TTL), Some(true));
+    assert!(state.changes.stats().last_polled.is_none());
+}
@@ -0,0 +1,73 @@
+use axum::{http::StatusCode, middleware, routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{deadline::{self, DEADLINE_HEADER}, handlers, state::AppState};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+fn create_test_app(client: Arc<MockTmdbClient>) -> Router {
+    let state = AppState::new(client);
+
+    Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .layer(middleware::from_fn_with_state(state.clone(), deadline::deadline_middleware))
+        .with_state(state)
+}
+
+/// `X-Request-Deadline` value `offset_ms` from now, in the past when negative
+fn deadline_in(offset_ms: i64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as i64;
+    (now + offset_ms).to_string()
+}
+
+#[tokio::test]
+async fn test_request_within_deadline_succeeds() {
+    let server = TestServer::new(create_test_app(Arc::new(MockTmdbClient::new()))).unwrap();
+
+    server
+        .get("/api/trending")
+        .add_header(DEADLINE_HEADER, deadline_in(60_000))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_expired_deadline_skips_upstream() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = TestServer::new(create_test_app(client.clone())).unwrap();
+
+    server
+        .get("/api/trending")
+        .add_header(DEADLINE_HEADER, deadline_in(-1_000))
+        .await
+        .assert_status(StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(client.calls(), 0);
+}
+
+// Paused time skips ahead through the mock's latency, so the test doesn't wait
+#[tokio::test(start_paused = true)]
+async fn test_slow_upstream_is_abandoned_at_deadline() {
+    let client = Arc::new(MockTmdbClient::builder().with_latency(Duration::from_secs(10)).build());
+    let server = TestServer::new(create_test_app(client.clone())).unwrap();
+
+    let started = Instant::now();
+    server
+        .get("/api/trending")
+        .add_header(DEADLINE_HEADER, deadline_in(100))
+        .await
+        .assert_status(StatusCode::GATEWAY_TIMEOUT);
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(client.calls_to_trending(), 1);
+}
+
+#[tokio::test]
+async fn test_malformed_deadline_is_rejected() {
+    let server = TestServer::new(create_test_app(Arc::new(MockTmdbClient::new()))).unwrap();
+
+    server
+        .get("/api/trending")
+        .add_header(DEADLINE_HEADER, "tomorrow")
+        .await
+        .assert_status_bad_request();
+}
@@ -0,0 +1,85 @@
+use axum::{middleware, routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{etag, handlers, state::AppState};
+use std::sync::Arc;
+
+fn create_test_app() -> Router {
+    let state = AppState::new(Arc::new(MockTmdbClient::new()));
+
+    Router::new()
+        .route("/", get(handlers::root))
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/search", get(handlers::search_content))
+        .layer(middleware::from_fn(etag::etag_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_json_response_has_etag() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/trending").await;
+
+    assert_eq!(response.status_code(), 200);
+    let etag = response.header("etag");
+    assert!(etag.to_str().unwrap().starts_with('"'));
+}
+
+#[tokio::test]
+async fn test_etag_is_identical_across_instances() {
+    // Two independent servers stand in for two replicas
+    let replica_a = TestServer::new(create_test_app()).unwrap();
+    let replica_b = TestServer::new(create_test_app()).unwrap();
+
+    let etag_a = replica_a.get("/api/search?query=matrix&page=2").await.header("etag");
+    let etag_b = replica_b.get("/api/search?page=2&query=matrix").await.header("etag");
+
+    assert_eq!(etag_a, etag_b);
+}
+
+#[tokio::test]
+async fn test_etag_differs_by_params() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let page1 = server.get("/api/trending?page=1").await.header("etag");
+    let page2 = server.get("/api/trending?page=2").await.header("etag");
+
+    assert_ne!(page1, page2);
+}
+
+#[tokio::test]
+async fn test_if_none_match_returns_not_modified() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let etag = server.get("/api/trending").await.header("etag");
+    let response = server
+        .get("/api/trending")
+        .add_header("if-none-match", etag.clone())
+        .await;
+
+    assert_eq!(response.status_code(), 304);
+    assert_eq!(response.header("etag"), etag);
+    assert!(response.text().is_empty());
+}
+
+#[tokio::test]
+async fn test_stale_if_none_match_returns_full_body() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/api/trending")
+        .add_header("if-none-match", "\"0000000000000000\"")
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+}
+
+#[tokio::test]
+async fn test_plain_text_response_has_no_etag() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/").await;
+
+    assert!(response.maybe_header("etag").is_none());
+}
@@ -0,0 +1,95 @@
+use axum::Router;
+use axum_test::TestServer;
+use netflix_service::frontend;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes a minimal SPA build into a fresh temp directory
+fn create_frontend_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("netflix-frontend-{}-{}", name, std::process::id()));
+    fs::create_dir_all(dir.join("assets")).unwrap();
+    fs::write(dir.join("index.html"), "<html>index</html>").unwrap();
+    fs::write(dir.join("assets/app.3f2a9c1b.js"), "console.log('app')").unwrap();
+    fs::write(dir.join("favicon.ico"), "icon").unwrap();
+    dir
+}
+
+fn create_test_app(dir: &PathBuf) -> Router {
+    frontend::router(dir)
+}
+
+#[tokio::test]
+async fn test_serves_index() {
+    let dir = create_frontend_dir("index");
+    let server = TestServer::new(create_test_app(&dir)).unwrap();
+
+    let response = server.get("/app/").await;
+
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.text(), "<html>index</html>");
+    assert_eq!(response.header("cache-control"), "no-cache");
+}
+
+#[tokio::test]
+async fn test_client_side_route_falls_back_to_index() {
+    let dir = create_frontend_dir("fallback");
+    let server = TestServer::new(create_test_app(&dir)).unwrap();
+
+    let response = server.get("/app/browse/movie/550").await;
+
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.text(), "<html>index</html>");
+    assert_eq!(response.header("cache-control"), "no-cache");
+}
+
+#[tokio::test]
+async fn test_hashed_assets_are_immutable() {
+    let dir = create_frontend_dir("assets");
+    let server = TestServer::new(create_test_app(&dir)).unwrap();
+
+    let response = server.get("/app/assets/app.3f2a9c1b.js").await;
+
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.text(), "console.log('app')");
+    assert_eq!(
+        response.header("cache-control"),
+        "public, max-age=31536000, immutable"
+    );
+}
+
+#[tokio::test]
+async fn test_missing_hashed_assets_are_not_found() {
+    let dir = create_frontend_dir("missing-asset");
+    let server = TestServer::new(create_test_app(&dir)).unwrap();
+
+    let response = server.get("/app/assets/app.0000000.js").await;
+
+    assert_eq!(response.status_code(), 404);
+    assert!(response.maybe_header("cache-control").is_none());
+}
+
+#[tokio::test]
+async fn test_unhashed_files_are_revalidated() {
+    let dir = create_frontend_dir("unhashed");
+    let server = TestServer::new(create_test_app(&dir)).unwrap();
+
+    let response = server.get("/app/favicon.ico").await;
+
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.header("cache-control"), "no-cache");
+}
+
+/// Runs against whatever UI `frontend/dist` held at build time
+#[cfg(feature = "embed-frontend")]
+#[tokio::test]
+async fn test_embedded_frontend_falls_back_like_the_directory() {
+    let server = TestServer::new(frontend::embedded_router::<()>()).unwrap();
+
+    let index = server.get("/app/").await;
+    let route = server.get("/app/browse/movie/550").await;
+    assert_eq!(route.status_code(), index.status_code());
+    assert_eq!(route.text(), index.text());
+    assert_eq!(route.header("cache-control"), "no-cache");
+
+    server.get("/app/assets/missing.0000000.js").await.assert_status_not_found();
+}
@@ -0,0 +1,71 @@
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{test_app, AppStateOverrides}, error::TmdbError, upstream_metrics::Operation};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TOKEN: &str = "test-admin-token";
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client).with_admin_token(TOKEN))).unwrap()
+}
+
+#[tokio::test]
+async fn test_healthy_upstream() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let first = server.get("/health/upstream").await;
+    first.assert_status_ok();
+    let body = first.json::<Value>();
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["circuit"], "closed");
+    assert_eq!(body["mode"], "normal");
+    assert_eq!(body["probe"]["ok"], true);
+    assert_eq!(body["cached"], false);
+
+    // A second check within the TTL reuses the probe
+    assert_eq!(server.get("/health/upstream").await.json::<Value>()["cached"], true);
+    assert_eq!(client.calls_to(Operation::Network), 1);
+}
+
+#[tokio::test]
+async fn test_failing_upstream_is_down() {
+    let client = MockTmdbClient::builder().with_network_response(49, Err(TmdbError::ServerError(502))).build();
+    let server = create_test_server(Arc::new(client));
+
+    let response = server.get("/health/upstream").await;
+
+    response.assert_status_service_unavailable();
+    let body = response.json::<Value>();
+    assert_eq!(body["status"], "down");
+    assert_eq!(body["probe"]["ok"], false);
+    assert!(body["probe"]["error"].as_str().unwrap().contains("502"));
+}
+
+#[tokio::test]
+async fn test_slow_upstream_is_degraded() {
+    let client = MockTmdbClient::builder().with_latency(Duration::from_millis(600)).build();
+    let server = create_test_server(Arc::new(client));
+
+    let response = server.get("/health/upstream").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.json::<Value>()["status"], "degraded");
+}
+
+#[tokio::test]
+async fn test_maintenance_disables_the_probe_but_not_the_check() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+    server.put("/admin/mode").authorization_bearer(TOKEN).json(&json!({ "mode": "maintenance" })).await.assert_status_ok();
+
+    let response = server.get("/health/upstream").await;
+
+    response.assert_status_service_unavailable();
+    let body = response.json::<Value>();
+    assert_eq!(body["status"], "disabled");
+    assert_eq!(body["probe"], Value::Null);
+    assert_eq!(client.calls(), 0);
+}
@@ -0,0 +1,87 @@
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{test_app, AppStateOverrides}, error::TmdbError, models::{BatchTitleResult, MediaType}, upstream_metrics::Operation};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client))).unwrap()
+}
+
+fn cast_names(item: &Value) -> Vec<&str> {
+    item["cast"].as_array().unwrap().iter().map(|member| member["name"].as_str().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_credits_load_details_once_per_title() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let response = server.get("/api/trending").add_query_param("include", "credits,providers").await;
+
+    response.assert_status_ok();
+    let body = response.json::<Value>();
+    for item in body["results"].as_array().unwrap() {
+        // Top five in billing order
+        assert_eq!(cast_names(item), vec!["Actor 1", "Actor 2", "Actor 3", "Actor 4", "Actor 5"]);
+        assert_eq!(item["providers"][0]["name"], "Netflix");
+    }
+    assert_eq!(client.calls_to(Operation::Details), 2);
+
+    // Each include answers the next load from its cache
+    server.get("/api/trending").add_query_param("include", "credits,providers").await;
+    assert_eq!(client.calls_to(Operation::Details), 2);
+    assert_eq!(client.calls_to(Operation::WatchProviders), 2);
+}
+
+#[tokio::test]
+async fn test_titles_whose_details_fail_have_no_credits() {
+    let client = MockTmdbClient::builder()
+        .with_details_error(MediaType::Movie, 123, TmdbError::ServerError(502))
+        .build();
+    let server = create_test_server(Arc::new(client));
+
+    let response = server.get("/api/trending").add_query_param("include", "credits").await;
+
+    response.assert_status_ok();
+    let body = response.json::<Value>();
+    assert!(body["results"][0].get("cast").is_none());
+    assert_eq!(cast_names(&body["results"][1]).len(), 5);
+}
+
+#[tokio::test]
+async fn test_batch_lookups_reuse_their_details() {
+    let client = Arc::new(MockTmdbClient::builder().with_details_error(MediaType::Movie, 404, TmdbError::NotFound).build());
+    let server = create_test_server(client.clone());
+
+    let results: Vec<BatchTitleResult> = server
+        .post("/api/titles/batch")
+        .add_query_param("include", "credits,providers")
+        .add_query_param("region", "GB")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 603 }, { "media_type": "movie", "id": 404 }] }))
+        .await
+        .json();
+
+    assert_eq!(results[0].cast.as_ref().unwrap()[0].name, "Actor 1");
+    assert_eq!(results[0].providers.as_ref().unwrap()[0].name, "Netflix");
+    assert!(results[1].error.is_some());
+    assert!(results[1].cast.is_none() && results[1].providers.is_none());
+    // Credits reuse the details the batch loaded, and failed titles aren't enriched
+    assert_eq!(client.calls_to(Operation::Details), 2);
+    assert_eq!(client.calls_to(Operation::WatchProviders), 1);
+}
+
+#[tokio::test]
+async fn test_unknown_includes_are_rejected() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let response = server.get("/api/trending").add_query_param("include", "videos,reviews").await;
+
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "unknown include 'reviews'");
+    assert_eq!(client.calls_to(Operation::Trending), 0);
+
+    // Details are only loaded for the includes built on them
+    server.get("/api/trending").add_query_param("include", "details").await.assert_status_bad_request();
+}
@@ -0,0 +1,74 @@
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{metadata::{MetadataChains, MetadataSources, OmdbMetadata}, models::{MediaType, TitleDetails}, services::CatalogService, state::AppState};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::{method, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn matrix() -> TitleDetails {
+    serde_json::from_value(json!({
+        "id": 603,
+        "title": "The Matrix",
+        "overview": "",
+        "vote_average": 7.0,
+        "vote_count": 100,
+        "release_date": "1999-03-30",
+        "runtime": 136,
+        "external_ids": { "imdb_id": "tt0133093" }
+    }))
+    .unwrap()
+}
+
+fn state_with_omdb(server: &MockServer, chains: &str) -> AppState {
+    let client = MockTmdbClient::builder().with_details_response(MediaType::Movie, 603, Ok(matrix())).build();
+    let omdb = OmdbMetadata::new("omdb-key".to_string(), Duration::from_secs(60)).with_base_url(server.uri());
+    let sources = MetadataSources::new(MetadataChains::parse(chains).unwrap(), vec![Arc::new(omdb)]).unwrap();
+    AppState::new(Arc::new(client)).with_metadata_sources(sources)
+}
+
+#[tokio::test]
+async fn test_field_groups_come_from_their_sources() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("i", "tt0133093"))
+        .and(query_param("apikey", "omdb-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "Response": "True",
+            "Title": "The Matrix",
+            "Plot": "A hacker learns the truth.",
+            "Released": "31 Mar 1999",
+            "Runtime": "N/A",
+            "imdbRating": "8.7",
+            "imdbVotes": "2,100,000"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let state = state_with_omdb(&server, "ratings=omdb+tmdb,text=tmdb+omdb");
+
+    let details = state.catalog().details(MediaType::Movie, 603).await.unwrap();
+    // OMDb is primary for ratings
+    assert_eq!((details.vote_average, details.vote_count), (Some(8.7), Some(2_100_000)));
+    // TMDB is primary for text; OMDb fills in the missing overview
+    assert_eq!(details.title.as_deref(), Some("The Matrix"));
+    assert_eq!(details.overview.as_deref(), Some("A hacker learns the truth."));
+    // Release isn't configured and stays TMDB's
+    assert_eq!(details.release_date, "1999-03-30".parse().ok());
+    assert_eq!(details.runtime, Some(136));
+
+    // OMDb answers are cached
+    state.catalog().details(MediaType::Movie, 603).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_failing_sources_fall_back_to_the_next() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET")).respond_with(ResponseTemplate::new(503)).mount(&server).await;
+    let state = state_with_omdb(&server, "ratings=omdb+tmdb,release=omdb");
+
+    let details = state.catalog().details(MediaType::Movie, 603).await.unwrap();
+    assert_eq!((details.vote_average, details.vote_count), (Some(7.0), Some(100)));
+    // Without TMDB in its chain, release has no source left
+    assert_eq!((details.release_date, details.runtime), (None, None));
+}
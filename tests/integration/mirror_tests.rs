@@ -0,0 +1,70 @@
+use axum::{extract::Query, routing::get, Json, Router};
+use netflix_service::{clock::SystemClock, error::TmdbError, mirrors::Mirrors, tmdb_client::{RealTmdbClient, TmdbClient}};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Serves a minimal TMDB trending endpoint on a random local port
+async fn spawn_mirror() -> String {
+    let app = Router::new().route(
+        "/3/trending/all/week",
+        get(|Query(params): Query<HashMap<String, String>>| async move {
+            let page: i32 = params["page"].parse().unwrap();
+            Json(json!({ "page": page, "total_pages": 1, "results": [{ "id": 603, "title": params["api_key"] }] }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}/3", addr)
+}
+
+/// A base URL nothing is listening on
+async fn unreachable_url() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    format!("http://{}/3", addr)
+}
+
+#[tokio::test]
+async fn test_fails_over_to_next_mirror_on_network_error() {
+    let (down, up) = (unreachable_url().await, spawn_mirror().await);
+    let mirrors = Arc::new(Mirrors::new(vec![down, up], Arc::new(SystemClock)));
+    let client = RealTmdbClient::new("test-key".to_string()).with_mirrors(mirrors.clone());
+
+    let response = client.get_trending(2).await.unwrap();
+    assert_eq!(response.page, 2);
+    assert_eq!(response.results[0].title.as_deref(), Some("test-key"));
+
+    let status = mirrors.status();
+    assert!(!status[0].healthy);
+    assert_eq!(status[0].failures, 1);
+    assert_eq!(status[1].successes, 1);
+
+    // The failed mirror is skipped while it cools down
+    client.get_trending(1).await.unwrap();
+    let status = mirrors.status();
+    assert_eq!((status[0].failures, status[1].successes), (1, 2));
+}
+
+#[tokio::test]
+async fn test_http_errors_do_not_fail_over() {
+    let up = spawn_mirror().await;
+    let mirrors = Arc::new(Mirrors::new(vec![up.clone(), up], Arc::new(SystemClock)));
+    let client = RealTmdbClient::new("test-key".to_string()).with_mirrors(mirrors.clone());
+
+    assert!(client.get_movie_videos(603).await.is_err());
+    let status = mirrors.status();
+    assert_eq!((status[0].successes, status[1].successes), (1, 0));
+    assert!(status.iter().all(|mirror| mirror.healthy));
+}
+
+#[tokio::test]
+async fn test_network_error_when_all_mirrors_are_down() {
+    let mirrors = Arc::new(Mirrors::new(vec![unreachable_url().await], Arc::new(SystemClock)));
+    let client = RealTmdbClient::new("test-key".to_string()).with_mirrors(mirrors);
+
+    let error = client.get_trending(1).await.unwrap_err();
+    assert!(matches!(error, TmdbError::NetworkError(_)));
+}
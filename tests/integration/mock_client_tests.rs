@@ -0,0 +1,96 @@
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{error::TmdbError, models::{MediaType, TmdbResponse}, tmdb_client::TmdbClient, upstream_metrics::Operation};
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[tokio::test]
+async fn test_failure_rate_fails_calls_evenly() {
+    let client = MockTmdbClient::builder().with_failure_rate(0.5).build();
+
+    let mut failed = Vec::new();
+    for _ in 0..6 {
+        failed.push(matches!(client.get_trending(1).await, Err(TmdbError::ServerError(503))));
+    }
+
+    assert_eq!(failed, [false, true, false, true, false, true]);
+}
+
+#[tokio::test]
+async fn test_failure_rate_bounds() {
+    let always = MockTmdbClient::builder().with_failure_rate(1.0).build();
+    let never = MockTmdbClient::new();
+
+    for _ in 0..3 {
+        assert!(always.get_movie_videos(603).await.is_err());
+        assert!(never.get_movie_videos(603).await.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_calls_are_counted_per_operation() {
+    let client = MockTmdbClient::new();
+
+    client.get_trending(1).await.unwrap();
+    client.get_trending(2).await.unwrap();
+    client.get_title_details(MediaType::Tv, 1399).await.unwrap();
+
+    assert_eq!(client.calls(), 3);
+    assert_eq!(client.calls_to_trending(), 2);
+    assert_eq!(client.calls_to(Operation::Details), 1);
+    assert_eq!(client.calls_to(Operation::Search), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_latency_delays_every_call() {
+    let client = MockTmdbClient::builder().with_latency(Duration::from_secs(2)).build();
+    let started = Instant::now();
+
+    client.get_trending(1).await.unwrap();
+    client.search_content("matrix", 1).await.unwrap();
+
+    assert_eq!(started.elapsed(), Duration::from_secs(4));
+}
+
+#[tokio::test]
+async fn test_sequence_fails_twice_then_succeeds() {
+    let client = MockTmdbClient::builder()
+        .with_trending_sequence(1, vec![Err(TmdbError::ServerError(502)), Err(TmdbError::RateLimitExceeded), Ok(TmdbResponse { page: 1, results: vec![], total_pages: 1 })])
+        .build();
+
+    assert!(matches!(client.get_trending(1).await, Err(TmdbError::ServerError(502))));
+    assert!(matches!(client.get_trending(1).await, Err(TmdbError::RateLimitExceeded)));
+    assert_eq!(client.get_trending(1).await.unwrap().page, 1);
+    // Other pages are not scripted
+    assert!(client.get_trending(2).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_sequence_falls_back_to_fixed_response_when_used_up() {
+    let client = MockTmdbClient::builder()
+        .with_video_sequence(603, vec![Err(TmdbError::ServerError(503))])
+        .with_video_error(603, TmdbError::NotFound)
+        .with_network_sequence(49, vec![Err(TmdbError::Unauthorized)])
+        .build();
+
+    assert!(matches!(client.get_movie_videos(603).await, Err(TmdbError::ServerError(503))));
+    assert!(matches!(client.get_movie_videos(603).await, Err(TmdbError::NotFound)));
+    assert!(matches!(client.get_network(49).await, Err(TmdbError::Unauthorized)));
+    assert_eq!(client.get_network(49).await.unwrap().id, 49);
+}
+
+#[tokio::test]
+async fn test_sequences_are_keyed_per_request() {
+    let client = MockTmdbClient::builder()
+        .with_search_sequence("matrix", 1, vec![Err(TmdbError::ServerError(500))])
+        .with_details_sequence(MediaType::Tv, 1399, vec![Err(TmdbError::NotFound)])
+        .with_provider_list_sequence("US", vec![Err(TmdbError::ServerError(500))])
+        .build();
+
+    assert!(client.search_content("matrix", 2).await.is_ok());
+    assert!(client.search_content("matrix", 1).await.is_err());
+    assert!(client.get_title_details(MediaType::Movie, 1399).await.is_ok());
+    assert!(client.get_title_details(MediaType::Tv, 1399).await.is_err());
+    assert!(client.get_provider_list("IT").await.is_ok());
+    assert!(client.get_provider_list("US").await.is_err());
+    assert!(client.get_provider_list("US").await.is_ok());
+}
@@ -1,8 +1,41 @@
+#![allow(dead_code)]
+
 use netflix_service::error::TmdbError;
-use netflix_service::models::{Movie, TmdbResponse, Video, VideoResponse};
+use netflix_service::models::{AlternativeTitle, AlternativeTitles, CastMember, ChangesResponse, Collection, DiscoverFilter, ExternalIds, Genre, Image, Keyword, Network, ProviderList, Season, SeasonSummary, Episode, TmdbList, TmdbListItem, RegionProviders, WatchProvider, WatchProvidersResponse, ImagesResponse, MediaType, Movie, TitleDetails, TmdbResponse, Video, VideoResponse};
 use netflix_service::tmdb_client::TmdbClient;
+use netflix_service::upstream_metrics::Operation;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use chrono::NaiveDate;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Scripted responses for one operation, keyed by request. Each call takes
+/// the next response in line; once a script runs out, calls fall back to the
+/// fixed responses.
+struct Sequences<K, T>(Mutex<HashMap<K, VecDeque<Result<T, TmdbError>>>>);
+
+impl<K, T> Default for Sequences<K, T> {
+    fn default() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+}
+
+impl<K: Eq + Hash, T> Sequences<K, T> {
+    fn push(&mut self, key: K, responses: Vec<Result<T, TmdbError>>) {
+        self.0.get_mut().unwrap().entry(key).or_default().extend(responses);
+    }
+
+    fn next<Q>(&self, key: &Q) -> Option<Result<T, TmdbError>>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.0.lock().unwrap().get_mut(key)?.pop_front()
+    }
+}
 
 /// Mock implementation of TmdbClient for testing purposes.
 ///
@@ -10,6 +43,7 @@ use std::collections::HashMap;
 /// - Success responses with custom data
 /// - Error responses for testing error handling
 /// - Default responses when no specific configuration is provided
+/// - Scripted sequences, e.g. "fail twice then succeed"
 ///
 /// # Example
 /// ```ignore
@@ -23,10 +57,38 @@ use std::collections::HashMap;
 pub struct MockTmdbClient {
     trending_responses: HashMap<i32, Result<TmdbResponse, TmdbError>>,
     search_responses: HashMap<(String, i32), Result<TmdbResponse, TmdbError>>,
-    video_responses: HashMap<i32, Result<VideoResponse, TmdbError>>,
+    video_responses: HashMap<i64, Result<VideoResponse, TmdbError>>,
+    details_responses: HashMap<(MediaType, i64), Result<TitleDetails, TmdbError>>,
+    image_responses: HashMap<(MediaType, i64), Result<ImagesResponse, TmdbError>>,
+    alternative_title_responses: HashMap<(MediaType, i64), Result<AlternativeTitles, TmdbError>>,
+    watch_provider_responses: HashMap<(MediaType, i64), Result<WatchProvidersResponse, TmdbError>>,
+    provider_list_responses: HashMap<String, Result<ProviderList, TmdbError>>,
+    network_responses: HashMap<i64, Result<Network, TmdbError>>,
+    list_responses: HashMap<i64, Result<TmdbList, TmdbError>>,
+    change_responses: HashMap<MediaType, Result<ChangesResponse, TmdbError>>,
+    season_responses: HashMap<(i64, i32), Result<Season, TmdbError>>,
+    collection_responses: HashMap<i64, Result<Collection, TmdbError>>,
+    trending_sequences: Sequences<i32, TmdbResponse>,
+    search_sequences: Sequences<(String, i32), TmdbResponse>,
+    video_sequences: Sequences<i64, VideoResponse>,
+    details_sequences: Sequences<(MediaType, i64), TitleDetails>,
+    image_sequences: Sequences<(MediaType, i64), ImagesResponse>,
+    alternative_title_sequences: Sequences<(MediaType, i64), AlternativeTitles>,
+    watch_provider_sequences: Sequences<(MediaType, i64), WatchProvidersResponse>,
+    discover_sequences: Sequences<(DiscoverFilter, i32), TmdbResponse>,
+    top_rated_sequences: Sequences<(MediaType, i32), TmdbResponse>,
+    popular_sequences: Sequences<(MediaType, i32), TmdbResponse>,
+    provider_list_sequences: Sequences<String, ProviderList>,
+    network_sequences: Sequences<i64, Network>,
+    list_sequences: Sequences<i64, TmdbList>,
     default_trending: Option<Result<TmdbResponse, TmdbError>>,
     default_search: Option<Result<TmdbResponse, TmdbError>>,
     default_video: Option<Result<VideoResponse, TmdbError>>,
+    default_details: Option<Result<TitleDetails, TmdbError>>,
+    latency: Option<Duration>,
+    failure_rate: f64,
+    calls: AtomicUsize,
+    calls_by_operation: [AtomicUsize; Operation::ALL.len()],
 }
 
 impl MockTmdbClient {
@@ -36,12 +98,71 @@ impl MockTmdbClient {
             trending_responses: HashMap::new(),
             search_responses: HashMap::new(),
             video_responses: HashMap::new(),
+            details_responses: HashMap::new(),
+            image_responses: HashMap::new(),
+            alternative_title_responses: HashMap::new(),
+            watch_provider_responses: HashMap::new(),
+            provider_list_responses: HashMap::new(),
+            network_responses: HashMap::new(),
+            list_responses: HashMap::new(),
+            change_responses: HashMap::new(),
+            season_responses: HashMap::new(),
+            collection_responses: HashMap::new(),
+            trending_sequences: Sequences::default(),
+            search_sequences: Sequences::default(),
+            video_sequences: Sequences::default(),
+            details_sequences: Sequences::default(),
+            image_sequences: Sequences::default(),
+            alternative_title_sequences: Sequences::default(),
+            watch_provider_sequences: Sequences::default(),
+            discover_sequences: Sequences::default(),
+            top_rated_sequences: Sequences::default(),
+            popular_sequences: Sequences::default(),
+            provider_list_sequences: Sequences::default(),
+            network_sequences: Sequences::default(),
+            list_sequences: Sequences::default(),
             default_trending: None,
             default_search: None,
             default_video: None,
+            default_details: None,
+            latency: None,
+            failure_rate: 0.0,
+            calls: AtomicUsize::new(0),
+            calls_by_operation: Default::default(),
         }
     }
 
+    /// Number of API calls made so far, across all methods
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Number of calls made so far to one operation
+    pub fn calls_to(&self, operation: Operation) -> usize {
+        self.calls_by_operation[operation as usize].load(Ordering::SeqCst)
+    }
+
+    pub fn calls_to_trending(&self) -> usize {
+        self.calls_to(Operation::Trending)
+    }
+
+    /// Counts a call, waits for the configured latency, if any, and fails
+    /// the call when the failure rate says so
+    async fn start_call(&self, operation: Operation) -> Result<(), TmdbError> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        self.calls_by_operation[operation as usize].fetch_add(1, Ordering::SeqCst);
+        if let Some(latency) = self.latency {
+            tokio::time::sleep(latency).await;
+        }
+        // Failures are spread evenly rather than drawn at random, e.g. every
+        // fourth call at 0.25, so tests stay deterministic
+        let failures = |calls: usize| (calls as f64 * self.failure_rate).floor();
+        if failures(call) > failures(call - 1) {
+            return Err(TmdbError::ServerError(503));
+        }
+        Ok(())
+    }
+
     /// Creates a builder for configuring mock responses
     pub fn builder() -> MockTmdbClientBuilder {
         MockTmdbClientBuilder::new()
@@ -60,8 +181,15 @@ impl MockTmdbClient {
                     poster_path: Some("/test1.jpg".to_string()),
                     backdrop_path: Some("/backdrop1.jpg".to_string()),
                     vote_average: Some(8.5),
-                    release_date: Some("2024-01-01".to_string()),
+                    vote_count: Some(1200),
+                    quality_score: None,
+                    has_trailer: None,
+                    providers: None,
+                    cast: None,
+                    genre_ids: vec![28, 12],
+                    release_date: "2024-01-01".parse().ok(),
                     media_type: Some("movie".to_string()),
+                    adult: false,
                 },
                 Movie {
                     id: 456,
@@ -71,8 +199,15 @@ impl MockTmdbClient {
                     poster_path: Some("/test2.jpg".to_string()),
                     backdrop_path: Some("/backdrop2.jpg".to_string()),
                     vote_average: Some(7.8),
-                    release_date: Some("2024-02-01".to_string()),
+                    vote_count: Some(1200),
+                    quality_score: None,
+                    has_trailer: None,
+                    providers: None,
+                    cast: None,
+                    genre_ids: vec![18, 9648],
+                    release_date: "2024-02-01".parse().ok(),
                     media_type: Some("tv".to_string()),
+                    adult: false,
                 },
             ],
         })
@@ -91,14 +226,21 @@ impl MockTmdbClient {
                     poster_path: Some("/search.jpg".to_string()),
                     backdrop_path: Some("/search_backdrop.jpg".to_string()),
                     vote_average: Some(9.0),
-                    release_date: Some("2023-12-01".to_string()),
+                    vote_count: Some(1200),
+                    quality_score: None,
+                    has_trailer: None,
+                    providers: None,
+                    cast: None,
+                    genre_ids: Vec::new(),
+                    release_date: "2023-12-01".parse().ok(),
                     media_type: Some("movie".to_string()),
+                    adult: false,
                 },
             ],
         })
     }
 
-    fn default_video_response(&self, movie_id: i32) -> Result<VideoResponse, TmdbError> {
+    fn default_video_response(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
         Ok(VideoResponse {
             id: movie_id,
             results: vec![
@@ -119,11 +261,251 @@ impl MockTmdbClient {
             ],
         })
     }
+
+    fn default_images_response(&self, id: i64) -> Result<ImagesResponse, TmdbError> {
+        Ok(ImagesResponse {
+            id,
+            posters: vec![
+                image("/poster_en.jpg", Some("en"), 2000, 3000, 5.5),
+                image("/poster_it.jpg", Some("it"), 2000, 3000, 5.2),
+            ],
+            backdrops: vec![image("/backdrop_text_free.jpg", None, 3840, 2160, 5.3)],
+            logos: vec![
+                image("/logo_en.png", Some("en"), 1200, 400, 5.4),
+                image("/logo_de.png", Some("de"), 1200, 400, 5.1),
+            ],
+        })
+    }
+
+    fn default_alternative_titles_response(&self, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        Ok(AlternativeTitles {
+            id,
+            titles: vec![
+                alternative_title("IT", "Film di Prova 1", ""),
+                alternative_title("DE", "Testfilm 1", ""),
+                alternative_title("US", "Untitled Test Project", "working title"),
+            ],
+        })
+    }
+
+    fn default_watch_providers_response(&self, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        let netflix = watch_provider(8, "Netflix", 1);
+        let apple = watch_provider(2, "Apple TV", 4);
+        let us = RegionProviders {
+            link: Some(format!("https://www.themoviedb.org/movie/{}/watch?locale=US", id)),
+            flatrate: vec![netflix.clone()],
+            rent: vec![apple.clone()],
+            buy: vec![apple],
+            ..RegionProviders::default()
+        };
+        let gb = RegionProviders {
+            flatrate: vec![netflix],
+            ..RegionProviders::default()
+        };
+
+        Ok(WatchProvidersResponse {
+            id,
+            results: HashMap::from([("US".to_string(), us), ("GB".to_string(), gb)]),
+        })
+    }
+
+    fn default_discover_response(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let region = filter.region.as_deref().unwrap_or("anywhere");
+        let title = match (filter.networks.first(), filter.companies.first(), filter.genres.first(), filter.keywords.first()) {
+            (Some(network), ..) => format!("On network {}", network),
+            (None, Some(company), ..) => format!("By company {}", company),
+            (None, None, Some(genre), _) => format!("In genre {}", genre),
+            (None, None, None, Some(keyword)) => format!("With keyword {}", keyword),
+            (None, None, None, None) => format!("Streaming on {:?} in {}", filter.providers, region),
+        };
+        Ok(TmdbResponse {
+            page,
+            total_pages: 3,
+            results: vec![
+                Movie {
+                    id: 321,
+                    title: Some(title),
+                    name: None,
+                    overview: Some("Discovered content".to_string()),
+                    poster_path: Some("/discover.jpg".to_string()),
+                    backdrop_path: Some("/discover_backdrop.jpg".to_string()),
+                    vote_average: Some(7.1),
+                    vote_count: Some(1200),
+                    quality_score: None,
+                    has_trailer: None,
+                    providers: None,
+                    cast: None,
+                    genre_ids: Vec::new(),
+                    release_date: "2023-06-01".parse().ok(),
+                    media_type: Some(filter.media_type.as_str().to_string()),
+                    adult: false,
+                },
+            ],
+        })
+    }
+
+    /// Default page of a per-media-type chart, titled "<chart> 1" and "<chart> 2"
+    fn default_chart_response(&self, chart: &str, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        let rated = |id: i64, rank: usize, vote_average: f64| Movie {
+            id,
+            title: (media_type == MediaType::Movie).then(|| format!("{} {}", chart, rank)),
+            name: (media_type == MediaType::Tv).then(|| format!("{} {}", chart, rank)),
+            overview: Some(format!("{} title", chart)),
+            poster_path: Some(format!("/chart_{}.jpg", id)),
+            backdrop_path: None,
+            vote_average: Some(vote_average),
+            vote_count: Some(25_000),
+            quality_score: None,
+            has_trailer: None,
+            providers: None,
+            cast: None,
+            genre_ids: vec![18],
+            release_date: "1994-09-23".parse().ok(),
+            media_type: Some(media_type.as_str().to_string()),
+            adult: false,
+        };
+        Ok(TmdbResponse {
+            page,
+            total_pages: 5,
+            results: vec![rated(278, 1, 8.7), rated(238, 2, 8.6)],
+        })
+    }
+
+    fn default_provider_list_response(&self) -> Result<ProviderList, TmdbError> {
+        Ok(ProviderList {
+            results: vec![
+                watch_provider(337, "Disney Plus", 3),
+                watch_provider(8, "Netflix", 1),
+                watch_provider(9, "Amazon Prime Video", 2),
+            ],
+        })
+    }
+
+    fn default_details_response(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        let (title, name, release_date, first_air_date, runtime) = match media_type {
+            MediaType::Movie => (Some("Test Movie 1".to_string()), None, "2024-01-01".parse().ok(), None, Some(139)),
+            MediaType::Tv => (None, Some("Test Show 1".to_string()), None, "2024-02-01".parse().ok(), None),
+        };
+        let (original_title, original_name) = match media_type {
+            MediaType::Movie => (Some("Película de Prueba 1".to_string()), None),
+            MediaType::Tv => (None, Some("Serie de Prueba 1".to_string())),
+        };
+
+        let seasons = match media_type {
+            MediaType::Movie => Vec::new(),
+            MediaType::Tv => vec![
+                season_summary(0, "Specials", "2010-12-05"),
+                season_summary(1, "Season 1", "2011-04-17"),
+                season_summary(2, "Season 2", "2012-04-01"),
+            ],
+        };
+
+        Ok(TitleDetails {
+            id,
+            title,
+            name,
+            tagline: Some("A tagline".to_string()),
+            overview: Some("A great test title".to_string()),
+            poster_path: Some("/details.jpg".to_string()),
+            backdrop_path: Some("/details_backdrop.jpg".to_string()),
+            vote_average: Some(8.1),
+            vote_count: Some(1200),
+            release_date,
+            first_air_date,
+            runtime,
+            genres: vec![Genre { id: 18, name: "Drama".to_string() }],
+            original_title,
+            original_name,
+            original_language: Some("es".to_string()),
+            alternative_titles: vec![alternative_title("IT", "Film di Prova 1", "")],
+            keywords: vec![
+                Keyword { id: 818, name: "based on novel or book".to_string() },
+                Keyword { id: 10292, name: "gore".to_string() },
+            ],
+            // Out of billing order, with one more actor than a badge shows
+            cast: (0..6).rev().map(|order| cast_member(id * 10 + order as i64, order)).collect(),
+            external_ids: ExternalIds::default(),
+            seasons,
+            adult: false,
+        })
+    }
+}
+
+fn collection_part(id: i64, title: &str, release_date: &str) -> Movie {
+    Movie {
+        id,
+        title: Some(title.to_string()),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: Some(7.0),
+        vote_count: Some(100),
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: vec![28],
+        release_date: release_date.parse().ok(),
+        media_type: Some("movie".to_string()),
+        adult: false,
+    }
+}
+
+fn season_summary(season_number: i32, name: &str, air_date: &str) -> SeasonSummary {
+    SeasonSummary {
+        season_number,
+        name: name.to_string(),
+        air_date: air_date.parse().ok(),
+        episode_count: 2,
+    }
+}
+
+fn image(file_path: &str, language: Option<&str>, width: u32, height: u32, vote_average: f64) -> Image {
+    Image {
+        file_path: file_path.to_string(),
+        iso_639_1: language.map(str::to_string),
+        width,
+        height,
+        vote_average,
+        vote_count: 10,
+    }
+}
+
+fn alternative_title(country: &str, title: &str, kind: &str) -> AlternativeTitle {
+    AlternativeTitle {
+        iso_3166_1: country.to_string(),
+        title: title.to_string(),
+        r#type: kind.to_string(),
+    }
+}
+
+fn cast_member(id: i64, order: i32) -> CastMember {
+    CastMember {
+        id,
+        name: format!("Actor {}", order + 1),
+        character: Some(format!("Character {}", order + 1)),
+        profile_path: None,
+        order,
+    }
+}
+
+fn watch_provider(id: i64, name: &str, display_priority: i32) -> WatchProvider {
+    WatchProvider {
+        provider_id: id,
+        provider_name: name.to_string(),
+        logo_path: Some(format!("/provider_{}.jpg", id)),
+        display_priority,
+    }
 }
 
 #[async_trait]
 impl TmdbClient for MockTmdbClient {
     async fn get_trending(&self, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.start_call(Operation::Trending).await?;
+        if let Some(response) = self.trending_sequences.next(&page) {
+            return response;
+        }
         // Check for specific page response
         if let Some(response) = self.trending_responses.get(&page) {
             return response.clone();
@@ -139,7 +521,11 @@ impl TmdbClient for MockTmdbClient {
     }
 
     async fn search_content(&self, query: &str, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.start_call(Operation::Search).await?;
         let key = (query.to_string(), page);
+        if let Some(response) = self.search_sequences.next(&key) {
+            return response;
+        }
 
         // Check for specific query/page response
         if let Some(response) = self.search_responses.get(&key) {
@@ -155,7 +541,11 @@ impl TmdbClient for MockTmdbClient {
         self.default_search_response(query, page)
     }
 
-    async fn get_movie_videos(&self, movie_id: i32) -> Result<VideoResponse, TmdbError> {
+    async fn get_movie_videos(&self, movie_id: i64) -> Result<VideoResponse, TmdbError> {
+        self.start_call(Operation::Videos).await?;
+        if let Some(response) = self.video_sequences.next(&movie_id) {
+            return response;
+        }
         // Check for specific movie ID response
         if let Some(response) = self.video_responses.get(&movie_id) {
             return response.clone();
@@ -169,16 +559,253 @@ impl TmdbClient for MockTmdbClient {
         // Use built-in default
         self.default_video_response(movie_id)
     }
+
+    async fn get_title_details(&self, media_type: MediaType, id: i64) -> Result<TitleDetails, TmdbError> {
+        self.start_call(Operation::Details).await?;
+        if let Some(response) = self.details_sequences.next(&(media_type, id)) {
+            return response;
+        }
+        // Check for specific title response
+        if let Some(response) = self.details_responses.get(&(media_type, id)) {
+            return response.clone();
+        }
+
+        // Fall back to default configured response
+        if let Some(response) = &self.default_details {
+            return response.clone();
+        }
+
+        // Use built-in default
+        self.default_details_response(media_type, id)
+    }
+
+    async fn get_images(&self, media_type: MediaType, id: i64) -> Result<ImagesResponse, TmdbError> {
+        self.start_call(Operation::Images).await?;
+        if let Some(response) = self.image_sequences.next(&(media_type, id)) {
+            return response;
+        }
+        // Check for specific title response
+        if let Some(response) = self.image_responses.get(&(media_type, id)) {
+            return response.clone();
+        }
+
+        // Use built-in default
+        self.default_images_response(id)
+    }
+
+    async fn get_alternative_titles(&self, media_type: MediaType, id: i64) -> Result<AlternativeTitles, TmdbError> {
+        self.start_call(Operation::AlternativeTitles).await?;
+        if let Some(response) = self.alternative_title_sequences.next(&(media_type, id)) {
+            return response;
+        }
+        // Check for specific title response
+        if let Some(response) = self.alternative_title_responses.get(&(media_type, id)) {
+            return response.clone();
+        }
+
+        // Use built-in default
+        self.default_alternative_titles_response(id)
+    }
+
+    async fn get_watch_providers(&self, media_type: MediaType, id: i64) -> Result<WatchProvidersResponse, TmdbError> {
+        self.start_call(Operation::WatchProviders).await?;
+        if let Some(response) = self.watch_provider_sequences.next(&(media_type, id)) {
+            return response;
+        }
+        // Check for specific title response
+        if let Some(response) = self.watch_provider_responses.get(&(media_type, id)) {
+            return response.clone();
+        }
+
+        // Use built-in default
+        self.default_watch_providers_response(id)
+    }
+
+    async fn discover(&self, filter: &DiscoverFilter, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.start_call(Operation::Discover).await?;
+        if let Some(response) = self.discover_sequences.next(&(filter.clone(), page)) {
+            return response;
+        }
+        self.default_discover_response(filter, page)
+    }
+
+    async fn get_top_rated(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.start_call(Operation::TopRated).await?;
+        if let Some(response) = self.top_rated_sequences.next(&(media_type, page)) {
+            return response;
+        }
+        self.default_chart_response("Top Rated", media_type, page)
+    }
+
+    async fn get_popular(&self, media_type: MediaType, page: i32) -> Result<TmdbResponse, TmdbError> {
+        self.start_call(Operation::Popular).await?;
+        if let Some(response) = self.popular_sequences.next(&(media_type, page)) {
+            return response;
+        }
+        self.default_chart_response("Popular", media_type, page)
+    }
+
+    async fn get_network(&self, id: i64) -> Result<Network, TmdbError> {
+        self.start_call(Operation::Network).await?;
+        if let Some(response) = self.network_sequences.next(&id) {
+            return response;
+        }
+        // Check for specific network response
+        if let Some(response) = self.network_responses.get(&id) {
+            return response.clone();
+        }
+
+        // Use built-in default
+        Ok(Network {
+            id,
+            name: "HBO".to_string(),
+            headquarters: Some("New York City, New York, United States".to_string()),
+            homepage: Some(String::new()),
+            logo_path: Some("/hbo.png".to_string()),
+            origin_country: Some("US".to_string()),
+        })
+    }
+
+    async fn get_list(&self, list_id: i64) -> Result<TmdbList, TmdbError> {
+        self.start_call(Operation::List).await?;
+        if let Some(response) = self.list_sequences.next(&list_id) {
+            return response;
+        }
+        // Check for specific list response
+        if let Some(response) = self.list_responses.get(&list_id) {
+            return response.clone();
+        }
+
+        // Use built-in default
+        Ok(TmdbList {
+            name: "Best of the 90s".to_string(),
+            description: Some("Imported from TMDB".to_string()),
+            items: vec![
+                TmdbListItem { id: 603, media_type: Some("movie".to_string()) },
+                TmdbListItem { id: 1399, media_type: Some("tv".to_string()) },
+                TmdbListItem { id: 550, media_type: None },
+            ],
+        })
+    }
+
+    async fn get_collection(&self, id: i64) -> Result<Collection, TmdbError> {
+        self.start_call(Operation::Collection).await?;
+        // Check for specific collection response
+        if let Some(response) = self.collection_responses.get(&id) {
+            return response.clone();
+        }
+
+        // Use built-in default: parts out of release order, one not yet dated
+        Ok(Collection {
+            id,
+            name: "The Matrix Collection".to_string(),
+            overview: None,
+            parts: vec![
+                collection_part(604, "The Matrix Reloaded", "2003-05-15"),
+                collection_part(603, "The Matrix", "1999-03-30"),
+                collection_part(1_000_001, "The Matrix 5", ""),
+                collection_part(624860, "The Matrix Resurrections", "2021-12-16"),
+            ],
+        })
+    }
+
+    async fn get_tv_season(&self, tv_id: i64, season_number: i32) -> Result<Season, TmdbError> {
+        self.start_call(Operation::Season).await?;
+        // Check for specific season response
+        if let Some(response) = self.season_responses.get(&(tv_id, season_number)) {
+            return response.clone();
+        }
+
+        // Use built-in default: two episodes, the second not yet scheduled
+        Ok(Season {
+            id: 3624,
+            season_number,
+            name: format!("Season {}", season_number),
+            overview: Some("Lord Eddard Stark is called south to serve the king.".to_string()),
+            air_date: NaiveDate::from_ymd_opt(2011, 4, 17),
+            poster_path: Some("/season.jpg".to_string()),
+            episodes: vec![
+                Episode {
+                    id: 63056,
+                    episode_number: 1,
+                    name: "Winter Is Coming".to_string(),
+                    overview: Some("A royal visit to Winterfell sets the Starks on a new path.".to_string()),
+                    air_date: NaiveDate::from_ymd_opt(2011, 4, 17),
+                    still_path: Some("/still1.jpg".to_string()),
+                    runtime: Some(62),
+                },
+                Episode {
+                    id: 63057,
+                    episode_number: 2,
+                    name: "The Kingsroad".to_string(),
+                    overview: None,
+                    air_date: None,
+                    still_path: None,
+                    runtime: None,
+                },
+            ],
+        })
+    }
+
+    async fn get_provider_list(&self, region: &str) -> Result<ProviderList, TmdbError> {
+        self.start_call(Operation::ProviderList).await?;
+        if let Some(response) = self.provider_list_sequences.next(region) {
+            return response;
+        }
+        // Check for specific region response
+        if let Some(response) = self.provider_list_responses.get(region) {
+            return response.clone();
+        }
+
+        // Use built-in default
+        self.default_provider_list_response()
+    }
+
+    async fn get_changes(&self, media_type: MediaType, _start_date: NaiveDate, page: i32) -> Result<ChangesResponse, TmdbError> {
+        self.start_call(Operation::Changes).await?;
+        if let Some(response) = self.change_responses.get(&media_type) {
+            return response.clone();
+        }
+
+        // Use built-in default: nothing changed
+        Ok(ChangesResponse { results: vec![], page, total_pages: 0 })
+    }
 }
 
 /// Builder for creating MockTmdbClient with custom responses
 pub struct MockTmdbClientBuilder {
     trending_responses: HashMap<i32, Result<TmdbResponse, TmdbError>>,
     search_responses: HashMap<(String, i32), Result<TmdbResponse, TmdbError>>,
-    video_responses: HashMap<i32, Result<VideoResponse, TmdbError>>,
+    video_responses: HashMap<i64, Result<VideoResponse, TmdbError>>,
+    details_responses: HashMap<(MediaType, i64), Result<TitleDetails, TmdbError>>,
+    image_responses: HashMap<(MediaType, i64), Result<ImagesResponse, TmdbError>>,
+    alternative_title_responses: HashMap<(MediaType, i64), Result<AlternativeTitles, TmdbError>>,
+    watch_provider_responses: HashMap<(MediaType, i64), Result<WatchProvidersResponse, TmdbError>>,
+    provider_list_responses: HashMap<String, Result<ProviderList, TmdbError>>,
+    network_responses: HashMap<i64, Result<Network, TmdbError>>,
+    list_responses: HashMap<i64, Result<TmdbList, TmdbError>>,
+    change_responses: HashMap<MediaType, Result<ChangesResponse, TmdbError>>,
+    season_responses: HashMap<(i64, i32), Result<Season, TmdbError>>,
+    collection_responses: HashMap<i64, Result<Collection, TmdbError>>,
+    trending_sequences: Sequences<i32, TmdbResponse>,
+    search_sequences: Sequences<(String, i32), TmdbResponse>,
+    video_sequences: Sequences<i64, VideoResponse>,
+    details_sequences: Sequences<(MediaType, i64), TitleDetails>,
+    image_sequences: Sequences<(MediaType, i64), ImagesResponse>,
+    alternative_title_sequences: Sequences<(MediaType, i64), AlternativeTitles>,
+    watch_provider_sequences: Sequences<(MediaType, i64), WatchProvidersResponse>,
+    discover_sequences: Sequences<(DiscoverFilter, i32), TmdbResponse>,
+    top_rated_sequences: Sequences<(MediaType, i32), TmdbResponse>,
+    popular_sequences: Sequences<(MediaType, i32), TmdbResponse>,
+    provider_list_sequences: Sequences<String, ProviderList>,
+    network_sequences: Sequences<i64, Network>,
+    list_sequences: Sequences<i64, TmdbList>,
     default_trending: Option<Result<TmdbResponse, TmdbError>>,
     default_search: Option<Result<TmdbResponse, TmdbError>>,
     default_video: Option<Result<VideoResponse, TmdbError>>,
+    default_details: Option<Result<TitleDetails, TmdbError>>,
+    latency: Option<Duration>,
+    failure_rate: f64,
 }
 
 impl MockTmdbClientBuilder {
@@ -187,12 +814,51 @@ impl MockTmdbClientBuilder {
             trending_responses: HashMap::new(),
             search_responses: HashMap::new(),
             video_responses: HashMap::new(),
+            details_responses: HashMap::new(),
+            image_responses: HashMap::new(),
+            alternative_title_responses: HashMap::new(),
+            watch_provider_responses: HashMap::new(),
+            provider_list_responses: HashMap::new(),
+            network_responses: HashMap::new(),
+            list_responses: HashMap::new(),
+            change_responses: HashMap::new(),
+            season_responses: HashMap::new(),
+            collection_responses: HashMap::new(),
+            trending_sequences: Sequences::default(),
+            search_sequences: Sequences::default(),
+            video_sequences: Sequences::default(),
+            details_sequences: Sequences::default(),
+            image_sequences: Sequences::default(),
+            alternative_title_sequences: Sequences::default(),
+            watch_provider_sequences: Sequences::default(),
+            discover_sequences: Sequences::default(),
+            top_rated_sequences: Sequences::default(),
+            popular_sequences: Sequences::default(),
+            provider_list_sequences: Sequences::default(),
+            network_sequences: Sequences::default(),
+            list_sequences: Sequences::default(),
             default_trending: None,
             default_search: None,
             default_video: None,
+            default_details: None,
+            latency: None,
+            failure_rate: 0.0,
         }
     }
 
+    /// Delay every response by `latency`, to simulate a slow upstream
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Fail this share of calls, between 0 and 1, with `ServerError(503)`.
+    /// Failing calls are spread evenly, so `0.5` fails every second call.
+    pub fn with_failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate.clamp(0.0, 1.0);
+        self
+    }
+
     /// Set a specific response for a trending request with given page
     pub fn with_trending_response(mut self, page: i32, response: Result<TmdbResponse, TmdbError>) -> Self {
         self.trending_responses.insert(page, response);
@@ -218,7 +884,7 @@ impl MockTmdbClientBuilder {
     }
 
     /// Set a specific response for a movie videos request with given movie ID
-    pub fn with_video_response(mut self, movie_id: i32, response: Result<VideoResponse, TmdbError>) -> Self {
+    pub fn with_video_response(mut self, movie_id: i64, response: Result<VideoResponse, TmdbError>) -> Self {
         self.video_responses.insert(movie_id, response);
         self
     }
@@ -229,6 +895,150 @@ impl MockTmdbClientBuilder {
         self
     }
 
+    /// Set a specific response for a details request with given media type and ID
+    pub fn with_details_response(mut self, media_type: MediaType, id: i64, response: Result<TitleDetails, TmdbError>) -> Self {
+        self.details_responses.insert((media_type, id), response);
+        self
+    }
+
+    /// Set a default response for all details requests
+    pub fn with_default_details(mut self, response: Result<TitleDetails, TmdbError>) -> Self {
+        self.default_details = Some(response);
+        self
+    }
+
+    /// Set a specific response for an images request with given media type and ID
+    pub fn with_images_response(mut self, media_type: MediaType, id: i64, response: Result<ImagesResponse, TmdbError>) -> Self {
+        self.image_responses.insert((media_type, id), response);
+        self
+    }
+
+    /// Set a specific response for an alternative titles request with given media type and ID
+    pub fn with_alternative_titles_response(mut self, media_type: MediaType, id: i64, response: Result<AlternativeTitles, TmdbError>) -> Self {
+        self.alternative_title_responses.insert((media_type, id), response);
+        self
+    }
+
+    /// Set a specific response for a watch providers request with given media type and ID
+    pub fn with_watch_providers_response(mut self, media_type: MediaType, id: i64, response: Result<WatchProvidersResponse, TmdbError>) -> Self {
+        self.watch_provider_responses.insert((media_type, id), response);
+        self
+    }
+
+    /// Set a specific response for a provider list request with given region
+    pub fn with_provider_list_response(mut self, region: &str, response: Result<ProviderList, TmdbError>) -> Self {
+        self.provider_list_responses.insert(region.to_string(), response);
+        self
+    }
+
+    /// Set a specific response for a network request with given network ID
+    pub fn with_network_response(mut self, id: i64, response: Result<Network, TmdbError>) -> Self {
+        self.network_responses.insert(id, response);
+        self
+    }
+
+    /// Set a specific response for a TMDB list request with given list ID
+    pub fn with_list_response(mut self, list_id: i64, response: Result<TmdbList, TmdbError>) -> Self {
+        self.list_responses.insert(list_id, response);
+        self
+    }
+
+    /// Set a specific response for a season request with given TV show ID and season number
+    pub fn with_season_response(mut self, tv_id: i64, season_number: i32, response: Result<Season, TmdbError>) -> Self {
+        self.season_responses.insert((tv_id, season_number), response);
+        self
+    }
+
+    /// Set a specific response for a collection request
+    pub fn with_collection_response(mut self, id: i64, response: Result<Collection, TmdbError>) -> Self {
+        self.collection_responses.insert(id, response);
+        self
+    }
+
+    /// Set the changed titles listed for a media type, on every page and from any start date
+    pub fn with_changes_response(mut self, media_type: MediaType, response: Result<ChangesResponse, TmdbError>) -> Self {
+        self.change_responses.insert(media_type, response);
+        self
+    }
+
+    /// Script responses for a trending request with given page, returned one per call in order
+    pub fn with_trending_sequence(mut self, page: i32, responses: Vec<Result<TmdbResponse, TmdbError>>) -> Self {
+        self.trending_sequences.push(page, responses);
+        self
+    }
+
+    /// Script responses for a search request with given query and page, returned one per call in order
+    pub fn with_search_sequence(mut self, query: &str, page: i32, responses: Vec<Result<TmdbResponse, TmdbError>>) -> Self {
+        self.search_sequences.push((query.to_string(), page), responses);
+        self
+    }
+
+    /// Script responses for a movie videos request with given movie ID, returned one per call in order
+    pub fn with_video_sequence(mut self, movie_id: i64, responses: Vec<Result<VideoResponse, TmdbError>>) -> Self {
+        self.video_sequences.push(movie_id, responses);
+        self
+    }
+
+    /// Script responses for a details request with given media type and ID, returned one per call in order
+    pub fn with_details_sequence(mut self, media_type: MediaType, id: i64, responses: Vec<Result<TitleDetails, TmdbError>>) -> Self {
+        self.details_sequences.push((media_type, id), responses);
+        self
+    }
+
+    /// Script responses for an images request with given media type and ID, returned one per call in order
+    pub fn with_images_sequence(mut self, media_type: MediaType, id: i64, responses: Vec<Result<ImagesResponse, TmdbError>>) -> Self {
+        self.image_sequences.push((media_type, id), responses);
+        self
+    }
+
+    /// Script responses for an alternative titles request with given media type and ID, returned one per call in order
+    pub fn with_alternative_titles_sequence(mut self, media_type: MediaType, id: i64, responses: Vec<Result<AlternativeTitles, TmdbError>>) -> Self {
+        self.alternative_title_sequences.push((media_type, id), responses);
+        self
+    }
+
+    /// Script responses for a watch providers request with given media type and ID, returned one per call in order
+    pub fn with_watch_providers_sequence(mut self, media_type: MediaType, id: i64, responses: Vec<Result<WatchProvidersResponse, TmdbError>>) -> Self {
+        self.watch_provider_sequences.push((media_type, id), responses);
+        self
+    }
+
+    /// Script responses for a discover request with given filter and page, returned one per call in order
+    pub fn with_discover_sequence(mut self, filter: DiscoverFilter, page: i32, responses: Vec<Result<TmdbResponse, TmdbError>>) -> Self {
+        self.discover_sequences.push((filter, page), responses);
+        self
+    }
+
+    /// Script responses for a top rated request with given media type and page, returned one per call in order
+    pub fn with_top_rated_sequence(mut self, media_type: MediaType, page: i32, responses: Vec<Result<TmdbResponse, TmdbError>>) -> Self {
+        self.top_rated_sequences.push((media_type, page), responses);
+        self
+    }
+
+    /// Script responses for a popular request with given media type and page, returned one per call in order
+    pub fn with_popular_sequence(mut self, media_type: MediaType, page: i32, responses: Vec<Result<TmdbResponse, TmdbError>>) -> Self {
+        self.popular_sequences.push((media_type, page), responses);
+        self
+    }
+
+    /// Script responses for a provider list request with given region, returned one per call in order
+    pub fn with_provider_list_sequence(mut self, region: &str, responses: Vec<Result<ProviderList, TmdbError>>) -> Self {
+        self.provider_list_sequences.push(region.to_string(), responses);
+        self
+    }
+
+    /// Script responses for a network request with given network ID, returned one per call in order
+    pub fn with_network_sequence(mut self, id: i64, responses: Vec<Result<Network, TmdbError>>) -> Self {
+        self.network_sequences.push(id, responses);
+        self
+    }
+
+    /// Script responses for a TMDB list request with given list ID, returned one per call in order
+    pub fn with_list_sequence(mut self, list_id: i64, responses: Vec<Result<TmdbList, TmdbError>>) -> Self {
+        self.list_sequences.push(list_id, responses);
+        self
+    }
+
     /// Convenience method to set a trending error
     pub fn with_trending_error(self, page: i32, error: TmdbError) -> Self {
         self.with_trending_response(page, Err(error))
@@ -240,19 +1050,52 @@ impl MockTmdbClientBuilder {
     }
 
     /// Convenience method to set a video error
-    pub fn with_video_error(self, movie_id: i32, error: TmdbError) -> Self {
+    pub fn with_video_error(self, movie_id: i64, error: TmdbError) -> Self {
         self.with_video_response(movie_id, Err(error))
     }
 
+    /// Convenience method to set a details error
+    pub fn with_details_error(self, media_type: MediaType, id: i64, error: TmdbError) -> Self {
+        self.with_details_response(media_type, id, Err(error))
+    }
+
     /// Build the MockTmdbClient
     pub fn build(self) -> MockTmdbClient {
         MockTmdbClient {
             trending_responses: self.trending_responses,
             search_responses: self.search_responses,
             video_responses: self.video_responses,
+            details_responses: self.details_responses,
+            image_responses: self.image_responses,
+            alternative_title_responses: self.alternative_title_responses,
+            watch_provider_responses: self.watch_provider_responses,
+            provider_list_responses: self.provider_list_responses,
+            network_responses: self.network_responses,
+            list_responses: self.list_responses,
+            change_responses: self.change_responses,
+            season_responses: self.season_responses,
+            collection_responses: self.collection_responses,
+            trending_sequences: self.trending_sequences,
+            search_sequences: self.search_sequences,
+            video_sequences: self.video_sequences,
+            details_sequences: self.details_sequences,
+            image_sequences: self.image_sequences,
+            alternative_title_sequences: self.alternative_title_sequences,
+            watch_provider_sequences: self.watch_provider_sequences,
+            discover_sequences: self.discover_sequences,
+            top_rated_sequences: self.top_rated_sequences,
+            popular_sequences: self.popular_sequences,
+            provider_list_sequences: self.provider_list_sequences,
+            network_sequences: self.network_sequences,
+            list_sequences: self.list_sequences,
             default_trending: self.default_trending,
             default_search: self.default_search,
             default_video: self.default_video,
+            default_details: self.default_details,
+            latency: self.latency,
+            failure_rate: self.failure_rate,
+            calls: AtomicUsize::new(0),
+            calls_by_operation: Default::default(),
         }
     }
 }
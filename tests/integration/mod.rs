@@ -1,3 +1,49 @@
 // Integration tests module
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use netflix_service::session::SESSION_HEADER;
+
+mod admin_tests;
 mod api_tests;
+mod app_tests;
+mod blocklist_tests;
+mod budget_tests;
+mod cache_policy_tests;
+mod change_feed_tests;
+mod deadline_tests;
+mod etag_tests;
+mod frontend_tests;
+mod health_tests;
+mod include_tests;
+mod metadata_tests;
+mod mirror_tests;
+mod mock_client_tests;
 mod mock_tmdb_client;
+mod mode_tests;
+mod outage_tests;
+mod page_tests;
+mod problem_tests;
+mod profiling_tests;
+mod provider_badge_tests;
+mod push_tests;
+mod real_client_tests;
+mod safe_method_tests;
+mod safe_search_tests;
+mod saved_search_tests;
+mod session_tests;
+mod shadow_tests;
+mod signing_tests;
+mod snapshot_tests;
+mod stats_tests;
+mod sync_tests;
+mod slow_log_tests;
+mod theme_tests;
+mod tls_tests;
+mod trailer_tests;
+mod versioning_tests;
+
+/// A new session's token header, issued by any request to `server`
+async fn session(server: &TestServer) -> (HeaderName, HeaderValue) {
+    let response = server.get("/api/searches").await;
+    (HeaderName::from_static(SESSION_HEADER), response.header(SESSION_HEADER))
+}
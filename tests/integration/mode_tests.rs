@@ -0,0 +1,122 @@
+use axum::{http::{header, StatusCode}, middleware, routing::{get, post}, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{admin, handlers, mode::{self, ModeAwareTmdbClient, ModeStatus, ModeSwitch, ServiceMode}, state::AppState};
+use serde_json::json;
+use std::sync::Arc;
+
+const TOKEN: &str = "secret-token";
+
+fn create_test_app(client: Arc<MockTmdbClient>) -> Router {
+    let mode_switch = Arc::new(ModeSwitch::new());
+    let state = AppState::new(Arc::new(ModeAwareTmdbClient::new(client, mode_switch.clone())))
+        .with_mode_switch(mode_switch)
+        .with_admin_token(TOKEN);
+
+    Router::new()
+        .route("/", get(handlers::root))
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
+        .route("/api/tv/{id}/where_to_start", get(handlers::get_where_to_start))
+        .nest("/admin", admin::router(state.clone()))
+        .layer(middleware::from_fn_with_state(state.clone(), mode::mode_middleware))
+        .with_state(state)
+}
+
+async fn set_mode(server: &TestServer, mode: &str) -> ModeStatus {
+    server
+        .put("/admin/mode")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "mode": mode }))
+        .await
+        .json::<ModeStatus>()
+}
+
+fn batch_body() -> serde_json::Value {
+    json!({ "items": [{ "media_type": "movie", "id": 603 }] })
+}
+
+#[tokio::test]
+async fn test_mode_defaults_to_normal() {
+    let server = TestServer::new(create_test_app(Arc::new(MockTmdbClient::new()))).unwrap();
+
+    let status = server.get("/admin/mode").authorization_bearer(TOKEN).await.json::<ModeStatus>();
+    assert_eq!(status.mode, ServiceMode::Normal);
+    assert!(status.changed_at.is_none());
+
+    server.post("/api/titles/batch").json(&batch_body()).await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_degraded_mode_disables_expensive_routes() {
+    let server = TestServer::new(create_test_app(Arc::new(MockTmdbClient::new()))).unwrap();
+
+    let status = set_mode(&server, "degraded").await;
+    assert_eq!(status.mode, ServiceMode::Degraded);
+    assert!(status.changed_at.is_some());
+
+    server
+        .post("/api/titles/batch")
+        .json(&batch_body())
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    server.get("/api/tv/1399/where_to_start").await.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    server.get("/api/trending").await.assert_status_ok();
+
+    set_mode(&server, "normal").await;
+    server.get("/api/tv/1399/where_to_start").await.assert_status_ok();
+    server.post("/api/titles/batch").json(&batch_body()).await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_cache_only_mode_stops_upstream_calls() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = TestServer::new(create_test_app(client.clone())).unwrap();
+
+    set_mode(&server, "cache_only").await;
+
+    let response = server.get("/api/trending").await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.text(), "Upstream calls are disabled");
+    assert_eq!(client.calls(), 0);
+}
+
+#[tokio::test]
+async fn test_mode_changes_require_admin_token_and_known_mode() {
+    let server = TestServer::new(create_test_app(Arc::new(MockTmdbClient::new()))).unwrap();
+
+    server
+        .put("/admin/mode")
+        .json(&json!({ "mode": "degraded" }))
+        .await
+        .assert_status_unauthorized();
+    server
+        .put("/admin/mode")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "mode": "panic" }))
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_maintenance_mode_keeps_allowlisted_routes() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = TestServer::new(create_test_app(client.clone())).unwrap();
+
+    set_mode(&server, "maintenance").await;
+
+    let response = server.get("/api/trending").await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.header(header::RETRY_AFTER), "300");
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["error"], "maintenance");
+    assert_eq!(body["retry_after"], 300);
+    assert_eq!(client.calls(), 0);
+
+    server.get("/").await.assert_status_ok();
+    let status = server.get("/admin/mode").authorization_bearer(TOKEN).await.json::<ModeStatus>();
+    assert_eq!(status.mode, ServiceMode::Maintenance);
+
+    set_mode(&server, "normal").await;
+    server.get("/api/trending").await.assert_status_ok();
+}
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{self, AppOptions}, clock::MockClock, error::TmdbError, mode::ServiceMode, outage::{OutageMonitor, StatusIndicator, StatusPage, UPSTREAM_STATUS_HEADER}, state::AppState};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const TOKEN: &str = "secret-token";
+
+/// A status page showing whatever the test sets
+struct FakeStatusPage(Mutex<StatusIndicator>);
+
+impl FakeStatusPage {
+    fn set(&self, indicator: StatusIndicator) {
+        *self.0.lock().unwrap() = indicator;
+    }
+}
+
+#[async_trait]
+impl StatusPage for FakeStatusPage {
+    async fn indicator(&self) -> Result<StatusIndicator, TmdbError> {
+        Ok(*self.0.lock().unwrap())
+    }
+}
+
+fn create_test_server(client: MockTmdbClient, monitor: OutageMonitor) -> (TestServer, AppState, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(SystemTime::now()));
+    let state = AppState::new(Arc::new(client)).with_clock(clock.clone()).with_outage_monitor(monitor).with_admin_token(TOKEN);
+    let server = TestServer::new(app::router(state.clone(), &AppOptions::default())).unwrap();
+    (server, state, clock)
+}
+
+/// Runs a check after the last probe result expired
+async fn check(state: &AppState, clock: &MockClock) {
+    clock.advance(Duration::from_secs(60));
+    state.outage.check(state).await;
+}
+
+#[tokio::test]
+async fn test_failing_probes_switch_to_degraded_mode_until_recovery() {
+    let failure = || Err(TmdbError::ServerError(503));
+    let client = MockTmdbClient::builder().with_network_sequence(49, vec![failure(), failure(), failure(), failure()]).build();
+    let (server, state, clock) = create_test_server(client, OutageMonitor::new());
+
+    check(&state, &clock).await;
+    check(&state, &clock).await;
+    assert!(!state.outage.in_outage());
+    assert!(server.get("/").await.maybe_header(UPSTREAM_STATUS_HEADER).is_none());
+    check(&state, &clock).await;
+
+    assert_eq!(state.mode.mode(), ServiceMode::Degraded);
+    let response = server.get("/health/upstream").await;
+    assert_eq!(response.header(UPSTREAM_STATUS_HEADER), "degraded");
+    let outage = &response.json::<Value>()["outage"];
+    assert_eq!(outage["outage"], true);
+    assert_eq!(outage["reason"], "3 failed probes in a row");
+    assert_eq!(outage["switched_mode"], true);
+
+    // One failure and one success aren't a recovery yet
+    check(&state, &clock).await;
+    check(&state, &clock).await;
+    assert!(state.outage.in_outage());
+    check(&state, &clock).await;
+
+    assert!(!state.outage.in_outage());
+    assert_eq!(state.mode.mode(), ServiceMode::Normal);
+    assert!(server.get("/").await.maybe_header(UPSTREAM_STATUS_HEADER).is_none());
+    let status = server.get("/admin/metrics/outage").authorization_bearer(TOKEN).await.json::<Value>();
+    assert_eq!((status["outage"].clone(), status["detected"].clone()), (json!(false), json!(1)));
+}
+
+#[tokio::test]
+async fn test_status_page_maintenance_is_an_outage_and_operators_win() {
+    let page = Arc::new(FakeStatusPage(Mutex::new(StatusIndicator::Minor)));
+    let (server, state, clock) = create_test_server(MockTmdbClient::new(), OutageMonitor::new().with_status_page(page.clone()));
+
+    check(&state, &clock).await;
+    assert!(!state.outage.in_outage());
+    page.set(StatusIndicator::Maintenance);
+    check(&state, &clock).await;
+
+    let status = state.outage.status();
+    assert_eq!(status.reason.as_deref(), Some("status page reports maintenance"));
+    assert_eq!(status.indicator, Some(StatusIndicator::Maintenance));
+    assert_eq!(state.mode.mode(), ServiceMode::Degraded);
+    // Probes succeed, but the outage lasts as long as the page reports it
+    check(&state, &clock).await;
+    check(&state, &clock).await;
+    assert!(state.outage.in_outage());
+
+    // An operator's mode change isn't undone when the outage ends
+    server.put("/admin/mode").authorization_bearer(TOKEN).json(&json!({ "mode": "cache_only" })).await.assert_status_ok();
+    page.set(StatusIndicator::None);
+    check(&state, &clock).await;
+    assert!(!state.outage.in_outage());
+    assert_eq!(state.mode.mode(), ServiceMode::CacheOnly);
+}
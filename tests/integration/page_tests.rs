@@ -0,0 +1,224 @@
+use axum::{routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{error::TmdbError, models::{ExternalIds, MediaType, Movie, TitleDetails, TmdbResponse}, pages, state::AppState};
+use std::sync::Arc;
+
+fn create_test_app_with_client(client: MockTmdbClient) -> Router {
+    let state = AppState::new(Arc::new(client));
+
+    Router::new()
+        .route("/title/{media_type}/{id}", get(pages::title_page))
+        .route("/sitemap.xml", get(pages::sitemap))
+        .with_state(state)
+}
+
+fn create_test_app() -> Router {
+    create_test_app_with_client(MockTmdbClient::new())
+}
+
+#[tokio::test]
+async fn test_movie_page_has_open_graph_tags() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/title/movie/550").await;
+
+    assert_eq!(response.status_code(), 200);
+    assert!(response.header("content-type").to_str().unwrap().starts_with("text/html"));
+
+    let html = response.text();
+    assert!(html.contains(r#"<meta property="og:type" content="video.movie">"#));
+    assert!(html.contains(r#"<meta property="og:title" content="Test Movie 1">"#));
+    assert!(html.contains(r#"<meta property="og:description" content="A great test title">"#));
+    assert!(html.contains(r#"<meta property="og:image" content="https://image.tmdb.org/t/p/w500/details.jpg">"#));
+    assert!(html.contains(r#"<meta name="twitter:card" content="summary_large_image">"#));
+}
+
+#[tokio::test]
+async fn test_tv_page_uses_show_name() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/title/tv/1399").await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let html = response.text();
+    assert!(html.contains(r#"<meta property="og:type" content="video.tv_show">"#));
+    assert!(html.contains("<title>Test Show 1</title>"));
+}
+
+#[tokio::test]
+async fn test_page_escapes_html() {
+    let details = TitleDetails {
+        id: 1,
+        title: Some("<script>alert(1)</script>".to_string()),
+        name: None,
+        tagline: None,
+        overview: Some("\"quoted\" & more".to_string()),
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: None,
+        vote_count: None,
+        release_date: None,
+        first_air_date: None,
+        runtime: None,
+        genres: vec![],
+        original_title: None,
+        original_name: None,
+        original_language: None,
+        alternative_titles: vec![],
+        keywords: vec![],
+        cast: vec![],
+        external_ids: ExternalIds::default(),
+        seasons: Vec::new(),
+        adult: false,
+    };
+    let mock_client = MockTmdbClient::builder()
+        .with_details_response(MediaType::Movie, 1, Ok(details))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let html = server.get("/title/movie/1").await.text();
+
+    assert!(!html.contains("<script>"));
+    assert!(!html.contains("og:image"));
+    assert!(html.contains("alert(1)"));
+}
+
+#[tokio::test]
+async fn test_page_not_found() {
+    let mock_client = MockTmdbClient::builder()
+        .with_details_error(MediaType::Movie, 99999, TmdbError::NotFound)
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let response = server.get("/title/movie/99999").await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_page_rejects_unknown_media_type() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/title/person/1").await;
+
+    assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+async fn test_page_out_of_range_id() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    assert_eq!(server.get("/title/movie/-5").await.status_code(), 404);
+    assert_eq!(server.get("/title/tv/0").await.status_code(), 404);
+    assert_eq!(server.get("/title/movie/abc").await.status_code(), 400);
+}
+
+fn trending_item(id: i64, media_type: &str) -> Movie {
+    Movie {
+        id,
+        title: Some(format!("Title {}", id)),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: None,
+        vote_count: None,
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: Vec::new(),
+        release_date: None,
+        media_type: Some(media_type.to_string()),
+        adult: false,
+    }
+}
+
+#[tokio::test]
+async fn test_kids_profile_refuses_adult_title_pages() {
+    let adult: TitleDetails = serde_json::from_value(serde_json::json!({ "id": 2, "title": "Adult Film", "adult": true })).unwrap();
+    let mock_client = MockTmdbClient::builder()
+        .with_details_response(MediaType::Movie, 2, Ok(adult))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    assert_eq!(server.get("/title/movie/2?device=kids").await.status_code(), 403);
+    assert_eq!(server.get("/title/movie/2").await.status_code(), 200);
+}
+
+#[tokio::test]
+async fn test_sitemap_lists_trending_titles() {
+    let state = AppState::new(Arc::new(MockTmdbClient::new())).with_public_base_url("https://netflix.example.com/");
+    let app = Router::new().route("/sitemap.xml", get(pages::sitemap)).with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    let response = server.get("/sitemap.xml").await;
+
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.header("content-type"), "application/xml");
+
+    let xml = response.text();
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains("<loc>https://netflix.example.com/title/movie/123</loc>"));
+    assert!(xml.contains("<loc>https://netflix.example.com/title/tv/456</loc>"));
+    // The mock returns the same titles for every page; each is listed once
+    assert_eq!(xml.matches("/title/movie/123<").count(), 1);
+}
+
+#[tokio::test]
+async fn test_sitemap_ignores_request_host() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let xml = server
+        .get("/sitemap.xml")
+        .add_header("host", "attacker.example")
+        .add_header("x-forwarded-proto", "javascript")
+        .await
+        .text();
+
+    assert!(!xml.contains("attacker.example"));
+    assert!(xml.contains("<loc>http://localhost:8080/title/movie/123</loc>"));
+}
+
+#[tokio::test]
+async fn test_sitemap_skips_people() {
+    let mock_client = MockTmdbClient::builder()
+        .with_default_trending(Ok(TmdbResponse {
+            page: 1,
+            total_pages: 1,
+            results: vec![trending_item(1, "person"), trending_item(2, "movie")],
+        }))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let xml = server.get("/sitemap.xml").await.text();
+
+    assert!(!xml.contains("/title/person/1"));
+    assert!(xml.contains("/title/movie/2"));
+}
+
+#[tokio::test]
+async fn test_sitemap_tolerates_later_page_failures() {
+    let mock_client = MockTmdbClient::builder()
+        .with_trending_error(3, TmdbError::ServerError(500))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let response = server.get("/sitemap.xml").await;
+
+    assert_eq!(response.status_code(), 200);
+}
+
+#[tokio::test]
+async fn test_sitemap_fails_without_first_page() {
+    let mock_client = MockTmdbClient::builder()
+        .with_trending_error(1, TmdbError::ServerError(500))
+        .build();
+    let server = TestServer::new(create_test_app_with_client(mock_client)).unwrap();
+
+    let response = server.get("/sitemap.xml").await;
+
+    assert_eq!(response.status_code(), 502);
+}
@@ -0,0 +1,105 @@
+use axum::http::{header, HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::app::{test_app, AppStateOverrides};
+use netflix_service::error::TmdbError;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const TOKEN: &str = "test-admin-token";
+const PROBLEM_JSON: HeaderValue = HeaderValue::from_static("application/problem+json");
+const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+fn create_test_server(client: MockTmdbClient) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(Arc::new(client)).with_admin_token(TOKEN))).unwrap()
+}
+
+#[tokio::test]
+async fn test_upstream_errors_become_problem_details() {
+    let client = MockTmdbClient::builder().with_video_response(603, Err(TmdbError::NotFound)).build();
+    let server = create_test_server(client);
+
+    let response = server
+        .get("/api/movie/603/videos")
+        .add_header(header::ACCEPT, PROBLEM_JSON)
+        .add_header(REQUEST_ID, HeaderValue::from_static("req-42"))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.header(header::CONTENT_TYPE), "application/problem+json");
+    assert_eq!(response.header(REQUEST_ID), "req-42");
+    assert_eq!(
+        response.json::<Value>(),
+        json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "Resource not found",
+            "instance": "/api/movie/603/videos",
+            "code": "not_found",
+            "request_id": "req-42",
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_plain_text_errors_are_kept_without_negotiation() {
+    let client = MockTmdbClient::builder().with_video_response(603, Err(TmdbError::RateLimitExceeded)).build();
+    let server = create_test_server(client);
+
+    let response = server.get("/api/movie/603/videos").await;
+
+    response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.header(header::CONTENT_TYPE).to_str().unwrap().starts_with("text/plain"));
+    // Every response still carries a generated request id
+    assert_eq!(response.header(REQUEST_ID).len(), 16);
+
+    let problem = server.get("/api/movie/603/videos").add_header(header::ACCEPT, PROBLEM_JSON).await.json::<Value>();
+    assert_eq!(problem["code"], "upstream_rate_limited");
+    assert_eq!(problem["status"], 429);
+}
+
+#[tokio::test]
+async fn test_extractor_and_middleware_errors_share_the_shape() {
+    let server = create_test_server(MockTmdbClient::new());
+
+    let invalid = server
+        .get("/api/trending")
+        .add_query_param("include", "bogus")
+        .add_header(header::ACCEPT, PROBLEM_JSON)
+        .await;
+    invalid.assert_status(StatusCode::BAD_REQUEST);
+    let body = invalid.json::<Value>();
+    assert_eq!(body["code"], "bad_request");
+    assert_eq!(body["detail"], "unknown include 'bogus'");
+
+    server
+        .put("/admin/mode")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "mode": "maintenance" }))
+        .await
+        .assert_status_ok();
+    let maintenance = server.get("/api/trending").add_header(header::ACCEPT, PROBLEM_JSON).await;
+    maintenance.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(maintenance.header(header::RETRY_AFTER), "300");
+    let body = maintenance.json::<Value>();
+    assert_eq!(body["code"], "maintenance");
+    assert_eq!(body["title"], "Service Unavailable");
+    assert_eq!(body["instance"], "/api/trending");
+}
+
+#[tokio::test]
+async fn test_rejected_accept_and_invalid_request_ids_are_ignored() {
+    let server = create_test_server(MockTmdbClient::new());
+
+    let response = server
+        .get("/api/trending")
+        .add_query_param("include", "bogus")
+        .add_header(header::ACCEPT, HeaderValue::from_static("application/problem+json;q=0, text/plain"))
+        .add_header(REQUEST_ID, HeaderValue::from_static("not a valid id"))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.text(), "unknown include 'bogus'");
+    assert_ne!(response.header(REQUEST_ID), "not a valid id");
+}
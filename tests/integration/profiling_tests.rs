@@ -0,0 +1,51 @@
+use axum::Router;
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{profiling::{self, AllocationStats}, state::AppState};
+use std::sync::Arc;
+
+const TOKEN: &str = "secret-token";
+
+fn create_test_app() -> Router {
+    let state = AppState::new(Arc::new(MockTmdbClient::new())).with_admin_token(TOKEN);
+
+    Router::new()
+        .nest(profiling::MOUNT_PATH, profiling::router(state.clone()))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_profiling_requires_admin_token() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    server.get("/debug/pprof/profile").await.assert_status_unauthorized();
+    server.get("/debug/pprof/allocs").await.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn test_profile_rejects_out_of_range_duration() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    for seconds in ["0", "61"] {
+        server
+            .get("/debug/pprof/profile")
+            .add_query_param("seconds", seconds)
+            .authorization_bearer(TOKEN)
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn test_allocation_stats() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/debug/pprof/allocs")
+        .authorization_bearer(TOKEN)
+        .await;
+
+    response.assert_status_ok();
+    let stats = response.json::<AllocationStats>();
+    assert_eq!(stats.live_bytes, stats.allocated_bytes - stats.freed_bytes);
+}
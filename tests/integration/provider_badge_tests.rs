@@ -0,0 +1,95 @@
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{test_app, AppStateOverrides}, error::TmdbError, models::{MediaType, RegionProviders, WatchProvider, WatchProvidersResponse}, upstream_metrics::Operation};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client))).unwrap()
+}
+
+fn provider(id: i64, display_priority: i32) -> WatchProvider {
+    WatchProvider { provider_id: id, provider_name: format!("Provider {}", id), logo_path: None, display_priority }
+}
+
+#[tokio::test]
+async fn test_items_carry_streaming_providers_for_the_region() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let response = server.get("/api/trending").add_query_param("include", "providers").await;
+
+    response.assert_status_ok();
+    let body = response.json::<Value>();
+    // Rentals and purchases on Apple TV don't count as streaming
+    assert_eq!(body["results"][0]["providers"][0]["name"], "Netflix");
+    assert_eq!(body["results"][0]["providers"].as_array().unwrap().len(), 1);
+    assert_eq!(body["results"][1]["providers"][0]["id"], 8);
+
+    // The second page load is answered from the availability cache
+    let calls = client.calls_to(Operation::WatchProviders);
+    server.get("/api/trending").add_query_param("include", "providers").await;
+    assert_eq!(client.calls_to(Operation::WatchProviders), calls);
+}
+
+#[tokio::test]
+async fn test_badges_keep_the_three_most_prominent_providers() {
+    let offers = RegionProviders {
+        flatrate: vec![provider(1, 9), provider(2, 1), provider(3, 5)],
+        free: vec![provider(4, 3), provider(2, 1)],
+        ads: vec![provider(5, 7)],
+        buy: vec![provider(6, 0)],
+        ..RegionProviders::default()
+    };
+    let client = MockTmdbClient::builder()
+        .with_watch_providers_response(MediaType::Movie, 123, Ok(WatchProvidersResponse {
+            id: 123,
+            results: HashMap::from([("GB".to_string(), offers)]),
+        }))
+        .build();
+    let server = create_test_server(Arc::new(client));
+
+    let body = server
+        .get("/api/trending")
+        .add_query_param("include", "videos,providers")
+        .add_query_param("region", "gb")
+        .await
+        .json::<Value>();
+
+    let ids: Vec<i64> = body["results"][0]["providers"].as_array().unwrap().iter().map(|p| p["id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, vec![2, 4, 3]);
+}
+
+#[tokio::test]
+async fn test_failed_lookups_leave_items_without_badges() {
+    let client = MockTmdbClient::builder()
+        .with_watch_providers_response(MediaType::Movie, 123, Err(TmdbError::ServerError(502)))
+        .with_watch_providers_response(MediaType::Tv, 456, Err(TmdbError::NotFound))
+        .build();
+    let server = create_test_server(Arc::new(client));
+
+    let response = server.get("/api/trending").add_query_param("include", "providers").await;
+
+    response.assert_status_ok();
+    let body = response.json::<Value>();
+    assert!(body["results"][0].get("providers").is_none());
+    assert_eq!(body["results"][1]["providers"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_badges_are_opt_in_and_validate_the_region() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let body = server.get("/api/trending").await.json::<Value>();
+    assert!(body["results"][0].get("providers").is_none());
+    assert_eq!(client.calls_to(Operation::WatchProviders), 0);
+
+    let response = server
+        .get("/api/trending")
+        .add_query_param("include", "providers")
+        .add_query_param("region", "USA")
+        .await;
+    response.assert_status_bad_request();
+}
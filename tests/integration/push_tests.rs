@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use super::{mock_tmdb_client::MockTmdbClient, session};
+use netflix_service::{app::{test_app_with_state, AppStateOverrides}, error::TmdbError, models::{Movie, TmdbResponse}, push::{Platform, PushError, PushMessage, PushNotifier, PushSender}, state::AppState};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Answers pushes from a script per token and records every attempt
+#[derive(Default)]
+struct MockSender {
+    script: Mutex<HashMap<String, VecDeque<Result<(), PushError>>>>,
+    sent: Mutex<Vec<(String, PushMessage)>>,
+}
+
+impl MockSender {
+    fn with_script(self, token: &str, results: Vec<Result<(), PushError>>) -> Self {
+        self.script.lock().unwrap().insert(token.to_string(), results.into());
+        self
+    }
+
+    fn attempts(&self, token: &str) -> usize {
+        self.sent.lock().unwrap().iter().filter(|(sent_to, _)| sent_to == token).count()
+    }
+}
+
+#[async_trait]
+impl PushSender for MockSender {
+    async fn send(&self, token: &str, message: &PushMessage) -> Result<(), PushError> {
+        self.sent.lock().unwrap().push((token.to_string(), message.clone()));
+        self.script.lock().unwrap().get_mut(token).and_then(VecDeque::pop_front).unwrap_or(Ok(()))
+    }
+}
+
+fn movie(id: i64) -> Movie {
+    Movie {
+        id,
+        title: Some(format!("Movie {}", id)),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: Some(7.0),
+        vote_count: Some(500),
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: Vec::new(),
+        release_date: None,
+        media_type: None,
+        adult: false,
+    }
+}
+
+fn page(results: Vec<Movie>) -> Result<TmdbResponse, TmdbError> {
+    Ok(TmdbResponse { page: 1, results, total_pages: 1 })
+}
+
+fn create_test_server(client: MockTmdbClient, sender: Arc<MockSender>) -> (TestServer, AppState) {
+    let overrides = AppStateOverrides::new(Arc::new(client))
+        .with_state(move |state| state.with_push(PushNotifier::default().with_sender(Platform::Fcm, sender)));
+    let (app, state) = test_app_with_state(overrides);
+    (TestServer::new(app).unwrap(), state)
+}
+
+#[tokio::test]
+async fn test_devices_are_registered_per_session() {
+    let (server, _) = create_test_server(MockTmdbClient::new(), Arc::new(MockSender::default()));
+    let (name, token) = session(&server).await;
+    let register = |device: Value| server.post("/api/push/devices").add_header(name.clone(), token.clone()).json(&device);
+
+    // Only platforms with a configured sender are accepted
+    register(json!({ "platform": "apns", "token": "abc" })).await.assert_status_bad_request();
+    register(json!({ "platform": "fcm", "token": " " })).await.assert_status_bad_request();
+    register(json!({ "platform": "fcm", "token": "device-1" })).await.assert_status(StatusCode::CREATED);
+    register(json!({ "platform": "fcm", "token": "device-1" })).await.assert_status(StatusCode::NO_CONTENT);
+
+    let devices = server.get("/api/push/devices").add_header(name.clone(), token.clone()).await.json::<Value>();
+    assert_eq!(devices, json!([{ "platform": "fcm", "token": "device-1" }]));
+    let (other_name, other_token) = session(&server).await;
+    let others = server.get("/api/push/devices").add_header(other_name, other_token).await.json::<Value>();
+    assert_eq!(others, json!([]));
+
+    let unregister = || server.delete("/api/push/devices/device-1").add_header(name.clone(), token.clone());
+    unregister().await.assert_status(StatusCode::NO_CONTENT);
+    unregister().await.assert_status_not_found();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_notifications_are_pushed_with_retries_and_dead_tokens_removed() {
+    let client = MockTmdbClient::builder()
+        .with_search_sequence("dune", 1, vec![page(vec![movie(1)]), page(vec![movie(1), movie(2), movie(3)])])
+        .build();
+    let sender = Arc::new(
+        MockSender::default()
+            .with_script("flaky", vec![Err(PushError::Transient("503".to_string()))])
+            .with_script("uninstalled", vec![Err(PushError::Unregistered("UNREGISTERED".to_string()))])
+            .with_script("down", vec![Err(PushError::Transient("503".to_string())); 3]),
+    );
+    let (server, state) = create_test_server(client, sender.clone());
+    let (name, token) = session(&server).await;
+    server.post("/api/searches").add_header(name.clone(), token.clone()).json(&json!({ "source": "search", "query": "dune", "name": "Dune" })).await;
+    for device in ["flaky", "uninstalled", "down"] {
+        let device = json!({ "platform": "fcm", "token": device });
+        server.post("/api/push/devices").add_header(name.clone(), token.clone()).json(&device).await.assert_status(StatusCode::CREATED);
+    }
+
+    let client = state.tmdb_client.as_ref();
+    state.saved_searches.refresh(client, &state.blocklist, SystemTime::now()).await;
+    assert_eq!(state.push.deliver(&state.saved_searches, SystemTime::now()).await, 0);
+    assert_eq!(state.saved_searches.refresh(client, &state.blocklist, SystemTime::now()).await, 1);
+    assert_eq!(state.push.deliver(&state.saved_searches, SystemTime::now()).await, 1);
+
+    assert_eq!((sender.attempts("flaky"), sender.attempts("uninstalled"), sender.attempts("down")), (2, 1, 3));
+    let (_, message) = sender.sent.lock().unwrap()[0].clone();
+    assert_eq!(message.title, "2 new matches for 'Dune'");
+    assert_eq!(message.body, "Movie 2, Movie 3");
+
+    let devices = server.get("/api/push/devices").add_header(name, token).await.json::<Value>();
+    assert_eq!(devices, json!([{ "platform": "fcm", "token": "flaky" }, { "platform": "fcm", "token": "down" }]));
+    let stats = state.push.stats();
+    assert_eq!((stats.devices, stats.delivered, stats.failed, stats.removed), (2, 1, 1, 1));
+
+    // Delivered notifications aren't pushed again
+    assert_eq!(state.push.deliver(&state.saved_searches, SystemTime::now()).await, 0);
+    assert_eq!(sender.sent.lock().unwrap().len(), 6);
+}
@@ -0,0 +1,339 @@
+use netflix_service::{clock::SystemClock, compression::{CompressionMode, UpstreamCompression}, error::TmdbError, mirrors::Mirrors, models::{DiscoverFilter, MediaType}, new_on::NewOnCache, tmdb_client::{RealTmdbClient, TmdbClient}};
+use chrono::NaiveDate;
+use serde_json::json;
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::{header, headers, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client_for(servers: &[&MockServer]) -> RealTmdbClient {
+    let urls = servers.iter().map(|server| format!("{}/3", server.uri())).collect();
+    RealTmdbClient::new("test-key".to_string()).with_mirrors(Arc::new(Mirrors::new(urls, Arc::new(SystemClock))))
+}
+
+fn trending_body() -> serde_json::Value {
+    json!({ "page": 2, "total_pages": 10, "results": [{ "id": 603, "title": "The Matrix", "media_type": "movie", "genre_ids": [28] }] })
+}
+
+#[tokio::test]
+async fn test_success_sends_key_and_params() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/3/trending/all/week"))
+        .and(query_param("api_key", "test-key"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client_for(&[&server]).get_trending(2).await.unwrap();
+
+    assert_eq!((response.page, response.total_pages), (2, 10));
+    assert_eq!(response.results[0].title.as_deref(), Some("The Matrix"));
+    assert_eq!(response.results[0].genre_ids, [28]);
+}
+
+#[tokio::test]
+async fn test_details_and_discover_params() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/tv/1399"))
+        .and(query_param("append_to_response", "alternative_titles,keywords,credits,external_ids"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": 1399,
+            "name": "Game of Thrones",
+            "credits": { "cast": [{ "id": 22970, "name": "Peter Dinklage", "character": "Tyrion Lannister", "order": 0 }], "crew": [] }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(path("/3/discover/tv"))
+        .and(query_param("with_networks", "49"))
+        .and(query_param("sort_by", "popularity.desc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = client_for(&[&server]);
+
+    let details = client.get_title_details(MediaType::Tv, 1399).await.unwrap();
+    assert_eq!(details.name.as_deref(), Some("Game of Thrones"));
+    assert_eq!(details.cast[0].name, "Peter Dinklage");
+    let filter = DiscoverFilter { networks: vec![49], ..DiscoverFilter::new(MediaType::Tv) };
+    assert_eq!(client.discover(&filter, 1).await.unwrap().results.len(), 1);
+}
+
+#[tokio::test]
+async fn test_new_on_rows_sort_by_release_date() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/discover/tv"))
+        .and(query_param("sort_by", "first_air_date.desc"))
+        .and(query_param("first_air_date.lte", "2026-10-18"))
+        .and(query_param("with_watch_providers", "8"))
+        .and(query_param("watch_region", "US"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let filter = NewOnCache::filter(MediaType::Tv, 8, "US", NaiveDate::from_ymd_opt(2026, 10, 18).unwrap());
+    assert_eq!(client_for(&[&server]).discover(&filter, 1).await.unwrap().results.len(), 1);
+}
+
+#[tokio::test]
+async fn test_top_rated_titles_get_the_requested_media_type() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/tv/top_rated"))
+        .and(query_param("page", "3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "page": 3, "total_pages": 90, "results": [{ "id": 1396, "name": "Breaking Bad", "vote_average": 8.9 }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client_for(&[&server]).get_top_rated(MediaType::Tv, 3).await.unwrap();
+
+    assert_eq!(response.page, 3);
+    assert_eq!(response.results[0].media_type.as_deref(), Some("tv"));
+}
+
+#[tokio::test]
+async fn test_popular_requests_the_media_type_chart() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/movie/popular"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "page": 1, "total_pages": 500, "results": [{ "id": 550, "title": "Fight Club" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client_for(&[&server]).get_popular(MediaType::Movie, 1).await.unwrap();
+
+    assert_eq!(response.results[0].media_type.as_deref(), Some("movie"));
+}
+
+#[tokio::test]
+async fn test_collection_parts_are_movies() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/collection/2344"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": 2344, "name": "The Matrix Collection", "parts": [{ "id": 603, "title": "The Matrix", "release_date": "1999-03-30" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let collection = client_for(&[&server]).get_collection(2344).await.unwrap();
+
+    assert_eq!(collection.name, "The Matrix Collection");
+    assert_eq!(collection.parts[0].media_type.as_deref(), Some("movie"));
+}
+
+#[tokio::test]
+async fn test_adult_titles_are_only_requested_when_included() {
+    let server = MockServer::start().await;
+    for include_adult in ["false", "true"] {
+        Mock::given(path("/3/search/multi"))
+            .and(query_param("include_adult", include_adult))
+            .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/3/discover/movie"))
+            .and(query_param("include_adult", include_adult))
+            .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    let client = client_for(&[&server]);
+
+    client.search_content("matrix", 1).await.unwrap();
+    client.search_content_including_adult("matrix", 1).await.unwrap();
+    client.discover(&DiscoverFilter::new(MediaType::Movie), 1).await.unwrap();
+    let filter = DiscoverFilter { include_adult: true, ..DiscoverFilter::new(MediaType::Movie) };
+    client.discover(&filter, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_changes_params() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/tv/changes"))
+        .and(query_param("start_date", "2024-05-01"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [{ "id": 1399, "adult": false }, { "id": 1396, "adult": null }],
+            "page": 2,
+            "total_pages": 3,
+            "total_results": 250
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let start_date = "2024-05-01".parse().unwrap();
+    let changes = client_for(&[&server]).get_changes(MediaType::Tv, start_date, 2).await.unwrap();
+
+    assert_eq!(changes.results.iter().map(|title| title.id).collect::<Vec<_>>(), [1399, 1396]);
+    assert_eq!(changes.total_pages, 3);
+}
+
+#[tokio::test]
+async fn test_tv_season_parses_episodes_leniently() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/3/tv/1399/season/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": 3624,
+            "season_number": 1,
+            "name": "Season 1",
+            "air_date": "2011-04-17",
+            "episodes": [
+                { "id": 63056, "episode_number": 1, "name": "Winter Is Coming", "air_date": "2011-04-17", "still_path": "/still.jpg" },
+                { "id": 63057, "episode_number": 2, "name": null, "air_date": "", "still_path": null, "runtime": "unknown" },
+                { "name": "No id" }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let season = client_for(&[&server]).get_tv_season(1399, 1).await.unwrap();
+
+    assert_eq!(season.episodes.len(), 2);
+    assert_eq!(season.episodes[0].still_path.as_deref(), Some("/still.jpg"));
+    assert_eq!(season.episodes[0].air_date, "2011-04-17".parse().ok());
+    assert_eq!(season.episodes[1].name, "");
+    assert_eq!(season.episodes[1].air_date, None);
+    assert_eq!(season.episodes[1].runtime, None);
+}
+
+/// The error a videos lookup gives when TMDB answers `status`
+async fn error_for(status: u16) -> TmdbError {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/movie/603/videos"))
+        .respond_with(ResponseTemplate::new(status).set_body_string(r#"{"status_message": "invalid page"}"#))
+        .mount(&server)
+        .await;
+
+    client_for(&[&server]).get_movie_videos(603).await.unwrap_err()
+}
+
+#[tokio::test]
+async fn test_error_statuses() {
+    assert!(matches!(error_for(400).await, TmdbError::BadRequest(body) if body.contains("invalid page")));
+    assert!(matches!(error_for(401).await, TmdbError::Unauthorized));
+    assert!(matches!(error_for(404).await, TmdbError::NotFound));
+    assert!(matches!(error_for(429).await, TmdbError::RateLimitExceeded));
+    assert!(matches!(error_for(500).await, TmdbError::ServerError(500)));
+    assert!(matches!(error_for(503).await, TmdbError::ServerError(503)));
+    assert!(matches!(error_for(418).await, TmdbError::Unknown(418, _)));
+}
+
+#[tokio::test]
+async fn test_malformed_json_is_a_parse_error() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/trending/all/week"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"page": 1, "results": ["#))
+        .mount(&server)
+        .await;
+    Mock::given(path("/3/network/49"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "forty-nine" })))
+        .mount(&server)
+        .await;
+    let client = client_for(&[&server]);
+
+    assert!(matches!(client.get_trending(1).await, Err(TmdbError::ParseError(_))));
+    // Valid JSON in the wrong shape is a parse error too
+    assert!(matches!(client.get_network(49).await, Err(TmdbError::ParseError(_))));
+}
+
+#[tokio::test]
+async fn test_slow_response_times_out_and_fails_over() {
+    let (slow, fast) = (MockServer::start().await, MockServer::start().await);
+    Mock::given(path("/3/trending/all/week"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()).set_delay(Duration::from_secs(5)))
+        .mount(&slow)
+        .await;
+    Mock::given(path("/3/trending/all/week"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+        .expect(1)
+        .mount(&fast)
+        .await;
+
+    let alone = client_for(&[&slow]).with_timeout(Duration::from_millis(200));
+    assert!(matches!(alone.get_trending(1).await, Err(TmdbError::NetworkError(_))));
+
+    let with_fallback = client_for(&[&slow, &fast]).with_timeout(Duration::from_millis(200));
+    assert_eq!(with_fallback.get_trending(1).await.unwrap().page, 2);
+}
+
+#[tokio::test]
+async fn test_http_errors_are_not_retried() {
+    let (first, second) = (MockServer::start().await, MockServer::start().await);
+    Mock::given(path("/3/trending/all/week"))
+        .respond_with(ResponseTemplate::new(502))
+        .expect(1)
+        .mount(&first)
+        .await;
+    Mock::given(path("/3/trending/all/week"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+        .expect(0)
+        .mount(&second)
+        .await;
+
+    let result = client_for(&[&first, &second]).get_trending(1).await;
+
+    assert!(matches!(result, Err(TmdbError::ServerError(502))));
+}
+
+fn gzip(body: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn test_gzip_responses_are_decoded_and_counted() {
+    let server = MockServer::start().await;
+    let body = serde_json::to_vec(&trending_body()).unwrap();
+    let compressed = gzip(&body);
+    Mock::given(path("/3/trending/all/week"))
+        .and(headers("accept-encoding", vec!["br", "gzip"]))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-encoding", "gzip").set_body_raw(compressed.clone(), "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let compression = Arc::new(UpstreamCompression::default());
+
+    let response = client_for(&[&server]).with_compression(compression.clone()).get_trending(2).await.unwrap();
+
+    assert_eq!(response.results[0].title.as_deref(), Some("The Matrix"));
+    let status = compression.status();
+    assert_eq!((status.responses, status.compressed_responses), (1, 1));
+    assert_eq!(status.compressed_bytes, compressed.len() as u64);
+    assert_eq!(status.decompressed_bytes, body.len() as u64);
+}
+
+#[tokio::test]
+async fn test_compression_off_asks_for_identity() {
+    let server = MockServer::start().await;
+    Mock::given(path("/3/trending/all/week"))
+        .and(header("accept-encoding", "identity"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trending_body()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let compression = Arc::new(UpstreamCompression::new(CompressionMode::Gzip));
+    compression.set(CompressionMode::Off);
+
+    client_for(&[&server]).with_compression(compression.clone()).get_trending(2).await.unwrap();
+
+    let status = compression.status();
+    assert_eq!((status.responses, status.compressed_responses, status.saved_bytes), (1, 0, 0));
+}
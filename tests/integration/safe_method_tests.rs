@@ -0,0 +1,74 @@
+use axum::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::app::{test_app, AppStateOverrides};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client))).unwrap()
+}
+
+fn vary(response: &axum_test::TestResponse) -> Vec<String> {
+    response
+        .headers()
+        .get_all(header::VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_get_with_a_body_is_rejected_before_the_handler() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let response = server.get("/api/trending").json(&json!({ "page": 2 })).await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.text(), "GET and HEAD requests must not have a body");
+    assert_eq!(client.calls(), 0);
+
+    let problem = server
+        .get("/api/trending")
+        .json(&json!({}))
+        .add_header(header::ACCEPT, HeaderValue::from_static("application/problem+json"))
+        .await
+        .json::<Value>();
+    assert_eq!(problem["code"], "body_not_allowed");
+}
+
+#[tokio::test]
+async fn test_cacheable_routes_are_read_only() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let response = server.post("/api/trending").json(&json!({})).await;
+
+    response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.header(header::ALLOW), "GET, HEAD");
+    assert_eq!(client.calls(), 0);
+
+    // Routes without a cache policy keep their own methods
+    server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 603 }] }))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_get_responses_vary_on_negotiated_headers() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+
+    let response = server.get("/api/trending").await;
+
+    response.assert_status_ok();
+    assert_eq!(vary(&response)[..2], ["accept", "x-client-profile"]);
+    // Errors vary too, since Accept picks their format
+    let error = server.get("/api/trending").add_query_param("include", "bogus").await;
+    assert_eq!(vary(&error)[..2], ["accept", "x-client-profile"]);
+
+    let batch = server.post("/api/titles/batch").json(&json!({ "items": [] })).await;
+    assert!(vary(&batch).iter().all(|name| name != "x-client-profile"));
+}
@@ -0,0 +1,83 @@
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::app::{test_app, AppStateOverrides};
+use netflix_service::models::TmdbResponse;
+use netflix_service::safe_search::AdultContentPolicy;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const TOKEN: &str = "test-admin-token";
+
+fn create_test_server(policy: AdultContentPolicy) -> TestServer {
+    let results: TmdbResponse = serde_json::from_value(json!({
+        "page": 1,
+        "total_pages": 1,
+        "results": [
+            { "id": 1, "title": "Family Film", "media_type": "movie", "adult": false },
+            { "id": 2, "title": "Adult Film", "media_type": "movie", "adult": true }
+        ]
+    }))
+    .unwrap();
+    let client = MockTmdbClient::builder().with_default_search(Ok(results)).build();
+    let overrides = AppStateOverrides::new(Arc::new(client))
+        .with_admin_token(TOKEN)
+        .with_state(move |state| state.with_adult_content_policy(policy));
+    TestServer::new(test_app(overrides)).unwrap()
+}
+
+fn ids(body: &Value) -> Vec<i64> {
+    body["results"].as_array().unwrap().iter().map(|item| item["id"].as_i64().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_adult_titles_are_filtered_and_counted_by_default() {
+    let server = create_test_server(AdultContentPolicy::default());
+
+    let body = server.get("/api/search").add_query_param("query", "film").await.json::<Value>();
+    assert_eq!(ids(&body), [1]);
+
+    let refused = server.get("/api/search").add_query_param("query", "film").add_query_param("include_adult", "true").await;
+    refused.assert_status(StatusCode::FORBIDDEN);
+    server
+        .get("/api/discover")
+        .add_query_param("include_adult", "true")
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    // Opting out explicitly is always fine
+    server
+        .get("/api/search")
+        .add_query_param("query", "film")
+        .add_query_param("include_adult", "false")
+        .await
+        .assert_status_ok();
+
+    let stats = server.get("/admin/metrics/safe-search").authorization_bearer(TOKEN).await.json::<Value>();
+    assert_eq!(stats, json!({ "policy": "always_off", "filtered": 2 }));
+}
+
+#[tokio::test]
+async fn test_user_choice_lets_clients_opt_in() {
+    let server = create_test_server(AdultContentPolicy::UserChoice);
+
+    let search = |include_adult: &'static str| {
+        server.get("/api/search").add_query_param("query", "film").add_query_param("include_adult", include_adult)
+    };
+    assert_eq!(ids(&search("true").await.json::<Value>()), [1, 2]);
+    assert_eq!(ids(&search("false").await.json::<Value>()), [1]);
+}
+
+#[tokio::test]
+async fn test_profile_based_refuses_profiles_that_drop_adult_titles() {
+    let server = create_test_server(AdultContentPolicy::ProfileBased);
+
+    let search = |device: &'static str| {
+        server
+            .get("/api/search")
+            .add_query_param("query", "film")
+            .add_query_param("include_adult", "true")
+            .add_header("x-client-profile", device)
+    };
+    search("kids").await.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(search("web").await.json::<Value>()["results"].as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,138 @@
+use axum_test::TestServer;
+use super::{mock_tmdb_client::MockTmdbClient, session};
+use netflix_service::{app::{test_app_with_state, AppStateOverrides}, error::TmdbError, models::{DiscoverFilter, MediaType, Movie, TmdbResponse}, state::AppState};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+fn movie(id: i64, vote_average: f64) -> Movie {
+    Movie {
+        id,
+        title: Some(format!("Movie {}", id)),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: Some(vote_average),
+        vote_count: Some(500),
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: vec![878],
+        release_date: None,
+        media_type: None,
+        adult: false,
+    }
+}
+
+fn page(results: Vec<Movie>) -> Result<TmdbResponse, TmdbError> {
+    Ok(TmdbResponse { page: 1, results, total_pages: 1 })
+}
+
+fn create_test_server(client: MockTmdbClient) -> (TestServer, AppState) {
+    let (app, state) = test_app_with_state(AppStateOverrides::new(Arc::new(client)));
+    (TestServer::new(app).unwrap(), state)
+}
+
+#[tokio::test]
+async fn test_new_matches_become_notifications() {
+    let sci_fi = DiscoverFilter { genres: vec![878], ..DiscoverFilter::new(MediaType::Movie) };
+    let client = MockTmdbClient::builder()
+        .with_discover_sequence(sci_fi, 1, vec![
+            page(vec![movie(1, 8.0)]),
+            page(vec![movie(1, 8.0), movie(2, 8.1), movie(3, 6.0)]),
+            page(vec![movie(2, 8.1), movie(1, 8.0)]),
+        ])
+        .build();
+    let (server, state) = create_test_server(client);
+    let (name, token) = session(&server).await;
+
+    let response = server
+        .post("/api/searches")
+        .add_header(name.clone(), token.clone())
+        .json(&json!({ "source": "discover", "media_type": "movie", "genres": [878], "min_vote": 7.5, "name": "New sci-fi" }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    assert_eq!(response.json::<Value>()["name"], "New sci-fi");
+
+    // The first run records what already matches
+    assert_eq!(state.saved_searches.refresh(state.tmdb_client.as_ref(), &state.blocklist, SystemTime::now()).await, 0);
+    assert_eq!(state.saved_searches.refresh(state.tmdb_client.as_ref(), &state.blocklist, SystemTime::now()).await, 1);
+    assert_eq!(state.saved_searches.refresh(state.tmdb_client.as_ref(), &state.blocklist, SystemTime::now()).await, 0);
+
+    let notifications = server.get("/api/notifications").add_header(name.clone(), token.clone()).await.json::<Value>();
+    assert_eq!(notifications.as_array().unwrap().len(), 1);
+    assert_eq!(notifications[0]["message"], "1 new match for 'New sci-fi'");
+    // Titles rated below min_vote don't match
+    assert_eq!(notifications[0]["titles"].as_array().unwrap().len(), 1);
+    assert_eq!(notifications[0]["titles"][0]["id"], 2);
+
+    let searches = server.get("/api/searches").add_header(name.clone(), token.clone()).await.json::<Value>();
+    assert!(searches[0]["last_checked_at"].is_u64());
+
+    server.delete("/api/notifications").add_header(name.clone(), token.clone()).await;
+    let notifications = server.get("/api/notifications").add_header(name, token).await.json::<Value>();
+    assert!(notifications.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_failed_runs_keep_the_baseline() {
+    let client = MockTmdbClient::builder()
+        .with_search_sequence("dune", 1, vec![
+            Err(TmdbError::ServerError(502)),
+            page(vec![movie(1, 8.0)]),
+            page(vec![movie(1, 8.0), movie(2, 7.0)]),
+        ])
+        .build();
+    let (server, state) = create_test_server(client);
+    let (name, token) = session(&server).await;
+    server.post("/api/searches").add_header(name, token).json(&json!({ "source": "search", "query": " dune " })).await;
+
+    let client = state.tmdb_client.as_ref();
+    assert_eq!(state.saved_searches.refresh(client, &state.blocklist, SystemTime::now()).await, 0);
+    assert_eq!(state.saved_searches.refresh(client, &state.blocklist, SystemTime::now()).await, 0);
+    assert_eq!(state.saved_searches.refresh(client, &state.blocklist, SystemTime::now()).await, 1);
+}
+
+#[tokio::test]
+async fn test_searches_belong_to_their_session() {
+    let (server, _) = create_test_server(MockTmdbClient::new());
+    let (name, token) = session(&server).await;
+    let (_, other) = session(&server).await;
+
+    let saved = server
+        .post("/api/searches")
+        .add_header(name.clone(), token.clone())
+        .json(&json!({ "source": "search", "query": "batman" }))
+        .await
+        .json::<Value>();
+    assert_eq!(saved["name"], "batman");
+    let id = saved["id"].as_u64().unwrap();
+
+    let others = server.get("/api/searches").add_header(name.clone(), other.clone()).await.json::<Value>();
+    assert!(others.as_array().unwrap().is_empty());
+    server.delete(&format!("/api/searches/{}", id)).add_header(name.clone(), other).await.assert_status_not_found();
+
+    server.delete(&format!("/api/searches/{}", id)).add_header(name.clone(), token.clone()).await.assert_status(axum::http::StatusCode::NO_CONTENT);
+    let mine = server.get("/api/searches").add_header(name, token).await.json::<Value>();
+    assert!(mine.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_invalid_searches_are_rejected() {
+    let (server, _) = create_test_server(MockTmdbClient::new());
+
+    let empty = server.post("/api/searches").json(&json!({ "source": "search", "query": "  " })).await;
+    empty.assert_status_bad_request();
+    assert_eq!(empty.text(), "query must not be empty");
+
+    let no_region = server
+        .post("/api/searches")
+        .json(&json!({ "source": "discover", "media_type": "movie", "providers": [8] }))
+        .await;
+    no_region.assert_status_bad_request();
+
+    let bad_vote = server.post("/api/searches").json(&json!({ "source": "search", "query": "x", "min_vote": 11 })).await;
+    bad_vote.assert_status_bad_request();
+}
@@ -0,0 +1,218 @@
+use axum::{middleware, routing::{get, post}, Router};
+use axum_test::TestServer;
+use serde_json::json;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{clock::MockClock, handlers, pages, models::TmdbResponse, session::{self, RecentActivity, RecentTitle, DEFAULT_SESSION_TTL}, state::AppState, taste::TasteProfile};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn create_test_app() -> Router {
+    let state = AppState::new(Arc::new(MockTmdbClient::new()));
+
+    Router::new()
+        .route("/api/search", get(handlers::search_content))
+        .route("/api/session/recent", get(handlers::get_session_recent).delete(handlers::clear_session_recent))
+        .route("/api/session/recent/picks", post(handlers::add_session_pick))
+        .route("/api/recent", get(handlers::get_recently_viewed).delete(handlers::clear_recently_viewed))
+        .route("/api/me/taste", get(handlers::get_taste))
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/title/{media_type}/{id}", get(pages::title_page))
+        .layer(middleware::from_fn_with_state(state.clone(), session::session_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_new_session_is_issued() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server.get("/api/session/recent").await;
+
+    assert_eq!(response.status_code(), 200);
+    let token = response.header("x-session-token");
+    let cookie = response.header("set-cookie");
+    let cookie = cookie.to_str().unwrap();
+    assert!(cookie.starts_with(&format!("session={};", token.to_str().unwrap())));
+    assert!(cookie.contains("HttpOnly"));
+
+    let recent: RecentActivity = response.json();
+    assert!(recent.searches.is_empty());
+    assert!(recent.picks.is_empty());
+}
+
+#[tokio::test]
+async fn test_existing_session_is_reused() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let token = server.get("/api/session/recent").await.header("x-session-token");
+
+    let response = server
+        .get("/api/session/recent")
+        .add_header("x-session-token", token)
+        .await;
+
+    assert!(response.maybe_header("set-cookie").is_none());
+}
+
+#[tokio::test]
+async fn test_searches_and_picks_are_recorded_per_session() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let token = server.get("/api/session/recent").await.header("x-session-token");
+    let cookie = format!("session={}", token.to_str().unwrap());
+
+    server.get("/api/search?query=matrix").add_header("cookie", cookie.clone()).await;
+    let pick = server
+        .post("/api/session/recent/picks")
+        .add_header("cookie", cookie.clone())
+        .json(&json!({ "media_type": "movie", "id": 603, "title": "The Matrix" }))
+        .await;
+    assert_eq!(pick.status_code(), 204);
+
+    // A search from another device does not show up
+    server.get("/api/search?query=alien").await;
+
+    let recent: RecentActivity = server
+        .get("/api/session/recent")
+        .add_header("cookie", cookie)
+        .await
+        .json();
+    assert_eq!(recent.searches, vec!["matrix"]);
+    assert_eq!(recent.picks.len(), 1);
+    assert_eq!(recent.picks[0].id, 603);
+}
+
+#[tokio::test]
+async fn test_clear_recent() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let token = server.get("/api/session/recent").await.header("x-session-token");
+
+    server.get("/api/search?query=matrix").add_header("x-session-token", token.clone()).await;
+    let cleared = server.delete("/api/session/recent").add_header("x-session-token", token.clone()).await;
+    assert_eq!(cleared.status_code(), 204);
+
+    let recent: RecentActivity = server
+        .get("/api/session/recent")
+        .add_header("x-session-token", token)
+        .await
+        .json();
+    assert!(recent.searches.is_empty());
+}
+
+#[tokio::test]
+async fn test_forged_token_gets_new_session() {
+    let server = TestServer::new(create_test_app()).unwrap();
+
+    let response = server
+        .get("/api/session/recent")
+        .add_header("x-session-token", "deadbeef.00")
+        .await;
+
+    assert_ne!(response.header("x-session-token"), "deadbeef.00");
+}
+
+#[tokio::test]
+async fn test_recently_viewed_titles() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let token = server.get("/api/recent").await.header("x-session-token");
+
+    for path in ["/title/movie/603", "/title/tv/1399", "/title/movie/603"] {
+        server.get(path).add_header("x-session-token", token.clone()).await;
+    }
+
+    let recent: Vec<RecentTitle> = server
+        .get("/api/recent")
+        .add_header("x-session-token", token.clone())
+        .await
+        .json();
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0].id, 603);
+    assert_eq!(recent[0].title, "Test Movie 1");
+    assert_eq!(recent[0].poster_path, Some("/details.jpg".to_string()));
+    assert_eq!(recent[1].id, 1399);
+
+    let limited: Vec<RecentTitle> = server
+        .get("/api/recent?limit=1")
+        .add_header("x-session-token", token)
+        .await
+        .json();
+    assert_eq!(limited.len(), 1);
+}
+
+#[tokio::test]
+async fn test_clear_recently_viewed_keeps_searches() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let token = server.get("/api/recent").await.header("x-session-token");
+
+    server.get("/title/movie/603").add_header("x-session-token", token.clone()).await;
+    server.get("/api/search?query=matrix").add_header("x-session-token", token.clone()).await;
+
+    let cleared = server.delete("/api/recent").add_header("x-session-token", token.clone()).await;
+    assert_eq!(cleared.status_code(), 204);
+
+    let recent: Vec<RecentTitle> = server
+        .get("/api/recent")
+        .add_header("x-session-token", token.clone())
+        .await
+        .json();
+    assert!(recent.is_empty());
+
+    let activity: RecentActivity = server
+        .get("/api/session/recent")
+        .add_header("x-session-token", token)
+        .await
+        .json();
+    assert_eq!(activity.searches, vec!["matrix"]);
+}
+
+#[tokio::test]
+async fn test_idle_session_expires() {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+    let state = AppState::new(Arc::new(MockTmdbClient::new())).with_clock(clock.clone());
+    let app = Router::new()
+        .route("/api/search", get(handlers::search_content))
+        .route("/api/session/recent", get(handlers::get_session_recent))
+        .layer(middleware::from_fn_with_state(state.clone(), session::session_middleware))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+    let token = server.get("/api/session/recent").await.header("x-session-token");
+    let cookie = format!("session={}", token.to_str().unwrap());
+
+    server.get("/api/search?query=matrix").add_header("cookie", cookie.clone()).await;
+    clock.advance(DEFAULT_SESSION_TTL - Duration::from_secs(1));
+    let recent: RecentActivity = server.get("/api/session/recent").add_header("cookie", cookie.clone()).await.json();
+    assert_eq!(recent.searches, vec!["matrix"]);
+
+    clock.advance(Duration::from_secs(2));
+    let recent: RecentActivity = server.get("/api/session/recent").add_header("cookie", cookie).await.json();
+    assert!(recent.searches.is_empty());
+}
+
+#[tokio::test]
+async fn test_taste_profile_personalizes_trending() {
+    let server = TestServer::new(create_test_app()).unwrap();
+    let token = server.get("/api/me/taste").await.header("x-session-token");
+    let trending_ids = |response: TmdbResponse| response.results.iter().map(|item| item.id).collect::<Vec<_>>();
+
+    let empty: TasteProfile = server.get("/api/me/taste").add_header("x-session-token", token.clone()).await.json();
+    assert_eq!(empty, TasteProfile::default());
+
+    // Mock details are all dramas; the trending drama is second in TMDB's order
+    for path in ["/title/movie/603", "/title/tv/1399"] {
+        server.get(path).add_header("x-session-token", token.clone()).await;
+    }
+    let taste: TasteProfile = server.get("/api/me/taste").add_header("x-session-token", token.clone()).await.json();
+    assert_eq!(taste.views, 2);
+    assert_eq!((taste.genres[0].id, taste.genres[0].name.as_str(), taste.genres[0].weight), (18, "Drama", 1.0));
+
+    let plain = server.get("/api/trending").add_header("x-session-token", token.clone()).await.json();
+    let personalized = server
+        .get("/api/trending?personalized=true")
+        .add_header("x-session-token", token.clone())
+        .await
+        .json();
+    assert_eq!(trending_ids(plain), [123, 456]);
+    assert_eq!(trending_ids(personalized), [456, 123]);
+
+    // Clearing the viewing history resets the taste built from it
+    server.delete("/api/recent").add_header("x-session-token", token.clone()).await;
+    let cleared: TasteProfile = server.get("/api/me/taste").add_header("x-session-token", token).await.json();
+    assert_eq!(cleared, TasteProfile::default());
+}
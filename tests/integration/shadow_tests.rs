@@ -0,0 +1,102 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClientBuilder;
+use netflix_service::{app::{self, AppOptions}, models::{Movie, TmdbResponse}, problem::ApiError, shadow::{ListPipeline, ListRequest, ListRoute, ShadowStatus, ShadowTraffic}, state::AppState};
+use serde_json::json;
+use std::sync::Arc;
+
+const TOKEN: &str = "secret-token";
+
+/// What the candidate does with the TMDB response
+enum Candidate {
+    Unchanged,
+    Reversed,
+    Failing,
+}
+
+#[async_trait]
+impl ListPipeline for Candidate {
+    async fn normalize(&self, _request: ListRequest, mut upstream: TmdbResponse) -> Result<TmdbResponse, ApiError> {
+        match self {
+            Candidate::Unchanged => Ok(upstream),
+            Candidate::Reversed => {
+                upstream.results.reverse();
+                Ok(upstream)
+            }
+            Candidate::Failing => Err(ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", "not ready")),
+        }
+    }
+}
+
+fn movie(id: i64, title: &str) -> Movie {
+    serde_json::from_value(json!({ "id": id, "title": title, "media_type": "movie" })).unwrap()
+}
+
+fn create_test_server(candidate: Candidate, sample_rate: f64) -> (TestServer, AppState) {
+    let trending = TmdbResponse { page: 1, results: vec![movie(603, "The Matrix"), movie(604, "The Matrix Reloaded")], total_pages: 1 };
+    let client = MockTmdbClientBuilder::new().with_default_trending(Ok(trending)).build();
+    let shadow = ShadowTraffic::new(Arc::new(candidate), sample_rate).unwrap();
+    let state = AppState::new(Arc::new(client)).with_admin_token(TOKEN).with_shadow_traffic(shadow);
+    let server = TestServer::new(app::router(state.clone(), &AppOptions::default())).unwrap();
+    (server, state)
+}
+
+fn route_stats(status: &ShadowStatus, route: ListRoute) -> (u64, u64, u64, u64) {
+    let stats = status.routes.iter().find(|stats| stats.route == route).unwrap();
+    (stats.compared, stats.matched, stats.mismatched, stats.failed)
+}
+
+#[tokio::test]
+async fn test_matching_candidate_is_counted_without_mismatches() {
+    let (server, state) = create_test_server(Candidate::Unchanged, 1.0);
+
+    server.get("/api/trending").await.assert_status_ok();
+    state.shadow.idle().await;
+
+    let status = server.get("/admin/metrics/shadow").authorization_bearer(TOKEN).await.json::<ShadowStatus>();
+    assert!(status.enabled);
+    assert_eq!(route_stats(&status, ListRoute::Trending), (1, 1, 0, 0));
+    assert!(status.recent_mismatches.is_empty());
+}
+
+#[tokio::test]
+async fn test_mismatches_record_differing_paths_and_leave_the_response_alone() {
+    let (server, state) = create_test_server(Candidate::Reversed, 1.0);
+
+    let response = server.get("/api/trending").await;
+    state.shadow.idle().await;
+
+    assert_eq!(response.json::<TmdbResponse>().results[0].id, 603);
+    let status = state.shadow.status();
+    assert_eq!(route_stats(&status, ListRoute::Trending), (1, 0, 1, 0));
+    let differences = &status.recent_mismatches[0].differences;
+    assert!(differences.contains(&"/results/0/id".to_string()));
+    assert!(differences.contains(&"/results/1/title".to_string()));
+}
+
+#[tokio::test]
+async fn test_candidate_failures_and_sample_rate() {
+    let (server, state) = create_test_server(Candidate::Failing, 0.0);
+
+    server.get("/api/trending").await.assert_status_ok();
+    state.shadow.idle().await;
+    assert_eq!(route_stats(&state.shadow.status(), ListRoute::Trending), (0, 0, 0, 0));
+
+    server
+        .put("/admin/shadow")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "sample_rate": 1.5 }))
+        .await
+        .assert_status_bad_request();
+    server
+        .put("/admin/shadow")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "sample_rate": 1.0 }))
+        .await
+        .assert_status_ok();
+
+    server.get("/api/trending").await.assert_status_ok();
+    state.shadow.idle().await;
+    assert_eq!(route_stats(&state.shadow.status(), ListRoute::Trending), (1, 0, 0, 1));
+}
@@ -0,0 +1,68 @@
+use axum::http::{header, HeaderValue};
+use axum_test::TestServer;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{self, AppOptions}, error::TmdbError, signing::{canonical_body, PublicKeySet, ResponseSigner, SIGNATURE_HEADER, SIGNATURE_KEY_HEADER}, state::AppState};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use std::sync::Arc;
+
+const SEED: [u8; 32] = [7; 32];
+const PROBLEM_JSON: HeaderValue = HeaderValue::from_static("application/problem+json");
+
+fn create_test_server(client: MockTmdbClient, signer: ResponseSigner) -> TestServer {
+    let state = AppState::new(Arc::new(client)).with_response_signer(signer);
+    TestServer::new(app::router(state, &AppOptions::default())).unwrap()
+}
+
+fn verify(keys: &PublicKeySet, kid: &str, body: &[u8], signature: &str) -> bool {
+    let key = keys.keys.iter().find(|key| key.kid == kid).expect("signing key is published");
+    let public_key = URL_SAFE_NO_PAD.decode(&key.x).unwrap();
+    let signature = STANDARD.decode(signature).unwrap();
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(&canonical_body(body, true), &signature)
+        .is_ok()
+}
+
+#[tokio::test]
+async fn test_responses_verify_against_published_key() {
+    let retired = STANDARD.encode([9u8; 32]);
+    let client = MockTmdbClient::builder().with_video_response(603, Err(TmdbError::NotFound)).build();
+    let server = create_test_server(client, ResponseSigner::from_seed(&SEED).unwrap().with_retired_key(&retired).unwrap());
+
+    let keys: PublicKeySet = server.get("/api/keys/public").await.json();
+    assert_eq!(keys.keys.len(), 2);
+    assert!(!keys.keys[0].retired);
+    assert!(keys.keys[1].retired);
+    assert_eq!(keys.keys[0].crv, "Ed25519");
+
+    // Successful and problem responses are both signed
+    for path in ["/api/trending", "/api/movie/603/videos"] {
+        let response = server.get(path).add_header(header::ACCEPT, PROBLEM_JSON).await;
+        let signature = response.header(SIGNATURE_HEADER);
+        let kid = response.header(SIGNATURE_KEY_HEADER);
+        assert_eq!(kid.to_str().unwrap(), keys.keys[0].kid);
+        let body = response.as_bytes();
+        assert!(verify(&keys, kid.to_str().unwrap(), body, signature.to_str().unwrap()), "{}", path);
+
+        // Reordering keys or whitespace keeps the signature valid, changing a value doesn't
+        let mut value: serde_json::Value = serde_json::from_slice(body).unwrap();
+        let reencoded = serde_json::to_vec_pretty(&value).unwrap();
+        assert!(verify(&keys, kid.to_str().unwrap(), &reencoded, signature.to_str().unwrap()));
+        value["tampered"] = serde_json::Value::Bool(true);
+        let tampered = serde_json::to_vec(&value).unwrap();
+        assert!(!verify(&keys, kid.to_str().unwrap(), &tampered, signature.to_str().unwrap()));
+    }
+}
+
+#[tokio::test]
+async fn test_nothing_is_signed_without_a_key() {
+    let server = create_test_server(MockTmdbClient::new(), ResponseSigner::default());
+
+    let response = server.get("/api/trending").await;
+    assert!(response.maybe_header(SIGNATURE_HEADER).is_none());
+    assert!(response.maybe_header(SIGNATURE_KEY_HEADER).is_none());
+
+    let keys: PublicKeySet = server.get("/api/keys/public").await.json();
+    assert!(keys.keys.is_empty());
+}
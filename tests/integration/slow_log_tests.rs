@@ -0,0 +1,108 @@
+use axum::{middleware, routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{admin, handlers, slow_log::{self, SlowEventCounts, SlowLog, SlowThresholds, TimedTmdbClient}, state::AppState, upstream_metrics::{Operation, OperationStats, UpstreamMetrics}};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TOKEN: &str = "secret-token";
+
+fn create_test_app(thresholds: SlowThresholds) -> Router {
+    let slow_log = Arc::new(SlowLog::new(thresholds));
+    let upstream_metrics = Arc::new(UpstreamMetrics::new());
+    let client = TimedTmdbClient::new(MockTmdbClient::new(), slow_log.clone()).with_metrics(upstream_metrics.clone());
+    let state = AppState::new(Arc::new(client))
+        .with_slow_log(slow_log)
+        .with_upstream_metrics(upstream_metrics)
+        .with_admin_token(TOKEN);
+
+    Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/search", get(handlers::search_content))
+        .nest("/admin", admin::router(state.clone()))
+        .layer(middleware::from_fn_with_state(state.clone(), slow_log::slow_request_middleware))
+        .with_state(state)
+}
+
+async fn slow_counts(server: &TestServer) -> SlowEventCounts {
+    server
+        .get("/admin/metrics/slow")
+        .authorization_bearer(TOKEN)
+        .await
+        .json::<SlowEventCounts>()
+}
+
+#[tokio::test]
+async fn test_fast_requests_are_not_counted() {
+    let server = TestServer::new(create_test_app(SlowThresholds {
+        request: Duration::from_secs(60),
+        upstream: Duration::from_secs(60),
+    }))
+    .unwrap();
+
+    server.get("/api/trending").await.assert_status_ok();
+
+    let counts = slow_counts(&server).await;
+    assert_eq!(counts.slow_requests, 0);
+    assert_eq!(counts.slow_upstream_calls, 0);
+}
+
+#[tokio::test]
+async fn test_slow_requests_and_upstream_calls_are_counted() {
+    let server = TestServer::new(create_test_app(SlowThresholds {
+        request: Duration::ZERO,
+        upstream: Duration::ZERO,
+    }))
+    .unwrap();
+
+    server.get("/api/trending").await.assert_status_ok();
+    server.get("/api/search").add_query_param("query", "matrix").await.assert_status_ok();
+
+    let counts = slow_counts(&server).await;
+    assert_eq!(counts.slow_requests, 2);
+    assert_eq!(counts.slow_upstream_calls, 2);
+}
+
+#[tokio::test]
+async fn test_slow_metrics_require_admin_token() {
+    let server = TestServer::new(create_test_app(SlowThresholds::default())).unwrap();
+
+    server.get("/admin/metrics/slow").await.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn test_upstream_calls_are_counted_per_operation() {
+    let server = TestServer::new(create_test_app(SlowThresholds::default())).unwrap();
+
+    server.get("/api/trending").await.assert_status_ok();
+    server.get("/api/search").add_query_param("query", "matrix").await.assert_status_ok();
+    server.get("/api/search").add_query_param("query", "alien").await.assert_status_ok();
+
+    let stats = server
+        .get("/admin/metrics/upstream")
+        .authorization_bearer(TOKEN)
+        .await
+        .json::<Vec<OperationStats>>();
+    let calls: Vec<(Operation, u64)> = stats.iter().map(|stats| (stats.operation, stats.calls)).collect();
+    assert_eq!(
+        calls,
+        vec![
+            (Operation::Trending, 1),
+            (Operation::Search, 2),
+            (Operation::Videos, 0),
+            (Operation::Details, 0),
+            (Operation::Images, 0),
+            (Operation::AlternativeTitles, 0),
+            (Operation::WatchProviders, 0),
+            (Operation::Discover, 0),
+            (Operation::ProviderList, 0),
+            (Operation::Network, 0),
+            (Operation::List, 0),
+            (Operation::Changes, 0),
+            (Operation::Season, 0),
+            (Operation::TopRated, 0),
+            (Operation::Popular, 0),
+            (Operation::Collection, 0),
+        ]
+    );
+}
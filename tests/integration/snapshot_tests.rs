@@ -0,0 +1,167 @@
+//! Golden files for the JSON API. A failing test here means the wire format
+//! changed: review the diff with `cargo insta review` and accept it only if
+//! the change is intended.
+
+use axum::{middleware, routing::{get, post}, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{clock::{self, MockClock}, error::TmdbError, handlers, lists::CuratedListInput, models::{MediaType, TitleRef, TmdbId}, pages, session, state::AppState};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn create_test_app(mock_client: MockTmdbClient) -> Router {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_750_000_000)));
+    let state = AppState::new(Arc::new(mock_client)).with_clock(clock);
+    let picks = CuratedListInput {
+        title: "Staff picks".to_string(),
+        description: Some("Chosen by the editors".to_string()),
+        items: vec![
+            TitleRef { media_type: MediaType::Movie, id: TmdbId::try_from(603).unwrap() },
+            TitleRef { media_type: MediaType::Tv, id: TmdbId::try_from(1399).unwrap() },
+        ],
+    };
+    state.lists.put("staff-picks", picks, UNIX_EPOCH).unwrap();
+
+    Router::new()
+        .route("/api/trending", get(handlers::get_trending_movies))
+        .route("/api/home", get(handlers::get_home))
+        .route("/api/search", get(handlers::search_content))
+        .route("/api/movie/{id}/videos", get(handlers::get_movie_videos))
+        .route("/api/movie/{id}/images", get(handlers::get_movie_images))
+        .route("/api/movie/{id}/alternative-titles", get(handlers::get_movie_alternative_titles))
+        .route("/api/movie/{id}/availability", get(handlers::get_movie_availability))
+        .route("/api/discover", get(handlers::discover))
+        .route("/api/providers", get(handlers::get_providers))
+        .route("/api/tv/{id}/season/{season_number}", get(handlers::get_tv_season))
+        .route("/api/network/{id}", get(handlers::get_network))
+        .route("/api/network/{id}/titles", get(handlers::get_network_titles))
+        .route("/api/company/{id}/titles", get(handlers::get_company_titles))
+        .route("/api/lists/{slug}", get(handlers::get_curated_list))
+        .route("/api/session/recent", get(handlers::get_session_recent))
+        .route("/api/session/recent/picks", post(handlers::add_session_pick))
+        .route("/api/recent", get(handlers::get_recently_viewed))
+        .route("/api/me/taste", get(handlers::get_taste))
+        .route("/api/titles/batch", post(handlers::get_titles_batch))
+        .route("/title/{media_type}/{id}", get(pages::title_page))
+        .route("/oembed", get(handlers::get_oembed))
+        .layer(middleware::from_fn_with_state(state.clone(), clock::today_middleware))
+        .layer(middleware::from_fn_with_state(state.clone(), session::session_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_read_endpoints() {
+    let server = TestServer::new(create_test_app(MockTmdbClient::new())).unwrap();
+    let routes = [
+        ("trending", "/api/trending"),
+        ("trending_by_quality", "/api/trending?sort=quality"),
+        ("home", "/api/home"),
+        ("search", "/api/search?query=matrix"),
+        ("movie_videos", "/api/movie/603/videos"),
+        ("movie_images", "/api/movie/603/images?language=en,null"),
+        ("movie_alternative_titles", "/api/movie/603/alternative-titles"),
+        ("movie_availability", "/api/movie/603/availability?regions=US,IT"),
+        ("discover", "/api/discover"),
+        ("discover_by_provider", "/api/discover?providers=8&region=US"),
+        ("providers", "/api/providers?region=US"),
+        ("tv_season", "/api/tv/1399/season/1"),
+        ("network", "/api/network/49"),
+        ("network_titles", "/api/network/49/titles"),
+        ("company_titles", "/api/company/420/titles"),
+        ("curated_list", "/api/lists/staff-picks"),
+        ("oembed", "/oembed?url=http://localhost:8080/title/movie/603"),
+    ];
+
+    for (name, route) in routes {
+        let response = server.get(route).await;
+        assert_eq!(response.status_code(), 200, "{}", route);
+        insta::assert_json_snapshot!(name, response.json::<Value>());
+    }
+}
+
+#[tokio::test]
+async fn test_session_endpoints() {
+    let server = TestServer::builder().save_cookies().build(create_test_app(MockTmdbClient::new())).unwrap();
+    server.get("/api/search?query=matrix").await.assert_status_ok();
+    server
+        .post("/api/session/recent/picks")
+        .json(&json!({ "media_type": "movie", "id": 603, "title": "The Matrix" }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server.get("/title/movie/603").await.assert_status_ok();
+
+    insta::assert_json_snapshot!("session_recent", server.get("/api/session/recent").await.json::<Value>());
+    insta::assert_json_snapshot!("recently_viewed", server.get("/api/recent").await.json::<Value>());
+    insta::assert_json_snapshot!("taste", server.get("/api/me/taste").await.json::<Value>());
+}
+
+#[tokio::test]
+async fn test_batch_with_failed_item() {
+    let mock_client = MockTmdbClient::builder().with_details_error(MediaType::Movie, 404, TmdbError::NotFound).build();
+    let server = TestServer::new(create_test_app(mock_client)).unwrap();
+
+    let response = server
+        .post("/api/titles/batch")
+        .json(&json!({ "items": [{ "media_type": "movie", "id": 603 }, { "media_type": "movie", "id": 404 }], "include_logo": true }))
+        .await;
+
+    insta::assert_json_snapshot!("titles_batch", response.json::<Value>());
+}
+
+/// Status and body of every upstream error, as seen through one route
+#[tokio::test]
+async fn test_upstream_error_variants() {
+    let errors = [
+        TmdbError::NotFound,
+        TmdbError::Unauthorized,
+        TmdbError::RateLimitExceeded,
+        TmdbError::BadRequest("invalid page".to_string()),
+        TmdbError::ServerError(503),
+        TmdbError::NetworkError("connection reset".to_string()),
+        TmdbError::ParseError("expected value".to_string()),
+        TmdbError::Unknown(418, "teapot".to_string()),
+        TmdbError::UpstreamDisabled,
+    ];
+
+    let mut responses = Vec::new();
+    for error in errors {
+        let label = format!("{:?}", error);
+        let mock_client = MockTmdbClient::builder().with_video_error(603, error).build();
+        let server = TestServer::new(create_test_app(mock_client)).unwrap();
+        let response = server.get("/api/movie/603/videos").await;
+        responses.push(json!({ "error": label, "status": response.status_code().as_u16(), "body": response.text() }));
+    }
+
+    insta::assert_json_snapshot!("upstream_errors", responses);
+}
+
+/// Status and body of the request validation errors
+#[tokio::test]
+async fn test_client_errors() {
+    let server = TestServer::new(create_test_app(MockTmdbClient::new())).unwrap();
+    let routes = [
+        "/api/movie/0/videos",
+        "/api/movie/abc/videos",
+        "/api/search?query=%20",
+        "/api/discover?providers=8",
+        "/api/discover?providers=x&region=US",
+        "/api/movie/603/availability?regions=USA",
+        "/api/providers?region=1",
+        "/api/lists/unknown",
+        "/api/trending?sort=newest",
+        "/oembed?url=http://localhost:8080/title/tv/1399",
+        "/oembed?url=http://localhost:8080/title/movie/603&format=xml",
+    ];
+
+    let mut responses = Vec::new();
+    for route in routes {
+        let response = server.get(route).await;
+        responses.push(json!({ "route": route, "status": response.status_code().as_u16(), "body": response.text() }));
+    }
+    let too_many: Vec<Value> = (1..=51).map(|id| json!({ "media_type": "movie", "id": id })).collect();
+    let response = server.post("/api/titles/batch").json(&json!({ "items": too_many })).await;
+    responses.push(json!({ "route": "POST /api/titles/batch (51 items)", "status": response.status_code().as_u16(), "body": response.text() }));
+
+    insta::assert_json_snapshot!("client_errors", responses);
+}
@@ -0,0 +1,54 @@
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{test_app, AppStateOverrides}, clock::MockClock};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+const TOKEN: &str = "test-admin-token";
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client).with_admin_token(TOKEN))).unwrap()
+}
+
+#[tokio::test]
+async fn test_fresh_service_has_no_snapshot() {
+    let server = create_test_server(Arc::new(MockTmdbClient::new()));
+
+    let response = server.get("/api/stats/public").await;
+
+    response.assert_status_ok();
+    let body = response.json::<Value>();
+    assert_eq!(body["api_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(body["catalog_snapshot_at"], Value::Null);
+    assert_eq!(body["catalog_snapshot_date"], Value::Null);
+    assert_eq!(body["cached_titles"], 0);
+    assert!(body["uptime_seconds"].is_u64());
+    assert!(body["started_at"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_snapshot_and_cached_titles_follow_traffic() {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+    let overrides = AppStateOverrides::new(Arc::new(MockTmdbClient::new())).with_clock(clock.clone());
+    let server = TestServer::new(test_app(overrides)).unwrap();
+
+    server.get("/api/trending").add_query_param("include", "providers").await.assert_status_ok();
+    clock.advance(Duration::from_secs(3600));
+    let body = server.get("/api/stats/public").await.json::<Value>();
+
+    assert_eq!(body["catalog_snapshot_at"], 1_700_000_000);
+    assert_eq!(body["catalog_snapshot_date"], "2023-11-14");
+    // One movie and one TV show, cached both as availability and as an enrichment
+    assert_eq!(body["cached_titles"], 2);
+}
+
+#[tokio::test]
+async fn test_stats_are_served_during_maintenance() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+    server.put("/admin/mode").authorization_bearer(TOKEN).json(&json!({ "mode": "maintenance" })).await.assert_status_ok();
+
+    server.get("/api/stats/public").await.assert_status_ok();
+    assert_eq!(client.calls(), 0);
+}
@@ -0,0 +1,114 @@
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use super::{mock_tmdb_client::MockTmdbClient, session};
+use netflix_service::{app::{test_app, AppStateOverrides}, clock::MockClock};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+const TOKEN: &str = "secret-token";
+
+fn create_test_server() -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(Arc::new(MockTmdbClient::new())).with_admin_token(TOKEN))).unwrap()
+}
+
+async fn put_list(server: &TestServer, slug: &str) {
+    let body = json!({ "title": slug, "items": [{ "media_type": "movie", "id": 603 }] });
+    server.put(&format!("/admin/lists/{}", slug)).authorization_bearer(TOKEN).json(&body).await.assert_status_success();
+}
+
+async fn sync(server: &TestServer, session: &(HeaderName, HeaderValue), since: Option<&str>) -> Value {
+    let mut request = server.get("/api/sync").add_header(session.0.clone(), session.1.clone());
+    if let Some(since) = since {
+        request = request.add_query_param("since", since);
+    }
+    let response = request.await;
+    response.assert_status_ok();
+    response.json()
+}
+
+fn keys(delta: &Value, key: &str) -> Vec<Value> {
+    delta["upserted"].as_array().unwrap().iter().map(|entry| entry[key].clone()).collect()
+}
+
+#[tokio::test]
+async fn test_sync_sends_only_changes_since_the_token() {
+    let server = create_test_server();
+    let session = session(&server).await;
+    put_list(&server, "staff-picks").await;
+    put_list(&server, "classics").await;
+    let search = server
+        .post("/api/searches")
+        .add_header(session.0.clone(), session.1.clone())
+        .json(&json!({ "source": "search", "query": "matrix" }))
+        .await
+        .json::<Value>();
+
+    let first = sync(&server, &session, None).await;
+    assert_eq!(first["lists"]["full"], true);
+    assert_eq!(keys(&first["lists"], "slug"), [json!("classics"), json!("staff-picks")]);
+    assert_eq!(first["saved_searches"]["full"], true);
+    assert_eq!(keys(&first["saved_searches"], "id"), [search["id"].clone()]);
+
+    // Nothing changed
+    let token = first["sync_token"].as_str().unwrap().to_string();
+    let unchanged = sync(&server, &session, Some(&token)).await;
+    assert_eq!(unchanged["sync_token"], token.as_str());
+    assert_eq!(unchanged["lists"], json!({ "full": false, "upserted": [], "deleted": [] }));
+    assert_eq!(unchanged["saved_searches"], json!({ "full": false, "upserted": [], "deleted": [] }));
+
+    put_list(&server, "staff-picks").await;
+    server.delete("/admin/lists/classics").authorization_bearer(TOKEN).await.assert_status(StatusCode::NO_CONTENT);
+    let path = format!("/api/searches/{}", search["id"]);
+    server.delete(&path).add_header(session.0.clone(), session.1.clone()).await.assert_status(StatusCode::NO_CONTENT);
+
+    let delta = sync(&server, &session, Some(&token)).await;
+    assert_eq!(delta["lists"]["full"], false);
+    assert_eq!(keys(&delta["lists"], "slug"), [json!("staff-picks")]);
+    assert_eq!(delta["lists"]["deleted"], json!(["classics"]));
+    assert_eq!(delta["saved_searches"]["deleted"], json!([search["id"]]));
+    assert_ne!(delta["sync_token"], token.as_str());
+}
+
+#[tokio::test]
+async fn test_tokens_of_another_process_get_a_full_copy() {
+    let server = create_test_server();
+    let session = session(&server).await;
+    put_list(&server, "staff-picks").await;
+
+    let body = sync(&server, &session, Some("1234abcd.5.7")).await;
+    assert_eq!(body["lists"]["full"], true);
+    assert_eq!(keys(&body["lists"], "slug"), [json!("staff-picks")]);
+
+    let response = server
+        .get("/api/sync")
+        .add_header(session.0.clone(), session.1.clone())
+        .add_query_param("since", "yesterday")
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_timestamps_send_changes_made_at_or_after_them() {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+    let overrides = AppStateOverrides::new(Arc::new(MockTmdbClient::new())).with_admin_token(TOKEN).with_clock(clock.clone());
+    let server = TestServer::new(test_app(overrides)).unwrap();
+    let session = session(&server).await;
+    put_list(&server, "staff-picks").await;
+    put_list(&server, "classics").await;
+
+    clock.advance(Duration::from_secs(60));
+    put_list(&server, "classics").await;
+    server.delete("/admin/lists/staff-picks").authorization_bearer(TOKEN).await.assert_status(StatusCode::NO_CONTENT);
+
+    let delta = sync(&server, &session, Some("1700000030")).await;
+    assert_eq!(delta["lists"]["full"], false);
+    assert_eq!(keys(&delta["lists"], "slug"), [json!("classics")]);
+    assert_eq!(delta["lists"]["deleted"], json!(["staff-picks"]));
+    assert_eq!(delta["saved_searches"], json!({ "full": false, "upserted": [], "deleted": [] }));
+
+    // Nothing is known from before the first change, e.g. of an earlier process
+    let before = sync(&server, &session, Some("1699999999")).await;
+    assert_eq!(before["lists"]["full"], true);
+    assert_eq!(keys(&before["lists"], "slug"), [json!("classics")]);
+}
@@ -0,0 +1,94 @@
+use axum::{http::StatusCode, routing::get, Router};
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{admin, clock::MockClock, handlers, home::{HomeLayout, HomePage}, models::TmdbResponse, state::AppState, themes::ThemedRow};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+const TOKEN: &str = "secret-token";
+
+/// 2026-10-18, midday UTC
+const OCTOBER_18: Duration = Duration::from_secs(1_792_324_800);
+
+fn themed_app(clock: Arc<MockClock>) -> (Router, AppState) {
+    let layout = HomeLayout::from_json(&json!({
+        "rows": [{ "id": "halloween", "title": "Halloween picks", "source": "theme", "slug": "halloween" }]
+    }).to_string()).unwrap();
+    let state = AppState::new(Arc::new(MockTmdbClient::new()))
+        .with_admin_token(TOKEN)
+        .with_clock(clock)
+        .with_home_layout(layout);
+    let app = Router::new()
+        .route("/api/home", get(handlers::get_home))
+        .route("/api/rows/themes", get(handlers::get_themes))
+        .route("/api/rows/themes/{slug}", get(handlers::get_theme_titles))
+        .nest("/admin", admin::router(state.clone()))
+        .with_state(state.clone());
+    (app, state)
+}
+
+#[tokio::test]
+async fn test_admin_launches_a_themed_row() {
+    let (app, _) = themed_app(Arc::new(MockClock::new(UNIX_EPOCH + OCTOBER_18)));
+    let server = TestServer::new(app).unwrap();
+    server.get("/api/rows/themes/halloween").await.assert_status_not_found();
+
+    let body = json!({
+        "title": "Halloween picks",
+        "media_type": "movie",
+        "keywords": [3335],
+        "live_from": "2026-10-01",
+        "live_until": "2026-10-31"
+    });
+    let created = server.put("/admin/themes/halloween").authorization_bearer(TOKEN).json(&body).await;
+    assert_eq!(created.status_code(), 201);
+    let replaced = server.put("/admin/themes/halloween").authorization_bearer(TOKEN).json(&body).await;
+    assert_eq!(replaced.status_code(), 200);
+
+    let live: Vec<ThemedRow> = server.get("/api/rows/themes").await.json();
+    assert_eq!(live.len(), 1);
+    assert_eq!(live[0].title, "Halloween picks");
+    let titles: TmdbResponse = server.get("/api/rows/themes/halloween").await.json();
+    assert_eq!(titles.results[0].title.as_deref(), Some("With keyword 3335"));
+    let home: HomePage = server.get("/api/home").await.json();
+    assert_eq!(home.rows[0].items[0].title.as_deref(), Some("With keyword 3335"));
+
+    server.delete("/admin/themes/halloween").authorization_bearer(TOKEN).await.assert_status(StatusCode::NO_CONTENT);
+    server.get("/api/rows/themes/halloween").await.assert_status_not_found();
+    server.get("/admin/themes/halloween").authorization_bearer(TOKEN).await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_themed_rows_only_show_between_live_dates() {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + OCTOBER_18));
+    let (app, _) = themed_app(clock.clone());
+    let server = TestServer::new(app).unwrap();
+    let body = json!({ "title": "Halloween picks", "media_type": "movie", "keywords": [3335], "live_until": "2026-10-31" });
+    server.put("/admin/themes/halloween").authorization_bearer(TOKEN).json(&body).await.assert_status(StatusCode::CREATED);
+
+    clock.advance(Duration::from_secs(14 * 24 * 60 * 60));
+
+    let live: Vec<ThemedRow> = server.get("/api/rows/themes").await.json();
+    assert!(live.is_empty());
+    server.get("/api/rows/themes/halloween").await.assert_status_not_found();
+    let home: HomePage = server.get("/api/home").await.json();
+    assert!(home.rows[0].items.is_empty());
+    let all: Vec<ThemedRow> = server.get("/admin/themes").authorization_bearer(TOKEN).await.json();
+    assert_eq!(all.len(), 1);
+}
+
+#[tokio::test]
+async fn test_invalid_theme_rules_are_rejected() {
+    let (app, state) = themed_app(Arc::new(MockClock::new(UNIX_EPOCH + OCTOBER_18)));
+    let server = TestServer::new(app).unwrap();
+
+    let response = server
+        .put("/admin/themes/everything")
+        .authorization_bearer(TOKEN)
+        .json(&json!({ "title": "Everything", "media_type": "movie" }))
+        .await;
+    response.assert_status_bad_request();
+    assert_eq!(response.text(), "rules need at least one keyword, genre, company or release date");
+    assert!(state.themes.all().is_empty());
+}
@@ -0,0 +1,83 @@
+use axum::middleware;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{self, AppOptions}, mtls::{ClientCertPolicy, RoleMap}, state::AppState, tls::{self, TlsListener, TlsPeer, TlsSettings}};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const FIXTURES: &str = "tests/fixtures/tls";
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(FIXTURES).join(name)
+}
+
+async fn spawn_tls_server() -> SocketAddr {
+    let roles = RoleMap::parse("spiffe://mesh/ns/ops/*=admin,monitor.internal=metrics").unwrap();
+    let state = AppState::new(Arc::new(MockTmdbClient::new())).with_client_cert_policy(ClientCertPolicy::new(roles));
+    let app = app::router(state, &AppOptions::default()).layer(middleware::from_fn(tls::peer_middleware));
+
+    let settings = TlsSettings {
+        cert_path: fixture("server.pem"),
+        key_path: fixture("server-key.pem"),
+        client_ca_path: Some(fixture("ca.pem")),
+        crl_paths: vec![fixture("crl.pem")],
+    };
+    let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let (listener, reloader) = TlsListener::new(tcp, settings).unwrap();
+    reloader.reload().unwrap();
+    let addr = axum::serve::Listener::local_addr(&listener).unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<TlsPeer>()).await.unwrap()
+    });
+    addr
+}
+
+fn client(identity: Option<&str>) -> reqwest::Client {
+    let ca = std::fs::read(fixture("ca.pem")).unwrap();
+    let mut builder = reqwest::Client::builder()
+        .use_rustls_tls()
+        .add_root_certificate(reqwest::Certificate::from_pem(&ca).unwrap());
+    if let Some(name) = identity {
+        let mut pem = std::fs::read(fixture(&format!("{}.pem", name))).unwrap();
+        pem.extend(std::fs::read(fixture(&format!("{}-key.pem", name))).unwrap());
+        builder = builder.identity(reqwest::Identity::from_pem(&pem).unwrap());
+    }
+    builder.build().unwrap()
+}
+
+async fn status(client: &reqwest::Client, addr: SocketAddr, path: &str) -> u16 {
+    client
+        .get(format!("https://localhost:{}{}", addr.port(), path))
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[tokio::test]
+async fn test_admin_routes_require_a_client_certificate_role() {
+    let addr = spawn_tls_server().await;
+    let console = client(Some("console"));
+    let monitor = client(Some("monitor"));
+    let anonymous = client(None);
+
+    assert_eq!(status(&console, addr, "/admin/mode").await, 200);
+    assert_eq!(status(&console, addr, "/admin/metrics/slow").await, 200);
+    assert_eq!(status(&monitor, addr, "/admin/metrics/slow").await, 200);
+    assert_eq!(status(&monitor, addr, "/admin/mode").await, 403);
+    assert_eq!(status(&anonymous, addr, "/admin/metrics/slow").await, 401);
+    assert_eq!(status(&anonymous, addr, "/").await, 200);
+}
+
+#[tokio::test]
+async fn test_revoked_client_certificate_fails_the_handshake() {
+    let addr = spawn_tls_server().await;
+
+    let result = client(Some("revoked"))
+        .get(format!("https://localhost:{}/", addr.port()))
+        .send()
+        .await;
+
+    assert!(result.is_err());
+}
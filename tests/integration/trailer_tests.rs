@@ -0,0 +1,81 @@
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClient;
+use netflix_service::{app::{test_app, AppStateOverrides}, error::TmdbError, models::VideoResponse, upstream_metrics::Operation};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_test_server(client: Arc<MockTmdbClient>) -> TestServer {
+    TestServer::new(test_app(AppStateOverrides::new(client))).unwrap()
+}
+
+/// Trending with badges, retried until the background lookup has filled them in
+async fn trending_with_badges(server: &TestServer) -> Value {
+    for _ in 0..50 {
+        let body = server.get("/api/trending").add_query_param("include", "videos").await.json::<Value>();
+        if body["results"][0].get("has_trailer").is_some() {
+            return body;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("trailer badges were never filled in");
+}
+
+#[tokio::test]
+async fn test_badges_are_looked_up_in_the_background() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let first = server.get("/api/trending").add_query_param("include", "videos").await.json::<Value>();
+    assert!(first["results"][0].get("has_trailer").is_none());
+
+    let body = trending_with_badges(&server).await;
+    assert_eq!(body["results"][0]["has_trailer"], true);
+    // TV shows have no movie videos to look up
+    assert!(body["results"][1].get("has_trailer").is_none());
+
+    // Later pages are answered from the index, one lookup per movie in total
+    server.get("/api/trending").add_query_param("include", "videos").await;
+    assert_eq!(client.calls_to(Operation::Videos), 1);
+}
+
+#[tokio::test]
+async fn test_movies_without_trailer_or_unknown_to_tmdb_have_none() {
+    let client = MockTmdbClient::builder()
+        .with_video_response(123, Ok(VideoResponse { id: 123, results: vec![] }))
+        .build();
+    let server = create_test_server(Arc::new(client));
+    assert_eq!(trending_with_badges(&server).await["results"][0]["has_trailer"], false);
+
+    let client = MockTmdbClient::builder().with_video_error(123, TmdbError::NotFound).build();
+    let server = create_test_server(Arc::new(client));
+    assert_eq!(trending_with_badges(&server).await["results"][0]["has_trailer"], false);
+}
+
+#[tokio::test]
+async fn test_failed_lookups_are_retried() {
+    let client = Arc::new(
+        MockTmdbClient::builder()
+            .with_video_sequence(123, vec![Err(TmdbError::ServerError(502))])
+            .build(),
+    );
+    let server = create_test_server(client.clone());
+
+    server.get("/api/trending").add_query_param("include", "videos").await;
+    let body = trending_with_badges(&server).await;
+
+    assert_eq!(body["results"][0]["has_trailer"], true);
+    assert_eq!(client.calls_to(Operation::Videos), 2);
+}
+
+#[tokio::test]
+async fn test_badges_are_opt_in() {
+    let client = Arc::new(MockTmdbClient::new());
+    let server = create_test_server(client.clone());
+
+    let body = server.get("/api/trending").await.json::<Value>();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert!(body["results"][0].get("has_trailer").is_none());
+    assert_eq!(client.calls_to(Operation::Videos), 0);
+}
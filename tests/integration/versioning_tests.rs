@@ -0,0 +1,81 @@
+use axum_test::TestServer;
+use super::mock_tmdb_client::MockTmdbClientBuilder;
+use netflix_service::{app::{self, AppOptions}, models::{Movie, TmdbResponse}, state::AppState, versioning::V2_MEDIA_TYPE};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn create_test_server() -> TestServer {
+    let results: Vec<Movie> = serde_json::from_value(json!([
+        { "id": 603, "title": "The Matrix", "overview": "A hacker learns the truth.", "vote_average": 8.23, "vote_count": 25000 },
+        { "id": 1399, "name": "Game of Thrones", "media_type": "tv", "vote_average": 8.4, "vote_count": 24000 }
+    ]))
+    .unwrap();
+    let client = MockTmdbClientBuilder::new()
+        .with_default_trending(Ok(TmdbResponse { page: 1, results, total_pages: 3 }))
+        .build();
+    let state = AppState::new(Arc::new(client));
+    TestServer::new(app::router(state, &AppOptions::default())).unwrap()
+}
+
+#[tokio::test]
+async fn test_v1_stays_the_default() {
+    let server = create_test_server();
+
+    let response = server.get("/api/trending").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/json");
+    let body = response.json::<Value>();
+    assert_eq!(body["results"][1]["name"], "Game of Thrones");
+    assert_eq!(body["results"][0]["vote_average"], 8.23);
+}
+
+#[tokio::test]
+async fn test_v2_normalizes_list_items() {
+    let server = create_test_server();
+
+    let response = server.get("/api/trending").add_header("accept", V2_MEDIA_TYPE).await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), V2_MEDIA_TYPE);
+    let body = response.json::<Value>();
+    assert_eq!(body["pagination"], json!({ "page": 1, "total_pages": 3 }));
+    let items = body["items"].as_array().unwrap();
+    assert_eq!(items[0]["title"], "The Matrix");
+    assert_eq!(items[0]["media_type"], "movie");
+    assert_eq!(items[0]["rating"]["average"], 8.23);
+    assert_eq!(items[0]["rating"]["rounded"], 8.2);
+    assert_eq!(items[0]["rating"]["count"], 25000);
+    assert_eq!(items[1]["title"], "Game of Thrones");
+    assert_eq!(items[1]["media_type"], "tv");
+    assert!(items[1].get("name").is_none());
+    assert!(items[1].get("vote_average").is_none());
+}
+
+#[tokio::test]
+async fn test_v2_keeps_device_shaping() {
+    let server = create_test_server();
+
+    let body = server
+        .get("/api/trending")
+        .add_query_param("device", "tv")
+        .add_header("accept", V2_MEDIA_TYPE)
+        .await
+        .json::<Value>();
+
+    let item = &body["items"][0];
+    assert!(item.get("overview").is_none());
+    assert!(item["poster_url"].is_null());
+    assert_eq!(item["title"], "The Matrix");
+}
+
+#[tokio::test]
+async fn test_unknown_version_is_not_acceptable() {
+    let server = create_test_server();
+
+    server
+        .get("/api/trending")
+        .add_header("accept", "application/vnd.netflix-service.v7+json")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_ACCEPTABLE);
+}
@@ -0,0 +1,2 @@
+// Live TMDB contract tests module
+mod tmdb_contract_tests;
@@ -0,0 +1,121 @@
+//! Every `RealTmdbClient` operation against the real TMDB API. A
+//! `ParseError` here means TMDB changed a response our models rely on.
+//!
+//! Tests are skipped when `TMDB_API_KEY` is not set.
+
+use netflix_service::models::{DiscoverFilter, MediaType};
+use netflix_service::tmdb_client::{RealTmdbClient, TmdbClient};
+
+const THE_MATRIX: i64 = 603;
+const GAME_OF_THRONES: i64 = 1399;
+const HBO: i64 = 49;
+/// "The Marvel Universe", one of TMDB's oldest public lists
+const PUBLIC_LIST: i64 = 1;
+
+fn live_client() -> Option<RealTmdbClient> {
+    dotenv::dotenv().ok();
+    match std::env::var("TMDB_API_KEY") {
+        Ok(api_key) if !api_key.is_empty() => Some(RealTmdbClient::new(api_key)),
+        _ => {
+            eprintln!("TMDB_API_KEY is not set, skipping live TMDB test");
+            None
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_trending() {
+    let Some(client) = live_client() else { return };
+
+    let response = client.get_trending(1).await.expect("trending should deserialize");
+    assert_eq!(response.page, 1);
+    assert!(!response.results.is_empty());
+    assert!(response.results.iter().all(|item| item.media_type.is_some()));
+}
+
+#[tokio::test]
+async fn test_search() {
+    let Some(client) = live_client() else { return };
+
+    let response = client.search_content("matrix", 1).await.expect("search should deserialize");
+    assert!(response.results.iter().any(|item| item.id == THE_MATRIX));
+}
+
+#[tokio::test]
+async fn test_movie_videos() {
+    let Some(client) = live_client() else { return };
+
+    let response = client.get_movie_videos(THE_MATRIX).await.expect("videos should deserialize");
+    assert_eq!(response.id, THE_MATRIX);
+    assert!(!response.results.is_empty());
+}
+
+#[tokio::test]
+async fn test_title_details() {
+    let Some(client) = live_client() else { return };
+
+    let movie = client.get_title_details(MediaType::Movie, THE_MATRIX).await.expect("movie details should deserialize");
+    assert_eq!(movie.title.as_deref(), Some("The Matrix"));
+    let show = client.get_title_details(MediaType::Tv, GAME_OF_THRONES).await.expect("TV details should deserialize");
+    assert_eq!(show.name.as_deref(), Some("Game of Thrones"));
+}
+
+#[tokio::test]
+async fn test_images() {
+    let Some(client) = live_client() else { return };
+
+    let images = client.get_images(MediaType::Movie, THE_MATRIX).await.expect("images should deserialize");
+    assert!(!images.posters.is_empty());
+}
+
+#[tokio::test]
+async fn test_alternative_titles() {
+    let Some(client) = live_client() else { return };
+
+    // Movies and TV shows use different keys for the title list
+    let movie = client.get_alternative_titles(MediaType::Movie, THE_MATRIX).await.expect("movie titles should deserialize");
+    assert!(!movie.titles.is_empty());
+    let show = client.get_alternative_titles(MediaType::Tv, GAME_OF_THRONES).await.expect("TV titles should deserialize");
+    assert!(!show.titles.is_empty());
+}
+
+#[tokio::test]
+async fn test_watch_providers() {
+    let Some(client) = live_client() else { return };
+
+    let providers = client.get_watch_providers(MediaType::Movie, THE_MATRIX).await.expect("watch providers should deserialize");
+    assert!(!providers.results.is_empty());
+}
+
+#[tokio::test]
+async fn test_discover() {
+    let Some(client) = live_client() else { return };
+
+    let filter = DiscoverFilter { networks: vec![HBO], ..DiscoverFilter::new(MediaType::Tv) };
+    let response = client.discover(&filter, 1).await.expect("discover should deserialize");
+    assert!(!response.results.is_empty());
+}
+
+#[tokio::test]
+async fn test_provider_list() {
+    let Some(client) = live_client() else { return };
+
+    let list = client.get_provider_list("US").await.expect("provider list should deserialize");
+    assert!(!list.results.is_empty());
+}
+
+#[tokio::test]
+async fn test_network() {
+    let Some(client) = live_client() else { return };
+
+    let network = client.get_network(HBO).await.expect("network should deserialize");
+    assert_eq!(network.name, "HBO");
+}
+
+#[tokio::test]
+async fn test_list() {
+    let Some(client) = live_client() else { return };
+
+    let list = client.get_list(PUBLIC_LIST).await.expect("list should deserialize");
+    assert!(!list.title_refs().is_empty());
+}
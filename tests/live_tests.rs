@@ -0,0 +1,2 @@
+// Contract tests against the real TMDB API, run with `cargo test --features live-tests`
+mod live;
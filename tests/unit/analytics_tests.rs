@@ -0,0 +1,112 @@
+use netflix_service::analytics::{normalize_query, parse_window, SearchAnalytics, TitleAnalytics};
+use netflix_service::models::MediaType;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[test]
+fn test_normalize_query() {
+    assert_eq!(normalize_query("  The   Matrix "), "the matrix");
+    assert_eq!(normalize_query("AVENGERS"), "avengers");
+    assert_eq!(normalize_query("   "), "");
+}
+
+#[test]
+fn test_report_ranks_queries() {
+    let analytics = SearchAnalytics::new();
+    analytics.record("matrix", 3, Duration::from_millis(10), at(100));
+    analytics.record("Matrix", 3, Duration::from_millis(30), at(101));
+    analytics.record("alien", 1, Duration::from_millis(20), at(102));
+    analytics.record("qwerty", 0, Duration::from_millis(20), at(103));
+
+    let report = analytics.report(at(0), at(1000), 10);
+
+    assert_eq!(report.total_searches, 4);
+    assert_eq!(report.zero_result_searches, 1);
+    assert_eq!(report.average_latency_ms, 20);
+    assert_eq!(report.top_queries[0].query, "matrix");
+    assert_eq!(report.top_queries[0].count, 2);
+    // Equal counts are ordered alphabetically
+    assert_eq!(report.top_queries[1].query, "alien");
+    assert_eq!(report.top_zero_result_queries[0].query, "qwerty");
+}
+
+#[test]
+fn test_report_filters_by_time_range() {
+    let analytics = SearchAnalytics::new();
+    analytics.record("early", 1, Duration::ZERO, at(10));
+    analytics.record("late", 1, Duration::ZERO, at(20));
+
+    let report = analytics.report(at(15), at(25), 10);
+
+    assert_eq!(report.total_searches, 1);
+    assert_eq!(report.top_queries[0].query, "late");
+}
+
+#[test]
+fn test_report_limit() {
+    let analytics = SearchAnalytics::new();
+    for query in ["a", "b", "c"] {
+        analytics.record(query, 1, Duration::ZERO, at(1));
+    }
+
+    let report = analytics.report(at(0), at(10), 2);
+
+    assert_eq!(report.top_queries.len(), 2);
+}
+
+#[test]
+fn test_capacity_drops_oldest_events() {
+    let analytics = SearchAnalytics::with_capacity(2);
+    analytics.record("first", 1, Duration::ZERO, at(1));
+    analytics.record("second", 1, Duration::ZERO, at(2));
+    analytics.record("third", 1, Duration::ZERO, at(3));
+
+    let report = analytics.report(at(0), at(10), 10);
+
+    assert_eq!(report.total_searches, 2);
+    assert!(report.top_queries.iter().all(|q| q.query != "first"));
+}
+
+#[test]
+fn test_top_titles_ranking() {
+    let analytics = TitleAnalytics::new();
+    analytics.record(MediaType::Movie, 603, at(10));
+    analytics.record(MediaType::Movie, 603, at(11));
+    analytics.record(MediaType::Tv, 603, at(12));
+    analytics.record(MediaType::Movie, 550, at(13));
+
+    let top = analytics.top(at(0), 10);
+
+    assert_eq!(top.len(), 3);
+    assert_eq!((top[0].media_type, top[0].id, top[0].count), (MediaType::Movie, 603, 2));
+    // A movie and a show sharing an id are counted separately
+    assert!(top.iter().any(|t| t.media_type == MediaType::Tv && t.id == 603));
+}
+
+#[test]
+fn test_top_titles_window() {
+    let analytics = TitleAnalytics::new();
+    analytics.record(MediaType::Movie, 1, at(10));
+    analytics.record(MediaType::Movie, 2, at(100));
+
+    let top = analytics.top(at(50), 10);
+
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].id, 2);
+}
+
+#[test]
+fn test_parse_window() {
+    assert_eq!(parse_window("45s"), Some(Duration::from_secs(45)));
+    assert_eq!(parse_window("30m"), Some(Duration::from_secs(30 * 60)));
+    assert_eq!(parse_window("24h"), Some(Duration::from_secs(24 * 60 * 60)));
+    assert_eq!(parse_window("7d"), Some(Duration::from_secs(7 * 24 * 60 * 60)));
+    assert_eq!(parse_window("7"), None);
+    assert_eq!(parse_window("d"), None);
+    assert_eq!(parse_window("7w"), None);
+    assert_eq!(parse_window("7é"), None);
+    assert_eq!(parse_window(""), None);
+}
@@ -0,0 +1,80 @@
+use netflix_service::bench::{percentile, BenchConfig, BenchReport, MixEntry};
+use std::time::Duration;
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+fn millis(values: &[u64]) -> Vec<Duration> {
+    values.iter().copied().map(Duration::from_millis).collect()
+}
+
+#[test]
+fn test_default_config() {
+    let config = BenchConfig::from_args(&[]).unwrap();
+
+    assert_eq!(config.url, "http://localhost:8080");
+    assert_eq!(config.requests, 200);
+    assert_eq!(config.concurrency, 8);
+    assert_eq!(config.mix.len(), 3);
+}
+
+#[test]
+fn test_parses_flags_and_mix() {
+    let config = BenchConfig::from_args(&args(&[
+        "--url", "http://staging:8080/",
+        "--requests", "50",
+        "--concurrency", "2",
+        "--mix", "2:/api/trending",
+        "--mix", "1:/api/search?query=a:b",
+    ]))
+    .unwrap();
+
+    assert_eq!(config.url, "http://staging:8080");
+    assert_eq!(config.requests, 50);
+    assert_eq!(config.concurrency, 2);
+    assert_eq!(
+        config.mix,
+        vec![
+            MixEntry { weight: 2, path: "/api/trending".to_string() },
+            MixEntry { weight: 1, path: "/api/search?query=a:b".to_string() },
+        ]
+    );
+}
+
+#[test]
+fn test_rejects_bad_arguments() {
+    assert!(BenchConfig::from_args(&args(&["--requests", "0"])).is_err());
+    assert!(BenchConfig::from_args(&args(&["--requests"])).is_err());
+    assert!(BenchConfig::from_args(&args(&["--mix", "/api/trending"])).is_err());
+    assert!(BenchConfig::from_args(&args(&["--verbose"])).is_err());
+}
+
+#[test]
+fn test_mix_follows_weights() {
+    let config = BenchConfig::from_args(&args(&["--mix", "2:/a", "--mix", "1:/b"])).unwrap();
+
+    let paths: Vec<&str> = (0..6).map(|index| config.path_for(index)).collect();
+    assert_eq!(paths, vec!["/a", "/a", "/b", "/a", "/a", "/b"]);
+}
+
+#[test]
+fn test_percentile_nearest_rank() {
+    let sorted = millis(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+    assert_eq!(percentile(&sorted, 50.0), Duration::from_millis(5));
+    assert_eq!(percentile(&sorted, 90.0), Duration::from_millis(9));
+    assert_eq!(percentile(&sorted, 99.0), Duration::from_millis(10));
+    assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+}
+
+#[test]
+fn test_report_from_samples() {
+    let report = BenchReport::from_samples(millis(&[30, 10, 20, 40]), 1, 2, Duration::from_secs(1));
+
+    assert_eq!(report.requests, 4);
+    assert_eq!(report.p50, Duration::from_millis(20));
+    assert_eq!(report.max, Duration::from_millis(40));
+    assert_eq!(report.cache_hit_rate(), 0.5);
+    assert!(report.to_string().contains("cache hits: 50.0%"));
+}
@@ -0,0 +1,39 @@
+use netflix_service::blocklist::Blocklist;
+use netflix_service::models::{MediaType, Movie};
+
+fn item(id: i64, media_type: Option<&str>, title: &str) -> Movie {
+    serde_json::from_value(serde_json::json!({ "id": id, "title": title, "media_type": media_type })).unwrap()
+}
+
+#[test]
+fn test_items_without_a_media_type_take_the_listed_one() {
+    let blocklist = Blocklist::from_json(r#"{"titles": [{"media_type": "tv", "id": 1}]}"#).unwrap();
+    let mut items = vec![item(1, None, "A"), item(1, Some("movie"), "B"), item(2, None, "C")];
+
+    blocklist.retain(&mut items, MediaType::Tv);
+
+    assert_eq!(items.iter().map(|item| item.title.as_deref().unwrap()).collect::<Vec<_>>(), ["B", "C"]);
+    assert!(blocklist.blocks_id(MediaType::Tv, 1));
+    assert!(!blocklist.blocks_id(MediaType::Movie, 1));
+}
+
+#[test]
+fn test_keywords_ignore_case_and_punctuation_but_not_word_boundaries() {
+    let blocklist = Blocklist::from_json(r#"{"keywords": ["star-crossed lovers"]}"#).unwrap();
+    let mut items = vec![
+        item(1, None, "Star Crossed  Lovers!"),
+        item(2, None, "Star-crossed loversville"),
+        item(3, None, "The Lovers"),
+    ];
+
+    blocklist.retain(&mut items, MediaType::Movie);
+
+    assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), [2, 3]);
+}
+
+#[test]
+fn test_rules_need_usable_keywords() {
+    assert!(Blocklist::from_json(r#"{"keywords": ["!!"]}"#).is_err());
+    assert!(Blocklist::from_json(r#"{"titles": [{"media_type": "movie", "id": 0}]}"#).is_err());
+    assert_eq!(Blocklist::default().rules(), Default::default());
+}
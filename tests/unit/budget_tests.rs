@@ -0,0 +1,56 @@
+use netflix_service::budget::{BudgetLimits, RequestBudget};
+use std::time::{Duration, UNIX_EPOCH};
+
+const BATCH: &str = "/api/titles/batch";
+
+fn budget(capacity: u32, refill_per_sec: u32) -> RequestBudget {
+    RequestBudget::new(BudgetLimits { capacity, refill_per_sec })
+}
+
+#[test]
+fn test_uncosted_routes_are_free() {
+    let budget = budget(1, 0);
+    let now = UNIX_EPOCH;
+
+    assert_eq!(budget.cost("/api/trending"), 0);
+    for _ in 0..100 {
+        assert!(budget.try_spend("client", "/api/trending", now).is_ok());
+    }
+}
+
+#[test]
+fn test_costly_route_exhausts_budget_and_refills() {
+    let budget = budget(20, 2);
+    let now = UNIX_EPOCH + Duration::from_secs(1_000);
+    assert_eq!(budget.cost("/api/tv/{id}/where_to_start"), budget.cost(BATCH));
+
+    assert!(budget.try_spend("client", BATCH, now).is_ok());
+    assert!(budget.try_spend("client", BATCH, now).is_ok());
+    assert_eq!(budget.try_spend("client", BATCH, now), Err(Duration::from_secs(5)));
+
+    assert!(budget.try_spend("client", BATCH, now + Duration::from_secs(4)).is_err());
+    assert!(budget.try_spend("client", BATCH, now + Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn test_clients_have_separate_budgets() {
+    let budget = budget(10, 0);
+    let now = UNIX_EPOCH;
+
+    assert!(budget.try_spend("a", BATCH, now).is_ok());
+    assert!(budget.try_spend("a", BATCH, now).is_err());
+    assert!(budget.try_spend("b", BATCH, now).is_ok());
+}
+
+#[test]
+fn test_route_costs_can_be_overridden() {
+    let budget = budget(10, 0)
+        .with_route_cost(BATCH, 0)
+        .with_route_cost("/api/search", 4);
+    let now = UNIX_EPOCH;
+
+    assert!(budget.try_spend("client", BATCH, now).is_ok());
+    assert!(budget.try_spend("client", "/api/search", now).is_ok());
+    assert!(budget.try_spend("client", "/api/search", now).is_ok());
+    assert!(budget.try_spend("client", "/api/search", now).is_err());
+}
@@ -0,0 +1,44 @@
+use netflix_service::cache_policy::CachePolicies;
+use netflix_service::negative_cache::NegativeCacheTtls;
+use std::time::Duration;
+
+#[test]
+fn test_default_policies() {
+    let policies = CachePolicies::default();
+
+    assert_eq!(policies.for_route("/api/trending"), Some(Duration::from_secs(300)));
+    assert_eq!(policies.for_route("/api/search"), Some(Duration::from_secs(60)));
+    assert_eq!(policies.for_route("/api/movie/{id}/videos"), Some(Duration::from_secs(86_400)));
+    assert_eq!(policies.for_route("/title/{media_type}/{id}"), Some(Duration::from_secs(3_600)));
+    assert_eq!(policies.for_route("/api/movie/{id}/availability"), Some(Duration::from_secs(21_600)));
+    assert_eq!(policies.for_route("/api/providers"), Some(Duration::from_secs(86_400)));
+    assert_eq!(policies.for_route("/api/rows/new_on"), Some(Duration::from_secs(21_600)));
+    assert_eq!(policies.for_route("/api/session/recent"), None);
+    assert_eq!(policies.negative, Duration::from_secs(30));
+}
+
+#[test]
+fn test_parse_overrides_listed_kinds() {
+    let policies = CachePolicies::parse("trending=10m, search=0s,negative=5s").unwrap();
+
+    assert_eq!(policies.trending, Duration::from_secs(600));
+    assert_eq!(policies.for_route("/api/search"), None);
+    assert_eq!(policies.details, CachePolicies::default().details);
+    assert_eq!(
+        policies.negative_ttls(),
+        NegativeCacheTtls { videos: Some(Duration::from_secs(5)), details: Some(Duration::from_secs(5)) }
+    );
+}
+
+#[test]
+fn test_zero_negative_ttl_disables_negative_cache() {
+    let policies = CachePolicies::parse("negative=0s").unwrap();
+    assert_eq!(policies.negative_ttls(), NegativeCacheTtls { videos: None, details: None });
+}
+
+#[test]
+fn test_parse_rejects_bad_entries() {
+    assert!(CachePolicies::parse("trending").is_err());
+    assert!(CachePolicies::parse("trending=soon").is_err());
+    assert!(CachePolicies::parse("people=60s").is_err());
+}
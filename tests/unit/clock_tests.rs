@@ -0,0 +1,25 @@
+use netflix_service::clock::{Clock, MockClock, SystemClock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn test_mock_clock_only_moves_when_told() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_000);
+    let clock = MockClock::new(start);
+
+    assert_eq!(clock.now(), start);
+    assert_eq!(clock.now(), start);
+
+    clock.advance(Duration::from_secs(30));
+    assert_eq!(clock.now(), start + Duration::from_secs(30));
+
+    clock.set(UNIX_EPOCH);
+    assert_eq!(clock.now(), UNIX_EPOCH);
+}
+
+#[test]
+fn test_system_clock_tracks_wall_time() {
+    let before = SystemTime::now();
+    let now = SystemClock.now();
+
+    assert!(now >= before);
+}
@@ -0,0 +1,38 @@
+use flate2::{write::GzEncoder, Compression};
+use netflix_service::compression::{CompressionMode, UpstreamCompression};
+use std::io::Write;
+
+#[test]
+fn test_modes_parse_and_choose_accept_encoding() {
+    assert_eq!("gzip".parse::<CompressionMode>().unwrap().accept_encoding(), "gzip");
+    assert_eq!("off".parse::<CompressionMode>().unwrap().accept_encoding(), "identity");
+    assert_eq!(CompressionMode::default().accept_encoding(), "br, gzip");
+    assert!("deflate".parse::<CompressionMode>().is_err());
+}
+
+#[test]
+fn test_decode_counts_bytes_saved() {
+    let body = br#"{"results":[]}"#.repeat(100);
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&body).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let compression = UpstreamCompression::default();
+
+    assert_eq!(compression.decode(Some("GZIP"), &compressed).unwrap(), body);
+    assert_eq!(compression.decode(None, b"{}").unwrap(), b"{}");
+
+    let status = compression.status();
+    assert_eq!((status.responses, status.compressed_responses), (2, 1));
+    assert_eq!(status.compressed_bytes, compressed.len() as u64 + 2);
+    assert_eq!(status.decompressed_bytes, body.len() as u64 + 2);
+    assert_eq!(status.saved_bytes, (body.len() - compressed.len()) as u64);
+}
+
+#[test]
+fn test_decode_rejects_unknown_and_corrupt_bodies() {
+    let compression = UpstreamCompression::default();
+
+    assert!(compression.decode(Some("zstd"), b"{}").is_err());
+    assert!(compression.decode(Some("gzip"), b"{}").is_err());
+    assert_eq!(compression.status().responses, 0);
+}
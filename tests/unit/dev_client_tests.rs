@@ -0,0 +1,112 @@
+use netflix_service::dev_client::DevTmdbClient;
+use netflix_service::error::TmdbError;
+use netflix_service::models::{DiscoverFilter, MediaType, Movie};
+use netflix_service::tmdb_client::TmdbClient;
+
+#[test]
+fn test_catalog_is_realistically_sized() {
+    let client = DevTmdbClient::new();
+
+    assert!((50..=100).contains(&client.len()));
+}
+
+#[tokio::test]
+async fn test_trending_pages_through_the_catalog() {
+    let client = DevTmdbClient::new();
+
+    let first = client.get_trending(1).await.unwrap();
+    let last = client.get_trending(first.total_pages).await.unwrap();
+
+    assert_eq!(first.results.len(), 20);
+    assert_eq!(first.total_pages as usize, client.len().div_ceil(20));
+    assert!(!last.results.is_empty());
+    assert!(client.get_trending(first.total_pages + 1).await.unwrap().results.is_empty());
+    // Every title has artwork to show
+    assert!(first.results.iter().all(|movie| movie.poster_path.is_some() && movie.backdrop_path.is_some()));
+}
+
+#[tokio::test]
+async fn test_search_matches_names_in_any_language() {
+    let client = DevTmdbClient::new();
+
+    let matrix = client.search_content("MATRIX", 1).await.unwrap();
+    let original = client.search_content("casa de papel", 1).await.unwrap();
+
+    assert_eq!(matrix.results[0].id, 603);
+    assert_eq!(original.results[0].name.as_deref(), Some("Money Heist"));
+    assert!(client.search_content("no such title", 1).await.unwrap().results.is_empty());
+}
+
+#[tokio::test]
+async fn test_titles_have_details_videos_and_images() {
+    let client = DevTmdbClient::new();
+
+    let details = client.get_title_details(MediaType::Tv, 1399).await.unwrap();
+    let videos = client.get_movie_videos(603).await.unwrap();
+    let images = client.get_images(MediaType::Movie, 603).await.unwrap();
+
+    assert_eq!(details.name.as_deref(), Some("Game of Thrones"));
+    assert!(!details.genres.is_empty());
+    assert_eq!(videos.best_trailer().unwrap().r#type, "Trailer");
+    assert_eq!((images.posters.len(), images.backdrops.len()), (1, 1));
+}
+
+#[tokio::test]
+async fn test_unknown_titles_are_not_found() {
+    let client = DevTmdbClient::new();
+
+    assert!(matches!(client.get_title_details(MediaType::Movie, 1399).await, Err(TmdbError::NotFound)));
+    assert!(matches!(client.get_movie_videos(1).await, Err(TmdbError::NotFound)));
+    assert!(matches!(client.get_network(1).await, Err(TmdbError::NotFound)));
+    assert!(matches!(client.get_list(2).await, Err(TmdbError::NotFound)));
+}
+
+#[tokio::test]
+async fn test_discover_filters_the_catalog() {
+    let client = DevTmdbClient::new();
+    let hbo = DiscoverFilter { networks: vec![49], ..DiscoverFilter::new(MediaType::Tv) };
+    let netflix_us = DiscoverFilter { providers: vec![8], region: Some("US".to_string()), ..DiscoverFilter::new(MediaType::Movie) };
+    let netflix_it = DiscoverFilter { region: Some("IT".to_string()), ..netflix_us.clone() };
+
+    let hbo_titles = client.discover(&hbo, 1).await.unwrap().results;
+    assert!(hbo_titles.iter().any(|title| title.id == 1399));
+    assert!(hbo_titles.iter().all(|title| title.media_type.as_deref() == Some("tv")));
+
+    for movie in client.discover(&netflix_us, 1).await.unwrap().results {
+        let providers = client.get_watch_providers(MediaType::Movie, movie.id).await.unwrap();
+        assert!(providers.results["US"].flatrate.iter().any(|provider| provider.provider_id == 8));
+    }
+    assert!(client.discover(&netflix_it, 1).await.unwrap().results.is_empty());
+
+    let drama_or_crime = DiscoverFilter { genres: vec![18, 80], ..DiscoverFilter::new(MediaType::Movie) };
+    let drama_and_crime = DiscoverFilter { all_genres: true, ..drama_or_crime.clone() };
+    let ids = |results: Vec<Movie>| results.into_iter().map(|movie| movie.id).collect::<Vec<_>>();
+    assert!(ids(client.discover(&drama_or_crime, 1).await.unwrap().results).contains(&550));
+    let both = ids(client.discover(&drama_and_crime, 1).await.unwrap().results);
+    assert!(both.contains(&278) && !both.contains(&550));
+}
+
+#[tokio::test]
+async fn test_providers_networks_and_lists() {
+    let client = DevTmdbClient::new();
+
+    assert!(!client.get_provider_list("us").await.unwrap().results.is_empty());
+    assert!(client.get_provider_list("IT").await.unwrap().results.is_empty());
+    assert_eq!(client.get_network(49).await.unwrap().name, "HBO");
+    // Every list entry resolves to a catalog title
+    for title in client.get_list(1).await.unwrap().title_refs() {
+        client.get_title_details(title.media_type, title.id.get()).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_fixture_seasons() {
+    let client = DevTmdbClient::new();
+
+    let show = client.get_title_details(MediaType::Tv, 1399).await.unwrap();
+    assert_eq!(show.seasons.iter().map(|season| season.season_number).collect::<Vec<_>>(), [1]);
+    let season = client.get_tv_season(1399, 1).await.unwrap();
+    assert_eq!(season.episodes.first().map(|episode| episode.name.as_str()), Some("Winter Is Coming"));
+    assert!(matches!(client.get_tv_season(1399, 2).await, Err(TmdbError::NotFound)));
+    assert!(matches!(client.get_tv_season(603, 1).await, Err(TmdbError::NotFound)));
+}
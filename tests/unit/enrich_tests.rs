@@ -0,0 +1,38 @@
+use netflix_service::enrich::{stages, Enriched, EnrichmentCache, Include, TitleRef};
+use netflix_service::models::MediaType;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_parse_accepts_only_public_includes() {
+    assert_eq!(Include::parse(" credits "), Ok(Include::Credits));
+    assert_eq!(Include::parse("videos").map(|include| include.as_str()), Ok("videos"));
+    assert_eq!(Include::parse("details"), Err("unknown include 'details'".to_string()));
+}
+
+#[test]
+fn test_stages_run_dependencies_first() {
+    assert_eq!(stages(&[Include::Videos, Include::Providers]), vec![vec![Include::Videos, Include::Providers]]);
+    assert_eq!(
+        stages(&[Include::Credits, Include::Videos]),
+        vec![vec![Include::Details, Include::Videos], vec![Include::Credits]]
+    );
+    assert!(stages(&[]).is_empty());
+}
+
+#[test]
+fn test_cache_is_per_include_variant_and_title() {
+    let cache = EnrichmentCache::new();
+    let now = UNIX_EPOCH + Duration::from_secs(1_000);
+    let title = TitleRef { media_type: MediaType::Movie, id: 550 };
+    let found = Enriched { has_trailer: Some(true), ..Enriched::default() };
+    cache.store(Include::Providers, "GB", title, found, now, Duration::from_secs(60));
+
+    assert!(cache.get(Include::Providers, "GB", title, now + Duration::from_secs(59)).is_some());
+    assert!(cache.get(Include::Providers, "GB", title, now + Duration::from_secs(60)).is_none());
+    assert!(cache.get(Include::Providers, "US", title, now).is_none());
+    assert!(cache.get(Include::Videos, "GB", title, now).is_none());
+    assert!(cache.get(Include::Providers, "GB", TitleRef { media_type: MediaType::Tv, id: 550 }, now).is_none());
+
+    cache.store(Include::Videos, "", title, Enriched::default(), now, Duration::ZERO);
+    assert!(cache.get(Include::Videos, "", title, now).is_none());
+}
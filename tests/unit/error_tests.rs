@@ -13,6 +13,10 @@ fn test_error_display() {
 
     let error = TmdbError::ServerError(503);
     assert_eq!(error.to_string(), "Server error: 503");
+
+    let error = TmdbError::UpstreamDisabled;
+    assert_eq!(error.to_string(), "Upstream calls are disabled");
+    assert!(!error.is_retryable());
 }
 
 #[tokio::test]
@@ -82,3 +86,25 @@ fn test_error_debug() {
     let debug_str = format!("{:?}", error);
     assert!(debug_str.contains("NotFound"));
 }
+
+#[test]
+fn test_error_display_redacts_secrets() {
+    let error = TmdbError::NetworkError("timeout for https://api.themoviedb.org/3/movie/1?api_key=secret123".to_string());
+    assert!(!error.to_string().contains("secret123"));
+
+    let error = TmdbError::Unknown(418, "contact admin@example.com".to_string());
+    assert_eq!(error.to_string(), "Unknown error (418): contact [REDACTED]");
+}
+
+#[test]
+fn test_from_status_redacts_body() {
+    let error = TmdbError::from_status(
+        reqwest::StatusCode::BAD_REQUEST,
+        "invalid request ?api_key=secret123".to_string(),
+    );
+
+    match error {
+        TmdbError::BadRequest(body) => assert!(!body.contains("secret123")),
+        _ => panic!("Expected BadRequest variant"),
+    }
+}
@@ -0,0 +1,75 @@
+use netflix_service::experiments::{Experiment, Experiments};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+fn experiments() -> Experiments {
+    Experiments::new(vec![
+        Experiment {
+            name: "hero_row".to_string(),
+            variants: vec!["control".to_string(), "large".to_string()],
+        },
+        Experiment {
+            name: "search_ranking".to_string(),
+            variants: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        },
+    ])
+}
+
+#[test]
+fn test_assignment_is_deterministic() {
+    let first = experiments().assign("user-42");
+    let second = experiments().assign("user-42");
+
+    assert_eq!(first, second);
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0].experiment, "hero_row");
+    assert_eq!(first[1].experiment, "search_ranking");
+}
+
+#[test]
+fn test_assignment_spreads_subjects_across_variants() {
+    let experiments = experiments();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for i in 0..1000 {
+        let assignment = &experiments.assign(&format!("user-{}", i))[0];
+        *counts.entry(assignment.variant.clone()).or_default() += 1;
+    }
+
+    // Equal shares within a generous tolerance
+    assert_eq!(counts.len(), 2);
+    assert!(counts.values().all(|&count| (400..=600).contains(&count)));
+}
+
+#[test]
+fn test_from_json_skips_experiments_without_variants() {
+    let experiments = Experiments::from_json(
+        r#"[{"name": "empty", "variants": []}, {"name": "only", "variants": ["x"]}]"#,
+    )
+    .unwrap();
+
+    let assignments = experiments.assign("anyone");
+
+    assert_eq!(assignments.len(), 1);
+    assert_eq!(assignments[0].variant, "x");
+}
+
+#[test]
+fn test_from_json_rejects_invalid_config() {
+    assert!(Experiments::from_json("{not json").is_err());
+    assert!(Experiments::from_json("[]").unwrap().is_empty());
+}
+
+#[test]
+fn test_exposure_counts() {
+    let experiments = experiments();
+    let assignments = experiments.assign("user-1");
+    experiments.record_exposures("user-1", &assignments, SystemTime::now());
+    experiments.record_exposures("user-1", &assignments, SystemTime::now());
+
+    let counts = experiments.exposure_counts();
+
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[0].experiment, "hero_row");
+    assert_eq!(counts[0].variant, assignments[0].variant);
+    assert_eq!(counts[0].exposures, 2);
+}
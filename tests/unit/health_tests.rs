@@ -0,0 +1,41 @@
+use netflix_service::dev_client::DevTmdbClient;
+use netflix_service::health::{CircuitState, UpstreamProbe};
+use netflix_service::mirrors::MirrorStatus;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn mirror(healthy: bool) -> MirrorStatus {
+    MirrorStatus {
+        base_url: "https://api.themoviedb.org/3".to_string(),
+        healthy,
+        successes: 0,
+        failures: 0,
+        consecutive_failures: 0,
+        last_failure: None,
+    }
+}
+
+#[test]
+fn test_circuit_state_from_mirrors() {
+    assert_eq!(CircuitState::from_mirrors(&[mirror(true), mirror(true)]), CircuitState::Closed);
+    assert_eq!(CircuitState::from_mirrors(&[mirror(true), mirror(false)]), CircuitState::PartiallyOpen);
+    assert_eq!(CircuitState::from_mirrors(&[mirror(false)]), CircuitState::Open);
+}
+
+#[tokio::test]
+async fn test_probe_is_reused_within_ttl() {
+    let probe = UpstreamProbe::new(Duration::from_secs(15));
+    let client = DevTmdbClient::new();
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+
+    let (first, cached) = probe.check(&client, at(1_000)).await;
+    assert!(first.ok && !cached);
+    assert_eq!(first.checked_at, 1_000);
+
+    let (again, cached) = probe.check(&client, at(1_014)).await;
+    assert!(cached);
+    assert_eq!(again.checked_at, 1_000);
+
+    let (fresh, cached) = probe.check(&client, at(1_015)).await;
+    assert!(!cached);
+    assert_eq!(fresh.checked_at, 1_015);
+}
@@ -0,0 +1,135 @@
+use netflix_service::home::{diversify, DiversityLimits, HomeLayout, HomeRow, HomeRowCache, HomeRowMetrics, HomeRowStats, RowKind, RowOutcome, RowSource, RowStatus, DEFAULT_ROW_TIMEOUT};
+use netflix_service::models::{MediaType, Movie};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn item(id: i64, media_type: &str, genre_ids: Vec<i64>) -> Movie {
+    Movie {
+        id,
+        title: Some(format!("Title {}", id)),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: None,
+        vote_count: None,
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids,
+        release_date: None,
+        media_type: Some(media_type.to_string()),
+        adult: false,
+    }
+}
+
+fn row(id: &str, items: Vec<Movie>) -> HomeRow {
+    HomeRow { id: id.to_string(), title: id.to_string(), kind: RowKind::default(), status: RowStatus::Ok, error: None, items }
+}
+
+fn ids(rows: &[HomeRow]) -> Vec<Vec<i64>> {
+    rows.iter().map(|row| row.items.iter().map(|item| item.id).collect()).collect()
+}
+
+#[test]
+fn test_titles_appear_in_one_row_by_default() {
+    let mut rows = vec![
+        row("trending", vec![item(1, "movie", vec![]), item(2, "movie", vec![])]),
+        row("movies", vec![item(2, "movie", vec![]), item(3, "movie", vec![])]),
+        row("tv", vec![item(1, "movie", vec![]), item(1, "tv", vec![])]),
+    ];
+
+    diversify(&mut rows, &DiversityLimits::default());
+
+    assert_eq!(ids(&rows), [vec![1, 2], vec![3], vec![1]]);
+    assert_eq!(rows[2].items[0].media_type.as_deref(), Some("tv"));
+}
+
+#[test]
+fn test_appearance_and_genre_limits() {
+    let limits = DiversityLimits { max_appearances: 2, max_per_genre: Some(1) };
+    let mut rows = vec![
+        row("first", vec![item(1, "movie", vec![28]), item(2, "movie", vec![28, 18]), item(3, "movie", vec![18])]),
+        row("second", vec![item(1, "movie", vec![28]), item(3, "movie", vec![18])]),
+        row("third", vec![item(1, "movie", vec![28]), item(4, "movie", vec![])]),
+    ];
+
+    diversify(&mut rows, &limits);
+
+    // Title 2 shares its main genre with title 1; title 1 is used up after two rows
+    assert_eq!(ids(&rows), [vec![1, 3], vec![1, 3], vec![4]]);
+}
+
+#[test]
+fn test_parse_limits() {
+    let limits = DiversityLimits::parse("max_appearances=2, max_per_genre=4").unwrap();
+    assert_eq!(limits, DiversityLimits { max_appearances: 2, max_per_genre: Some(4) });
+    assert_eq!(DiversityLimits::parse("").unwrap(), DiversityLimits::default());
+
+    assert!(DiversityLimits::parse("max_appearances=0").is_err());
+    assert!(DiversityLimits::parse("max_rows=2").is_err());
+    assert!(DiversityLimits::parse("max_per_genre").is_err());
+}
+
+#[test]
+fn test_parse_layout() {
+    let layout = HomeLayout::from_json(r#"{"rows": [
+        {"id": "top", "title": "Top 10", "kind": "ranked", "source": "trending", "ttl": "10m"},
+        {"id": "hbo", "title": "On HBO", "source": "discover", "media_type": "tv", "networks": [49], "timeout": "1s"},
+        {"id": "comedy", "title": "Comedies", "source": "genre", "media_type": "movie", "genre": 35},
+        {"id": "picks", "title": "Staff picks", "source": "curated_list", "slug": "staff-picks"}
+    ]}"#).unwrap();
+
+    assert_eq!(layout.rows[0].kind, RowKind::Ranked);
+    assert_eq!(layout.rows[0].ttl(), Some(Duration::from_secs(600)));
+    assert_eq!(layout.rows[0].timeout(), DEFAULT_ROW_TIMEOUT);
+    assert_eq!(layout.rows[1].kind, RowKind::Carousel);
+    assert_eq!(layout.rows[1].source.filter().unwrap().networks, [49]);
+    assert_eq!(layout.rows[1].timeout(), Duration::from_secs(1));
+    assert_eq!(layout.rows[2].source.filter().unwrap().genres, [35]);
+    assert_eq!(layout.rows[2].source.filter().unwrap().media_type, MediaType::Movie);
+    assert_eq!(layout.rows[3].source, RowSource::CuratedList { slug: "staff-picks".to_string() });
+    assert_eq!(layout.rows[3].source.filter(), None);
+}
+
+#[test]
+fn test_reject_invalid_layouts() {
+    let rejected = |rows: &str| HomeLayout::from_json(&format!(r#"{{"rows": [{}]}}"#, rows)).is_err();
+
+    assert!(rejected(r#"{"id": "a", "title": "A", "source": "trending"}, {"id": "a", "title": "B", "source": "trending"}"#));
+    assert!(rejected(r#"{"id": "a", "title": "A", "source": "trending", "ttl": "soon"}"#));
+    assert!(rejected(r#"{"id": "a", "title": "A", "source": "trending", "timeout": "0s"}"#));
+    assert!(rejected(r#"{"id": "a", "title": "A", "source": "popular"}"#));
+    assert!(rejected(r#"{"id": "a", "title": "A", "source": "discover", "media_type": "movie", "providers": [8]}"#));
+    assert!(rejected(r#"{"id": "a", "title": "A", "source": "curated_list", "slug": "Not A Slug"}"#));
+    assert!(!rejected(r#"{"id": "a", "title": "A", "source": "discover", "media_type": "movie", "providers": [8], "region": "US"}"#));
+}
+
+#[test]
+fn test_default_layout_keeps_original_rows() {
+    let ids: Vec<String> = HomeLayout::default().rows.into_iter().map(|row| row.id).collect();
+    assert_eq!(ids, ["trending", "popular_movies", "popular_tv"]);
+}
+
+#[test]
+fn test_row_cache_expires() {
+    let cache = HomeRowCache::new();
+    cache.store("top", vec![item(1, "movie", vec![])], UNIX_EPOCH, Duration::from_secs(60));
+    cache.store("off", vec![item(2, "movie", vec![])], UNIX_EPOCH, Duration::ZERO);
+
+    assert_eq!(cache.get("top", UNIX_EPOCH + Duration::from_secs(59)).map(|items| items.len()), Some(1));
+    assert!(cache.get("top", UNIX_EPOCH + Duration::from_secs(60)).is_none());
+    assert!(cache.get("off", UNIX_EPOCH).is_none());
+}
+
+#[test]
+fn test_row_metrics_failure_rate() {
+    let metrics = HomeRowMetrics::new();
+    metrics.record("trending", RowOutcome::Ok);
+    metrics.record("trending", RowOutcome::Error);
+    metrics.record("trending", RowOutcome::TimedOut);
+    metrics.record("picks", RowOutcome::Ok);
+
+    let stats = |row: &str, loads, errors, timeouts, failure_rate| HomeRowStats { row: row.to_string(), loads, errors, timeouts, failure_rate };
+    assert_eq!(metrics.stats(), [stats("picks", 1, 0, 0, 0.0), stats("trending", 3, 1, 1, 0.667)]);
+}
@@ -0,0 +1,44 @@
+use netflix_service::lists::{validate_slug, CuratedListInput, CuratedLists};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn input(title: &str) -> CuratedListInput {
+    CuratedListInput { title: title.to_string(), description: None, items: Vec::new() }
+}
+
+#[test]
+fn test_validate_slug() {
+    assert!(validate_slug("staff-picks").is_ok());
+    assert!(validate_slug("top-10-2024").is_ok());
+    assert!(validate_slug("").is_err());
+    assert!(validate_slug("Staff-Picks").is_err());
+    assert!(validate_slug("staff picks").is_err());
+    assert!(validate_slug(&"a".repeat(65)).is_err());
+}
+
+#[test]
+fn test_put_reports_creation_and_trims_fields() {
+    let lists = CuratedLists::new();
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let first = CuratedListInput { description: Some("  ".to_string()), ..input("  Staff picks ") };
+
+    let (list, created) = lists.put("staff-picks", first, now).unwrap();
+    assert!(created);
+    assert_eq!(list.title, "Staff picks");
+    assert!(list.description.is_none());
+    assert_eq!(list.updated_at, 1_700_000_000);
+
+    let (_, created) = lists.put("staff-picks", input("Staff picks"), now).unwrap();
+    assert!(!created);
+}
+
+#[test]
+fn test_lists_are_ordered_by_slug() {
+    let lists = CuratedLists::new();
+    lists.put("new-releases", input("New releases"), UNIX_EPOCH).unwrap();
+    lists.put("awards", input("Awards"), UNIX_EPOCH).unwrap();
+
+    let slugs: Vec<String> = lists.all().into_iter().map(|list| list.slug).collect();
+    assert_eq!(slugs, ["awards", "new-releases"]);
+    assert!(lists.delete("awards", UNIX_EPOCH));
+    assert!(!lists.delete("awards", UNIX_EPOCH));
+}
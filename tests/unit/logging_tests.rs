@@ -0,0 +1,108 @@
+use netflix_service::logging::{LogConfig, LogOutput, LogRotation, SizeRotatingFile};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn config(vars: &[(&str, &str)]) -> Result<LogConfig, String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    LogConfig::from_lookup(|key| vars.get(key).cloned())
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("netflix-service-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_defaults_to_stdout_text() {
+    let config = config(&[]).unwrap();
+    assert_eq!(config.output, LogOutput::Stdout);
+    assert!(!config.json);
+}
+
+#[test]
+fn test_stdout_json() {
+    let config = config(&[("LOG_FORMAT", "json")]).unwrap();
+    assert_eq!(config.output, LogOutput::Stdout);
+    assert!(config.json);
+}
+
+#[test]
+fn test_file_output_defaults() {
+    let config = config(&[("LOG_OUTPUT", "file")]).unwrap();
+    assert_eq!(
+        config.output,
+        LogOutput::File {
+            dir: PathBuf::from("logs"),
+            rotation: LogRotation::Daily,
+            max_files: 7,
+        }
+    );
+}
+
+#[test]
+fn test_file_output_with_size_rotation() {
+    let config = config(&[
+        ("LOG_OUTPUT", "file"),
+        ("LOG_DIR", "/var/log/netflix"),
+        ("LOG_ROTATION", "size"),
+        ("LOG_MAX_FILE_BYTES", "1024"),
+        ("LOG_MAX_FILES", "3"),
+    ])
+    .unwrap();
+    assert_eq!(
+        config.output,
+        LogOutput::File {
+            dir: PathBuf::from("/var/log/netflix"),
+            rotation: LogRotation::Size(1024),
+            max_files: 3,
+        }
+    );
+}
+
+#[test]
+fn test_invalid_values_are_rejected() {
+    assert!(config(&[("LOG_OUTPUT", "syslog")]).is_err());
+    assert!(config(&[("LOG_FORMAT", "xml")]).is_err());
+    assert!(config(&[("LOG_OUTPUT", "file"), ("LOG_ROTATION", "weekly")]).is_err());
+    assert!(config(&[("LOG_OUTPUT", "file"), ("LOG_MAX_FILES", "many")]).is_err());
+}
+
+#[test]
+fn test_size_rotation_keeps_max_files() {
+    let dir = temp_dir("size-rotation");
+    let mut file = SizeRotatingFile::new(&dir, 10, 2).unwrap();
+
+    for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+        file.write_all(line.as_bytes()).unwrap();
+    }
+    file.flush().unwrap();
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+    assert_eq!(read("netflix-service.log"), "fourth\n");
+    assert_eq!(read("netflix-service.log.1"), "third\n");
+    assert_eq!(read("netflix-service.log.2"), "second\n");
+    assert!(!dir.join("netflix-service.log.3").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_size_rotation_appends_until_limit() {
+    let dir = temp_dir("size-append");
+    let mut file = SizeRotatingFile::new(&dir, 64, 1).unwrap();
+
+    file.write_all(b"one\n").unwrap();
+    file.write_all(b"two\n").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(fs::read_to_string(dir.join("netflix-service.log")).unwrap(), "one\ntwo\n");
+    assert!(!dir.join("netflix-service.log.1").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
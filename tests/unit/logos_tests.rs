@@ -0,0 +1,69 @@
+use netflix_service::logos::{best_logo, LogoCache};
+use netflix_service::models::{Image, ImageLink, ImagesResponse, MediaType};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn logo(file_path: &str, language: Option<&str>, vote_average: f64, vote_count: u32) -> Image {
+    Image {
+        file_path: file_path.to_string(),
+        iso_639_1: language.map(str::to_string),
+        width: 1000,
+        height: 400,
+        vote_average,
+        vote_count,
+    }
+}
+
+fn images(logos: Vec<Image>) -> ImagesResponse {
+    ImagesResponse { id: 1, posters: vec![], backdrops: vec![], logos }
+}
+
+fn best(images: &ImagesResponse, language: &str) -> Option<String> {
+    best_logo(images, language).map(|image| image.file_path.clone())
+}
+
+#[test]
+fn test_requested_language_then_english_then_textless() {
+    let all = images(vec![
+        logo("/none.png", None, 9.0, 50),
+        logo("/en.png", Some("en"), 5.0, 2),
+        logo("/it.png", Some("it"), 5.0, 2),
+        logo("/de.png", Some("de"), 9.9, 90),
+    ]);
+
+    assert_eq!(best(&all, "it").as_deref(), Some("/it.png"));
+    assert_eq!(best(&all, "fr").as_deref(), Some("/en.png"));
+
+    let textless = images(vec![logo("/none.png", None, 1.0, 1), logo("/de.png", Some("de"), 9.0, 9)]);
+    assert_eq!(best(&textless, "fr").as_deref(), Some("/none.png"));
+
+    let foreign_only = images(vec![logo("/de.png", Some("de"), 9.0, 9)]);
+    assert_eq!(best(&foreign_only, "fr"), None);
+}
+
+#[test]
+fn test_png_then_votes_break_ties() {
+    let images = images(vec![
+        logo("/vector.svg", Some("en"), 9.0, 90),
+        logo("/low.png", Some("en"), 5.0, 90),
+        logo("/high.png", Some("en"), 5.5, 3),
+        logo("/high_more_votes.png", Some("en"), 5.5, 30),
+    ]);
+
+    assert_eq!(best(&images, "en").as_deref(), Some("/high_more_votes.png"));
+}
+
+#[test]
+fn test_cache_remembers_choice_until_ttl() {
+    let cache = LogoCache::new();
+    let now = UNIX_EPOCH + Duration::from_secs(1_000);
+    let link = ImageLink::from(&logo("/en.png", Some("en"), 5.0, 1));
+
+    assert_eq!(cache.get(MediaType::Movie, 603, "en", now).map(|logo| logo.is_some()), None);
+    cache.store(MediaType::Movie, 603, "en", Some(link), now, Duration::from_secs(60));
+    cache.store(MediaType::Movie, 604, "en", None, now, Duration::from_secs(60));
+
+    assert!(cache.get(MediaType::Movie, 603, "en", now).unwrap().is_some());
+    assert!(cache.get(MediaType::Movie, 604, "en", now).unwrap().is_none());
+    assert!(cache.get(MediaType::Movie, 603, "it", now).is_none());
+    assert!(cache.get(MediaType::Movie, 603, "en", now + Duration::from_secs(60)).is_none());
+}
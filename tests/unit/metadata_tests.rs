@@ -0,0 +1,22 @@
+use netflix_service::metadata::{FieldGroup, MetadataChains, MetadataSources};
+
+#[test]
+fn test_chains_parse_per_field_group() {
+    let chains = MetadataChains::parse("ratings=omdb+tmdb, text = tmdb + omdb").unwrap();
+
+    assert_eq!(chains.sources(FieldGroup::Ratings), ["omdb", "tmdb"]);
+    assert_eq!(chains.sources(FieldGroup::Text), ["tmdb", "omdb"]);
+    assert_eq!(chains.sources(FieldGroup::Release), ["tmdb"]);
+    assert_eq!(MetadataChains::parse("").unwrap(), MetadataChains::default());
+}
+
+#[test]
+fn test_invalid_chains_are_rejected() {
+    assert!(MetadataChains::parse("ratings").is_err());
+    assert!(MetadataChains::parse("artwork=tmdb").is_err());
+    assert!(MetadataChains::parse("ratings=omdb+").is_err());
+
+    // Sources must be configured
+    let chains = MetadataChains::parse("ratings=omdb+tmdb").unwrap();
+    assert_eq!(MetadataSources::new(chains, Vec::new()).err().as_deref(), Some("unknown metadata source 'omdb'"));
+}
@@ -0,0 +1,57 @@
+use netflix_service::clock::MockClock;
+use netflix_service::mirrors::{Mirrors, DEFAULT_BASE_URL};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn mirrors(spec: &str) -> (Mirrors, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    (Mirrors::parse(spec, clock.clone()), clock)
+}
+
+#[test]
+fn test_defaults_to_official_endpoint() {
+    let (mirrors, _) = mirrors("");
+
+    assert_eq!(mirrors.attempt_order(), vec![0]);
+    assert_eq!(mirrors.base_url(0), DEFAULT_BASE_URL);
+}
+
+#[test]
+fn test_parse_trims_urls() {
+    let (mirrors, _) = mirrors(" https://mirror.internal/3/ , https://api.themoviedb.org/3");
+
+    assert_eq!(mirrors.base_url(0), "https://mirror.internal/3");
+    assert_eq!(mirrors.base_url(1), "https://api.themoviedb.org/3");
+}
+
+#[test]
+fn test_failed_mirror_is_tried_last_until_it_recovers() {
+    let (mirrors, clock) = mirrors("https://a/3,https://b/3,https://c/3");
+    assert_eq!(mirrors.attempt_order(), vec![0, 1, 2]);
+
+    mirrors.record_failure(0);
+    assert_eq!(mirrors.attempt_order(), vec![1, 2, 0]);
+    assert!(!mirrors.status()[0].healthy);
+
+    clock.advance(Duration::from_secs(30));
+    assert_eq!(mirrors.attempt_order(), vec![0, 1, 2]);
+    assert!(mirrors.status()[0].healthy);
+}
+
+#[test]
+fn test_status_counts_successes_and_failures() {
+    let (mirrors, _) = mirrors("https://a/3,https://b/3");
+    mirrors.record_failure(0);
+    mirrors.record_failure(0);
+    mirrors.record_success(1);
+
+    let status = mirrors.status();
+    assert_eq!((status[0].failures, status[0].consecutive_failures), (2, 2));
+    assert_eq!(status[0].last_failure, Some(1_000));
+    assert_eq!((status[1].successes, status[1].failures), (1, 0));
+
+    mirrors.record_success(0);
+    let status = mirrors.status();
+    assert_eq!(status[0].consecutive_failures, 0);
+    assert!(status[0].healthy);
+}
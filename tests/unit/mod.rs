@@ -1,3 +1,45 @@
 // Unit tests module
+mod analytics_tests;
+mod bench_tests;
+mod blocklist_tests;
+mod budget_tests;
+mod cache_policy_tests;
+mod clock_tests;
+mod compression_tests;
+mod dev_client_tests;
+mod enrich_tests;
 mod error_tests;
+mod experiments_tests;
+mod health_tests;
+mod home_tests;
+mod logging_tests;
+mod lists_tests;
+mod logos_tests;
+mod metadata_tests;
+mod mirrors_tests;
+mod mode_tests;
+mod model_property_tests;
 mod model_tests;
+mod mtls_tests;
+mod negative_cache_tests;
+mod privacy_tests;
+mod problem_tests;
+mod quality_tests;
+mod ranking_tests;
+mod profiling_tests;
+mod redact_tests;
+mod safe_search_tests;
+mod services_tests;
+mod session_tests;
+mod shaping_tests;
+mod shutdown_tests;
+mod slo_tests;
+mod startup_tests;
+mod sync_tests;
+mod tags_tests;
+mod taste_tests;
+mod themes_tests;
+mod trailers_tests;
+mod upstream_metrics_tests;
+mod versioning_tests;
+mod viewing_order_tests;
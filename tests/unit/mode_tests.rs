@@ -0,0 +1,28 @@
+use netflix_service::mode::{ModeSwitch, ServiceMode};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_service_mode_from_str() {
+    assert_eq!("cache_only".parse::<ServiceMode>(), Ok(ServiceMode::CacheOnly));
+    assert_eq!(" maintenance ".parse::<ServiceMode>(), Ok(ServiceMode::Maintenance));
+    assert!("panic".parse::<ServiceMode>().is_err());
+}
+
+#[test]
+fn test_mode_restrictions() {
+    assert!(!ServiceMode::Normal.blocks_expensive_routes());
+    assert!(ServiceMode::Degraded.allows_upstream());
+    assert!(!ServiceMode::CacheOnly.allows_upstream());
+    assert!(!ServiceMode::Maintenance.allows_upstream());
+}
+
+#[test]
+fn test_switch_records_change_time() {
+    let switch = ModeSwitch::new();
+    assert_eq!(switch.mode(), ServiceMode::Normal);
+
+    let status = switch.set(ServiceMode::Maintenance, UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    assert_eq!(status.mode, ServiceMode::Maintenance);
+    assert_eq!(status.changed_at, Some(1_700_000_000));
+    assert_eq!(switch.mode(), ServiceMode::Maintenance);
+}
@@ -0,0 +1,285 @@
+use chrono::NaiveDate;
+use netflix_service::models::{
+    AlternativeTitle, AlternativeTitles, ExternalIds, Genre, Image, ImagesResponse, Keyword, Movie, Network, ProviderList,
+    RegionProviders, TitleDetails, TmdbList, TmdbListItem, TmdbResponse, Video, VideoResponse, WatchProvider,
+    WatchProvidersResponse,
+};
+use proptest::collection::{hash_map, vec};
+use proptest::option;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+/// Serializing, parsing and serializing again gives the same JSON
+fn roundtrip<T: Serialize + DeserializeOwned>(model: &T) -> Result<(), TestCaseError> {
+    let json = serde_json::to_value(model).unwrap();
+    let parsed: T = serde_json::from_value(json.clone()).map_err(|e| TestCaseError::fail(e.to_string()))?;
+    prop_assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    Ok(())
+}
+
+fn text() -> impl Strategy<Value = Option<String>> {
+    option::of(any::<String>())
+}
+
+fn date() -> impl Strategy<Value = Option<NaiveDate>> {
+    option::of((1870..2100, 1..=365u32).prop_map(|(year, day)| NaiveDate::from_yo_opt(year, day).unwrap()))
+}
+
+fn vote() -> impl Strategy<Value = Option<f64>> {
+    option::of(0.0..=10.0)
+}
+
+fn genre() -> impl Strategy<Value = Genre> {
+    (any::<i64>(), any::<String>()).prop_map(|(id, name)| Genre { id, name })
+}
+
+/// Any JSON value, nested a few levels deep
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        any::<f64>().prop_map(Value::from),
+        any::<String>().prop_map(Value::from),
+    ];
+    leaf.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            vec(inner.clone(), 0..4).prop_map(Value::Array),
+            hash_map(any::<String>(), inner, 0..4).prop_map(|fields| Value::Object(fields.into_iter().collect())),
+        ]
+    })
+}
+
+prop_compose! {
+    fn movie()(
+        (id, title, name, overview, poster_path, backdrop_path) in (any::<i64>(), text(), text(), text(), text(), text()),
+        (vote_average, vote_count, quality_score) in (vote(), option::of(any::<u32>()), vote()),
+        (genre_ids, release_date, media_type, has_trailer) in (vec(any::<i64>(), 0..4), date(), text(), option::of(any::<bool>())),
+    ) -> Movie {
+        Movie {
+            id, title, name, overview, poster_path, backdrop_path,
+            vote_average, vote_count, quality_score, has_trailer, providers: None, cast: None, genre_ids, release_date, media_type, adult: false,
+        }
+    }
+}
+
+prop_compose! {
+    fn alternative_title()(iso_3166_1 in "[A-Z]{2}", title in any::<String>(), r#type in any::<String>()) -> AlternativeTitle {
+        AlternativeTitle { iso_3166_1, title, r#type }
+    }
+}
+
+prop_compose! {
+    fn title_details()(
+        (id, title, name, tagline, overview, poster_path, backdrop_path) in (any::<i64>(), text(), text(), text(), text(), text(), text()),
+        (vote_average, vote_count, release_date, first_air_date, runtime) in (vote(), option::of(any::<u32>()), date(), date(), option::of(any::<i32>())),
+        (genres, original_title, original_name, original_language) in (vec(genre(), 0..3), text(), text(), text()),
+        alternative_titles in vec(alternative_title(), 0..3),
+        keywords in vec((any::<i64>(), any::<String>()).prop_map(|(id, name)| Keyword { id, name }), 0..3),
+    ) -> TitleDetails {
+        TitleDetails {
+            id, title, name, tagline, overview, poster_path, backdrop_path,
+            vote_average, vote_count, release_date, first_air_date, runtime,
+            genres, original_title, original_name, original_language, alternative_titles, keywords,
+            cast: vec![],
+            external_ids: ExternalIds::default(),
+            seasons: Vec::new(),
+            adult: false,
+        }
+    }
+}
+
+prop_compose! {
+    fn image()(file_path in any::<String>(), iso_639_1 in text(), width in any::<u32>(), height in any::<u32>(), vote_average in 0.0..=10.0, vote_count in any::<u32>()) -> Image {
+        Image { file_path, iso_639_1, width, height, vote_average, vote_count }
+    }
+}
+
+prop_compose! {
+    fn watch_provider()(provider_id in any::<i64>(), provider_name in any::<String>(), logo_path in text(), display_priority in any::<i32>()) -> WatchProvider {
+        WatchProvider { provider_id, provider_name, logo_path, display_priority }
+    }
+}
+
+prop_compose! {
+    fn region_providers()(link in text(), offers in vec(vec(watch_provider(), 0..3), 5)) -> RegionProviders {
+        let [flatrate, free, ads, rent, buy]: [Vec<WatchProvider>; 5] = offers.try_into().unwrap();
+        RegionProviders { link, flatrate, free, ads, rent, buy }
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_list_payloads_roundtrip(page in any::<i32>(), total_pages in any::<i32>(), results in vec(movie(), 0..5)) {
+        roundtrip(&TmdbResponse { page, total_pages, results })?;
+    }
+
+    #[test]
+    fn test_title_details_roundtrip(details in title_details()) {
+        roundtrip(&details)?;
+        roundtrip(&AlternativeTitles { id: details.id, titles: details.alternative_titles })?;
+    }
+
+    #[test]
+    fn test_media_payloads_roundtrip(
+        id in any::<i64>(),
+        videos in vec((any::<String>(), any::<String>(), any::<String>(), any::<String>(), any::<String>()), 0..3),
+        posters in vec(image(), 0..3),
+        logos in vec(image(), 0..3),
+    ) {
+        let results = videos
+            .into_iter()
+            .map(|(id, key, site, r#type, name)| Video { id, key, site, r#type, name })
+            .collect();
+        roundtrip(&VideoResponse { id, results })?;
+        roundtrip(&ImagesResponse { id, posters, backdrops: Vec::new(), logos })?;
+    }
+
+    #[test]
+    fn test_provider_payloads_roundtrip(
+        id in any::<i64>(),
+        regions in hash_map("[A-Z]{2}", region_providers(), 0..3),
+        providers in vec(watch_provider(), 0..4),
+    ) {
+        roundtrip(&WatchProvidersResponse { id, results: regions })?;
+        roundtrip(&ProviderList { results: providers })?;
+    }
+
+    #[test]
+    fn test_network_and_list_roundtrip(
+        id in any::<i64>(),
+        name in any::<String>(),
+        (headquarters, homepage, logo_path, origin_country, description) in (text(), text(), text(), text(), text()),
+        items in vec((any::<i64>(), text()).prop_map(|(id, media_type)| TmdbListItem { id, media_type }), 0..4),
+    ) {
+        roundtrip(&Network { id, name: name.clone(), headquarters, homepage, logo_path, origin_country })?;
+        roundtrip(&TmdbList { name, description, items })?;
+    }
+
+    /// Any field but the id may hold any JSON value
+    #[test]
+    fn test_movie_survives_any_field_value(field in prop::sample::select(MOVIE_FIELDS), value in json_value()) {
+        let mut payload = json!({ "id": 603, "title": "The Matrix" });
+        payload[field] = value;
+
+        let movie: Movie = serde_json::from_value(payload).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(movie.id, 603);
+    }
+
+    #[test]
+    fn test_title_details_survive_any_field_value(field in prop::sample::select(DETAILS_FIELDS), value in json_value()) {
+        let mut payload = json!({ "id": 1399, "name": "Game of Thrones" });
+        payload[field] = value;
+
+        let details: TitleDetails = serde_json::from_value(payload).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(details.id, 1399);
+    }
+
+    /// Entries that aren't movies are dropped instead of failing the page
+    #[test]
+    fn test_list_results_skip_unparseable_entries(junk in vec(json_value(), 0..6)) {
+        let mut results = junk.clone();
+        results.push(json!({ "id": 603 }));
+        let payload = json!({ "page": 1, "total_pages": 1, "results": results });
+
+        let response: TmdbResponse = serde_json::from_value(payload).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert!(response.results.iter().any(|movie| movie.id == 603));
+        prop_assert!(response.results.len() <= junk.len() + 1);
+    }
+}
+
+const MOVIE_FIELDS: &[&str] = &[
+    "title", "name", "overview", "poster_path", "backdrop_path", "vote_average", "vote_count",
+    "quality_score", "genre_ids", "release_date", "media_type",
+];
+
+const DETAILS_FIELDS: &[&str] = &[
+    "title", "name", "tagline", "overview", "poster_path", "backdrop_path", "vote_average", "vote_count",
+    "release_date", "first_air_date", "runtime", "genres", "original_title", "original_name",
+    "original_language", "alternative_titles", "keywords",
+];
+
+/// Odd payloads seen from TMDB or close to it, each of which must still parse
+#[test]
+fn test_weird_payload_corpus() {
+    let huge = "x".repeat(1_000_000);
+
+    let trending: TmdbResponse = serde_json::from_value(json!({
+        "page": 1,
+        "total_pages": 500,
+        "results": [
+            { "id": 1, "title": null, "name": null, "overview": null, "vote_average": null, "vote_count": null, "genre_ids": null, "release_date": null, "media_type": null },
+            { "id": 2, "title": 42, "vote_average": "7.5", "vote_count": -3, "genre_ids": [28, "12", null, 1.5], "release_date": 20240101 },
+            { "id": 3, "title": huge, "overview": "\u{0}\u{202e}💥", "release_date": "2024-02-30", "genre_ids": {} },
+            { "id": 4, "vote_average": 1e308, "vote_count": 4294967296u64, "media_type": ["movie"] },
+            { "title": "No id" },
+            null,
+            "person",
+            { "id": "5" },
+            { "id": 6, "unexpected": { "nested": [1, 2, { "deep": true }] } },
+        ],
+    }))
+    .unwrap();
+    let ids: Vec<i64> = trending.results.iter().map(|movie| movie.id).collect();
+    assert_eq!(ids, [1, 2, 3, 4, 6]);
+    assert_eq!(trending.results[1].title, None);
+    assert_eq!(trending.results[1].genre_ids, [28]);
+    assert_eq!(trending.results[2].title.as_ref().map(String::len), Some(1_000_000));
+    assert_eq!(trending.results[2].release_date, None);
+    assert_eq!(trending.results[3].vote_count, None);
+
+    let details: TitleDetails = serde_json::from_value(json!({
+        "id": 603,
+        "title": "The Matrix",
+        "runtime": "136",
+        "genres": [{ "id": 28, "name": null }, { "name": "No id" }, 878],
+        "alternative_titles": { "titles": [{ "iso_3166_1": "IT", "title": "Matrix", "type": null }, { "title": "No country" }] },
+        "keywords": "based on novel",
+        "first_air_date": "",
+    }))
+    .unwrap();
+    assert_eq!(details.runtime, None);
+    assert_eq!(details.genres.iter().map(|genre| genre.id).collect::<Vec<_>>(), [28]);
+    assert_eq!(details.alternative_titles.len(), 1);
+    assert!(details.keywords.is_empty());
+
+    let images: ImagesResponse = serde_json::from_value(json!({
+        "id": 603,
+        "posters": [{ "file_path": "/a.jpg", "width": null, "height": "720", "vote_average": null }, { "width": 10 }],
+        "backdrops": null,
+        "logos": "none",
+    }))
+    .unwrap();
+    assert_eq!((images.posters.len(), images.posters[0].width), (1, 0));
+
+    let providers: WatchProvidersResponse = serde_json::from_value(json!({
+        "id": 603,
+        "results": { "US": { "link": 7, "flatrate": [{ "provider_id": 8, "provider_name": "Netflix", "display_priority": "1" }, {}], "rent": null } },
+    }))
+    .unwrap();
+    assert_eq!(providers.results["US"].flatrate.len(), 1);
+    assert_eq!(providers.results["US"].link, None);
+
+    let videos: VideoResponse = serde_json::from_value(json!({ "id": 603, "results": [{ "key": "abc" }, null] })).unwrap();
+    assert!(videos.results.is_empty());
+
+    let list: TmdbList = serde_json::from_value(json!({
+        "name": "Best of",
+        "description": false,
+        "items": [{ "id": 603, "media_type": 1 }, { "id": null }, { "id": 1399, "media_type": "tv" }],
+    }))
+    .unwrap();
+    assert_eq!(list.title_refs().len(), 2);
+
+    let network: Network = serde_json::from_value(json!({ "id": 49, "name": "HBO", "headquarters": [], "logo_path": null })).unwrap();
+    assert_eq!(network.headquarters, None);
+}
+
+#[test]
+fn test_required_fields_stay_strict() {
+    assert!(serde_json::from_value::<Movie>(json!({ "title": "No id" })).is_err());
+    assert!(serde_json::from_value::<TmdbResponse>(json!({ "results": [] })).is_err());
+    assert!(serde_json::from_value::<Network>(json!({ "id": 49 })).is_err());
+}
@@ -1,5 +1,5 @@
-use netflix_service::models::{Movie, TmdbResponse, Video, VideoResponse, PageQuery, SearchQuery};
-use serde_json;
+use chrono::NaiveDate;
+use netflix_service::models::{format_runtime, AlternativeTitles, AvailabilityQuery, DiscoverFilter, DiscoverQuery, DiscoverSort, ImagesQuery, ImagesResponse, MediaType, Movie, TitleDetails, TmdbId, TmdbList, TmdbResponse, Video, VideoResponse, PageQuery, SearchQuery};
 
 #[test]
 fn test_movie_serialization() {
@@ -11,8 +11,15 @@ fn test_movie_serialization() {
         poster_path: Some("/poster.jpg".to_string()),
         backdrop_path: Some("/backdrop.jpg".to_string()),
         vote_average: Some(8.5),
-        release_date: Some("2024-01-01".to_string()),
+        vote_count: Some(1200),
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: Vec::new(),
+        release_date: "2024-01-01".parse().ok(),
         media_type: Some("movie".to_string()),
+        adult: false,
     };
 
     let json = serde_json::to_string(&movie).unwrap();
@@ -49,8 +56,15 @@ fn test_tmdb_response_structure() {
                 poster_path: None,
                 backdrop_path: None,
                 vote_average: None,
+                vote_count: None,
+                quality_score: None,
+                has_trailer: None,
+                providers: None,
+                cast: None,
+                genre_ids: Vec::new(),
                 release_date: None,
                 media_type: None,
+                adult: false,
             },
             Movie {
                 id: 2,
@@ -60,8 +74,15 @@ fn test_tmdb_response_structure() {
                 poster_path: None,
                 backdrop_path: None,
                 vote_average: None,
+                vote_count: None,
+                quality_score: None,
+                has_trailer: None,
+                providers: None,
+                cast: None,
+                genre_ids: Vec::new(),
                 release_date: None,
                 media_type: None,
+                adult: false,
             },
         ],
     };
@@ -105,6 +126,7 @@ fn test_search_query_structure() {
     let query = SearchQuery {
         query: "avengers".to_string(),
         page: Some(2),
+        include_adult: None,
     };
 
     assert_eq!(query.query, "avengers");
@@ -122,8 +144,15 @@ fn test_movie_with_tv_show_fields() {
         poster_path: None,
         backdrop_path: None,
         vote_average: Some(8.0),
+        vote_count: Some(1200),
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: Vec::new(),
         release_date: None,
         media_type: Some("tv".to_string()),
+        adult: false,
     };
 
     assert_eq!(tv_show.name, Some("TV Show Name".to_string()));
@@ -141,11 +170,407 @@ fn test_movie_optional_fields() {
         poster_path: None,
         backdrop_path: None,
         vote_average: None,
+        vote_count: None,
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: Vec::new(),
         release_date: None,
         media_type: None,
+        adult: false,
     };
 
     assert_eq!(minimal_movie.id, 100);
     assert!(minimal_movie.title.is_none());
     assert!(minimal_movie.overview.is_none());
 }
+
+#[test]
+fn test_media_type_serde() {
+    assert_eq!(serde_json::to_string(&MediaType::Movie).unwrap(), "\"movie\"");
+    assert_eq!(serde_json::from_str::<MediaType>("\"tv\"").unwrap(), MediaType::Tv);
+    assert!(serde_json::from_str::<MediaType>("\"person\"").is_err());
+    assert_eq!(MediaType::Tv.as_str(), "tv");
+}
+
+#[test]
+fn test_title_details_deserialization() {
+    let json = r#"{
+        "id": 1399,
+        "name": "Game of Thrones",
+        "first_air_date": "2011-04-17",
+        "genres": [{"id": 18, "name": "Drama"}]
+    }"#;
+
+    let details: TitleDetails = serde_json::from_str(json).unwrap();
+    assert_eq!(details.display_title(), "Game of Thrones");
+    assert_eq!(details.genres[0].name, "Drama");
+    assert_eq!(details.runtime, None);
+    assert_eq!(details.first_air_date, NaiveDate::from_ymd_opt(2011, 4, 17));
+    assert_eq!(details.year(), Some(2011));
+}
+
+#[test]
+fn test_movie_release_date_and_year() {
+    let json = r#"{"id": 603, "title": "The Matrix", "release_date": "1999-03-30"}"#;
+
+    let movie: Movie = serde_json::from_str(json).unwrap();
+    assert_eq!(movie.release_date, NaiveDate::from_ymd_opt(1999, 3, 30));
+    assert_eq!(movie.year(), Some(1999));
+
+    let value = serde_json::to_value(&movie).unwrap();
+    assert_eq!(value["release_date"], "1999-03-30");
+    assert_eq!(value["year"], 1999);
+}
+
+#[test]
+fn test_empty_and_malformed_release_dates_are_missing() {
+    for date in [r#""""#, r#""  ""#, r#""2024-13-45""#, r#""soon""#, "null"] {
+        let json = format!(r#"{{"id": 1, "release_date": {}}}"#, date);
+
+        let movie: Movie = serde_json::from_str(&json).unwrap();
+        assert_eq!(movie.release_date, None, "date {}", date);
+        assert_eq!(movie.year(), None);
+
+        let value = serde_json::to_value(&movie).unwrap();
+        assert!(value["release_date"].is_null());
+        assert!(value["year"].is_null());
+    }
+}
+
+#[test]
+fn test_movie_round_trips_through_json() {
+    let json = r#"{"id": 603, "title": "The Matrix", "release_date": "1999-03-30", "media_type": "movie"}"#;
+
+    let movie: Movie = serde_json::from_str(json).unwrap();
+    let round_trip: Movie = serde_json::from_str(&serde_json::to_string(&movie).unwrap()).unwrap();
+
+    assert_eq!(round_trip.release_date, movie.release_date);
+    assert_eq!(round_trip.year(), Some(1999));
+}
+
+fn video(key: &str, site: &str, kind: &str) -> Video {
+    Video {
+        id: key.to_string(),
+        key: key.to_string(),
+        site: site.to_string(),
+        r#type: kind.to_string(),
+        name: kind.to_string(),
+    }
+}
+
+#[test]
+fn test_best_trailer_prefers_youtube_trailer() {
+    let response = VideoResponse {
+        id: 1,
+        results: vec![
+            video("teaser", "YouTube", "Teaser"),
+            video("vimeo", "Vimeo", "Trailer"),
+            video("trailer", "YouTube", "Trailer"),
+        ],
+    };
+
+    assert_eq!(response.best_trailer().unwrap().key, "trailer");
+}
+
+#[test]
+fn test_best_trailer_falls_back_to_teaser() {
+    let response = VideoResponse {
+        id: 1,
+        results: vec![
+            video("clip", "YouTube", "Clip"),
+            video("teaser", "YouTube", "Teaser"),
+        ],
+    };
+
+    assert_eq!(response.best_trailer().unwrap().key, "teaser");
+}
+
+#[test]
+fn test_best_trailer_none_when_not_embeddable() {
+    let response = VideoResponse {
+        id: 1,
+        results: vec![video("vimeo", "Vimeo", "Trailer")],
+    };
+
+    assert!(response.best_trailer().is_none());
+}
+
+#[test]
+fn test_tmdb_id_range() {
+    assert_eq!(TmdbId::new(603).map(TmdbId::get), Some(603));
+    assert_eq!(TmdbId::new(i32::MAX as i64 + 1).map(TmdbId::get), Some(i32::MAX as i64 + 1));
+    assert!(TmdbId::new(0).is_none());
+    assert!(TmdbId::new(-5).is_none());
+}
+
+#[test]
+fn test_tmdb_id_deserialization() {
+    let id: TmdbId = serde_json::from_str("603").unwrap();
+    assert_eq!(id.get(), 603);
+    assert_eq!(serde_json::to_string(&id).unwrap(), "603");
+
+    let error = serde_json::from_str::<TmdbId>("-5").unwrap_err();
+    assert!(error.to_string().contains("positive"));
+}
+
+#[test]
+fn test_ids_beyond_i32_deserialize() {
+    let large_id = i32::MAX as i64 + 10;
+    let json = format!(
+        r#"{{"page": 1, "total_pages": 1, "results": [{{"id": {}, "title": "Big Id", "media_type": "movie"}}]}}"#,
+        large_id
+    );
+
+    let response: TmdbResponse = serde_json::from_str(&json).unwrap();
+    assert_eq!(response.results[0].id, large_id);
+
+    let videos: VideoResponse = serde_json::from_str(&format!(r#"{{"id": {}, "results": []}}"#, large_id)).unwrap();
+    assert_eq!(videos.id, large_id);
+
+    let details: TitleDetails = serde_json::from_str(&format!(r#"{{"id": {}, "name": "Big Show"}}"#, large_id)).unwrap();
+    assert_eq!(details.id, large_id);
+    assert_eq!(serde_json::to_value(&details).unwrap()["id"], large_id);
+}
+
+#[test]
+fn test_images_query_languages() {
+    let query = ImagesQuery { language: Some(" en, null ,IT,".to_string()) };
+    assert_eq!(query.languages(), Some(vec![Some("en".to_string()), None, Some("it".to_string())]));
+
+    assert_eq!(ImagesQuery { language: None }.languages(), None);
+}
+
+#[test]
+fn test_images_response_deserialization() {
+    let json = r#"{"id": 603, "logos": [{"file_path": "/logo.png", "iso_639_1": "en", "width": 500, "height": 200, "vote_average": 5.3}]}"#;
+
+    let images: ImagesResponse = serde_json::from_str(json).unwrap();
+    assert!(images.posters.is_empty());
+    let links = images.to_links(Some(&[Some("en".to_string())]));
+    assert_eq!(links.logos[0].url, "https://image.tmdb.org/t/p/original/logo.png");
+    assert!(images.to_links(Some(&[None])).logos.is_empty());
+}
+
+#[test]
+fn test_title_details_with_appended_alternative_titles() {
+    let movie = r#"{
+        "id": 129,
+        "title": "Spirited Away",
+        "original_title": "千と千尋の神隠し",
+        "original_language": "ja",
+        "alternative_titles": {"titles": [{"iso_3166_1": "IT", "title": "La città incantata", "type": ""}]},
+        "keywords": {"keywords": [{"id": 1721, "name": "fight"}]}
+    }"#;
+    let show = r#"{
+        "id": 1399,
+        "name": "Game of Thrones",
+        "original_name": "Game of Thrones",
+        "alternative_titles": {"results": [{"iso_3166_1": "DE", "title": "Das Lied von Eis und Feuer"}]},
+        "keywords": {"results": [{"id": 818, "name": "based on novel or book"}]}
+    }"#;
+
+    let movie: TitleDetails = serde_json::from_str(movie).unwrap();
+    assert_eq!(movie.original_display_title(), Some("千と千尋の神隠し"));
+    assert_eq!(movie.original_language.as_deref(), Some("ja"));
+    assert_eq!(movie.alternative_titles[0].title, "La città incantata");
+    assert_eq!(movie.keywords[0].name, "fight");
+
+    let show: TitleDetails = serde_json::from_str(show).unwrap();
+    assert_eq!(show.original_display_title(), Some("Game of Thrones"));
+    assert_eq!(show.alternative_titles[0].iso_3166_1, "DE");
+    assert_eq!(show.alternative_titles[0].r#type, "");
+    assert_eq!(show.keywords[0].id, 818);
+}
+
+#[test]
+fn test_title_details_alternative_titles_round_trip() {
+    let json = r#"{"id": 1, "alternative_titles": {"titles": [{"iso_3166_1": "FR", "title": "Titre"}]}}"#;
+    let details: TitleDetails = serde_json::from_str(json).unwrap();
+
+    let round_tripped: TitleDetails = serde_json::from_value(serde_json::to_value(&details).unwrap()).unwrap();
+    assert_eq!(round_tripped.alternative_titles, details.alternative_titles);
+
+    let without: TitleDetails = serde_json::from_str(r#"{"id": 1, "alternative_titles": null}"#).unwrap();
+    assert!(without.alternative_titles.is_empty());
+}
+
+#[test]
+fn test_alternative_titles_for_country() {
+    let json = r#"{"id": 129, "titles": [
+        {"iso_3166_1": "IT", "title": "La città incantata"},
+        {"iso_3166_1": "US", "title": "Spirited Away"},
+        {"iso_3166_1": "IT", "title": "Il castello errante", "type": "working title"}
+    ]}"#;
+
+    let titles: AlternativeTitles = serde_json::from_str(json).unwrap();
+    let italian = titles.for_country("it");
+    assert_eq!(italian.titles.len(), 2);
+    assert!(italian.titles.iter().all(|title| title.iso_3166_1 == "IT"));
+}
+
+#[test]
+fn test_format_runtime() {
+    assert_eq!(format_runtime(Some(139)).as_deref(), Some("2h 19m"));
+    assert_eq!(format_runtime(Some(120)).as_deref(), Some("2h"));
+    assert_eq!(format_runtime(Some(45)).as_deref(), Some("45m"));
+    assert_eq!(format_runtime(Some(0)), None);
+    assert_eq!(format_runtime(None), None);
+}
+
+#[test]
+fn test_vote_average_rounded_and_age() {
+    let json = r#"{"id": 550, "title": "Fight Club", "vote_average": 8.438, "release_date": "1999-10-15"}"#;
+    let movie: Movie = serde_json::from_str(json).unwrap();
+
+    assert_eq!(movie.vote_average_rounded(), Some(8.4));
+    assert_eq!(movie.age_at(NaiveDate::from_ymd_opt(2024, 10, 14).unwrap()), Some(24));
+    assert_eq!(movie.age_at(NaiveDate::from_ymd_opt(2024, 10, 15).unwrap()), Some(25));
+    // Not released yet
+    assert_eq!(movie.age_at(NaiveDate::from_ymd_opt(1999, 1, 1).unwrap()), None);
+
+    let value = serde_json::to_value(&movie).unwrap();
+    assert_eq!(value["vote_average_rounded"], 8.4);
+    assert!(value["age"].as_u64().unwrap() >= 25);
+}
+
+#[test]
+fn test_title_details_derived_fields() {
+    let json = r#"{"id": 1399, "name": "Game of Thrones", "first_air_date": "2011-04-17", "vote_average": 8.45}"#;
+    let show: TitleDetails = serde_json::from_str(json).unwrap();
+
+    assert_eq!(show.runtime_display(), None);
+    assert_eq!(show.vote_average_rounded(), Some(8.5));
+    assert_eq!(show.age_at(NaiveDate::from_ymd_opt(2021, 4, 17).unwrap()), Some(10));
+
+    let json = r#"{"id": 603, "title": "The Matrix", "runtime": 136, "release_date": "1999-03-30"}"#;
+    let value = serde_json::to_value(serde_json::from_str::<TitleDetails>(json).unwrap()).unwrap();
+    assert_eq!(value["runtime_display"], "2h 16m");
+    assert!(value["vote_average_rounded"].is_null());
+    assert!(value["age"].as_u64().is_some());
+}
+
+#[test]
+fn test_availability_query_regions() {
+    let query = |regions: &str| AvailabilityQuery { regions: Some(regions.to_string()) }.regions();
+
+    assert_eq!(query(" us,GB,,us ").unwrap(), ["US", "GB"]);
+    assert_eq!(query("U1").unwrap_err(), "invalid region 'U1'");
+    assert!(AvailabilityQuery { regions: None }.regions().unwrap().is_empty());
+}
+
+#[test]
+fn test_discover_query_filter() {
+    let query = DiscoverQuery { providers: Some("8, 9,8".to_string()), region: Some("gb".to_string()), ..DiscoverQuery::default() };
+
+    let filter = query.filter().unwrap();
+    assert_eq!(filter.media_type, MediaType::Movie);
+    assert_eq!(filter.providers, [8, 9]);
+    assert_eq!(filter.region.as_deref(), Some("GB"));
+    assert_eq!(
+        filter.tmdb_params(),
+        [
+            ("sort_by", "popularity.desc".to_string()),
+            ("with_watch_providers", "8|9".to_string()),
+            ("with_watch_monetization_types", "flatrate|free|ads".to_string()),
+            ("watch_region", "GB".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_discover_query_without_providers() {
+    let query = DiscoverQuery::default();
+
+    assert_eq!(query.filter().unwrap().tmdb_params(), [("sort_by", "popularity.desc".to_string())]);
+    let zero = DiscoverQuery { providers: Some("0".to_string()), region: Some("US".to_string()), ..DiscoverQuery::default() };
+    assert_eq!(zero.filter().unwrap_err(), "invalid provider id '0'");
+}
+
+#[test]
+fn test_discover_query_tmdb_filters() {
+    let query = DiscoverQuery {
+        with_genres: Some("28|12| 28".to_string()),
+        primary_release_year: Some(1999),
+        vote_average_gte: Some(7.25),
+        sort_by: Some("vote_average.desc".to_string()),
+        ..DiscoverQuery::default()
+    };
+
+    assert_eq!(
+        query.filter().unwrap().tmdb_params(),
+        [
+            ("sort_by", "vote_average.desc".to_string()),
+            ("primary_release_year", "1999".to_string()),
+            ("vote_average.gte", "7.3".to_string()),
+            ("with_genres", "28|12".to_string()),
+        ]
+    );
+    let invalid = |query: DiscoverQuery| query.filter().unwrap_err();
+    assert_eq!(invalid(DiscoverQuery { with_genres: Some("action".to_string()), ..DiscoverQuery::default() }), "invalid genre id 'action'");
+    assert_eq!(
+        invalid(DiscoverQuery { with_genres: Some("28,12|16".to_string()), ..DiscoverQuery::default() }),
+        "with_genres may use ',' (all of) or '|' (any of), not both"
+    );
+    let all_of = DiscoverQuery { with_genres: Some("28, 12,28".to_string()), ..DiscoverQuery::default() }.filter().unwrap();
+    assert!(all_of.all_genres);
+    assert_eq!(all_of.tmdb_params().last(), Some(&("with_genres", "28,12".to_string())));
+    assert_eq!(invalid(DiscoverQuery { primary_release_year: Some(99), ..DiscoverQuery::default() }), "invalid primary_release_year '99'");
+    assert_eq!(
+        invalid(DiscoverQuery { vote_average_gte: Some(11.0), ..DiscoverQuery::default() }),
+        "vote_average.gte must be between 0 and 10"
+    );
+    assert!(invalid(DiscoverQuery { sort_by: Some("revenue.desc".to_string()), ..DiscoverQuery::default() }).starts_with("unsupported sort_by 'revenue.desc'"));
+}
+
+#[test]
+fn test_discover_filter_networks_and_companies() {
+    let filter = DiscoverFilter { networks: vec![49, 2739], companies: vec![420], ..DiscoverFilter::new(MediaType::Tv) };
+
+    assert_eq!(
+        filter.tmdb_params(),
+        [
+            ("sort_by", "popularity.desc".to_string()),
+            ("with_networks", "49|2739".to_string()),
+            ("with_companies", "420".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_discover_filter_newest_released_by() {
+    let released_by = NaiveDate::from_ymd_opt(2026, 10, 18);
+    let movies = DiscoverFilter { sort: DiscoverSort::Newest, released_by, ..DiscoverFilter::new(MediaType::Movie) };
+    let shows = DiscoverFilter { sort: DiscoverSort::Newest, released_by, ..DiscoverFilter::new(MediaType::Tv) };
+
+    assert_eq!(
+        movies.tmdb_params(),
+        [
+            ("sort_by", "primary_release_date.desc".to_string()),
+            ("primary_release_date.lte", "2026-10-18".to_string()),
+        ]
+    );
+    assert_eq!(
+        shows.tmdb_params(),
+        [("sort_by", "first_air_date.desc".to_string()), ("first_air_date.lte", "2026-10-18".to_string())]
+    );
+}
+
+#[test]
+fn test_tmdb_list_title_refs() {
+    let json = r#"{
+        "name": "Best of the 90s",
+        "description": "",
+        "items": [
+            { "id": 603, "media_type": "movie", "title": "The Matrix" },
+            { "id": 1399, "media_type": "tv", "name": "Game of Thrones" },
+            { "id": 550, "title": "Fight Club" },
+            { "id": 287, "media_type": "person" }
+        ]
+    }"#;
+
+    let list: TmdbList = serde_json::from_str(json).unwrap();
+    let refs: Vec<(MediaType, i64)> = list.title_refs().iter().map(|item| (item.media_type, item.id.get())).collect();
+    assert_eq!(refs, [(MediaType::Movie, 603), (MediaType::Tv, 1399), (MediaType::Movie, 550)]);
+}
@@ -0,0 +1,62 @@
+use axum::http::{Method, StatusCode};
+use netflix_service::mtls::{ClientCertPolicy, ClientIdentity, Role, RoleMap};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::CertificateDer;
+use std::collections::BTreeSet;
+
+fn identity(san: &str) -> ClientIdentity {
+    ClientIdentity { sans: vec![san.to_string()] }
+}
+
+#[test]
+fn test_role_map_matches_exact_sans_and_prefixes() {
+    let roles = RoleMap::parse("console.ops.svc=admin+metrics, spiffe://mesh/ns/monitoring/*=metrics").unwrap();
+
+    assert_eq!(roles.roles(&identity("Console.Ops.Svc")), BTreeSet::from([Role::Admin, Role::Metrics]));
+    assert_eq!(roles.roles(&identity("spiffe://mesh/ns/monitoring/sa/grafana")), BTreeSet::from([Role::Metrics]));
+    assert!(roles.roles(&identity("spiffe://mesh/ns/web/sa/frontend")).is_empty());
+    assert!(roles.roles(&identity("console.ops.svc.evil")).is_empty());
+}
+
+#[test]
+fn test_role_map_rejects_invalid_specs() {
+    assert!(RoleMap::parse("").is_err());
+    assert!(RoleMap::parse("console.ops.svc").is_err());
+    assert!(RoleMap::parse("*=admin").is_err());
+    assert!(RoleMap::parse("console.ops.svc=root").is_err());
+}
+
+#[test]
+fn test_metrics_role_is_read_only() {
+    assert!(Role::Metrics.allows(&Method::GET, "/admin/metrics/slow"));
+    assert!(Role::Metrics.allows(&Method::GET, "/admin/slo"));
+    assert!(!Role::Metrics.allows(&Method::GET, "/admin/mode"));
+    assert!(!Role::Metrics.allows(&Method::PUT, "/admin/metrics/slow"));
+    assert!(Role::Admin.allows(&Method::PUT, "/admin/mode"));
+}
+
+#[test]
+fn test_policy_authorization() {
+    let policy = ClientCertPolicy::new(RoleMap::parse("monitor.internal=metrics").unwrap());
+    let monitor = identity("monitor.internal");
+
+    assert_eq!(
+        policy.authorize(None, &Method::GET, "/admin/slo").unwrap_err().0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert!(policy.authorize(Some(&monitor), &Method::GET, "/admin/slo").is_ok());
+    assert_eq!(
+        policy.authorize(Some(&monitor), &Method::GET, "/admin/mode").unwrap_err().0,
+        StatusCode::FORBIDDEN
+    );
+    assert!(ClientCertPolicy::default().authorize(None, &Method::PUT, "/admin/mode").is_ok());
+}
+
+#[test]
+fn test_identity_reads_certificate_sans() {
+    let console = CertificateDer::from_pem_file("tests/fixtures/tls/console.pem").unwrap();
+    let server = CertificateDer::from_pem_file("tests/fixtures/tls/server.pem").unwrap();
+
+    assert_eq!(ClientIdentity::from_der(&console).unwrap(), identity("spiffe://mesh/ns/ops/sa/console"));
+    assert_eq!(ClientIdentity::from_der(&server).unwrap().sans, vec!["localhost", "127.0.0.1"]);
+}
@@ -0,0 +1,78 @@
+use netflix_service::clock::MockClock;
+use netflix_service::models::MediaType;
+use netflix_service::negative_cache::{Lookup, NegativeCache, NegativeCacheTtls};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn cache(ttls: NegativeCacheTtls) -> (NegativeCache, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000)));
+    (NegativeCache::new(ttls, clock.clone()), clock)
+}
+
+#[test]
+fn test_miss_is_remembered_until_ttl() {
+    let (cache, clock) = cache(NegativeCacheTtls::default());
+    let lookup = Lookup::Videos(404);
+
+    assert!(!cache.is_known_missing(&lookup));
+    cache.store(lookup);
+    assert!(cache.is_known_missing(&lookup));
+
+    clock.advance(Duration::from_secs(30));
+    assert!(!cache.is_known_missing(&lookup));
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.stored, stats.entries), (1, 1, 0));
+}
+
+#[test]
+fn test_lookups_are_keyed_by_media_type() {
+    let (cache, _) = cache(NegativeCacheTtls::default());
+    cache.store(Lookup::Details(MediaType::Movie, 1));
+
+    assert!(cache.is_known_missing(&Lookup::Details(MediaType::Movie, 1)));
+    assert!(!cache.is_known_missing(&Lookup::Details(MediaType::Tv, 1)));
+    assert!(!cache.is_known_missing(&Lookup::Videos(1)));
+}
+
+#[test]
+fn test_forgetting_a_show_drops_its_seasons() {
+    let (cache, _) = cache(NegativeCacheTtls::default());
+    cache.store(Lookup::Season(1399, 9));
+    cache.store(Lookup::Season(1396, 9));
+
+    assert!(!cache.forget_title(MediaType::Movie, 1399));
+    assert!(cache.forget_title(MediaType::Tv, 1399));
+    assert!(!cache.is_known_missing(&Lookup::Season(1399, 9)));
+    assert!(cache.is_known_missing(&Lookup::Season(1396, 9)));
+}
+
+#[test]
+fn test_disabled_kind_is_not_cached() {
+    let (cache, _) = cache(NegativeCacheTtls::parse("videos=0s").unwrap());
+    cache.store(Lookup::Videos(404));
+
+    assert!(!cache.is_known_missing(&Lookup::Videos(404)));
+    assert_eq!(cache.stats().stored, 0);
+}
+
+#[test]
+fn test_parse_ttls() {
+    assert_eq!(
+        NegativeCacheTtls::parse("videos=10s, details=5m").unwrap(),
+        NegativeCacheTtls {
+            videos: Some(Duration::from_secs(10)),
+            details: Some(Duration::from_secs(300)),
+        }
+    );
+    assert_eq!(
+        NegativeCacheTtls::parse("details=0s").unwrap(),
+        NegativeCacheTtls {
+            videos: Some(Duration::from_secs(30)),
+            details: None,
+        }
+    );
+    assert!(NegativeCacheTtls::parse("search=30s").is_err());
+    assert!(NegativeCacheTtls::parse("videos").is_err());
+    assert!(NegativeCacheTtls::parse("videos=soon").is_err());
+}
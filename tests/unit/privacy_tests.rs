@@ -0,0 +1,26 @@
+use axum::http::{HeaderMap, HeaderValue};
+use netflix_service::privacy::tracking_allowed;
+
+fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.insert(*name, HeaderValue::from_static(value));
+    }
+    headers
+}
+
+#[test]
+fn test_tracking_allowed_by_default() {
+    assert!(tracking_allowed(&headers(&[])));
+}
+
+#[test]
+fn test_do_not_track_opts_out() {
+    assert!(!tracking_allowed(&headers(&[("dnt", "1")])));
+    assert!(tracking_allowed(&headers(&[("dnt", "0")])));
+}
+
+#[test]
+fn test_global_privacy_control_opts_out() {
+    assert!(!tracking_allowed(&headers(&[("sec-gpc", "1")])));
+}
@@ -0,0 +1,52 @@
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use netflix_service::error::TmdbError;
+use netflix_service::problem::{accepts_problem_json, ApiError, RequestId};
+
+fn accept(value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+    headers
+}
+
+#[test]
+fn test_problem_json_must_be_accepted_explicitly() {
+    assert!(accepts_problem_json(&accept("application/problem+json")));
+    assert!(accepts_problem_json(&accept("application/json, application/problem+json;q=0.5")));
+    assert!(!accepts_problem_json(&accept("application/problem+json; q=0")));
+    assert!(!accepts_problem_json(&accept("*/*")));
+    assert!(!accepts_problem_json(&HeaderMap::new()));
+}
+
+#[test]
+fn test_json_bodies_name_code_and_detail() {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    let body = br#"{"error": "maintenance", "message": "Back soon", "retry_after": 300}"#;
+
+    let error = ApiError::from_response(StatusCode::SERVICE_UNAVAILABLE, &headers, body);
+
+    assert_eq!(error, ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "maintenance", "Back soon"));
+}
+
+#[test]
+fn test_text_bodies_are_the_detail_and_empty_bodies_have_none() {
+    let text = ApiError::from_response(StatusCode::GATEWAY_TIMEOUT, &HeaderMap::new(), b"Deadline exceeded");
+    assert_eq!(text.code, "timeout");
+    assert_eq!(text.detail.as_deref(), Some("Deadline exceeded"));
+
+    let empty = ApiError::from_response(StatusCode::NOT_FOUND, &HeaderMap::new(), b"");
+    let problem = serde_json::to_value(empty.to_problem("/nope", None)).unwrap();
+    assert_eq!(problem["code"], "not_found");
+    assert!(problem.get("detail").is_none());
+    assert!(problem.get("request_id").is_none());
+}
+
+#[test]
+fn test_tmdb_errors_keep_their_status_and_get_a_code() {
+    let error = ApiError::from(TmdbError::NetworkError("connection refused".to_string()));
+    let problem = error.to_problem("/api/trending", Some(&RequestId("abc".to_string())));
+
+    assert_eq!(problem.status, error.status.as_u16());
+    assert_eq!(problem.code, "upstream_unreachable");
+    assert_eq!(problem.request_id.as_deref(), Some("abc"));
+}
@@ -0,0 +1,20 @@
+use netflix_service::profiling::{allocation_stats, CountingAllocator};
+use std::alloc::{GlobalAlloc, Layout};
+
+#[test]
+fn test_counting_allocator_tracks_bytes() {
+    let before = allocation_stats();
+    let layout = Layout::from_size_align(4096, 8).unwrap();
+
+    unsafe {
+        let ptr = CountingAllocator.alloc(layout);
+        assert!(!ptr.is_null());
+        CountingAllocator.dealloc(ptr, layout);
+    }
+
+    let after = allocation_stats();
+    assert!(after.allocations > before.allocations);
+    assert!(after.deallocations > before.deallocations);
+    assert!(after.allocated_bytes >= before.allocated_bytes + 4096);
+    assert!(after.freed_bytes >= before.freed_bytes + 4096);
+}
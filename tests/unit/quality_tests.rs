@@ -0,0 +1,80 @@
+use netflix_service::models::{Movie, TmdbResponse};
+use netflix_service::quality::{rank, ListSort, QualityPriors};
+
+fn item(id: i64, vote_average: Option<f64>, vote_count: Option<u32>) -> Movie {
+    Movie {
+        id,
+        title: Some(format!("Title {}", id)),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average,
+        vote_count,
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids: Vec::new(),
+        release_date: None,
+        media_type: Some("movie".to_string()),
+        adult: false,
+    }
+}
+
+fn page(results: Vec<Movie>) -> TmdbResponse {
+    TmdbResponse { page: 1, results, total_pages: 1 }
+}
+
+#[test]
+fn test_few_votes_pull_towards_the_prior() {
+    let priors = QualityPriors { mean: 6.0, votes: 100 };
+
+    assert_eq!(priors.score(Some(9.0), Some(10)), Some(6.27));
+    assert_eq!(priors.score(Some(8.4), Some(10_000)), Some(8.38));
+    assert_eq!(priors.score(Some(9.0), None), Some(6.0));
+    assert_eq!(priors.score(None, Some(500)), None);
+}
+
+#[test]
+fn test_zero_prior_votes_use_raw_average() {
+    let priors = QualityPriors { mean: 6.0, votes: 0 };
+
+    assert_eq!(priors.score(Some(7.25), Some(3)), Some(7.25));
+    assert_eq!(priors.score(Some(7.25), Some(0)), None);
+}
+
+#[test]
+fn test_parse_priors() {
+    assert_eq!(QualityPriors::parse("mean=7, votes=50").unwrap(), QualityPriors { mean: 7.0, votes: 50 });
+    assert_eq!(QualityPriors::parse("votes=10").unwrap().mean, QualityPriors::default().mean);
+    assert!(QualityPriors::parse("mean=11").is_err());
+    assert!(QualityPriors::parse("votes=-1").is_err());
+    assert!(QualityPriors::parse("weight=1").is_err());
+    assert!(QualityPriors::parse("mean").is_err());
+}
+
+#[test]
+fn test_rank_scores_and_keeps_order_by_default() {
+    let mut response = page(vec![item(1, Some(9.5), Some(3)), item(2, Some(8.0), Some(5_000))]);
+
+    rank(&mut response, &QualityPriors::default(), ListSort::Popularity);
+
+    assert_eq!(response.results[0].id, 1);
+    assert!(response.results.iter().all(|item| item.quality_score.is_some()));
+}
+
+#[test]
+fn test_rank_sorts_by_quality() {
+    let mut response = page(vec![
+        item(1, Some(9.5), Some(3)),
+        item(2, None, None),
+        item(3, Some(8.0), Some(5_000)),
+        item(4, Some(7.0), Some(20_000)),
+    ]);
+
+    rank(&mut response, &QualityPriors::default(), ListSort::Quality);
+
+    let ids: Vec<i64> = response.results.iter().map(|item| item.id).collect();
+    assert_eq!(ids, [3, 4, 1, 2]);
+}
@@ -0,0 +1,104 @@
+use chrono::NaiveDate;
+use netflix_service::experiments::{Assignment, Assignments};
+use netflix_service::models::{Genre, Movie};
+use netflix_service::ranking::{RankingChain, RankingContext, RankingStrategies};
+use netflix_service::taste::GenreCounts;
+
+fn item(id: i64, genre_ids: Vec<i64>, release_date: Option<&str>) -> Movie {
+    Movie {
+        id,
+        title: Some(format!("Title {}", id)),
+        name: None,
+        overview: None,
+        poster_path: None,
+        backdrop_path: None,
+        vote_average: None,
+        vote_count: None,
+        quality_score: None,
+        has_trailer: None,
+        providers: None,
+        cast: None,
+        genre_ids,
+        release_date: release_date.and_then(|date| date.parse().ok()),
+        media_type: Some("movie".to_string()),
+        adult: false,
+    }
+}
+
+fn context() -> RankingContext {
+    RankingContext { today: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(), taste: None }
+}
+
+fn ranked(chain: &str, mut items: Vec<Movie>, context: &RankingContext) -> Vec<i64> {
+    RankingChain::parse(chain).unwrap().apply(&mut items, context);
+    items.iter().map(|item| item.id).collect()
+}
+
+#[test]
+fn test_parse_chain() {
+    let chain = RankingChain::parse("popularity=1, recency=0.5,diversity=0.3").unwrap();
+    assert_eq!(chain.names(), ["popularity", "recency", "diversity"]);
+    assert!(RankingChain::parse("").unwrap().is_empty());
+
+    assert!(RankingChain::parse("trending=1").is_err());
+    assert!(RankingChain::parse("recency").is_err());
+    assert!(RankingChain::parse("recency=fast").is_err());
+}
+
+#[test]
+fn test_empty_chain_and_popularity_keep_order() {
+    let items = || vec![item(1, vec![28], None), item(2, vec![18], None), item(3, vec![35], None)];
+
+    assert_eq!(ranked("", items(), &context()), [1, 2, 3]);
+    assert_eq!(ranked("popularity=1", items(), &context()), [1, 2, 3]);
+}
+
+#[test]
+fn test_recency_prefers_newer_titles() {
+    let items = vec![
+        item(1, vec![], Some("2001-05-01")),
+        item(2, vec![], None),
+        item(3, vec![], Some("2025-05-01")),
+        item(4, vec![], Some("2026-01-01")),
+    ];
+
+    assert_eq!(ranked("recency=1", items, &context()), [4, 3, 1, 2]);
+}
+
+#[test]
+fn test_personalization_moves_favourite_genres_first() {
+    let mut counts = GenreCounts::default();
+    counts.record(&[Genre { id: 18, name: "Drama".to_string() }]);
+    counts.record(&[Genre { id: 18, name: "Drama".to_string() }, Genre { id: 35, name: "Comedy".to_string() }]);
+    let with_taste = RankingContext { taste: Some(counts.profile()), ..context() };
+    let items = || vec![item(1, vec![28], None), item(2, vec![35], None), item(3, vec![18], None), item(4, vec![12], None)];
+
+    assert_eq!(ranked("personalization=1", items(), &with_taste), [3, 2, 1, 4]);
+    // Without a taste profile nothing moves
+    assert_eq!(ranked("personalization=1", items(), &context()), [1, 2, 3, 4]);
+}
+
+#[test]
+fn test_diversity_spreads_genres() {
+    let items = vec![
+        item(1, vec![28], None),
+        item(2, vec![28, 12], None),
+        item(3, vec![28], None),
+        item(4, vec![18], None),
+    ];
+
+    assert_eq!(ranked("popularity=1,diversity=1", items, &context()), [1, 4, 2, 3]);
+}
+
+#[test]
+fn test_strategies_by_experiment_variant() {
+    let strategies = RankingStrategies::from_json(r#"{"default": "popularity=1", "fresh": "recency=1,diversity=0.5"}"#).unwrap();
+    let assigned = |variant: &str| {
+        Assignments(vec![Assignment { experiment: "ranking".to_string(), variant: variant.to_string() }])
+    };
+
+    assert_eq!(strategies.for_assignments(None).names(), ["popularity"]);
+    assert_eq!(strategies.for_assignments(Some(&assigned("fresh"))).names(), ["recency", "diversity"]);
+    assert_eq!(strategies.for_assignments(Some(&assigned("unknown"))).names(), ["popularity"]);
+    assert!(RankingStrategies::from_json(r#"{"fresh": "recency=soon"}"#).is_err());
+}
@@ -0,0 +1,42 @@
+use netflix_service::models::Movie;
+use netflix_service::safe_search::{AdultContentPolicy, SafeSearch};
+use netflix_service::shaping::{Device, DevicePresets};
+
+fn movie(id: i64, adult: bool) -> Movie {
+    serde_json::from_value(serde_json::json!({ "id": id, "title": "Film", "adult": adult })).unwrap()
+}
+
+#[test]
+fn test_policy_parses_config_names() {
+    assert_eq!("always_off".parse(), Ok(AdultContentPolicy::AlwaysOff));
+    assert_eq!(" profile_based ".parse(), Ok(AdultContentPolicy::ProfileBased));
+    assert!("sometimes".parse::<AdultContentPolicy>().is_err());
+}
+
+#[test]
+fn test_include_adult_follows_policy() {
+    let kids = Device(DevicePresets::default().get("kids").cloned());
+    let web = Device(None);
+
+    let always_off = SafeSearch::default();
+    assert_eq!(always_off.include_adult(None, &web).ok(), Some(false));
+    assert_eq!(always_off.include_adult(Some(false), &web).ok(), Some(false));
+    assert_eq!(always_off.include_adult(Some(true), &web).unwrap_err().status.as_u16(), 403);
+
+    let profile_based = SafeSearch::new(AdultContentPolicy::ProfileBased);
+    assert_eq!(profile_based.include_adult(Some(true), &web).ok(), Some(true));
+    assert!(profile_based.include_adult(Some(true), &kids).is_err());
+    assert_eq!(SafeSearch::new(AdultContentPolicy::UserChoice).include_adult(Some(true), &kids).ok(), Some(true));
+}
+
+#[test]
+fn test_retain_counts_filtered_titles() {
+    let safe_search = SafeSearch::default();
+    let mut items = vec![movie(1, false), movie(2, true), movie(3, true)];
+
+    safe_search.retain(&mut items, true, "search");
+    assert_eq!(items.len(), 3);
+    safe_search.retain(&mut items, false, "search");
+    assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), [1]);
+    assert_eq!(safe_search.stats().filtered, 2);
+}
@@ -0,0 +1,76 @@
+use axum::http::StatusCode;
+use netflix_service::dev_client::DevTmdbClient;
+use netflix_service::models::BatchTitlesRequest;
+use netflix_service::privacy::Tracker;
+use netflix_service::services::{CatalogService, SearchService, UserService};
+use netflix_service::session::SessionId;
+use netflix_service::state::AppState;
+use serde_json::json;
+use std::sync::Arc;
+
+fn state() -> AppState {
+    AppState::new(Arc::new(DevTmdbClient::new()))
+}
+
+#[tokio::test]
+async fn test_search_validates_and_records_the_session() {
+    let state = state().with_min_search_query_chars(3);
+    let (session, tracker) = (SessionId("s1".to_string()), Tracker::new(&state, true));
+
+    let empty = state.search().search("  ", 1, false, Some(&session), &tracker).await.unwrap_err();
+    assert_eq!((empty.status, empty.code.as_str()), (StatusCode::BAD_REQUEST, "bad_request"));
+
+    let short = state.search().search("in", 1, false, Some(&session), &tracker).await.unwrap();
+    assert!(short.results.is_empty());
+    assert!(state.users().recent(&session).searches.is_empty());
+
+    let found = state.search().search(" interstellar ", 1, false, Some(&session), &tracker).await.unwrap();
+    assert!(found.results.iter().any(|item| item.id == 157336));
+    assert_eq!(state.users().recent(&session).searches, ["interstellar"]);
+}
+
+#[tokio::test]
+async fn test_catalog_errors_carry_codes() {
+    let catalog = state().catalog();
+
+    let missing = catalog.curated_list("nope").await.unwrap_err();
+    assert_eq!((missing.status, missing.code.as_str()), (StatusCode::NOT_FOUND, "not_found"));
+
+    let unknown = catalog.videos(999_999_999).await.unwrap_err();
+    assert_eq!(unknown.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_batch_keeps_request_order_and_fails_items_alone() {
+    let request: BatchTitlesRequest = serde_json::from_value(json!({
+        "items": [
+            { "media_type": "movie", "id": 999_999_999 },
+            { "media_type": "movie", "id": 27205 }
+        ]
+    }))
+    .unwrap();
+
+    let results = state().catalog().batch(request).await;
+
+    assert_eq!(results.iter().map(|result| result.id).collect::<Vec<_>>(), [999_999_999, 27205]);
+    assert_eq!(results[0].error.as_ref().map(|error| error.status), Some(404));
+    assert!(results[1].details.is_some() && results[1].error.is_none());
+}
+
+#[test]
+fn test_users_map_store_failures_to_errors() {
+    let users = state().users();
+    let session = SessionId("s1".to_string());
+
+    let invalid = serde_json::from_value(json!({ "source": "search", "query": " " })).unwrap();
+    assert_eq!(users.save_search(&session, invalid).unwrap_err().code, "bad_request");
+    let missing = users.delete_saved_search(&session, 42).unwrap_err();
+    assert_eq!((missing.status, missing.detail.as_deref()), (StatusCode::NOT_FOUND, Some("Saved search not found")));
+    let unknown = users.unregister_device(&session, "unknown-token").unwrap_err();
+    assert_eq!((unknown.status, unknown.detail.as_deref()), (StatusCode::NOT_FOUND, Some("Device not registered")));
+
+    let input = serde_json::from_value(json!({ "source": "search", "query": "batman" })).unwrap();
+    let saved = users.save_search(&session, input).unwrap();
+    assert_eq!(users.saved_searches(&session).len(), 1);
+    assert!(users.delete_saved_search(&session, saved.id).is_ok());
+}
@@ -0,0 +1,113 @@
+use netflix_service::models::MediaType;
+use netflix_service::session::{RecentPick, SessionStore};
+use std::time::{Duration, UNIX_EPOCH};
+
+const TTL: Duration = Duration::from_secs(60);
+
+fn pick(id: i64) -> RecentPick {
+    RecentPick { media_type: MediaType::Movie, id, title: None }
+}
+
+#[test]
+fn test_issued_token_verifies() {
+    let store = SessionStore::new("secret", TTL);
+
+    let (session, token) = store.issue();
+
+    assert_eq!(store.verify(&token), Some(session));
+}
+
+#[test]
+fn test_tampered_token_is_rejected() {
+    let store = SessionStore::new("secret", TTL);
+    let (_, token) = store.issue();
+    let (_, signature) = token.split_once('.').unwrap();
+
+    assert_eq!(store.verify(&format!("forged.{}", signature)), None);
+    assert_eq!(store.verify("no-signature"), None);
+    assert_eq!(store.verify("abc.zz"), None);
+}
+
+#[test]
+fn test_token_from_other_secret_is_rejected() {
+    let (_, token) = SessionStore::new("secret-a", TTL).issue();
+
+    assert_eq!(SessionStore::new("secret-b", TTL).verify(&token), None);
+}
+
+#[test]
+fn test_recent_searches_are_deduplicated_most_recent_first() {
+    let store = SessionStore::new("secret", TTL);
+    let (session, _) = store.issue();
+    let now = UNIX_EPOCH + Duration::from_secs(1000);
+
+    store.record_search(&session, "matrix", now);
+    store.record_search(&session, "alien", now);
+    store.record_search(&session, " matrix ", now);
+    store.record_search(&session, "   ", now);
+
+    assert_eq!(store.recent(&session, now).searches, vec!["matrix", "alien"]);
+}
+
+#[test]
+fn test_recent_picks() {
+    let store = SessionStore::new("secret", TTL);
+    let (session, _) = store.issue();
+    let now = UNIX_EPOCH + Duration::from_secs(1000);
+
+    store.record_pick(&session, pick(1), now);
+    store.record_pick(&session, pick(2), now);
+    store.record_pick(&session, RecentPick { title: Some("Renamed".to_string()), ..pick(1) }, now);
+
+    let picks = store.recent(&session, now).picks;
+    assert_eq!(picks.len(), 2);
+    assert_eq!(picks[0].id, 1);
+    assert_eq!(picks[0].title, Some("Renamed".to_string()));
+}
+
+#[test]
+fn test_idle_sessions_expire() {
+    let store = SessionStore::new("secret", TTL);
+    let (session, _) = store.issue();
+    let start = UNIX_EPOCH + Duration::from_secs(1000);
+
+    store.record_search(&session, "matrix", start);
+
+    assert_eq!(store.recent(&session, start + TTL).searches.len(), 1);
+    let later = start + TTL + Duration::from_secs(1);
+    assert!(store.recent(&session, later).searches.is_empty());
+
+    // Writing to an expired session starts it afresh
+    store.record_search(&session, "alien", later);
+    assert_eq!(store.recent(&session, later).searches, vec!["alien"]);
+}
+
+#[test]
+fn test_full_store_drops_longest_idle_sessions() {
+    let store = SessionStore::new("secret", TTL).with_capacity(10);
+    let start = UNIX_EPOCH + Duration::from_secs(1000);
+    let sessions: Vec<_> = (0..11).map(|_| store.issue().0).collect();
+
+    for (i, session) in sessions.iter().enumerate() {
+        store.record_search(session, "matrix", start + Duration::from_secs(i as u64));
+    }
+
+    let now = start + Duration::from_secs(10);
+    assert!(store.recent(&sessions[0], now).searches.is_empty());
+    assert!(sessions[1..].iter().all(|session| store.recent(session, now).searches.len() == 1));
+}
+
+#[test]
+fn test_sessions_are_isolated_and_clearable() {
+    let store = SessionStore::new("secret", TTL);
+    let (first, _) = store.issue();
+    let (second, _) = store.issue();
+    let now = UNIX_EPOCH + Duration::from_secs(1000);
+
+    store.record_search(&first, "matrix", now);
+    store.record_search(&second, "alien", now);
+    store.clear(&first);
+
+    assert!(store.recent(&first, now).searches.is_empty());
+    assert_eq!(store.recent(&second, now).searches, vec!["alien"]);
+}
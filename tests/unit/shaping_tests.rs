@@ -0,0 +1,94 @@
+use netflix_service::models::{Movie, TmdbResponse};
+use netflix_service::shaping::{DevicePreset, DevicePresets, OverviewAction, OverviewFilter};
+
+fn response() -> TmdbResponse {
+    TmdbResponse {
+        page: 1,
+        total_pages: 1,
+        results: vec![Movie {
+            id: 603,
+            title: Some("The Matrix".to_string()),
+            name: None,
+            overview: Some("A hacker learns the truth".to_string()),
+            poster_path: Some("/matrix.jpg".to_string()),
+            backdrop_path: Some("/matrix_backdrop.jpg".to_string()),
+            vote_average: Some(8.2),
+            vote_count: Some(1200),
+            quality_score: None,
+            has_trailer: None,
+            providers: None,
+            cast: None,
+            genre_ids: Vec::new(),
+            release_date: "1999-03-30".parse().ok(),
+            media_type: Some("movie".to_string()),
+            adult: false,
+        }],
+    }
+}
+
+#[test]
+fn test_tv_preset_trims_items() {
+    let presets = DevicePresets::default();
+    let shaped = presets.get("tv").unwrap().shape(&response());
+
+    let item = &shaped["results"][0];
+    assert!(item.get("overview").is_none());
+    assert!(item.get("backdrop_path").is_none());
+    assert_eq!(item["poster_url"], "https://image.tmdb.org/t/p/w185/matrix.jpg");
+    assert_eq!(item["title"], "The Matrix");
+    assert_eq!(item["year"], 1999);
+    assert_eq!(shaped["page"], 1);
+}
+
+#[test]
+fn test_missing_poster_gives_null_url() {
+    let mut response = response();
+    response.results[0].poster_path = None;
+
+    let shaped = DevicePresets::default().get("web").unwrap().shape(&response);
+    assert!(shaped["results"][0]["poster_url"].is_null());
+    assert_eq!(shaped["results"][0]["overview"], "A hacker learns the truth");
+}
+
+#[test]
+fn test_presets_from_json_override_defaults() {
+    let presets = DevicePresets::from_json(r#"{"tv": {"omit": ["overview", "vote_average"]}, "watch": {"omit": ["overview"], "poster_size": "w92"}}"#).unwrap();
+
+    assert_eq!(
+        presets.get("tv"),
+        Some(&DevicePreset {
+            omit: vec!["overview".to_string(), "vote_average".to_string()],
+            poster_size: None,
+            drop_adult: false,
+            overview_filter: None,
+        })
+    );
+    assert_eq!(presets.get("watch").unwrap().poster_size.as_deref(), Some("w92"));
+    assert!(presets.get("mobile").is_some());
+    assert!(DevicePresets::from_json("[]").is_err());
+}
+
+#[test]
+fn test_kids_preset_drops_adult_titles_and_masks_overviews() {
+    let mut response = response();
+    response.results[0].overview = Some("What the hell, said the shell. Hello!".to_string());
+    let mut adult = response.results[0].clone();
+    adult.id = 604;
+    adult.adult = true;
+    response.results.push(adult);
+
+    let shaped = DevicePresets::default().get("kids").unwrap().shape(&response);
+
+    let items = shaped["results"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], 603);
+    assert_eq!(items[0]["overview"], "What the ****, said the shell. Hello!");
+}
+
+#[test]
+fn test_overview_filter_can_drop_overviews() {
+    let filter = OverviewFilter { words: vec!["Truth".to_string()], action: OverviewAction::Drop };
+
+    assert_eq!(filter.apply("A hacker learns the TRUTH"), None);
+    assert_eq!(filter.apply("Truthful hackers").as_deref(), Some("Truthful hackers"));
+}
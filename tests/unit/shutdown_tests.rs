@@ -0,0 +1,48 @@
+use netflix_service::shutdown::{stopped, with_grace};
+use std::future::pending;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::{sleep, Instant};
+
+#[tokio::test(start_paused = true)]
+async fn test_server_that_finishes_first_is_not_cut_short() {
+    let result = with_grace(async { "done" }, pending(), Duration::from_secs(1)).await;
+
+    assert_eq!(result.unwrap(), "done");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_server_gets_the_grace_period_to_drain() {
+    let started = Instant::now();
+    let server = async {
+        sleep(Duration::from_secs(7)).await;
+        "drained"
+    };
+
+    let result = with_grace(server, sleep(Duration::from_secs(5)), Duration::from_secs(3)).await;
+
+    assert_eq!(result.unwrap(), "drained");
+    assert_eq!(started.elapsed(), Duration::from_secs(7));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_server_is_dropped_when_grace_runs_out() {
+    let started = Instant::now();
+
+    let result = with_grace(pending::<()>(), sleep(Duration::from_secs(5)), Duration::from_secs(3)).await;
+
+    assert!(result.is_err());
+    assert_eq!(started.elapsed(), Duration::from_secs(8));
+}
+
+#[tokio::test]
+async fn test_stopped_wakes_every_waiter() {
+    let (stop, stopping) = watch::channel(false);
+    let first = tokio::spawn(stopped(stopping.clone()));
+    let second = tokio::spawn(stopped(stopping));
+
+    stop.send(true).unwrap();
+
+    first.await.unwrap();
+    second.await.unwrap();
+}
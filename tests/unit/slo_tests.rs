@@ -0,0 +1,102 @@
+use netflix_service::slo::{Slo, SloTracker, LONG_WINDOW};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn search_slo() -> Slo {
+    Slo {
+        route: "/api/search".to_string(),
+        availability: 0.99,
+        latency_ms: 500,
+        latency_target: 0.9,
+    }
+}
+
+#[test]
+fn test_no_traffic_is_compliant() {
+    let tracker = SloTracker::new(vec![search_slo()]);
+
+    let status = tracker.status(at(10_000));
+
+    assert_eq!(status.len(), 1);
+    assert_eq!(status[0].short_window.requests, 0);
+    assert_eq!(status[0].short_window.availability, 1.0);
+    assert_eq!(status[0].long_window.availability_burn_rate, 0.0);
+    assert!(!status[0].alerting);
+}
+
+#[test]
+fn test_burn_rate_from_errors_and_latency() {
+    let tracker = SloTracker::new(vec![search_slo()]);
+    let now = at(10_000);
+    for i in 0..8 {
+        tracker.record("/api/search", 200, Duration::from_millis(100), now - Duration::from_secs(i));
+    }
+    tracker.record("/api/search", 502, Duration::from_millis(100), now);
+    tracker.record("/api/search", 200, Duration::from_millis(900), now);
+
+    let status = &tracker.status(now)[0];
+
+    assert_eq!(status.short_window.requests, 10);
+    assert!((status.short_window.availability - 0.9).abs() < 1e-9);
+    assert!((status.short_window.availability_burn_rate - 10.0).abs() < 1e-6);
+    assert!((status.short_window.within_latency - 0.9).abs() < 1e-9);
+    assert!((status.short_window.latency_burn_rate - 1.0).abs() < 1e-6);
+    assert!(!status.alerting);
+}
+
+#[test]
+fn test_alerts_when_both_windows_burn_fast() {
+    let tracker = SloTracker::new(vec![search_slo()]);
+    let now = at(10_000);
+    tracker.record("/api/search", 500, Duration::from_millis(10), now);
+    tracker.record("/api/search", 200, Duration::from_millis(10), now);
+
+    assert!(tracker.status(now)[0].alerting);
+}
+
+#[test]
+fn test_short_window_excludes_older_requests() {
+    let tracker = SloTracker::new(vec![search_slo()]);
+    let now = at(10_000);
+    tracker.record("/api/search", 500, Duration::from_millis(10), now - Duration::from_secs(30 * 60));
+    tracker.record("/api/search", 200, Duration::from_millis(10), now);
+
+    let status = &tracker.status(now)[0];
+
+    assert_eq!(status.short_window.requests, 1);
+    assert_eq!(status.long_window.requests, 2);
+    assert!(!status.alerting);
+}
+
+#[test]
+fn test_samples_expire_after_long_window() {
+    let tracker = SloTracker::new(vec![search_slo()]);
+    let now = at(100_000);
+    tracker.record("/api/search", 500, Duration::from_millis(10), now - LONG_WINDOW - Duration::from_secs(1));
+
+    assert_eq!(tracker.status(now)[0].long_window.requests, 0);
+}
+
+#[test]
+fn test_routes_without_objective_are_ignored() {
+    let tracker = SloTracker::new(vec![search_slo()]);
+    tracker.record("/api/trending", 500, Duration::from_millis(10), at(10_000));
+
+    assert_eq!(tracker.status(at(10_000))[0].long_window.requests, 0);
+}
+
+#[test]
+fn test_from_json() {
+    let tracker = SloTracker::from_json(
+        r#"[{"route": "/api/search", "availability": 0.999, "latency_ms": 800, "latency_target": 0.99}]"#,
+    )
+    .unwrap();
+
+    let status = tracker.status(at(0));
+    assert_eq!(status[0].slo.route, "/api/search");
+    assert_eq!(status[0].slo.latency_ms, 800);
+    assert!(SloTracker::from_json("{}").is_err());
+}
@@ -0,0 +1,65 @@
+use netflix_service::app::AppOptions;
+use netflix_service::clock::SystemClock;
+use netflix_service::dev_client::DevTmdbClient;
+use netflix_service::mirrors::Mirrors;
+use netflix_service::redact::REDACTED;
+use netflix_service::startup::StartupSummary;
+use netflix_service::state::AppState;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+    move |key| vars.get(key).cloned()
+}
+
+#[test]
+fn test_summary_reports_effective_defaults() {
+    let state = AppState::new(Arc::new(DevTmdbClient::new()));
+
+    let summary = StartupSummary::new(&state, &AppOptions::default(), env(&[]))
+        .with_bind("0.0.0.0:8080".parse().unwrap())
+        .with_shutdown_grace(Duration::from_secs(10));
+
+    assert_eq!(summary.bind.as_deref(), Some("0.0.0.0:8080"));
+    assert_eq!(summary.upstream, "tmdb");
+    assert_eq!(summary.tmdb_mirrors, ["https://api.themoviedb.org/3"]);
+    assert_eq!(summary.tmdb_timeout_ms, 10_000);
+    assert_eq!(summary.cache_ttls.trending, 300);
+    assert_eq!(summary.request_budget_capacity, 60);
+    assert_eq!(summary.session_secret, "random");
+    assert!(!summary.admin_routes);
+    assert_eq!(summary.shutdown_grace, Some(10));
+    assert_eq!(summary.build.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_summary_never_contains_secrets() {
+    let mirrors = Mirrors::parse("https://mirror.internal/3?api_key=mirror-key", Arc::new(SystemClock));
+    let state = AppState::new(Arc::new(DevTmdbClient::new()))
+        .with_mirrors(Arc::new(mirrors))
+        .with_admin_token("admin-token");
+    let lookup = env(&[("TMDB_API_KEY", "tmdb-key"), ("ADMIN_TOKEN", "admin-token"), ("SESSION_SECRET", "session-secret")]);
+
+    let summary = StartupSummary::new(&state, &AppOptions::default(), lookup).with_dev_seed();
+    let logged = serde_json::to_string(&summary).unwrap();
+
+    for secret in ["tmdb-key", "admin-token", "session-secret", "mirror-key"] {
+        assert!(!logged.contains(secret), "{} leaked into {}", secret, logged);
+    }
+    assert_eq!(summary.secrets["TMDB_API_KEY"], REDACTED);
+    assert_eq!(summary.session_secret, "configured");
+    assert!(summary.admin_routes);
+    assert_eq!(summary.upstream, "dev-seed");
+}
+
+#[test]
+fn test_unset_secrets_are_marked() {
+    let state = AppState::new(Arc::new(DevTmdbClient::new()));
+
+    let summary = StartupSummary::new(&state, &AppOptions::default(), env(&[("ADMIN_TOKEN", "")]));
+
+    assert_eq!(summary.secrets["ADMIN_TOKEN"], "unset");
+    assert_eq!(summary.secrets["SESSION_SECRET"], "unset");
+}
@@ -0,0 +1,28 @@
+use netflix_service::sync::{Since, Stamp, SyncToken};
+
+#[test]
+fn test_sync_token_round_trips() {
+    let token = SyncToken { epoch: 0xdead_beef, lists: 12, searches: 40 };
+
+    assert_eq!(token.to_string(), "deadbeef.12.40");
+    assert_eq!("deadbeef.12.40".parse::<SyncToken>(), Ok(token));
+}
+
+#[test]
+fn test_malformed_sync_tokens_are_rejected() {
+    for token in ["", "2024-01-01T00:00:00Z", "1.2", "1.2.3.4", "x.1.2", "1.-1.2"] {
+        assert!(token.parse::<SyncToken>().is_err(), "{}", token);
+    }
+}
+
+#[test]
+fn test_stamps_compare_by_revision_or_time() {
+    let stamp = Stamp { revision: 5, at: 1_700_000_000 };
+
+    assert!(stamp.after(Since::Revision(4)));
+    assert!(!stamp.after(Since::Revision(5)));
+    assert!(stamp.after(Since::Time(1_700_000_000)));
+    assert!(!stamp.after(Since::Time(1_700_000_001)));
+    assert!(Since::Revision(6).is_ahead_of(5));
+    assert!(!Since::Time(u64::MAX).is_ahead_of(5));
+}
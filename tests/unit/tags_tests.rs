@@ -0,0 +1,44 @@
+use netflix_service::models::Keyword;
+use netflix_service::tags::TagRules;
+
+fn keywords(names: &[&str]) -> Vec<Keyword> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(id, name)| Keyword { id: id as i64, name: name.to_string() })
+        .collect()
+}
+
+#[test]
+fn test_default_tags_and_warnings() {
+    let rules = TagRules::default();
+    let keywords = keywords(&["Based on Novel or Book", "post-apocalyptic future", "suicide", "cannibal"]);
+
+    assert_eq!(rules.tags(&keywords), ["based on a book", "post-apocalyptic"]);
+    assert_eq!(rules.content_warnings(&keywords), ["suicide"]);
+}
+
+#[test]
+fn test_labels_are_deduplicated() {
+    let rules = TagRules::default();
+
+    let warnings = rules.content_warnings(&keywords(&["rape", "sexual abuse", "gore"]));
+    assert_eq!(warnings, ["sexual violence", "gore"]);
+}
+
+#[test]
+fn test_configured_warnings_replace_defaults() {
+    let rules = TagRules::parse_content_warnings("Gore=graphic violence, drug addiction = drugs").unwrap();
+    let keywords = keywords(&["suicide", "gore", "drug addiction", "based on comic"]);
+
+    assert_eq!(rules.content_warnings(&keywords), ["graphic violence", "drugs"]);
+    assert_eq!(rules.tags(&keywords), ["based on a comic"]);
+    assert!(TagRules::parse_content_warnings("").unwrap().content_warnings(&keywords).is_empty());
+}
+
+#[test]
+fn test_malformed_warnings_rejected() {
+    assert!(TagRules::parse_content_warnings("gore").is_err());
+    assert!(TagRules::parse_content_warnings("gore=").is_err());
+    assert!(TagRules::parse_content_warnings("=gore").is_err());
+}
@@ -0,0 +1,21 @@
+use netflix_service::models::Genre;
+use netflix_service::taste::GenreCounts;
+
+fn genre(id: i64, name: &str) -> Genre {
+    Genre { id, name: name.to_string() }
+}
+
+#[test]
+fn test_profile_weights_sum_to_one() {
+    let mut counts = GenreCounts::default();
+    counts.record(&[genre(18, "Drama"), genre(80, "Crime")]);
+    counts.record(&[genre(18, "Drama")]);
+    counts.record(&[genre(35, "Comedy")]);
+
+    let profile = counts.profile();
+    let genres: Vec<(i64, f64)> = profile.genres.iter().map(|genre| (genre.id, genre.weight)).collect();
+    assert_eq!(profile.views, 3);
+    assert_eq!(genres, [(18, 0.5), (35, 0.25), (80, 0.25)]);
+    assert_eq!(profile.affinity(&[18, 80]), 0.75);
+    assert_eq!(profile.affinity(&[99]), 0.0);
+}
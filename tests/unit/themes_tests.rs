@@ -0,0 +1,133 @@
+use chrono::NaiveDate;
+use netflix_service::models::{DiscoverSort, MediaType};
+use netflix_service::themes::{ThemeInput, ThemeRules, ThemedRows};
+use serde_json::json;
+use std::time::UNIX_EPOCH;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+}
+
+fn rules(media_type: MediaType) -> ThemeRules {
+    ThemeRules {
+        media_type,
+        keywords: Vec::new(),
+        genres: Vec::new(),
+        companies: Vec::new(),
+        released_from: None,
+        released_until: None,
+        sort: DiscoverSort::Popularity,
+    }
+}
+
+fn input(title: &str, rules: ThemeRules) -> ThemeInput {
+    ThemeInput { title: title.to_string(), rules, live_from: None, live_until: None }
+}
+
+#[test]
+fn test_rules_compile_into_discover_params() {
+    let halloween = ThemeRules { keywords: vec![3335, 9663], genres: vec![27], ..rules(MediaType::Movie) };
+    assert_eq!(
+        halloween.filter().tmdb_params(),
+        [
+            ("sort_by", "popularity.desc".to_string()),
+            ("with_genres", "27".to_string()),
+            ("with_keywords", "3335|9663".to_string()),
+        ]
+    );
+
+    let nineties = ThemeRules {
+        companies: vec![420],
+        released_from: Some(NaiveDate::from_ymd_opt(1990, 1, 1).unwrap()),
+        released_until: Some(NaiveDate::from_ymd_opt(1999, 12, 31).unwrap()),
+        sort: DiscoverSort::Newest,
+        ..rules(MediaType::Tv)
+    };
+    assert_eq!(
+        nineties.filter().tmdb_params(),
+        [
+            ("sort_by", "first_air_date.desc".to_string()),
+            ("first_air_date.gte", "1990-01-01".to_string()),
+            ("first_air_date.lte", "1999-12-31".to_string()),
+            ("with_companies", "420".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_rules_must_narrow_the_catalog() {
+    let err = |rules: ThemeRules| rules.validate().unwrap_err();
+
+    assert_eq!(err(rules(MediaType::Movie)), "rules need at least one keyword, genre, company or release date");
+    assert_eq!(err(ThemeRules { genres: vec![0], ..rules(MediaType::Movie) }), "invalid genres id 0");
+    assert_eq!(err(ThemeRules { keywords: (1..=21).collect(), ..rules(MediaType::Movie) }), "keywords may list at most 20 ids");
+    assert_eq!(
+        err(ThemeRules { released_from: Some(date(12, 1)), released_until: Some(date(1, 1)), ..rules(MediaType::Movie) }),
+        "released_from must not be after released_until"
+    );
+    assert!(ThemeRules { released_until: Some(date(1, 1)), ..rules(MediaType::Movie) }.validate().is_ok());
+}
+
+#[test]
+fn test_rows_are_live_between_their_dates() {
+    let themes = ThemedRows::new();
+    let halloween = ThemeInput {
+        live_from: Some(date(10, 1)),
+        live_until: Some(date(10, 31)),
+        ..input("Halloween picks", ThemeRules { keywords: vec![3335], ..rules(MediaType::Movie) })
+    };
+    themes.put("halloween", halloween, UNIX_EPOCH).unwrap();
+    themes.put("awards", input("Award winners", ThemeRules { keywords: vec![210024], ..rules(MediaType::Movie) }), UNIX_EPOCH).unwrap();
+
+    let live = |today| themes.live(today).into_iter().map(|row| row.slug).collect::<Vec<_>>();
+    assert_eq!(live(date(9, 30)), ["awards"]);
+    assert_eq!(live(date(10, 1)), ["awards", "halloween"]);
+    assert_eq!(live(date(10, 31)), ["awards", "halloween"]);
+    assert_eq!(live(date(11, 1)), ["awards"]);
+    assert_eq!(themes.live_filter("halloween", date(10, 18)).unwrap().keywords, [3335]);
+    assert!(themes.live_filter("halloween", date(11, 1)).is_none());
+    assert_eq!(themes.all().len(), 2);
+}
+
+#[test]
+fn test_put_validates_and_reports_new_rows() {
+    let themes = ThemedRows::new();
+    let genre = || ThemeRules { genres: vec![27], ..rules(MediaType::Movie) };
+
+    let (row, created) = themes.put("horror", input("  Horror  ", genre()), UNIX_EPOCH).unwrap();
+    assert!(created);
+    assert_eq!(row.title, "Horror");
+    assert!(!themes.put("horror", input("Scary", genre()), UNIX_EPOCH).unwrap().1);
+
+    assert_eq!(themes.put("horror", input(" ", genre()), UNIX_EPOCH).unwrap_err(), "title must not be empty");
+    assert!(themes.put("Horror!", input("Horror", genre()), UNIX_EPOCH).is_err());
+    let backwards = ThemeInput { live_from: Some(date(11, 1)), live_until: Some(date(10, 1)), ..input("Horror", genre()) };
+    assert_eq!(themes.put("horror", backwards, UNIX_EPOCH).unwrap_err(), "live_from must not be after live_until");
+
+    assert!(themes.delete("horror"));
+    assert!(!themes.delete("horror"));
+}
+
+#[test]
+fn test_from_json() {
+    let json = json!([
+        { "slug": "halloween", "title": "Halloween picks", "media_type": "movie", "keywords": [3335], "live_from": "2026-10-01", "live_until": "2026-10-31" },
+        { "slug": "newest-horror", "title": "New horror", "media_type": "tv", "genres": [9648], "sort": "newest" }
+    ]);
+    let themes = ThemedRows::from_json(&json.to_string(), UNIX_EPOCH).unwrap();
+
+    let halloween = themes.get("halloween").unwrap();
+    assert_eq!(halloween.live_until, Some(date(10, 31)));
+    assert_eq!(themes.get("newest-horror").unwrap().rules.sort, DiscoverSort::Newest);
+
+    let duplicate = json!([
+        { "slug": "horror", "title": "Horror", "media_type": "movie", "genres": [27] },
+        { "slug": "horror", "title": "Horror", "media_type": "movie", "genres": [27] }
+    ]);
+    assert_eq!(ThemedRows::from_json(&duplicate.to_string(), UNIX_EPOCH).err().unwrap(), "duplicate theme slug 'horror'");
+    let empty = json!([{ "slug": "all", "title": "Everything", "media_type": "movie" }]);
+    assert_eq!(
+        ThemedRows::from_json(&empty.to_string(), UNIX_EPOCH).err().unwrap(),
+        "theme 'all': rules need at least one keyword, genre, company or release date"
+    );
+}
@@ -0,0 +1,25 @@
+use netflix_service::trailers::TrailerIndex;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_answers_expire_after_the_ttl() {
+    let index = TrailerIndex::new();
+    let stored_at = UNIX_EPOCH + Duration::from_secs(1_000);
+    let ttl = Duration::from_secs(60);
+    index.store(550, true, stored_at);
+
+    assert_eq!(index.get(550, stored_at + Duration::from_secs(59), ttl), Some(true));
+    assert_eq!(index.get(550, stored_at + ttl, ttl), None);
+    assert_eq!(index.get(551, stored_at, ttl), None);
+}
+
+#[test]
+fn test_each_movie_is_claimed_once_until_released() {
+    let index = TrailerIndex::new();
+
+    assert_eq!(index.claim([1, 2]), vec![1, 2]);
+    assert_eq!(index.claim([2, 3]), vec![3]);
+
+    index.release(2);
+    assert_eq!(index.claim([1, 2]), vec![2]);
+}
@@ -0,0 +1,40 @@
+use netflix_service::upstream_metrics::{Operation, UpstreamMetrics};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_calls_errors_and_latency_per_operation() {
+    let metrics = UpstreamMetrics::new();
+    metrics.record(Operation::Details, true, Duration::from_millis(100), UNIX_EPOCH);
+    metrics.record(Operation::Details, false, Duration::from_millis(300), UNIX_EPOCH);
+    metrics.record(Operation::Search, true, Duration::from_millis(50), UNIX_EPOCH);
+
+    let stats = metrics.stats();
+    let details = stats.iter().find(|stats| stats.operation == Operation::Details).unwrap();
+    assert_eq!((details.calls, details.errors, details.average_latency_ms), (2, 1, 200));
+
+    let trending = stats.iter().find(|stats| stats.operation == Operation::Trending).unwrap();
+    assert_eq!((trending.calls, trending.average_latency_ms), (0, 0));
+}
+
+#[test]
+fn test_operation_labels() {
+    let labels: Vec<&str> = Operation::ALL.iter().map(Operation::as_str).collect();
+    assert_eq!(labels, ["trending", "search", "videos", "details", "images", "alternative_titles", "watch_providers", "discover", "provider_list", "network", "list", "changes", "season", "top_rated", "popular", "collection"]);
+    assert_eq!(serde_json::to_string(&Operation::AlternativeTitles).unwrap(), "\"alternative_titles\"");
+    assert_eq!(serde_json::to_string(&Operation::Videos).unwrap(), "\"videos\"");
+}
+
+#[test]
+fn test_last_success_ignores_failures() {
+    let metrics = UpstreamMetrics::new();
+    let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    metrics.record(Operation::Trending, false, Duration::from_millis(5), at);
+    assert!(metrics.last_success(Operation::Trending).is_none());
+
+    metrics.record(Operation::Trending, true, Duration::from_millis(5), at);
+    // An older call finishing late doesn't move the time back
+    metrics.record(Operation::Trending, true, Duration::from_millis(5), at - Duration::from_secs(60));
+
+    assert_eq!(metrics.last_success(Operation::Trending), Some(at));
+    assert!(metrics.last_success(Operation::Search).is_none());
+}
@@ -0,0 +1,23 @@
+use netflix_service::versioning::ApiVersion;
+
+#[test]
+fn test_version_defaults_to_v1() {
+    assert_eq!(ApiVersion::from_accept([]), Ok(ApiVersion::V1));
+    assert_eq!(ApiVersion::from_accept(["application/json, */*"]), Ok(ApiVersion::V1));
+    assert_eq!(ApiVersion::from_accept(["application/vnd.netflix-service.v1+json"]), Ok(ApiVersion::V1));
+}
+
+#[test]
+fn test_highest_accepted_version_wins() {
+    assert_eq!(ApiVersion::from_accept(["application/vnd.netflix-service.v2+json"]), Ok(ApiVersion::V2));
+    assert_eq!(
+        ApiVersion::from_accept(["application/vnd.netflix-service.v1+json", "Application/Vnd.Netflix-Service.V2+JSON; q=0.9"]),
+        Ok(ApiVersion::V2)
+    );
+    assert_eq!(ApiVersion::from_accept(["application/vnd.netflix-service.v2+json;q=0"]), Ok(ApiVersion::V1));
+}
+
+#[test]
+fn test_unknown_version_is_rejected() {
+    assert!(ApiVersion::from_accept(["application/vnd.netflix-service.v3+json"]).is_err());
+}
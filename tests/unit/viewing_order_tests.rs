@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use netflix_service::models::{Collection, Episode, Movie, Season, SeasonSummary};
+use netflix_service::viewing_order::{CollectionWhereToStart, ViewingOrder, WhereToStart, MAX_SEASONS};
+
+fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn today() -> NaiveDate {
+    date(2026, 10, 18).unwrap()
+}
+
+fn episode(episode_number: i32, name: &str, air_date: Option<NaiveDate>) -> Episode {
+    Episode { id: i64::from(episode_number), episode_number, name: name.to_string(), overview: None, air_date, still_path: None, runtime: None }
+}
+
+fn season(season_number: i32, episodes: Vec<Episode>) -> Season {
+    Season {
+        id: i64::from(season_number),
+        season_number,
+        name: format!("Season {}", season_number),
+        overview: None,
+        air_date: episodes.first().and_then(|episode| episode.air_date),
+        poster_path: None,
+        episodes,
+    }
+}
+
+/// Firefly: broadcast out of order, with a special aired after the cancellation
+fn firefly() -> Vec<Season> {
+    vec![
+        season(0, vec![episode(1, "Here's How It Was", date(2003, 12, 9))]),
+        season(1, vec![
+            episode(1, "Serenity", date(2002, 12, 20)),
+            episode(2, "The Train Job", date(2002, 9, 20)),
+            episode(3, "Bushwhacked", date(2002, 9, 27)),
+        ]),
+    ]
+}
+
+fn names(order: ViewingOrder, seasons: &[Season]) -> Vec<String> {
+    order.arrange(seasons, today()).into_iter().map(|entry| entry.name).collect()
+}
+
+#[test]
+fn test_release_order_follows_air_dates_with_specials() {
+    assert_eq!(names(ViewingOrder::Release, &firefly()), ["The Train Job", "Bushwhacked", "Serenity", "Here's How It Was"]);
+}
+
+#[test]
+fn test_chronological_order_follows_numbers_without_specials() {
+    assert_eq!(names(ViewingOrder::Chronological, &firefly()), ["Serenity", "The Train Job", "Bushwhacked"]);
+}
+
+#[test]
+fn test_unaired_episodes_are_left_out() {
+    let seasons = vec![season(1, vec![
+        episode(1, "Pilot", date(2026, 10, 18)),
+        episode(2, "Next week", date(2026, 10, 25)),
+        episode(3, "Unscheduled", None),
+    ])];
+
+    assert_eq!(names(ViewingOrder::Release, &seasons), ["Pilot"]);
+    assert_eq!(names(ViewingOrder::Chronological, &seasons), ["Pilot"]);
+}
+
+#[test]
+fn test_specials_come_after_episodes_of_the_same_day() {
+    let seasons = vec![
+        season(0, vec![episode(1, "Behind the scenes", date(2020, 1, 1))]),
+        season(1, vec![episode(1, "Pilot", date(2020, 1, 1))]),
+    ];
+
+    assert_eq!(names(ViewingOrder::Release, &seasons), ["Pilot", "Behind the scenes"]);
+}
+
+#[test]
+fn test_seasons_needed() {
+    let summary = |season_number, air_date| SeasonSummary { season_number, name: String::new(), air_date, episode_count: 10 };
+    let seasons = vec![summary(2, date(2021, 1, 1)), summary(0, date(2019, 6, 1)), summary(1, date(2020, 1, 1)), summary(3, None)];
+
+    assert_eq!(ViewingOrder::Release.seasons_needed(&seasons, today()), [0, 1, 2]);
+    assert_eq!(ViewingOrder::Chronological.seasons_needed(&seasons, today()), [1, 2]);
+
+    let long_running: Vec<SeasonSummary> = (1..=60).rev().map(|number| summary(number, date(2000, 1, 1))).collect();
+    let needed = ViewingOrder::Chronological.seasons_needed(&long_running, today());
+    assert_eq!(needed.len(), MAX_SEASONS);
+    assert_eq!(needed.first(), Some(&1));
+}
+
+#[test]
+fn test_where_to_start_begins_with_the_first_entry() {
+    let start = WhereToStart::new(78, ViewingOrder::Chronological, &firefly(), today());
+    assert_eq!(start.start.unwrap().name, "Serenity");
+    assert_eq!(start.episodes.len(), 3);
+
+    let upcoming = vec![season(1, vec![episode(1, "Pilot", None)])];
+    let nothing_aired = WhereToStart::new(1, ViewingOrder::Release, &upcoming, today());
+    assert!(nothing_aired.start.is_none());
+    assert!(nothing_aired.episodes.is_empty());
+}
+
+fn part(id: i64, title: &str, release_date: Option<NaiveDate>) -> Movie {
+    serde_json::from_value(serde_json::json!({ "id": id, "title": title, "release_date": release_date })).unwrap()
+}
+
+#[test]
+fn test_collections_are_ordered_by_release() {
+    let collection = Collection {
+        id: 10,
+        name: "Star Wars Collection".to_string(),
+        overview: None,
+        parts: vec![
+            part(1893, "The Phantom Menace", date(1999, 5, 19)),
+            part(11, "A New Hope", date(1977, 5, 25)),
+            part(1891, "The Empire Strikes Back", date(1980, 5, 17)),
+            part(999_999, "Untitled", None),
+            part(999_998, "Announced", date(2027, 5, 1)),
+        ],
+    };
+
+    let order = CollectionWhereToStart::new(&collection, today());
+
+    assert_eq!(order.order, ViewingOrder::Release);
+    let titles: Vec<&str> = order.movies.iter().map(|movie| movie.title.as_str()).collect();
+    assert_eq!(titles, ["A New Hope", "The Empire Strikes Back", "The Phantom Menace"]);
+    assert_eq!(order.start.unwrap().id, 11);
+}